@@ -1,5 +1,8 @@
 use criterion::{criterion_group, criterion_main, Criterion};
+use ibe::abe::{Attribute, Policy};
+use ibe::Derive;
 use pastey::paste;
+use pg_curve::Scalar;
 use std::hint::black_box;
 use std::time::Duration;
 
@@ -96,45 +99,803 @@ macro_rules! bench_multi_kem {
     };
 }
 
+macro_rules! bench_threshold_kem {
+    ($scheme: ident, $struct: ident) => {
+        paste! {
+            fn [<bench_threshold_kem_ $scheme>](criterion: &mut Criterion) {
+                use ibe::kem::$scheme::*;
+                use ibe::kem::threshold::ThresholdDecaps;
+                use ibe::{kem::IBKEM, Derive};
+
+                let mut rng = rand::thread_rng();
+
+                let id = "email:w.geraedts@sarif.nl".as_bytes();
+                let kid = <$struct as IBKEM>::Id::derive(id);
+
+                let (pk, sk) = $struct::setup(&mut rng);
+                let usk = $struct::extract_usk(Some(&pk), &sk, &kid, &mut rng);
+                let shares = $struct::split_usk(&usk, 3, 5, &mut rng).unwrap();
+
+                let (c, _k) = $struct::encaps(&pk, &kid, &mut rng);
+                let decaps_shares: Vec<_> = shares[..3]
+                    .iter()
+                    .map(|share| $struct::decaps_share(share, &c))
+                    .collect();
+
+                criterion.bench_function(
+                    &format!("kem_{} decaps_share", stringify!($scheme)).to_string(),
+                    |b| b.iter(|| $struct::decaps_share(black_box(&shares[0]), black_box(&c))),
+                );
+                criterion.bench_function(
+                    &format!("kem_{} combine 3-of-5", stringify!($scheme)).to_string(),
+                    |b| {
+                        b.iter(|| {
+                            $struct::combine(
+                                black_box(Some(&pk)),
+                                black_box(&kid),
+                                black_box(&c),
+                                black_box(&decaps_shares),
+                            )
+                        })
+                    },
+                );
+            }
+        }
+    };
+}
+
+macro_rules! bench_clkem {
+    ($scheme: ident, $struct: ident) => {
+        paste! {
+            fn [<bench_clkem_ $scheme>](criterion: &mut Criterion) {
+                use ibe::kem::$scheme::*;
+                use ibe::kem::CLKEM;
+
+                let mut rng = rand::thread_rng();
+
+                let id = "email:w.geraedts@sarif.nl".as_bytes();
+                let kid = <$struct as CLKEM>::Id::derive(id);
+
+                let (pk, sk) = $struct::setup(&mut rng);
+                let partial = $struct::extract_partial_key(Some(&pk), &sk, &kid, &mut rng);
+                let (upk, sv) = $struct::generate_user_keys(&pk, &mut rng);
+                let usk = $struct::combine_usk(&pk, &partial, &sv, &upk).unwrap();
+
+                let (c, _k) = $struct::encaps(&pk, &kid, &upk, &mut rng).unwrap();
+
+                criterion.bench_function(
+                    &format!("clkem_{} combine_usk", stringify!($scheme)).to_string(),
+                    |b| {
+                        b.iter(|| {
+                            $struct::combine_usk(
+                                black_box(&pk),
+                                black_box(&partial),
+                                black_box(&sv),
+                                black_box(&upk),
+                            )
+                        })
+                    },
+                );
+                criterion.bench_function(
+                    &format!("clkem_{} encaps", stringify!($scheme)).to_string(),
+                    |b| {
+                        let mut rng = rand::thread_rng();
+                        b.iter(|| $struct::encaps(black_box(&pk), black_box(&kid), black_box(&upk), &mut rng))
+                    },
+                );
+                criterion.bench_function(
+                    &format!("clkem_{} decaps", stringify!($scheme)).to_string(),
+                    |b| b.iter(|| $struct::decaps(black_box(Some(&pk)), black_box(&usk), black_box(&c))),
+                );
+            }
+        }
+    };
+}
+
+macro_rules! bench_pkem {
+    ($scheme: ident, $struct: ty) => {
+        paste! {
+            fn [<bench_pkem_ $scheme>](criterion: &mut Criterion) {
+                use ibe::kem::$scheme::*;
+                use ibe::kem::PKEM;
+
+                let mut rng = rand::thread_rng();
+
+                let tags: Vec<_> = (0..<$struct as PKEM>::TAGS)
+                    .map(|i| <$struct as PKEM>::Tag::derive(format!("msg:{}", i).as_bytes()))
+                    .collect();
+                let punctured = <$struct as PKEM>::Tag::derive(b"msg:punctured");
+
+                let (pk, mut sk) = <$struct>::keygen(&mut rng);
+                let (c, _k) = <$struct>::encaps(&pk, &tags, &mut rng).unwrap();
+
+                criterion.bench_function(
+                    &format!("pkem_{} encaps", stringify!($scheme)).to_string(),
+                    |b| {
+                        let mut rng = rand::thread_rng();
+                        b.iter(|| <$struct>::encaps(black_box(&pk), black_box(&tags), &mut rng))
+                    },
+                );
+                criterion.bench_function(
+                    &format!("pkem_{} puncture", stringify!($scheme)).to_string(),
+                    |b| {
+                        let mut rng = rand::thread_rng();
+                        b.iter_batched_ref(
+                            || sk.clone(),
+                            |sk| <$struct>::puncture(black_box(&pk), sk, black_box(&punctured), &mut rng),
+                            criterion::BatchSize::SmallInput,
+                        )
+                    },
+                );
+
+                <$struct>::puncture(&pk, &mut sk, &punctured, &mut rng);
+                criterion.bench_function(
+                    &format!("pkem_{} decaps (1 puncture)", stringify!($scheme)).to_string(),
+                    |b| b.iter(|| <$struct>::decaps(black_box(Some(&pk)), black_box(&sk), black_box(&c))),
+                );
+            }
+        }
+    };
+}
+
+macro_rules! bench_fskem {
+    ($scheme: ident, $struct: ty) => {
+        paste! {
+            fn [<bench_fskem_ $scheme>](criterion: &mut Criterion) {
+                use ibe::kem::$scheme::*;
+                use ibe::kem::FSKEM;
+
+                let mut rng = rand::thread_rng();
+
+                let id = "email:w.geraedts@sarif.nl".as_bytes();
+                let kid = <$struct as FSKEM>::Id::derive(id);
+                let epoch = <$struct as FSKEM>::EPOCHS / 2;
+
+                let (pk, sk) = <$struct>::setup(&mut rng);
+                let mut usk = <$struct>::extract_usk(Some(&pk), &sk, &kid, &mut rng);
+
+                criterion.bench_function(
+                    &format!("fskem_{} extract", stringify!($scheme)).to_string(),
+                    |b| {
+                        let mut rng = rand::thread_rng();
+                        b.iter(|| <$struct>::extract_usk(Some(&pk), &sk, black_box(&kid), &mut rng))
+                    },
+                );
+                criterion.bench_function(
+                    &format!("fskem_{} update", stringify!($scheme)).to_string(),
+                    |b| {
+                        let mut rng = rand::thread_rng();
+                        b.iter_batched_ref(
+                            || usk.clone(),
+                            |usk| <$struct>::update(black_box(&pk), usk, black_box(epoch), &mut rng),
+                            criterion::BatchSize::SmallInput,
+                        )
+                    },
+                );
+
+                <$struct>::update(&pk, &mut usk, epoch, &mut rng).unwrap();
+                let (c, _k) = <$struct>::encaps(&pk, &kid, epoch, &mut rng).unwrap();
+
+                criterion.bench_function(
+                    &format!("fskem_{} encaps", stringify!($scheme)).to_string(),
+                    |b| {
+                        let mut rng = rand::thread_rng();
+                        b.iter(|| <$struct>::encaps(black_box(&pk), black_box(&kid), black_box(epoch), &mut rng))
+                    },
+                );
+                criterion.bench_function(
+                    &format!("fskem_{} decaps", stringify!($scheme)).to_string(),
+                    |b| b.iter(|| <$struct>::decaps(black_box(Some(&pk)), black_box(&usk), black_box(&c))),
+                );
+            }
+        }
+    };
+}
+
+macro_rules! bench_tlkem {
+    ($scheme: ident, $struct: ident) => {
+        paste! {
+            fn [<bench_tlkem_ $scheme>](criterion: &mut Criterion) {
+                use ibe::kem::$scheme::*;
+                use ibe::kem::TLKEM;
+
+                let mut rng = rand::thread_rng();
+
+                let round = 1000;
+                let beacon = Beacon::new(&mut rng);
+                let pk = beacon.public_key();
+                let sig = beacon.sign(round);
+
+                let (c, _k) = $struct::encaps(&pk, round, &mut rng);
+
+                criterion.bench_function(
+                    &format!("tlkem_{} verify", stringify!($scheme)).to_string(),
+                    |b| b.iter(|| $struct::verify(black_box(&pk), black_box(round), black_box(&sig))),
+                );
+                criterion.bench_function(
+                    &format!("tlkem_{} encaps", stringify!($scheme)).to_string(),
+                    |b| {
+                        let mut rng = rand::thread_rng();
+                        b.iter(|| $struct::encaps(black_box(&pk), black_box(round), &mut rng))
+                    },
+                );
+                criterion.bench_function(
+                    &format!("tlkem_{} decaps", stringify!($scheme)).to_string(),
+                    |b| b.iter(|| $struct::decaps(black_box(&pk), black_box(&sig), black_box(&c))),
+                );
+            }
+        }
+    };
+}
+
+macro_rules! bench_signcryption {
+    ($scheme: ident, $struct: ident) => {
+        paste! {
+            fn [<bench_signcryption_ $scheme>](criterion: &mut Criterion) {
+                use ibe::kem::$scheme::*;
+                use ibe::{kem::{IBKEM, Signcryption}, Derive};
+
+                let mut rng = rand::thread_rng();
+
+                let sender = <$struct as IBKEM>::Id::derive_str("email:w.geraedts@sarif.nl");
+                let recipient = <$struct as IBKEM>::Id::derive_str("email:l.botros@cs.ru.nl");
+
+                let (pk, sk) = $struct::setup(&mut rng);
+                let usk_sender = $struct::extract_usk(Some(&pk), &sk, &sender, &mut rng);
+                let usk_recipient = $struct::extract_usk(Some(&pk), &sk, &recipient, &mut rng);
+
+                let (c, _k) = $struct::signcrypt(&pk, &usk_sender, &recipient, &mut rng);
+
+                criterion.bench_function(
+                    &format!("kem_{} signcrypt", stringify!($scheme)).to_string(),
+                    move |b| {
+                        let mut rng = rand::thread_rng();
+                        b.iter(|| {
+                            $struct::signcrypt(
+                                black_box(&pk),
+                                black_box(&usk_sender),
+                                black_box(&recipient),
+                                &mut rng,
+                            )
+                        })
+                    },
+                );
+                criterion.bench_function(
+                    &format!("kem_{} unsigncrypt", stringify!($scheme)).to_string(),
+                    move |b| {
+                        b.iter(|| {
+                            $struct::unsigncrypt(
+                                black_box(&pk),
+                                black_box(&usk_recipient),
+                                black_box(&sender),
+                                black_box(&c),
+                            )
+                        })
+                    },
+                );
+            }
+        }
+    };
+}
+
+macro_rules! bench_ibbkem {
+    ($scheme: ident, $struct: ident $(<$($param: literal),+>)?, $n: literal) => {
+        paste! {
+            fn [<bench_ibbkem_ $scheme>](criterion: &mut Criterion) {
+                use ibe::kem::$scheme::*;
+                use ibe::{kem::IBBKEM, Derive};
+
+                type Scheme = $struct$(<$($param),+>)?;
+
+                let mut rng = rand::thread_rng();
+
+                let kids: Vec<<Scheme as IBBKEM>::Id> = (0..$n)
+                    .map(|i| <Scheme as IBBKEM>::Id::derive_str(&format!("email:user{i}@example.com")))
+                    .collect();
+
+                let (pk, sk) = Scheme::setup(&mut rng);
+                let usk = Scheme::extract_usk(Some(&pk), &sk, &kids[0], &mut rng);
+
+                let (c, _k) = Scheme::encaps(&pk, &kids, &mut rng).unwrap();
+
+                criterion.bench_function(
+                    &format!("ibbkem_{} setup", stringify!($scheme)).to_string(),
+                    |b| {
+                        let mut rng = rand::thread_rng();
+                        b.iter(|| Scheme::setup(&mut rng))
+                    },
+                );
+                criterion.bench_function(
+                    &format!("ibbkem_{} extract", stringify!($scheme)).to_string(),
+                    |b| {
+                        let mut rng = rand::thread_rng();
+                        b.iter(|| {
+                            Scheme::extract_usk(
+                                black_box(Some(&pk)),
+                                black_box(&sk),
+                                black_box(&kids[0]),
+                                &mut rng,
+                            )
+                        })
+                    },
+                );
+                criterion.bench_function(
+                    &format!("ibbkem_{} encaps x{}", stringify!($scheme), $n).to_string(),
+                    |b| {
+                        let mut rng = rand::thread_rng();
+                        b.iter(|| Scheme::encaps(black_box(&pk), black_box(&kids), &mut rng))
+                    },
+                );
+                criterion.bench_function(
+                    &format!("ibbkem_{} decaps x{}", stringify!($scheme), $n).to_string(),
+                    |b| {
+                        b.iter(|| {
+                            Scheme::decaps(
+                                black_box(Some(&pk)),
+                                black_box(&usk),
+                                black_box(&kids),
+                                black_box(&c),
+                            )
+                        })
+                    },
+                );
+            }
+        }
+    };
+}
+
 macro_rules! bench_ibe {
+    ($scheme: ident, $struct: ident $(<$depth: literal>)?) => {
+        paste! {
+            fn [<bench_ibe_ $scheme>](criterion: &mut Criterion) {
+                use group::Group;
+                use ibe::ibe::$scheme::*;
+                use ibe::{ibe::IBE, Derive};
+                use rand::RngCore;
+
+                type Scheme = $struct $(<$depth>)?;
+
+                let mut rng = rand::thread_rng();
+
+                let id = "email:w.geraedts@sarif.nl".as_bytes();
+                let kid = <Scheme as IBE>::Id::derive(id);
+
+                let (pk, sk) = Scheme::setup(&mut rng);
+                let usk = Scheme::extract_usk(Some(&pk), &sk, &kid, &mut rng);
+
+                // let ppk = pk.to_bytes();
+                // criterion.bench_function(
+                //     &format!("ibe_{} unpack_pk", stringify!($scheme)).to_string(),
+                //     |b| b.iter(|| PublicKey::from_bytes(&ppk)),
+                // );
+
+                let m = <Scheme as IBE>::Msg::random(&mut rng);
+                type RngBytes = <Scheme as IBE>::RngBytes;
+                let mut rand_bytes: RngBytes = [0u8; core::mem::size_of::<RngBytes>()];
+                rng.fill_bytes(&mut rand_bytes);
+
+                let c = Scheme::encrypt(&pk, &kid, &m, &rand_bytes);
+
+                criterion.bench_function(
+                    &format!("ibe_{} setup", stringify!($scheme)).to_string(),
+                    |b| {
+                        let mut rng = rand::thread_rng();
+                        b.iter(|| Scheme::setup(&mut rng))
+                    },
+                );
+                criterion.bench_function(
+                    &format!("ibe_{} extract", stringify!($scheme)).to_string(),
+                    move |b| {
+                        let mut rng = rand::thread_rng();
+                        b.iter(|| {
+                            Scheme::extract_usk(
+                                black_box(Some(&pk)),
+                                black_box(&sk),
+                                black_box(&kid),
+                                &mut rng,
+                            )
+                        })
+                    },
+                );
+                criterion.bench_function(
+                    &format!("ibe_{} encrypt", stringify!($scheme)).to_string(),
+                    move |b| {
+                        b.iter(|| {
+                            Scheme::encrypt(
+                                black_box(&pk),
+                                black_box(&kid),
+                                black_box(&m),
+                                black_box(&rand_bytes),
+                            )
+                        })
+                    },
+                );
+                criterion.bench_function(
+                    &format!("ibe_{} decrypt", stringify!($scheme)).to_string(),
+                    move |b| b.iter(|| Scheme::decrypt(black_box(&usk), black_box(&c))),
+                );
+            }
+        }
+    };
+}
+
+macro_rules! bench_ibpre {
+    ($scheme: ident, $struct: ident) => {
+        paste! {
+            fn [<bench_ibpre_ $scheme>](criterion: &mut Criterion) {
+                use group::Group;
+                use ibe::ibe::$scheme::*;
+                use ibe::{ibe::{IBE, IBPRE}, Derive};
+                use rand::RngCore;
+
+                let mut rng = rand::thread_rng();
+
+                let from = <$struct as IBE>::Id::derive_str("email:w.geraedts@sarif.nl");
+                let to = <$struct as IBE>::Id::derive_str("email:l.botros@cs.ru.nl");
+
+                let (pk, sk) = $struct::setup(&mut rng);
+                let usk_from = $struct::extract_usk(Some(&pk), &sk, &from, &mut rng);
+                let usk_to = $struct::extract_usk(Some(&pk), &sk, &to, &mut rng);
+
+                let m = <$struct as IBE>::Msg::random(&mut rng);
+                let mut rand_bytes = [0u8; 64];
+                rng.fill_bytes(&mut rand_bytes);
+
+                let c = $struct::encrypt(&pk, &from, &m, &rand_bytes);
+                let rk = $struct::rekey(&pk, &usk_from, &to, &mut rng);
+                let rc = $struct::reencrypt(&rk, &c);
+
+                criterion.bench_function(
+                    &format!("ibpre_{} rekey", stringify!($scheme)).to_string(),
+                    |b| {
+                        let mut rng = rand::thread_rng();
+                        b.iter(|| {
+                            $struct::rekey(black_box(&pk), black_box(&usk_from), black_box(&to), &mut rng)
+                        })
+                    },
+                );
+                criterion.bench_function(
+                    &format!("ibpre_{} reencrypt", stringify!($scheme)).to_string(),
+                    |b| b.iter(|| $struct::reencrypt(black_box(&rk), black_box(&c))),
+                );
+                criterion.bench_function(
+                    &format!("ibpre_{} decrypt_reencrypted", stringify!($scheme)).to_string(),
+                    |b| b.iter(|| $struct::decrypt_reencrypted(black_box(&usk_to), black_box(&rc))),
+                );
+            }
+        }
+    };
+}
+
+macro_rules! bench_blind {
+    ($scheme: ident, $struct: ident) => {
+        paste! {
+            fn [<bench_blind_ $scheme>](criterion: &mut Criterion) {
+                use ibe::ibe::$scheme::*;
+                use ibe::{ibe::{ObliviousKeyOracle, IBE}, Derive};
+
+                let mut rng = rand::thread_rng();
+
+                let kid = <$struct as IBE>::Id::derive_str("email:w.geraedts@sarif.nl");
+
+                let (pk, sk) = $struct::setup(&mut rng);
+                let (_, req) = $struct::blind(&pk, &kid, &mut rng);
+
+                criterion.bench_function(
+                    &format!("blind_{} blind", stringify!($scheme)).to_string(),
+                    |b| {
+                        let mut rng = rand::thread_rng();
+                        b.iter(|| $struct::blind(black_box(&pk), black_box(&kid), &mut rng))
+                    },
+                );
+                criterion.bench_function(
+                    &format!("blind_{} oblivious_extract_usk", stringify!($scheme)).to_string(),
+                    |b| {
+                        let mut rng = rand::thread_rng();
+                        b.iter(|| {
+                            $struct::oblivious_extract_usk(
+                                black_box(Some(&pk)),
+                                black_box(&sk),
+                                black_box(&req),
+                                &mut rng,
+                            )
+                        })
+                    },
+                );
+                criterion.bench_function(
+                    &format!("blind_{} unblind", stringify!($scheme)).to_string(),
+                    |b| {
+                        let mut rng = rand::thread_rng();
+                        b.iter_batched(
+                            || {
+                                let (state, req) = $struct::blind(&pk, &kid, &mut rng);
+                                let resp = $struct::oblivious_extract_usk(None, &sk, &req, &mut rng);
+                                (state, resp)
+                            },
+                            |(state, resp)| $struct::unblind(black_box(&pk), state, black_box(&resp)),
+                            criterion::BatchSize::SmallInput,
+                        )
+                    },
+                );
+            }
+        }
+    };
+}
+
+macro_rules! bench_aibe {
     ($scheme: ident, $struct: ident) => {
         paste! {
-            fn [<bench_ibe_ $scheme>](criterion: &mut Criterion) {
+            fn [<bench_aibe_ $scheme>](criterion: &mut Criterion) {
+                use group::Group;
+                use ibe::ibe::$scheme::*;
+                use ibe::{ibe::AIBE, Derive};
+                use rand::RngCore;
+
+                let mut rng = rand::thread_rng();
+
+                let kid = <$struct as AIBE>::Id::derive_str("email:w.geraedts@sarif.nl");
+
+                let (pk, sk) = $struct::setup(&mut rng);
+                let (state, req) = $struct::request_usk(&pk, &kid, &mut rng);
+                let resp = $struct::issue_usk(&pk, &sk, &kid, &req, &mut rng).unwrap();
+                let usk = $struct::finish_usk(&pk, state, &resp).unwrap();
+
+                let m = <$struct as AIBE>::Msg::random(&mut rng);
+                let mut rand_bytes = [0u8; 64];
+                rng.fill_bytes(&mut rand_bytes);
+
+                let c = $struct::encrypt(&pk, &kid, &m, &rand_bytes);
+
+                criterion.bench_function(
+                    &format!("aibe_{} request_usk", stringify!($scheme)).to_string(),
+                    |b| {
+                        let mut rng = rand::thread_rng();
+                        b.iter(|| $struct::request_usk(black_box(&pk), black_box(&kid), &mut rng))
+                    },
+                );
+                criterion.bench_function(
+                    &format!("aibe_{} issue_usk", stringify!($scheme)).to_string(),
+                    |b| {
+                        let mut rng = rand::thread_rng();
+                        b.iter(|| {
+                            $struct::issue_usk(
+                                black_box(&pk),
+                                black_box(&sk),
+                                black_box(&kid),
+                                black_box(&req),
+                                &mut rng,
+                            )
+                        })
+                    },
+                );
+                criterion.bench_function(
+                    &format!("aibe_{} encrypt", stringify!($scheme)).to_string(),
+                    |b| {
+                        b.iter(|| {
+                            $struct::encrypt(
+                                black_box(&pk),
+                                black_box(&kid),
+                                black_box(&m),
+                                black_box(&rand_bytes),
+                            )
+                        })
+                    },
+                );
+                criterion.bench_function(
+                    &format!("aibe_{} decrypt", stringify!($scheme)).to_string(),
+                    |b| b.iter(|| $struct::decrypt(black_box(&usk), black_box(&c))),
+                );
+            }
+        }
+    };
+}
+
+macro_rules! bench_ribe {
+    ($scheme: ident, $struct: ident <$depth: literal>) => {
+        paste! {
+            fn [<bench_ribe_ $scheme>](criterion: &mut Criterion) {
                 use group::Group;
                 use ibe::ibe::$scheme::*;
-                use ibe::{ibe::IBE, Derive};
+                use ibe::{ibe::RIBE, Derive};
                 use rand::RngCore;
 
+                type Scheme = $struct<$depth>;
+
                 let mut rng = rand::thread_rng();
 
                 let id = "email:w.geraedts@sarif.nl".as_bytes();
-                let kid = <$struct as IBE>::Id::derive(id);
+                let kid = <Scheme as RIBE>::Id::derive(id);
+                let revoked: Vec<u64> = (1..=10).map(|i| i * 7).collect();
+
+                let (pk, sk) = Scheme::setup(&mut rng);
+                let usk = Scheme::extract_usk(Some(&pk), &sk, &kid, 0, &mut rng).unwrap();
+                let ku = Scheme::update_key(Some(&pk), &sk, 1, &revoked, &mut rng);
+                let dk = Scheme::derive_dk(&usk, &ku).unwrap();
+
+                let m = <Scheme as RIBE>::Msg::random(&mut rng);
+                let mut rand_bytes = [0u8; 64];
+                rng.fill_bytes(&mut rand_bytes);
+
+                let c = Scheme::encrypt(&pk, &kid, 1, &m, &rand_bytes);
+
+                criterion.bench_function(
+                    &format!("ribe_{} extract", stringify!($scheme)).to_string(),
+                    |b| {
+                        let mut rng = rand::thread_rng();
+                        b.iter(|| {
+                            Scheme::extract_usk(
+                                black_box(Some(&pk)),
+                                black_box(&sk),
+                                black_box(&kid),
+                                black_box(0),
+                                &mut rng,
+                            )
+                        })
+                    },
+                );
+                criterion.bench_function(
+                    &format!("ribe_{} update_key revoked x10", stringify!($scheme)).to_string(),
+                    |b| {
+                        let mut rng = rand::thread_rng();
+                        b.iter(|| {
+                            Scheme::update_key(
+                                black_box(Some(&pk)),
+                                black_box(&sk),
+                                black_box(1),
+                                black_box(&revoked),
+                                &mut rng,
+                            )
+                        })
+                    },
+                );
+                criterion.bench_function(
+                    &format!("ribe_{} derive_dk", stringify!($scheme)).to_string(),
+                    |b| b.iter(|| Scheme::derive_dk(black_box(&usk), black_box(&ku))),
+                );
+                criterion.bench_function(
+                    &format!("ribe_{} encrypt", stringify!($scheme)).to_string(),
+                    |b| {
+                        b.iter(|| {
+                            Scheme::encrypt(
+                                black_box(&pk),
+                                black_box(&kid),
+                                black_box(1),
+                                black_box(&m),
+                                black_box(&rand_bytes),
+                            )
+                        })
+                    },
+                );
+                criterion.bench_function(
+                    &format!("ribe_{} decrypt", stringify!($scheme)).to_string(),
+                    |b| b.iter(|| Scheme::decrypt(black_box(&dk), black_box(&c))),
+                );
+            }
+        }
+    };
+}
+
+macro_rules! bench_ake {
+    ($scheme: ident, $struct: ident) => {
+        paste! {
+            fn [<bench_ake_ $scheme>](criterion: &mut Criterion) {
+                use ibe::ake::$scheme::*;
+                use ibe::{ake::AKE, Derive};
+
+                let mut rng = rand::thread_rng();
+
+                let alice = <$struct as AKE>::Id::derive_str("email:w.geraedts@sarif.nl");
+                let bob = <$struct as AKE>::Id::derive_str("email:l.botros@cs.ru.nl");
 
                 let (pk, sk) = $struct::setup(&mut rng);
-                let usk = $struct::extract_usk(Some(&pk), &sk, &kid, &mut rng);
+                let usk_alice = $struct::extract_usk(Some(&pk), &sk, &alice, &mut rng);
+                let usk_bob = $struct::extract_usk(Some(&pk), &sk, &bob, &mut rng);
 
-                // let ppk = pk.to_bytes();
-                // criterion.bench_function(
-                //     &format!("ibe_{} unpack_pk", stringify!($scheme)).to_string(),
-                //     |b| b.iter(|| PublicKey::from_bytes(&ppk)),
-                // );
+                let (_, m1) = $struct::initiate(&pk, &usk_alice, &bob, &mut rng);
 
-                let m = <$struct as IBE>::Msg::random(&mut rng);
-                type RngBytes = <$struct as IBE>::RngBytes;
-                let mut rand_bytes: RngBytes = [0u8; core::mem::size_of::<RngBytes>()];
-                rng.fill_bytes(&mut rand_bytes);
+                criterion.bench_function(
+                    &format!("ake_{} initiate", stringify!($scheme)).to_string(),
+                    |b| {
+                        let mut rng = rand::thread_rng();
+                        b.iter(|| {
+                            $struct::initiate(black_box(&pk), black_box(&usk_alice), black_box(&bob), &mut rng)
+                        })
+                    },
+                );
+                criterion.bench_function(
+                    &format!("ake_{} respond", stringify!($scheme)).to_string(),
+                    |b| {
+                        let mut rng = rand::thread_rng();
+                        b.iter(|| {
+                            $struct::respond(black_box(&pk), black_box(&usk_bob), black_box(&m1), &mut rng)
+                        })
+                    },
+                );
+                criterion.bench_function(
+                    &format!("ake_{} finish_initiator", stringify!($scheme)).to_string(),
+                    |b| {
+                        let mut rng = rand::thread_rng();
+                        b.iter_batched(
+                            || {
+                                let (state, m1) = $struct::initiate(&pk, &usk_alice, &bob, &mut rng);
+                                let (_, m2) = $struct::respond(&pk, &usk_bob, &m1, &mut rng).unwrap();
+                                (state, m2)
+                            },
+                            |(state, m2)| $struct::finish_initiator(state, black_box(&m2)),
+                            criterion::BatchSize::SmallInput,
+                        )
+                    },
+                );
+            }
+        }
+    };
+}
 
-                let c = $struct::encrypt(&pk, &kid, &m, &rand_bytes);
+macro_rules! bench_nike {
+    ($scheme: ident, $struct: ident) => {
+        paste! {
+            fn [<bench_nike_ $scheme>](criterion: &mut Criterion) {
+                use ibe::nike::$scheme::*;
+                use ibe::{nike::NIKE, Derive};
+
+                let mut rng = rand::thread_rng();
+
+                let alice = <$struct as NIKE>::Id::derive_str("email:w.geraedts@sarif.nl");
+                let bob = <$struct as NIKE>::Id::derive_str("email:l.botros@cs.ru.nl");
+
+                let (pk, sk) = $struct::setup(&mut rng);
+                let usk = $struct::extract_usk(Some(&pk), &sk, &alice, &mut rng);
 
                 criterion.bench_function(
-                    &format!("ibe_{} setup", stringify!($scheme)).to_string(),
+                    &format!("nike_{} extract", stringify!($scheme)).to_string(),
+                    move |b| {
+                        let mut rng = rand::thread_rng();
+                        b.iter(|| {
+                            $struct::extract_usk(
+                                black_box(Some(&pk)),
+                                black_box(&sk),
+                                black_box(&alice),
+                                &mut rng,
+                            )
+                        })
+                    },
+                );
+                criterion.bench_function(
+                    &format!("nike_{} shared_secret", stringify!($scheme)).to_string(),
+                    move |b| b.iter(|| $struct::shared_secret(black_box(&usk), black_box(&bob))),
+                );
+            }
+        }
+    };
+}
+
+macro_rules! bench_ibs {
+    ($scheme: ident, $struct: ident) => {
+        paste! {
+            fn [<bench_ibs_ $scheme>](criterion: &mut Criterion) {
+                use ibe::ibs::$scheme::*;
+                use ibe::{ibs::IBS, Derive};
+
+                let mut rng = rand::thread_rng();
+
+                let id = "email:w.geraedts@sarif.nl".as_bytes();
+                let kid = <$struct as IBS>::Id::derive(id);
+                let msg = "hello world".as_bytes();
+
+                let (pk, sk) = $struct::setup(&mut rng);
+                let usk = $struct::extract_usk(Some(&pk), &sk, &kid, &mut rng);
+
+                let sig = $struct::sign(&usk, msg, &mut rng);
+
+                criterion.bench_function(
+                    &format!("ibs_{} setup", stringify!($scheme)).to_string(),
                     |b| {
                         let mut rng = rand::thread_rng();
                         b.iter(|| $struct::setup(&mut rng))
                     },
                 );
                 criterion.bench_function(
-                    &format!("ibe_{} extract", stringify!($scheme)).to_string(),
+                    &format!("ibs_{} extract", stringify!($scheme)).to_string(),
                     move |b| {
                         let mut rng = rand::thread_rng();
                         b.iter(|| {
@@ -148,12 +909,84 @@ macro_rules! bench_ibe {
                     },
                 );
                 criterion.bench_function(
-                    &format!("ibe_{} encrypt", stringify!($scheme)).to_string(),
+                    &format!("ibs_{} sign", stringify!($scheme)).to_string(),
+                    move |b| {
+                        let mut rng = rand::thread_rng();
+                        b.iter(|| $struct::sign(black_box(&usk), black_box(msg), &mut rng))
+                    },
+                );
+                criterion.bench_function(
+                    &format!("ibs_{} verify", stringify!($scheme)).to_string(),
                     move |b| {
                         b.iter(|| {
-                            $struct::encrypt(
+                            $struct::verify(
                                 black_box(&pk),
                                 black_box(&kid),
+                                black_box(msg),
+                                black_box(&sig),
+                            )
+                        })
+                    },
+                );
+            }
+        }
+    };
+}
+
+macro_rules! bench_abe {
+    ($scheme: ident, $struct: ident $(<$($param: literal),+>)?, $key_attrs: expr, $ct_attrs: expr) => {
+        paste! {
+            fn [<bench_abe_ $scheme>](criterion: &mut Criterion) {
+                use group::Group;
+                use ibe::abe::$scheme::*;
+                use ibe::abe::ABE;
+                use rand::RngCore;
+
+                type Scheme = $struct $(<$($param),+>)?;
+
+                let mut rng = rand::thread_rng();
+
+                let key_attrs = $key_attrs;
+                let ct_attrs = $ct_attrs;
+
+                let (pk, sk) = Scheme::setup(&mut rng);
+                let usk = Scheme::extract_usk(Some(&pk), &sk, &key_attrs, &mut rng);
+
+                let m = <Scheme as ABE>::Msg::random(&mut rng);
+                type RngBytes = <Scheme as ABE>::RngBytes;
+                let mut rand_bytes: RngBytes = [0u8; core::mem::size_of::<RngBytes>()];
+                rng.fill_bytes(&mut rand_bytes);
+
+                let c = Scheme::encrypt(&pk, &ct_attrs, &m, &rand_bytes);
+
+                criterion.bench_function(
+                    &format!("abe_{} setup", stringify!($scheme)).to_string(),
+                    |b| {
+                        let mut rng = rand::thread_rng();
+                        b.iter(|| Scheme::setup(&mut rng))
+                    },
+                );
+                criterion.bench_function(
+                    &format!("abe_{} extract", stringify!($scheme)).to_string(),
+                    |b| {
+                        let mut rng = rand::thread_rng();
+                        b.iter(|| {
+                            Scheme::extract_usk(
+                                black_box(Some(&pk)),
+                                black_box(&sk),
+                                black_box(&key_attrs),
+                                &mut rng,
+                            )
+                        })
+                    },
+                );
+                criterion.bench_function(
+                    &format!("abe_{} encrypt", stringify!($scheme)).to_string(),
+                    |b| {
+                        b.iter(|| {
+                            Scheme::encrypt(
+                                black_box(&pk),
+                                black_box(&ct_attrs),
                                 black_box(&m),
                                 black_box(&rand_bytes),
                             )
@@ -161,8 +994,68 @@ macro_rules! bench_ibe {
                     },
                 );
                 criterion.bench_function(
-                    &format!("ibe_{} decrypt", stringify!($scheme)).to_string(),
-                    move |b| b.iter(|| $struct::decrypt(black_box(&usk), black_box(&c))),
+                    &format!("abe_{} decrypt", stringify!($scheme)).to_string(),
+                    |b| b.iter(|| Scheme::decrypt(black_box(&usk), black_box(&c))),
+                );
+            }
+        }
+    };
+}
+
+macro_rules! bench_abkem {
+    ($scheme: ident, $struct: ident $(<$($param: literal),+>)?, $key_attrs: expr, $ct_attrs: expr) => {
+        paste! {
+            fn [<bench_abkem_ $scheme>](criterion: &mut Criterion) {
+                use ibe::abe::$scheme::*;
+                use ibe::abe::ABKEM;
+
+                type Scheme = $struct $(<$($param),+>)?;
+
+                let mut rng = rand::thread_rng();
+
+                let key_attrs = $key_attrs;
+                let ct_attrs = $ct_attrs;
+
+                let (pk, sk) = Scheme::setup(&mut rng);
+                let usk = Scheme::extract_usk(Some(&pk), &sk, &key_attrs, &mut rng);
+
+                let (c, _k) = Scheme::encaps(&pk, &ct_attrs, &mut rng);
+
+                criterion.bench_function(
+                    &format!("abkem_{} setup", stringify!($scheme)).to_string(),
+                    |b| {
+                        let mut rng = rand::thread_rng();
+                        b.iter(|| Scheme::setup(&mut rng))
+                    },
+                );
+                criterion.bench_function(
+                    &format!("abkem_{} extract", stringify!($scheme)).to_string(),
+                    |b| {
+                        let mut rng = rand::thread_rng();
+                        b.iter(|| {
+                            Scheme::extract_usk(
+                                black_box(Some(&pk)),
+                                black_box(&sk),
+                                black_box(&key_attrs),
+                                &mut rng,
+                            )
+                        })
+                    },
+                );
+                criterion.bench_function(
+                    &format!("abkem_{} encaps", stringify!($scheme)).to_string(),
+                    |b| {
+                        let mut rng = rand::thread_rng();
+                        b.iter(|| Scheme::encaps(black_box(&pk), black_box(&ct_attrs), &mut rng))
+                    },
+                );
+                criterion.bench_function(
+                    &format!("abkem_{} decaps", stringify!($scheme)).to_string(),
+                    |b| {
+                        b.iter(|| {
+                            Scheme::decaps(black_box(Some(&pk)), black_box(&usk), black_box(&c))
+                        })
+                    },
                 );
             }
         }
@@ -172,15 +1065,100 @@ macro_rules! bench_ibe {
 bench_kem!(kiltz_vahlis_one, KV1);
 bench_kem!(cgw_kv, CGWKV);
 bench_kem!(cgw_fo, CGWFO);
+bench_kem!(bf_fo, BFFO);
+bench_kem!(sakai_kasahara, SKKEM);
+bench_kem!(bb_fo, BBFO);
 
 bench_multi_kem!(kiltz_vahlis_one, KV1);
 bench_multi_kem!(cgw_kv, CGWKV);
 bench_multi_kem!(cgw_fo, CGWFO);
+bench_multi_kem!(bf_fo, BFFO);
+bench_multi_kem!(sakai_kasahara, SKKEM);
+bench_multi_kem!(bb_fo, BBFO);
+
+bench_threshold_kem!(kiltz_vahlis_one, KV1);
+bench_threshold_kem!(cgw_kv, CGWKV);
+bench_threshold_kem!(cgw_fo, CGWFO);
+
+bench_ibbkem!(delerablee, Delerablee<100>, 100);
+
+bench_signcryption!(blmq, SKKEM);
+
+bench_clkem!(al_riyami_paterson, AlRiyamiPaterson);
+
+bench_pkem!(green_miers, GreenMiers<4>);
+
+bench_fskem!(bbg_fs, BBGFS<11>);
+
+bench_tlkem!(tlock, Tlock);
 
 bench_ibe!(boyen_waters, BoyenWaters);
 bench_ibe!(waters, Waters);
 bench_ibe!(waters_naccache, WatersNaccache);
 bench_ibe!(cgw, CGW);
+bench_ibe!(boneh_franklin, BonehFranklin);
+bench_ibe!(boneh_boyen, BonehBoyen);
+bench_ibe!(boyen_waters_hibe, BoyenWatersHIBE<3>);
+bench_ibe!(boneh_boyen_goh, BonehBoyenGoh<3>);
+
+bench_ibpre!(green_ateniese, BonehFranklin);
+
+bench_aibe!(goyal, Goyal);
+
+bench_blind!(oblivious_key_oracle, BonehFranklin);
+
+bench_ribe!(boldyreva_goyal_kumar, BoldyrevaGoyalKumar<10>);
+
+bench_ibs!(cha_cheon, ChaCheon);
+
+bench_nike!(sakai_ohgishi_kasahara, SOK);
+
+bench_ake!(smart_chen_kudla, SCK);
+
+bench_abe!(
+    sahai_waters,
+    SahaiWaters<3>,
+    [
+        "email:alice@example.com",
+        "phone:0612345678",
+        "name:Alice Example",
+        "city:Nijmegen",
+    ]
+    .map(Attribute::derive_str),
+    [
+        "email:alice@example.com",
+        "phone:0612345678",
+        "name:Alice Example",
+        "birthdate:1990-01-01",
+        "city:Nijmegen",
+    ]
+    .map(Attribute::derive_str)
+);
+bench_abe!(
+    cgw_ipe,
+    CGWIPE<3, 4>,
+    [Scalar::from(6), -Scalar::from(5), Scalar::one()],
+    [1, 2, 4].map(Scalar::from)
+);
+
+bench_abkem!(
+    fame_fo,
+    FAMEFO,
+    ["email:alice@example.com", "phone:0612345678"].map(Attribute::derive_str),
+    Policy::parse("email:alice@example.com AND (phone:0612345678 OR age:18)").unwrap()
+);
+bench_abkem!(
+    gpsw_fo,
+    GPSWFO,
+    Policy::parse("dept:finance AND (year:2025 OR year:2026)").unwrap(),
+    ["dept:finance", "year:2026"].map(Attribute::derive_str)
+);
+bench_abkem!(
+    cgw_ipe_kv,
+    CGWIPEKV<3, 6, 4>,
+    [Scalar::from(6), -Scalar::from(5), Scalar::one()],
+    [1, 2, 4].map(Scalar::from)
+);
 
 criterion_group!(
     name = kem_benches;
@@ -189,9 +1167,26 @@ criterion_group!(
     bench_kem_kiltz_vahlis_one,
     bench_kem_cgw_fo,
     bench_kem_cgw_kv,
+    bench_kem_bf_fo,
+    bench_kem_sakai_kasahara,
+    bench_kem_bb_fo,
     bench_multi_kem_kiltz_vahlis_one,
     bench_multi_kem_cgw_kv,
     bench_multi_kem_cgw_fo,
+    bench_multi_kem_bf_fo,
+    bench_multi_kem_sakai_kasahara,
+    bench_multi_kem_bb_fo,
+    bench_threshold_kem_kiltz_vahlis_one,
+    bench_threshold_kem_cgw_kv,
+    bench_threshold_kem_cgw_fo,
+    bench_ibbkem_delerablee,
+    bench_signcryption_blmq,
+    bench_clkem_al_riyami_paterson,
+    bench_pkem_green_miers,
+    bench_fskem_bbg_fs,
+    bench_tlkem_tlock,
+    bench_nike_sakai_ohgishi_kasahara,
+    bench_ake_smart_chen_kudla,
 );
 
 criterion_group!(
@@ -202,6 +1197,26 @@ criterion_group!(
     bench_ibe_waters_naccache,
     bench_ibe_boyen_waters,
     bench_ibe_cgw,
+    bench_ibe_boneh_franklin,
+    bench_ibe_boneh_boyen,
+    bench_ibe_boyen_waters_hibe,
+    bench_ibe_boneh_boyen_goh,
+    bench_ibpre_green_ateniese,
+    bench_aibe_goyal,
+    bench_blind_oblivious_key_oracle,
+    bench_ribe_boldyreva_goyal_kumar,
+    bench_ibs_cha_cheon,
+);
+
+criterion_group!(
+    name = abe_benches;
+    config = Criterion::default().warm_up_time(Duration::new(0, 500));
+    targets =
+    bench_abe_sahai_waters,
+    bench_abe_cgw_ipe,
+    bench_abkem_fame_fo,
+    bench_abkem_gpsw_fo,
+    bench_abkem_cgw_ipe_kv,
 );
 
-criterion_main!(kem_benches, ibe_benches);
+criterion_main!(kem_benches, ibe_benches, abe_benches);
@@ -0,0 +1,409 @@
+//! IND-CPA secure inner-product predicate encryption by Chen, Gay and Wee.
+//! * From: "[Improved Dual System ABE in Prime-Order Groups via Predicate Encodings](https://link.springer.com/chapter/10.1007/978-3-540-79263-5_14)"
+//!
+//! This file contains the passively secure predicate encryption scheme, an instance of the
+//! [Chen-Gay-Wee engine](crate::pe::cgw) with the [inner product encoding](InnerProductEncoding).
+//! A user secret key for the vector `y` can decrypt a ciphertext for the vector `x` if and only if
+//! `<x, y> = 0`. This allows to express, among others, polynomial evaluation, CNF and DNF formulas
+//! and hidden vector predicates.
+//!
+//! The scheme is payload-hiding, but not attribute-hiding: the vector `x` is part of the
+//! ciphertext.
+//!
+//! The vectors are of length `L`. Because const generic expressions are not available, the number
+//! of master key components `N` has to be given explicitly and must equal `L + 1`, which is checked
+//! at compile time. For example, `CGWIPE<3, 4>` can be used to evaluate polynomials of degree two.
+//!
+//! The byte serialisation of all structures depends on `L` and is returned as a vector.
+
+extern crate alloc;
+use alloc::vec::Vec;
+
+use crate::abe::ABE;
+use crate::pe::cgw::{self as engine, CGWEngine};
+use crate::pe::encodings::{inner_product, InnerProductEncoding};
+use crate::pe::PredicateEncoding;
+use crate::util::*;
+use crate::Compress;
+use core::convert::TryInto;
+use pg_curve::{Gt, Scalar};
+use rand::{CryptoRng, Rng};
+use subtle::{Choice, ConstantTimeEq, CtOption};
+
+/// The Chen-Gay-Wee engine instantiated with the inner product predicate encoding.
+type Engine<const L: usize, const N: usize> = CGWEngine<InnerProductEncoding<L>, N, L, 1>;
+
+/// Size of the compressed message in bytes.
+pub const MSG_BYTES: usize = GT_BYTES;
+
+/// Public key parameters generated by the PKG used to encrypt messages.
+/// Also known as MPK.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct PublicKey<const N: usize>(pub(super) engine::PublicKey<N>);
+
+/// Secret key parameter generated by the PKG used to extract user secret keys.
+/// Also known as MSK.
+///
+/// # Zeroization
+///
+/// With the `zeroize` feature enabled this type derives `Zeroize` but **not**
+/// `ZeroizeOnDrop` (it is `Copy`). Secret material is **not** cleared on drop —
+/// you **MUST** call `.zeroize()` explicitly once done. See the
+/// [crate-level docs](crate#zeroizing-secret-material).
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize))]
+pub struct SecretKey<const N: usize>(pub(super) engine::SecretKey<N>);
+
+/// User secret key. Can be used to decrypt ciphertexts for vectors orthogonal to its vector.
+/// Also known as USK_{y}.
+///
+/// # Zeroization
+///
+/// With the `zeroize` feature enabled this type derives `Zeroize` but **not**
+/// `ZeroizeOnDrop` (it is `Copy`). Secret material is **not** cleared on drop —
+/// you **MUST** call `.zeroize()` explicitly once done. See the
+/// [crate-level docs](crate#zeroizing-secret-material).
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize))]
+pub struct UserSecretKey<const L: usize> {
+    y: [Scalar; L],
+    usk: engine::UserSecretKey<1>,
+}
+
+/// Encrypted message. Can only be decrypted with a user secret key for an orthogonal vector.
+/// Also known as CT_{x}.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct CipherText<const L: usize> {
+    x: [Scalar; L],
+    ct: engine::CipherText<L>,
+    cprime: Gt,
+}
+
+/// A message that can be encrypted using the PE.
+pub type Msg = Gt;
+
+/// The Chen-Gay-Wee inner-product predicate encryption scheme for vectors of length `L`, with
+/// `N = L + 1` master key components.
+#[derive(Debug)]
+pub struct CGWIPE<const L: usize, const N: usize>;
+
+impl<const L: usize, const N: usize> CGWIPE<L, N> {
+    /// Size of the compressed master public key in bytes.
+    pub const PK_BYTES: usize = engine::PublicKey::<N>::BYTES;
+
+    /// Size of the compressed master secret key in bytes.
+    pub const SK_BYTES: usize = engine::SecretKey::<N>::BYTES;
+
+    /// Size of the compressed user secret key in bytes.
+    pub const USK_BYTES: usize = L * SCALAR_BYTES + engine::UserSecretKey::<1>::BYTES;
+
+    /// Size of the compressed ciphertext in bytes.
+    pub const CT_BYTES: usize = L * SCALAR_BYTES + engine::CipherText::<L>::BYTES + GT_BYTES;
+}
+
+impl<const L: usize, const N: usize> ABE for CGWIPE<L, N> {
+    type Pk = PublicKey<N>;
+    type Sk = SecretKey<N>;
+    type Usk = UserSecretKey<L>;
+    type Ct = CipherText<L>;
+    type Msg = Msg;
+    type KeyAttrs = [Scalar; L];
+    type CtAttrs = [Scalar; L];
+    type RngBytes = [u8; 64];
+
+    const PK_BYTES: usize = Self::PK_BYTES;
+    const SK_BYTES: usize = Self::SK_BYTES;
+    const MSG_BYTES: usize = MSG_BYTES;
+
+    /// Generate a keypair used by the Private Key Generator (PKG).
+    fn setup<R: Rng + CryptoRng>(rng: &mut R) -> (PublicKey<N>, SecretKey<N>) {
+        let (pk, sk) = Engine::<L, N>::setup(rng);
+
+        (PublicKey(pk), SecretKey(sk))
+    }
+
+    /// Extract a user secret key for a vector.
+    ///
+    /// The user secret key is the negation of the key produced by the engine, such that
+    /// decryption adds the recovered value to the ciphertext.
+    fn extract_usk<R: Rng + CryptoRng>(
+        _pk: Option<&PublicKey<N>>,
+        sk: &SecretKey<N>,
+        y: &[Scalar; L],
+        rng: &mut R,
+    ) -> UserSecretKey<L> {
+        UserSecretKey {
+            y: *y,
+            usk: -Engine::<L, N>::extract_usk(&sk.0, y, rng),
+        }
+    }
+
+    /// Encrypt a message using the PKG public key for a vector.
+    fn encrypt(pk: &PublicKey<N>, x: &[Scalar; L], message: &Msg, rng: &[u8; 64]) -> CipherText<L> {
+        let s = Scalar::from_bytes_wide(rng);
+        let (ct, blinding) = Engine::<L, N>::encrypt(&pk.0, x, &s);
+
+        CipherText {
+            x: *x,
+            ct,
+            cprime: blinding + message,
+        }
+    }
+
+    /// Derive the same message from the CipherText using a UserSecretKey.
+    ///
+    /// Returns `None` if the vectors of the user secret key and the ciphertext are not orthogonal.
+    fn decrypt(usk: &UserSecretKey<L>, ct: &CipherText<L>) -> Option<Msg> {
+        if inner_product(&ct.x, &usk.y) != Scalar::zero() {
+            return None;
+        }
+
+        let s_d = <InnerProductEncoding<L> as PredicateEncoding<N, L, 1>>::s_d(&ct.x, &usk.y);
+        let r_d = <InnerProductEncoding<L> as PredicateEncoding<N, L, 1>>::r_d(&ct.x, &usk.y);
+
+        Some(ct.cprime + Engine::<L, N>::decrypt(&usk.usk, &ct.ct, &s_d, &r_d))
+    }
+}
+
+impl<const L: usize> UserSecretKey<L> {
+    /// The vector this user secret key was extracted for.
+    pub fn vector(&self) -> &[Scalar; L] {
+        &self.y
+    }
+}
+
+impl<const L: usize> CipherText<L> {
+    /// The vector this ciphertext was encrypted for.
+    pub fn vector(&self) -> &[Scalar; L] {
+        &self.x
+    }
+}
+
+/// Reads a vector of scalars, returning whether all scalars are canonical.
+pub(crate) fn read_vector<const L: usize>(bytes: &[u8]) -> ([Scalar; L], Choice) {
+    let mut res = [Scalar::zero(); L];
+
+    let mut is_some = Choice::from(1u8);
+    for (slot, chunk) in res.iter_mut().zip(bytes.chunks_exact(SCALAR_BYTES)) {
+        is_some &= Scalar::from_bytes(chunk.try_into().unwrap())
+            .map(|s| *slot = s)
+            .is_some();
+    }
+
+    (res, is_some)
+}
+
+impl<const N: usize> Compress for PublicKey<N> {
+    const OUTPUT_SIZE: usize = engine::PublicKey::<N>::BYTES;
+    type Output = Vec<u8>;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_bytes()
+    }
+
+    fn from_bytes(bytes: &Vec<u8>) -> CtOption<Self> {
+        if bytes.len() != Self::OUTPUT_SIZE {
+            return CtOption::new(PublicKey(engine::PublicKey::default()), Choice::from(0));
+        }
+
+        let (pk, is_some) = engine::PublicKey::read_bytes(bytes);
+
+        CtOption::new(PublicKey(pk), is_some)
+    }
+}
+
+impl<const N: usize> Compress for SecretKey<N> {
+    const OUTPUT_SIZE: usize = engine::SecretKey::<N>::BYTES;
+    type Output = Vec<u8>;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_bytes()
+    }
+
+    fn from_bytes(bytes: &Vec<u8>) -> CtOption<Self> {
+        if bytes.len() != Self::OUTPUT_SIZE {
+            return CtOption::new(SecretKey(engine::SecretKey::default()), Choice::from(0));
+        }
+
+        let (sk, is_some) = engine::SecretKey::read_bytes(bytes);
+
+        CtOption::new(SecretKey(sk), is_some)
+    }
+}
+
+impl<const L: usize> Compress for UserSecretKey<L> {
+    const OUTPUT_SIZE: usize = L * SCALAR_BYTES + engine::UserSecretKey::<1>::BYTES;
+    type Output = Vec<u8>;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut res = alloc::vec![0u8; Self::OUTPUT_SIZE];
+        let (y, usk) = res.split_at_mut(L * SCALAR_BYTES);
+
+        for (yi, chunk) in self.y.iter().zip(y.chunks_exact_mut(SCALAR_BYTES)) {
+            chunk.copy_from_slice(&yi.to_bytes());
+        }
+        self.usk.write_bytes(usk);
+
+        res
+    }
+
+    fn from_bytes(bytes: &Vec<u8>) -> CtOption<Self> {
+        let mut res = UserSecretKey {
+            y: [Scalar::zero(); L],
+            usk: engine::UserSecretKey::default(),
+        };
+
+        if bytes.len() != Self::OUTPUT_SIZE {
+            return CtOption::new(res, Choice::from(0));
+        }
+
+        let (y, usk) = bytes.split_at(L * SCALAR_BYTES);
+        let (y, mut is_some) = read_vector(y);
+        let (usk, usk_is_some) = engine::UserSecretKey::read_bytes(usk);
+        is_some &= usk_is_some;
+
+        res.y = y;
+        res.usk = usk;
+
+        CtOption::new(res, is_some)
+    }
+}
+
+impl<const L: usize> Compress for CipherText<L> {
+    const OUTPUT_SIZE: usize = L * SCALAR_BYTES + engine::CipherText::<L>::BYTES + GT_BYTES;
+    type Output = Vec<u8>;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut res = alloc::vec![0u8; Self::OUTPUT_SIZE];
+        let (x, rest) = res.split_at_mut(L * SCALAR_BYTES);
+        let (ct, cprime) = rest.split_at_mut(engine::CipherText::<L>::BYTES);
+
+        for (xi, chunk) in self.x.iter().zip(x.chunks_exact_mut(SCALAR_BYTES)) {
+            chunk.copy_from_slice(&xi.to_bytes());
+        }
+        self.ct.write_bytes(ct);
+        cprime.copy_from_slice(&self.cprime.to_compressed());
+
+        res
+    }
+
+    fn from_bytes(bytes: &Vec<u8>) -> CtOption<Self> {
+        let mut res = CipherText {
+            x: [Scalar::zero(); L],
+            ct: engine::CipherText::default(),
+            cprime: Gt::default(),
+        };
+
+        if bytes.len() != Self::OUTPUT_SIZE {
+            return CtOption::new(res, Choice::from(0));
+        }
+
+        let (x, rest) = bytes.split_at(L * SCALAR_BYTES);
+        let (ct, cprime) = rest.split_at(engine::CipherText::<L>::BYTES);
+
+        let (x, mut is_some) = read_vector(x);
+        let (ct, ct_is_some) = engine::CipherText::read_bytes(ct);
+        is_some &= ct_is_some;
+        is_some &= Gt::from_compressed(cprime.try_into().unwrap())
+            .map(|el| res.cprime = el)
+            .is_some();
+
+        res.x = x;
+        res.ct = ct;
+
+        CtOption::new(res, is_some)
+    }
+}
+
+impl<const L: usize> ConstantTimeEq for CipherText<L> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.x[..].ct_eq(&other.x[..]) & self.ct.ct_eq(&other.ct) & self.cprime.ct_eq(&other.cprime)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::RngCore;
+
+    /// Vectors of length three, i.e., polynomials of degree two.
+    type Scheme = CGWIPE<3, 4>;
+
+    /// The vector `(1, z, z^2)` for the evaluation of a polynomial of degree two in `z`.
+    fn powers(z: u64) -> [Scalar; 3] {
+        let z = Scalar::from(z);
+        [Scalar::one(), z, z.square()]
+    }
+
+    /// The coefficients of `(X - a)(X - b)`.
+    fn roots(a: u64, b: u64) -> [Scalar; 3] {
+        let (a, b) = (Scalar::from(a), Scalar::from(b));
+        [a * b, -(a + b), Scalar::one()]
+    }
+
+    fn encrypt_random(pk: &PublicKey<4>, x: &[Scalar; 3]) -> (CipherText<3>, Msg) {
+        let mut rng = rand::thread_rng();
+        let mut coins = [0u8; 64];
+        rng.fill_bytes(&mut coins);
+
+        let m = rand_gt(&mut rng);
+
+        (Scheme::encrypt(pk, x, &m, &coins), m)
+    }
+
+    #[test]
+    fn eq_encrypt_decrypt_orthogonal() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = Scheme::setup(&mut rng);
+        let usk = Scheme::extract_usk(None, &sk, &roots(2, 3), &mut rng);
+
+        for z in [2, 3] {
+            let (ct, m) = encrypt_random(&pk, &powers(z));
+            assert_eq!(Scheme::decrypt(&usk, &ct), Some(m));
+        }
+    }
+
+    #[test]
+    fn non_orthogonal_does_not_decrypt() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = Scheme::setup(&mut rng);
+        let usk = Scheme::extract_usk(None, &sk, &roots(2, 3), &mut rng);
+        let (mut ct, m) = encrypt_random(&pk, &powers(4));
+
+        assert_eq!(Scheme::decrypt(&usk, &ct), None);
+
+        // Relabelling the ciphertext with an orthogonal vector does not help.
+        ct.x = powers(2);
+        assert_ne!(Scheme::decrypt(&usk, &ct), Some(m));
+    }
+
+    #[test]
+    fn eq_serialize_deserialize() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = Scheme::setup(&mut rng);
+        let usk = Scheme::extract_usk(None, &sk, &roots(2, 3), &mut rng);
+        let (ct, _) = encrypt_random(&pk, &powers(2));
+
+        assert_eq!(pk, PublicKey::from_bytes(&pk.to_bytes()).unwrap());
+        assert_eq!(sk, SecretKey::from_bytes(&sk.to_bytes()).unwrap());
+        assert_eq!(usk, UserSecretKey::from_bytes(&usk.to_bytes()).unwrap());
+        assert_eq!(ct, CipherText::from_bytes(&ct.to_bytes()).unwrap());
+
+        assert_eq!(pk.to_bytes().len(), Scheme::PK_BYTES);
+        assert_eq!(usk.to_bytes().len(), Scheme::USK_BYTES);
+        assert_eq!(ct.to_bytes().len(), Scheme::CT_BYTES);
+    }
+
+    // Regression test for GHSA-25fp-2fjj-g84w.
+    #[test]
+    fn from_bytes_rejects_non_subgroup_point() {
+        let mut rng = rand::thread_rng();
+        let (pk, _) = Scheme::setup(&mut rng);
+        let (ct, _) = encrypt_random(&pk, &powers(2));
+
+        let mut bytes = ct.to_bytes();
+        bytes[3 * SCALAR_BYTES..3 * SCALAR_BYTES + G1_BYTES]
+            .copy_from_slice(&NON_SUBGROUP_G1_COMPRESSED);
+        assert!(bool::from(CipherText::<3>::from_bytes(&bytes).is_none()));
+    }
+}
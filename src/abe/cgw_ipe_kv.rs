@@ -0,0 +1,345 @@
+//! IND-CCA2 secure inner-product predicate KEM by Chen, Gay and Wee.
+//!  * From: "[Improved Dual System ABE in Prime-Order Groups via Predicate Encodings](https://link.springer.com/chapter/10.1007/978-3-540-79263-5_14)"
+//!
+//! CCA security due to a generalized approach.
+//!  * From: "[Efficient and Generic Transformations for Chosen-Ciphertext Secure Predicate Encryption](https://eprint.iacr.org/2022/1436.pdf)"
+//!  * Pp: 41-43, definition 23.
+//!
+//! This is an instance of the [Chen-Gay-Wee engine](crate::pe::cgw) with the
+//! [tagged inner product encoding](TaggedInnerProductEncoding), in the same way as
+//! [`CGWKV`](crate::kem::cgw_kv::CGWKV) is for identities. The tag binds the ciphertext to its first
+//! component and to its vector.
+//!
+//! The vectors are of length `L`. Because const generic expressions are not available, the number
+//! of master key components `N` and the number of ciphertext components `C` have to be given
+//! explicitly and must equal `L + 3` and `L + 1`, respectively, which is checked at compile time.
+//! For example, `CGWIPEKV<3, 6, 4>` can be used to evaluate polynomials of degree two.
+//!
+//! The byte serialisation of all structures depends on `L` and is returned as a vector.
+
+extern crate alloc;
+use alloc::vec::Vec;
+
+use crate::abe::cgw_ipe::read_vector;
+use crate::abe::ABKEM;
+use crate::kem::{Error, SharedSecret};
+use crate::pe::cgw::{self as engine, CGWEngine};
+use crate::pe::encodings::{inner_product, TaggedInnerProductEncoding};
+use crate::pe::PredicateEncoding;
+use crate::util::*;
+use crate::Compress;
+use pg_curve::{G1Affine, Scalar};
+use rand::{CryptoRng, Rng};
+use subtle::{Choice, CtOption};
+
+/// These structs are identical for the CCA KEM, for `N = L + 3` master key components.
+pub use crate::abe::cgw_ipe::{PublicKey, SecretKey};
+
+/// The Chen-Gay-Wee engine instantiated with the tagged inner product predicate encoding.
+type Engine<const L: usize, const N: usize, const C: usize> =
+    CGWEngine<TaggedInnerProductEncoding<L>, N, C, 2>;
+
+/// User secret key. Can be used to decaps ciphertexts for vectors orthogonal to its vector.
+/// Also known as USK_{y}.
+///
+/// # Zeroization
+///
+/// With the `zeroize` feature enabled this type derives `Zeroize` but **not**
+/// `ZeroizeOnDrop` (it is `Copy`). Secret material is **not** cleared on drop —
+/// you **MUST** call `.zeroize()` explicitly once done. See the
+/// [crate-level docs](crate#zeroizing-secret-material).
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize))]
+pub struct UserSecretKey<const L: usize> {
+    y: [Scalar; L],
+    usk: engine::UserSecretKey<2>,
+}
+
+/// Encrypted message. Can only be decapsed with a user secret key for an orthogonal vector.
+/// Also known as CT_{x}.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct CipherText<const L: usize, const C: usize> {
+    x: [Scalar; L],
+    ct: engine::CipherText<C>,
+    k: [u8; 32],
+}
+
+/// The CGW-KV1 inner-product predicate key encapsulation scheme for vectors of length `L`, with
+/// `N = L + 3` master key components and `C = L + 1` ciphertext components.
+#[derive(Debug, Clone, Copy)]
+pub struct CGWIPEKV<const L: usize, const N: usize, const C: usize>;
+
+impl<const L: usize, const N: usize, const C: usize> CGWIPEKV<L, N, C> {
+    /// Size of the compressed master public key in bytes.
+    pub const PK_BYTES: usize = engine::PublicKey::<N>::BYTES;
+
+    /// Size of the compressed master secret key in bytes.
+    pub const SK_BYTES: usize = engine::SecretKey::<N>::BYTES;
+
+    /// Size of the compressed user secret key in bytes.
+    pub const USK_BYTES: usize = L * SCALAR_BYTES + engine::UserSecretKey::<2>::BYTES;
+
+    /// Size of the compressed ciphertext in bytes.
+    pub const CT_BYTES: usize = L * SCALAR_BYTES + engine::CipherText::<C>::BYTES + 32;
+}
+
+/// Derives the tag from the random bytes, the vector and the first ciphertext component.
+fn tag<const L: usize>(k: &[u8; 32], x: &[Scalar; L], c0: &[G1Affine; 2]) -> Scalar {
+    let mut pre_k = Vec::with_capacity(32 + L * SCALAR_BYTES);
+    pre_k.extend_from_slice(k);
+    for xi in x.iter() {
+        pre_k.extend_from_slice(&xi.to_bytes());
+    }
+
+    rpc(&sha3_256(&pre_k), c0)
+}
+
+impl<const L: usize, const N: usize, const C: usize> ABKEM for CGWIPEKV<L, N, C> {
+    const IDENTIFIER: &'static str = "cgwipekv";
+
+    type Pk = PublicKey<N>;
+    type Sk = SecretKey<N>;
+    type Usk = UserSecretKey<L>;
+    type Ct = CipherText<L, C>;
+    type KeyAttrs = [Scalar; L];
+    type CtAttrs = [Scalar; L];
+
+    const PK_BYTES: usize = Self::PK_BYTES;
+    const SK_BYTES: usize = Self::SK_BYTES;
+
+    /// Generate a keypair used by the Private Key Generator (PKG).
+    fn setup<R: Rng + CryptoRng>(rng: &mut R) -> (PublicKey<N>, SecretKey<N>) {
+        let (pk, sk) = Engine::<L, N, C>::setup(rng);
+
+        (PublicKey(pk), SecretKey(sk))
+    }
+
+    /// Extract a user secret key for a vector.
+    fn extract_usk<R: Rng + CryptoRng>(
+        _pk: Option<&PublicKey<N>>,
+        sk: &SecretKey<N>,
+        y: &[Scalar; L],
+        rng: &mut R,
+    ) -> UserSecretKey<L> {
+        UserSecretKey {
+            y: *y,
+            usk: Engine::<L, N, C>::extract_usk(&sk.0, y, rng),
+        }
+    }
+
+    fn encaps<R: Rng + CryptoRng>(
+        pk: &PublicKey<N>,
+        x: &[Scalar; L],
+        rng: &mut R,
+    ) -> (CipherText<L, C>, SharedSecret) {
+        let s = rand_scalar(rng);
+        let c0 = Engine::<L, N, C>::encrypt_c0(&pk.0, &s);
+
+        let mut smallk = [0u8; 32];
+        rng.fill_bytes(&mut smallk);
+
+        let xprime = tag(&smallk, x, &c0);
+        let c = Engine::<L, N, C>::encrypt_c(&pk.0, &(*x, xprime), &s);
+
+        let k = Engine::<L, N, C>::blinding(&pk.0, &s);
+
+        (
+            CipherText {
+                x: *x,
+                ct: engine::CipherText { c0, c },
+                k: smallk,
+            },
+            SharedSecret::from(&k),
+        )
+    }
+
+    /// Derive the same SharedSecret from the CipherText using a UserSecretKey.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an [`Error`] when the vectors of the user secret key and the
+    /// ciphertext are not orthogonal. Otherwise, ciphertexts are implicitly rejected.
+    fn decaps(
+        _pk: Option<&PublicKey<N>>,
+        usk: &UserSecretKey<L>,
+        ct: &CipherText<L, C>,
+    ) -> Result<SharedSecret, Error> {
+        if inner_product(&ct.x, &usk.y) != Scalar::zero() {
+            return Err(Error);
+        }
+
+        let x = (ct.x, tag(&ct.k, &ct.x, &ct.ct.c0));
+        let s_d = <TaggedInnerProductEncoding<L> as PredicateEncoding<N, C, 2>>::s_d(&x, &usk.y);
+        let r_d = <TaggedInnerProductEncoding<L> as PredicateEncoding<N, C, 2>>::r_d(&x, &usk.y);
+
+        let m = Engine::<L, N, C>::decrypt(&usk.usk, &ct.ct, &s_d, &r_d);
+
+        Ok(SharedSecret::from(&m))
+    }
+}
+
+impl<const L: usize> UserSecretKey<L> {
+    /// The vector this user secret key was extracted for.
+    pub fn vector(&self) -> &[Scalar; L] {
+        &self.y
+    }
+}
+
+impl<const L: usize, const C: usize> CipherText<L, C> {
+    /// The vector this ciphertext was encapsulated for.
+    pub fn vector(&self) -> &[Scalar; L] {
+        &self.x
+    }
+}
+
+impl<const L: usize> Compress for UserSecretKey<L> {
+    const OUTPUT_SIZE: usize = L * SCALAR_BYTES + engine::UserSecretKey::<2>::BYTES;
+    type Output = Vec<u8>;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut res = alloc::vec![0u8; Self::OUTPUT_SIZE];
+        let (y, usk) = res.split_at_mut(L * SCALAR_BYTES);
+
+        for (yi, chunk) in self.y.iter().zip(y.chunks_exact_mut(SCALAR_BYTES)) {
+            chunk.copy_from_slice(&yi.to_bytes());
+        }
+        self.usk.write_bytes(usk);
+
+        res
+    }
+
+    fn from_bytes(bytes: &Vec<u8>) -> CtOption<Self> {
+        let mut res = UserSecretKey {
+            y: [Scalar::zero(); L],
+            usk: engine::UserSecretKey::default(),
+        };
+
+        if bytes.len() != Self::OUTPUT_SIZE {
+            return CtOption::new(res, Choice::from(0));
+        }
+
+        let (y, usk) = bytes.split_at(L * SCALAR_BYTES);
+        let (y, mut is_some) = read_vector(y);
+        let (usk, usk_is_some) = engine::UserSecretKey::read_bytes(usk);
+        is_some &= usk_is_some;
+
+        res.y = y;
+        res.usk = usk;
+
+        CtOption::new(res, is_some)
+    }
+}
+
+impl<const L: usize, const C: usize> Compress for CipherText<L, C> {
+    const OUTPUT_SIZE: usize = L * SCALAR_BYTES + engine::CipherText::<C>::BYTES + 32;
+    type Output = Vec<u8>;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut res = alloc::vec![0u8; Self::OUTPUT_SIZE];
+        let (x, rest) = res.split_at_mut(L * SCALAR_BYTES);
+        let (ct, k) = rest.split_at_mut(engine::CipherText::<C>::BYTES);
+
+        for (xi, chunk) in self.x.iter().zip(x.chunks_exact_mut(SCALAR_BYTES)) {
+            chunk.copy_from_slice(&xi.to_bytes());
+        }
+        self.ct.write_bytes(ct);
+        k.copy_from_slice(&self.k);
+
+        res
+    }
+
+    fn from_bytes(bytes: &Vec<u8>) -> CtOption<Self> {
+        let mut res = CipherText {
+            x: [Scalar::zero(); L],
+            ct: engine::CipherText::default(),
+            k: [0u8; 32],
+        };
+
+        if bytes.len() != Self::OUTPUT_SIZE {
+            return CtOption::new(res, Choice::from(0));
+        }
+
+        let (x, rest) = bytes.split_at(L * SCALAR_BYTES);
+        let (ct, k) = rest.split_at(engine::CipherText::<C>::BYTES);
+
+        let (x, mut is_some) = read_vector(x);
+        let (ct, ct_is_some) = engine::CipherText::read_bytes(ct);
+        is_some &= ct_is_some;
+
+        res.x = x;
+        res.ct = ct;
+        res.k.copy_from_slice(k);
+
+        CtOption::new(res, is_some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Vectors of length three, i.e., polynomials of degree two.
+    type Scheme = CGWIPEKV<3, 6, 4>;
+
+    /// The vector `(1, z, z^2)` for the evaluation of a polynomial of degree two in `z`.
+    fn powers(z: u64) -> [Scalar; 3] {
+        let z = Scalar::from(z);
+        [Scalar::one(), z, z.square()]
+    }
+
+    /// The coefficients of `(X - a)(X - b)`.
+    fn roots(a: u64, b: u64) -> [Scalar; 3] {
+        let (a, b) = (Scalar::from(a), Scalar::from(b));
+        [a * b, -(a + b), Scalar::one()]
+    }
+
+    #[test]
+    fn eq_encaps_decaps() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = Scheme::setup(&mut rng);
+        let usk = Scheme::extract_usk(None, &sk, &roots(2, 3), &mut rng);
+
+        for z in [2, 3] {
+            let (ct, k) = Scheme::encaps(&pk, &powers(z), &mut rng);
+            assert_eq!(k, Scheme::decaps(None, &usk, &ct).unwrap());
+        }
+    }
+
+    #[test]
+    fn decaps_rejects_non_orthogonal() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = Scheme::setup(&mut rng);
+        let usk = Scheme::extract_usk(None, &sk, &roots(2, 3), &mut rng);
+        let (ct, _) = Scheme::encaps(&pk, &powers(4), &mut rng);
+
+        assert!(Scheme::decaps(None, &usk, &ct).is_err());
+    }
+
+    #[test]
+    fn decaps_rejects_relabelled_ciphertext() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = Scheme::setup(&mut rng);
+        let usk = Scheme::extract_usk(None, &sk, &roots(2, 3), &mut rng);
+        let (mut ct, k) = Scheme::encaps(&pk, &powers(2), &mut rng);
+
+        // Relabelling with another orthogonal vector changes the tag.
+        ct.x = powers(3);
+        assert_ne!(k, Scheme::decaps(None, &usk, &ct).unwrap());
+    }
+
+    #[test]
+    fn eq_serialize_deserialize() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = Scheme::setup(&mut rng);
+        let usk = Scheme::extract_usk(None, &sk, &roots(2, 3), &mut rng);
+        let (ct, _) = Scheme::encaps(&pk, &powers(2), &mut rng);
+
+        assert_eq!(pk, PublicKey::from_bytes(&pk.to_bytes()).unwrap());
+        assert_eq!(sk, SecretKey::from_bytes(&sk.to_bytes()).unwrap());
+        assert_eq!(usk, UserSecretKey::from_bytes(&usk.to_bytes()).unwrap());
+        assert_eq!(ct, CipherText::from_bytes(&ct.to_bytes()).unwrap());
+
+        assert_eq!(usk.to_bytes().len(), Scheme::USK_BYTES);
+        assert_eq!(ct.to_bytes().len(), Scheme::CT_BYTES);
+    }
+}
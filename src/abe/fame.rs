@@ -0,0 +1,729 @@
+//! IND-CPA secure ciphertext-policy ABE by Agrawal and Chase (FAME).
+//! * From: "[FAME: Fast Attribute-based Message Encryption](https://eprint.iacr.org/2017/807.pdf)"
+//!
+//! Adaptively secure under the DLIN assumption in the random oracle model. Supports arbitrary
+//! monotone boolean policies, in which attributes may occur more than once. Attributes are hashed
+//! to G1 using RFC 9380 hash-to-curve.
+//!
+//! User secret keys grow linearly in the number of attributes and ciphertexts grow linearly in
+//! the size of the policy. Decryption takes six pairings, regardless of the policy.
+//! All structs' byte serialization use compression.
+
+extern crate alloc;
+use alloc::vec::Vec;
+
+use crate::abe::policy::Policy;
+use crate::abe::{Attribute, ABE};
+use crate::util::*;
+use crate::Compress;
+use pg_curve::{
+    multi_miller_loop, pairing, G1Affine, G1Projective, G2Affine, G2Prepared, G2Projective, Gt,
+    Scalar,
+};
+use rand::{CryptoRng, Rng};
+use subtle::{Choice, ConstantTimeEq, CtOption};
+
+/// Domain separation tag for hashing attributes and matrix columns to G1.
+const DST: &[u8] = b"IBE_FAME_BLS12381G1_XMD:SHA-256_SSWU_RO_";
+
+/// Size of the compressed message in bytes.
+pub const MSG_BYTES: usize = GT_BYTES;
+
+/// Size of the compressed master public key in bytes.
+pub const PK_BYTES: usize = 2 * G2_BYTES + 2 * GT_BYTES;
+
+/// Size of the compressed master secret key in bytes.
+pub const SK_BYTES: usize = 7 * SCALAR_BYTES;
+
+/// Size of the compressed user secret key in bytes, without any attributes.
+pub const USK_BASE_BYTES: usize = 3 * G2_BYTES + 3 * G1_BYTES + 4;
+
+/// Size of the compressed user secret key component for a single attribute in bytes.
+pub const USK_ATTR_BYTES: usize = ID_BYTES + 3 * G1_BYTES;
+
+/// Size of the compressed ciphertext in bytes, without the policy and its rows.
+pub const CT_BASE_BYTES: usize = 3 * G2_BYTES + GT_BYTES;
+
+/// Size of the compressed ciphertext component for a single row of the policy in bytes.
+pub const CT_ROW_BYTES: usize = 3 * G1_BYTES;
+
+/// Public key parameters generated by the PKG used to encrypt messages.
+/// Also known as MPK.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PublicKey {
+    h_a: [G2Affine; 2],
+    t: [Gt; 2],
+}
+
+/// Secret key parameter generated by the PKG used to extract user secret keys.
+/// Also known as MSK.
+///
+/// # Zeroization
+///
+/// With the `zeroize` feature enabled this type derives `Zeroize` but **not**
+/// `ZeroizeOnDrop` (it is `Copy`). Secret material is **not** cleared on drop —
+/// you **MUST** call `.zeroize()` explicitly once done. See the
+/// [crate-level docs](crate#zeroizing-secret-material).
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize))]
+pub struct SecretKey {
+    a: [Scalar; 2],
+    b: [Scalar; 2],
+    d: [Scalar; 3],
+}
+
+/// Key component for a single attribute.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize))]
+struct AttributeKey {
+    attr: Attribute,
+    k: [G1Affine; 3],
+}
+
+/// User secret key. Can be used to decrypt ciphertexts whose policy is satisfied by its
+/// attributes.
+/// Also known as USK_{S}.
+///
+/// # Zeroization
+///
+/// With the `zeroize` feature enabled this type derives `Zeroize` and `ZeroizeOnDrop`.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize, zeroize::ZeroizeOnDrop))]
+pub struct UserSecretKey {
+    k0: [G2Affine; 3],
+    kp: [G1Affine; 3],
+    ks: Vec<AttributeKey>,
+}
+
+/// Encrypted message. Can only be decrypted with a user secret key whose attributes satisfy the
+/// policy.
+/// Also known as CT_{policy}.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CipherText {
+    policy: Policy,
+    c0: [G2Affine; 3],
+    c: Vec<[G1Affine; 3]>,
+    cprime: Gt,
+}
+
+/// A message that can be encrypted using the ABE.
+pub type Msg = Gt;
+
+/// The FAME ciphertext-policy attribute-based encryption scheme.
+#[derive(Debug, Clone, Copy)]
+pub struct FAME;
+
+/// Hashes an attribute to G1, for the key component `l` and the DLIN component `t`.
+fn hash_attr(attr: &Attribute, l: u8, t: u8) -> G1Projective {
+    let mut buf = [0u8; 3 + ID_BYTES];
+    buf[..3].copy_from_slice(&[1, l, t]);
+    buf[3..].copy_from_slice(&attr.0);
+
+    hash_to_g1(&buf, DST)
+}
+
+/// Hashes a column of the access matrix to G1, for the key component `l` and the DLIN component
+/// `t`.
+fn hash_col(j: usize, l: u8, t: u8) -> G1Projective {
+    let mut buf = [0u8; 7];
+    buf[..3].copy_from_slice(&[0, l, t]);
+    buf[3..].copy_from_slice(&(j as u32).to_be_bytes());
+
+    hash_to_g1(&buf, DST)
+}
+
+/// Computes `sum_l (Br_l / a_t) · H_l + (sigma / a_t) · g` for both DLIN components `t`, and
+/// `-sigma · g`, where `H_l` is the hash for key component `l` and DLIN component `t`.
+fn key_component<F: Fn(u8, u8) -> G1Projective>(
+    br: &[Scalar; 3],
+    a_inv: &[Scalar; 2],
+    sigma: &Scalar,
+    hash: F,
+) -> [G1Projective; 3] {
+    let g = G1Projective::generator();
+
+    let mut res = [G1Projective::identity(); 3];
+    for t in 0..2 {
+        res[t] = (0..3u8).fold(g * (sigma * a_inv[t]), |acc, l| {
+            acc + hash(l, t as u8) * (br[l as usize] * a_inv[t])
+        });
+    }
+    res[2] = g * (-sigma);
+
+    res
+}
+
+impl ABE for FAME {
+    type Pk = PublicKey;
+    type Sk = SecretKey;
+    type Usk = UserSecretKey;
+    type Ct = CipherText;
+    type Msg = Msg;
+    type KeyAttrs = [Attribute];
+    type CtAttrs = Policy;
+    type RngBytes = [u8; 64];
+
+    const PK_BYTES: usize = PK_BYTES;
+    const SK_BYTES: usize = SK_BYTES;
+    const MSG_BYTES: usize = MSG_BYTES;
+
+    /// Generate a keypair used by the Private Key Generator (PKG).
+    fn setup<R: Rng + CryptoRng>(rng: &mut R) -> (PublicKey, SecretKey) {
+        let g = G1Affine::generator();
+        let h = G2Affine::generator();
+
+        let a = [rand_scalar(rng), rand_scalar(rng)];
+        let b = [rand_scalar(rng), rand_scalar(rng)];
+        let d = [rand_scalar(rng), rand_scalar(rng), rand_scalar(rng)];
+
+        let mut h_a = [G2Affine::default(); 2];
+        G2Projective::batch_normalize(&[h * a[0], h * a[1]], &mut h_a);
+
+        let e_gh = pairing(&g, &h);
+        let t = [e_gh * (d[0] * a[0] + d[2]), e_gh * (d[1] * a[1] + d[2])];
+
+        (PublicKey { h_a, t }, SecretKey { a, b, d })
+    }
+
+    /// Extract a user secret key for a set of attributes.
+    fn extract_usk<R: Rng + CryptoRng>(
+        _pk: Option<&PublicKey>,
+        sk: &SecretKey,
+        attrs: &[Attribute],
+        rng: &mut R,
+    ) -> UserSecretKey {
+        let g = G1Projective::generator();
+        let h = G2Projective::generator();
+
+        let r = [rand_scalar(rng), rand_scalar(rng)];
+        let br = [sk.b[0] * r[0], sk.b[1] * r[1], r[0] + r[1]];
+
+        // The a_t are non-zero with overwhelming probability.
+        let a_inv = [sk.a[0].invert().unwrap(), sk.a[1].invert().unwrap()];
+
+        let mut k0 = [G2Affine::default(); 3];
+        G2Projective::batch_normalize(&[h * br[0], h * br[1], h * br[2]], &mut k0);
+
+        let mut kp = key_component(&br, &a_inv, &rand_scalar(rng), |l, t| hash_col(0, l, t));
+        for (kpt, dt) in kp.iter_mut().zip(sk.d.iter()) {
+            *kpt += g * dt;
+        }
+
+        let mut kp_affine = [G1Affine::default(); 3];
+        G1Projective::batch_normalize(&kp, &mut kp_affine);
+
+        let ks = attrs
+            .iter()
+            .map(|attr| {
+                let k = key_component(&br, &a_inv, &rand_scalar(rng), |l, t| hash_attr(attr, l, t));
+
+                let mut res = AttributeKey {
+                    attr: *attr,
+                    k: [G1Affine::default(); 3],
+                };
+                G1Projective::batch_normalize(&k, &mut res.k);
+
+                res
+            })
+            .collect();
+
+        UserSecretKey {
+            k0,
+            kp: kp_affine,
+            ks,
+        }
+    }
+
+    /// Encrypt a message using the PKG public key under a policy.
+    fn encrypt(pk: &PublicKey, policy: &Policy, message: &Msg, rng: &[u8; 64]) -> CipherText {
+        let s = [
+            Scalar::from_bytes_wide(rng),
+            Scalar::from_bytes_wide(&sha3_512(rng)),
+        ];
+
+        let mut c0 = [G2Affine::default(); 3];
+        G2Projective::batch_normalize(
+            &[
+                pk.h_a[0] * s[0],
+                pk.h_a[1] * s[1],
+                G2Affine::generator() * (s[0] + s[1]),
+            ],
+            &mut c0,
+        );
+
+        let lsss = policy.to_lsss();
+
+        let cols: Vec<[[G1Projective; 2]; 3]> = (0..lsss.cols)
+            .map(|j| {
+                let mut col = [[G1Projective::identity(); 2]; 3];
+                for (l, hl) in col.iter_mut().enumerate() {
+                    for (t, hlt) in hl.iter_mut().enumerate() {
+                        *hlt = hash_col(j, l as u8, t as u8);
+                    }
+                }
+
+                col
+            })
+            .collect();
+
+        let rows: Vec<G1Projective> = lsss
+            .rows
+            .iter()
+            .flat_map(|(attr, m)| {
+                let cols = &cols;
+                (0..3).map(move |l| {
+                    (0..2).fold(G1Projective::identity(), |acc, t| {
+                        let h = m.iter().zip(cols.iter()).fold(
+                            hash_attr(attr, l as u8, t as u8),
+                            |h, (mj, col)| match mj {
+                                1 => h + col[l][t],
+                                -1 => h - col[l][t],
+                                _ => h,
+                            },
+                        );
+
+                        acc + h * s[t]
+                    })
+                })
+            })
+            .collect();
+
+        let mut rows_affine = alloc::vec![G1Affine::default(); rows.len()];
+        G1Projective::batch_normalize(&rows, &mut rows_affine);
+
+        let c = rows_affine
+            .chunks_exact(3)
+            .map(|ci| ci.try_into().unwrap())
+            .collect();
+
+        let cprime = pk.t[0] * s[0] + pk.t[1] * s[1] + message;
+
+        CipherText {
+            policy: policy.clone(),
+            c0,
+            c,
+            cprime,
+        }
+    }
+
+    /// Derive the same message from the CipherText using a UserSecretKey.
+    ///
+    /// Returns `None` if the attributes of the user secret key do not satisfy the policy.
+    fn decrypt(usk: &UserSecretKey, ct: &CipherText) -> Option<Msg> {
+        let attrs: Vec<Attribute> = usk.ks.iter().map(|k| k.attr).collect();
+        let rows = ct.policy.satisfying_rows(&attrs)?;
+
+        let mut num = [G1Projective::identity(); 3];
+        let mut den = usk.kp.map(G1Projective::from);
+
+        for (i, attr) in rows {
+            let k = usk.ks.iter().find(|k| k.attr == attr)?;
+
+            for l in 0..3 {
+                num[l] += ct.c.get(i)?[l];
+                den[l] += k.k[l];
+            }
+        }
+
+        let mut num_affine = [G1Affine::default(); 3];
+        let mut den_affine = [G1Affine::default(); 3];
+        G1Projective::batch_normalize(&num, &mut num_affine);
+        G1Projective::batch_normalize(&den.map(|d| -d), &mut den_affine);
+
+        let k0 = usk.k0.map(G2Prepared::from);
+        let c0 = ct.c0.map(G2Prepared::from);
+
+        let m = multi_miller_loop(&[
+            (&num_affine[0], &k0[0]),
+            (&num_affine[1], &k0[1]),
+            (&num_affine[2], &k0[2]),
+            (&den_affine[0], &c0[0]),
+            (&den_affine[1], &c0[1]),
+            (&den_affine[2], &c0[2]),
+        ])
+        .final_exponentiation();
+
+        Some(ct.cprime + m)
+    }
+}
+
+impl UserSecretKey {
+    /// The attributes this user secret key was extracted for.
+    pub fn attributes(&self) -> impl Iterator<Item = &Attribute> {
+        self.ks.iter().map(|k| &k.attr)
+    }
+}
+
+impl CipherText {
+    /// The policy this ciphertext was encrypted under.
+    pub fn policy(&self) -> &Policy {
+        &self.policy
+    }
+}
+
+impl ConstantTimeEq for CipherText {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        // The policies are public and determine the number of rows.
+        if self.policy != other.policy || self.c.len() != other.c.len() {
+            return Choice::from(0);
+        }
+
+        let rows = self
+            .c
+            .iter()
+            .flatten()
+            .zip(other.c.iter().flatten())
+            .fold(Choice::from(1), |acc, (a, b)| acc & a.ct_eq(b));
+
+        rows & self.c0.ct_eq(&other.c0) & self.cprime.ct_eq(&other.cprime)
+    }
+}
+
+impl Compress for PublicKey {
+    const OUTPUT_SIZE: usize = PK_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; PK_BYTES] {
+        let mut res = [0u8; PK_BYTES];
+        let (g2s, gts) = res.split_at_mut(2 * G2_BYTES);
+
+        for (chunk, el) in g2s.chunks_exact_mut(G2_BYTES).zip(self.h_a.iter()) {
+            chunk.copy_from_slice(&el.to_compressed());
+        }
+        for (chunk, el) in gts.chunks_exact_mut(GT_BYTES).zip(self.t.iter()) {
+            chunk.copy_from_slice(&el.to_compressed());
+        }
+
+        res
+    }
+
+    fn from_bytes(bytes: &[u8; PK_BYTES]) -> CtOption<Self> {
+        let mut res = PublicKey {
+            h_a: [G2Affine::default(); 2],
+            t: [Gt::default(); 2],
+        };
+        let (g2s, gts) = bytes.split_at(2 * G2_BYTES);
+
+        // Use the checked `from_compressed` variant, which verifies that each point is in the
+        // correct prime-order subgroup (GHSA-25fp-2fjj-g84w).
+        let mut is_some = Choice::from(1u8);
+        for (slot, chunk) in res.h_a.iter_mut().zip(g2s.chunks_exact(G2_BYTES)) {
+            is_some &= G2Affine::from_compressed(chunk.try_into().unwrap())
+                .map(|el| *slot = el)
+                .is_some();
+        }
+        for (slot, chunk) in res.t.iter_mut().zip(gts.chunks_exact(GT_BYTES)) {
+            is_some &= Gt::from_compressed(chunk.try_into().unwrap())
+                .map(|el| *slot = el)
+                .is_some();
+        }
+
+        CtOption::new(res, is_some)
+    }
+}
+
+impl Compress for SecretKey {
+    const OUTPUT_SIZE: usize = SK_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; SK_BYTES] {
+        let mut res = [0u8; SK_BYTES];
+        let scalars = self.a.iter().chain(self.b.iter()).chain(self.d.iter());
+
+        for (chunk, s) in res.chunks_exact_mut(SCALAR_BYTES).zip(scalars) {
+            chunk.copy_from_slice(&s.to_bytes());
+        }
+
+        res
+    }
+
+    fn from_bytes(bytes: &[u8; SK_BYTES]) -> CtOption<Self> {
+        let mut res = SecretKey {
+            a: [Scalar::zero(); 2],
+            b: [Scalar::zero(); 2],
+            d: [Scalar::zero(); 3],
+        };
+
+        let mut is_some = Choice::from(1u8);
+        let slots = res
+            .a
+            .iter_mut()
+            .chain(res.b.iter_mut())
+            .chain(res.d.iter_mut());
+        for (slot, chunk) in slots.zip(bytes.chunks_exact(SCALAR_BYTES)) {
+            is_some &= Scalar::from_bytes(chunk.try_into().unwrap())
+                .map(|s| *slot = s)
+                .is_some();
+        }
+
+        CtOption::new(res, is_some)
+    }
+}
+
+impl Compress for UserSecretKey {
+    /// The size of a user secret key depends on the number of attributes, this is the size of a
+    /// user secret key without attributes.
+    const OUTPUT_SIZE: usize = USK_BASE_BYTES;
+    type Output = Vec<u8>;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut res = Vec::with_capacity(USK_BASE_BYTES + self.ks.len() * USK_ATTR_BYTES);
+
+        for el in self.k0.iter() {
+            res.extend_from_slice(&el.to_compressed());
+        }
+        for el in self.kp.iter() {
+            res.extend_from_slice(&el.to_compressed());
+        }
+
+        res.extend_from_slice(&(self.ks.len() as u32).to_be_bytes());
+        for k in self.ks.iter() {
+            res.extend_from_slice(&k.attr.0);
+            for el in k.k.iter() {
+                res.extend_from_slice(&el.to_compressed());
+            }
+        }
+
+        res
+    }
+
+    fn from_bytes(bytes: &Vec<u8>) -> CtOption<Self> {
+        let mut res = UserSecretKey::default();
+
+        let n = match bytes.get(USK_BASE_BYTES - 4..USK_BASE_BYTES) {
+            Some(n) => u32::from_be_bytes(n.try_into().unwrap()) as usize,
+            None => return CtOption::new(res, Choice::from(0)),
+        };
+
+        let len = n
+            .checked_mul(USK_ATTR_BYTES)
+            .and_then(|len| len.checked_add(USK_BASE_BYTES));
+        if len != Some(bytes.len()) {
+            return CtOption::new(res, Choice::from(0));
+        }
+
+        let (g2s, rest) = bytes.split_at(3 * G2_BYTES);
+        let (g1s, rest) = rest.split_at(3 * G1_BYTES);
+
+        let mut is_some = Choice::from(1u8);
+        for (slot, chunk) in res.k0.iter_mut().zip(g2s.chunks_exact(G2_BYTES)) {
+            is_some &= G2Affine::from_compressed(chunk.try_into().unwrap())
+                .map(|el| *slot = el)
+                .is_some();
+        }
+        for (slot, chunk) in res.kp.iter_mut().zip(g1s.chunks_exact(G1_BYTES)) {
+            is_some &= G1Affine::from_compressed(chunk.try_into().unwrap())
+                .map(|el| *slot = el)
+                .is_some();
+        }
+
+        for chunk in rest[4..].chunks_exact(USK_ATTR_BYTES) {
+            let (attr, g1s) = chunk.split_at(ID_BYTES);
+
+            let mut k = AttributeKey {
+                attr: Attribute(attr.try_into().unwrap()),
+                k: [G1Affine::default(); 3],
+            };
+            for (slot, chunk) in k.k.iter_mut().zip(g1s.chunks_exact(G1_BYTES)) {
+                is_some &= G1Affine::from_compressed(chunk.try_into().unwrap())
+                    .map(|el| *slot = el)
+                    .is_some();
+            }
+
+            res.ks.push(k);
+        }
+
+        CtOption::new(res, is_some)
+    }
+}
+
+impl Compress for CipherText {
+    /// The size of a ciphertext depends on the policy, this is the size of a ciphertext without
+    /// the policy and its rows.
+    const OUTPUT_SIZE: usize = CT_BASE_BYTES;
+    type Output = Vec<u8>;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut res = Vec::with_capacity(
+            CT_BASE_BYTES + self.policy.bytes_len() + self.c.len() * CT_ROW_BYTES,
+        );
+
+        for el in self.c0.iter() {
+            res.extend_from_slice(&el.to_compressed());
+        }
+        res.extend_from_slice(&self.cprime.to_compressed());
+
+        self.policy.write_bytes(&mut res);
+        for el in self.c.iter().flatten() {
+            res.extend_from_slice(&el.to_compressed());
+        }
+
+        res
+    }
+
+    fn from_bytes(bytes: &Vec<u8>) -> CtOption<Self> {
+        let mut res = CipherText {
+            policy: Policy::Or(Vec::new()),
+            c0: [G2Affine::default(); 3],
+            c: Vec::new(),
+            cprime: Gt::default(),
+        };
+
+        if bytes.len() < CT_BASE_BYTES {
+            return CtOption::new(res, Choice::from(0));
+        }
+
+        let (g2s, rest) = bytes.split_at(3 * G2_BYTES);
+        let (gt, rest) = rest.split_at(GT_BYTES);
+
+        let rows = match Policy::read_bytes(rest) {
+            Some((policy, rows)) if rows.len() == policy.len() * CT_ROW_BYTES => {
+                res.policy = policy;
+                rows
+            }
+            _ => return CtOption::new(res, Choice::from(0)),
+        };
+
+        let mut is_some = Gt::from_compressed(gt.try_into().unwrap())
+            .map(|el| res.cprime = el)
+            .is_some();
+
+        for (slot, chunk) in res.c0.iter_mut().zip(g2s.chunks_exact(G2_BYTES)) {
+            is_some &= G2Affine::from_compressed(chunk.try_into().unwrap())
+                .map(|el| *slot = el)
+                .is_some();
+        }
+
+        for chunk in rows.chunks_exact(CT_ROW_BYTES) {
+            let mut row = [G1Affine::default(); 3];
+            for (slot, chunk) in row.iter_mut().zip(chunk.chunks_exact(G1_BYTES)) {
+                is_some &= G1Affine::from_compressed(chunk.try_into().unwrap())
+                    .map(|el| *slot = el)
+                    .is_some();
+            }
+
+            res.c.push(row);
+        }
+
+        CtOption::new(res, is_some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Derive;
+    use rand::RngCore;
+
+    const POLICY: &str = "email:alice@example.com AND (phone:0612345678 OR \"name:Alice Example\")";
+
+    fn attrs(names: &[&str]) -> Vec<Attribute> {
+        names.iter().map(|n| Attribute::derive_str(n)).collect()
+    }
+
+    fn encrypt_random(pk: &PublicKey, policy: &Policy) -> (CipherText, Msg) {
+        let mut rng = rand::thread_rng();
+        let mut coins = [0u8; 64];
+        rng.fill_bytes(&mut coins);
+
+        let m = rand_gt(&mut rng);
+
+        (FAME::encrypt(pk, policy, &m, &coins), m)
+    }
+
+    #[test]
+    fn eq_encrypt_decrypt() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = FAME::setup(&mut rng);
+        let policy = Policy::parse(POLICY).unwrap();
+
+        let (ct, m) = encrypt_random(&pk, &policy);
+
+        for set in [
+            &["email:alice@example.com", "phone:0612345678"][..],
+            &["name:Alice Example", "email:alice@example.com", "age:42"],
+        ] {
+            let usk = FAME::extract_usk(None, &sk, &attrs(set), &mut rng);
+            assert_eq!(FAME::decrypt(&usk, &ct), Some(m));
+        }
+    }
+
+    #[test]
+    fn eq_encrypt_decrypt_repeated_attribute() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = FAME::setup(&mut rng);
+        let policy = Policy::parse("(a AND b) OR (a AND c) OR (b AND c)").unwrap();
+
+        let (ct, m) = encrypt_random(&pk, &policy);
+
+        let usk = FAME::extract_usk(None, &sk, &attrs(&["b", "c"]), &mut rng);
+        assert_eq!(FAME::decrypt(&usk, &ct), Some(m));
+    }
+
+    #[test]
+    fn unsatisfied_policy_does_not_decrypt() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = FAME::setup(&mut rng);
+        let policy = Policy::parse(POLICY).unwrap();
+
+        let (ct, _) = encrypt_random(&pk, &policy);
+
+        let usk = FAME::extract_usk(None, &sk, &attrs(&["phone:0612345678"]), &mut rng);
+        assert_eq!(FAME::decrypt(&usk, &ct), None);
+    }
+
+    #[test]
+    fn colluding_keys_do_not_decrypt() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = FAME::setup(&mut rng);
+        let policy = Policy::parse("a AND b").unwrap();
+
+        let (ct, m) = encrypt_random(&pk, &policy);
+
+        // Combine the attribute keys of two users into a single key.
+        let usk_a = FAME::extract_usk(None, &sk, &attrs(&["a"]), &mut rng);
+        let mut usk_b = FAME::extract_usk(None, &sk, &attrs(&["b"]), &mut rng);
+        usk_b.ks.push(usk_a.ks[0].clone());
+
+        assert_ne!(FAME::decrypt(&usk_b, &ct), Some(m));
+    }
+
+    #[test]
+    fn eq_serialize_deserialize() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = FAME::setup(&mut rng);
+        let policy = Policy::parse(POLICY).unwrap();
+        let usk = FAME::extract_usk(
+            None,
+            &sk,
+            &attrs(&["email:alice@example.com", "phone:0612345678"]),
+            &mut rng,
+        );
+        let (ct, _) = encrypt_random(&pk, &policy);
+
+        assert_eq!(pk, PublicKey::from_bytes(&pk.to_bytes()).unwrap());
+        assert_eq!(sk, SecretKey::from_bytes(&sk.to_bytes()).unwrap());
+        assert_eq!(usk, UserSecretKey::from_bytes(&usk.to_bytes()).unwrap());
+        assert_eq!(ct, CipherText::from_bytes(&ct.to_bytes()).unwrap());
+
+        let mut bytes = ct.to_bytes();
+        bytes.pop();
+        assert!(bool::from(CipherText::from_bytes(&bytes).is_none()));
+
+        let mut bytes = usk.to_bytes();
+        bytes.pop();
+        assert!(bool::from(UserSecretKey::from_bytes(&bytes).is_none()));
+    }
+
+    #[test]
+    fn from_bytes_rejects_non_subgroup_point() {
+        let mut rng = rand::thread_rng();
+        let (pk, _) = FAME::setup(&mut rng);
+        let (ct, _) = encrypt_random(&pk, &Policy::attr("a"));
+
+        let mut bytes = ct.to_bytes();
+        let row = bytes.len() - CT_ROW_BYTES;
+        bytes[row..row + G1_BYTES].copy_from_slice(&NON_SUBGROUP_G1_COMPRESSED);
+        assert!(bool::from(CipherText::from_bytes(&bytes).is_none()));
+    }
+}
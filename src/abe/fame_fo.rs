@@ -0,0 +1,170 @@
+//! IND-CCA2 secure ciphertext-policy ABKEM from FAME by Agrawal and Chase.
+//! * From: "[FAME: Fast Attribute-based Message Encryption](https://eprint.iacr.org/2017/807.pdf)"
+//!
+//! CCA security due to a general approach by Fujisaki and Okamoto.
+//! * From: "[A Modular Analysis of the Fujisaki-Okamoto Transformation](https://eprint.iacr.org/2017/604.pdf)"
+//!
+//! Symmetric primitive G instantiated using sha3_512. The coins are bound to the policy, which is
+//! part of the ciphertext. The shared secret is derived from the message in the same way as for
+//! the identity-based KEMs.
+//!
+//! A drawback of a Fujisaki-Okamoto transform is that we now need the public key to decapsulate.
+
+extern crate alloc;
+use alloc::vec::Vec;
+
+use crate::abe::fame::{Msg, FAME};
+use crate::abe::policy::Policy;
+use crate::abe::{Attribute, ABE, ABKEM};
+use crate::kem::{Error, SharedSecret};
+use crate::util::*;
+use crate::Compress;
+use group::Group;
+use rand::{CryptoRng, Rng};
+use subtle::ConstantTimeEq;
+
+/// These struct are identical for the CCA KEM.
+pub use crate::abe::fame::{
+    CipherText, PublicKey, SecretKey, UserSecretKey, CT_BASE_BYTES, CT_ROW_BYTES, PK_BYTES,
+    SK_BYTES, USK_ATTR_BYTES, USK_BASE_BYTES,
+};
+
+/// The CCA2 secure KEM that results by applying the implicit rejection
+/// variant of the Fujisaki-Okamoto transform to the FAME CP-ABE scheme.
+#[derive(Debug, Clone, Copy)]
+pub struct FAMEFO;
+
+/// Derives the encryption coins from the message and the policy.
+fn coins(m: &Msg, policy: &Policy) -> [u8; 64] {
+    let mut pre_coins = Vec::with_capacity(GT_BYTES + policy.bytes_len());
+    pre_coins.extend_from_slice(&m.to_bytes());
+    policy.write_bytes(&mut pre_coins);
+
+    sha3_512(&pre_coins)
+}
+
+impl ABKEM for FAMEFO {
+    const IDENTIFIER: &'static str = "famefo";
+
+    type Pk = PublicKey;
+    type Sk = SecretKey;
+    type Usk = UserSecretKey;
+    type Ct = CipherText;
+    type KeyAttrs = [Attribute];
+    type CtAttrs = Policy;
+
+    const PK_BYTES: usize = PK_BYTES;
+    const SK_BYTES: usize = SK_BYTES;
+
+    fn setup<R: Rng + CryptoRng>(rng: &mut R) -> (PublicKey, SecretKey) {
+        FAME::setup(rng)
+    }
+
+    fn extract_usk<R: Rng + CryptoRng>(
+        _pk: Option<&PublicKey>,
+        sk: &SecretKey,
+        attrs: &[Attribute],
+        rng: &mut R,
+    ) -> UserSecretKey {
+        FAME::extract_usk(None, sk, attrs, rng)
+    }
+
+    fn encaps<R: Rng + CryptoRng>(
+        pk: &PublicKey,
+        policy: &Policy,
+        rng: &mut R,
+    ) -> (CipherText, SharedSecret) {
+        let m = Msg::random(rng);
+        let ct = FAME::encrypt(pk, policy, &m, &coins(&m, policy));
+
+        (ct, SharedSecret::from(&m))
+    }
+
+    /// Decapsulate a shared secret from the ciphertext.
+    ///
+    /// # Panics
+    ///
+    /// This scheme **does** requires the master public key due to usage the Fujisaki-Okamoto transform.
+    /// This function panics if no master public key is provided.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an [`Error`] when the attributes of the user secret key do not
+    /// satisfy the policy, or when an illegitimate ciphertext is encountered (explicit rejection).
+    fn decaps(
+        opk: Option<&PublicKey>,
+        usk: &UserSecretKey,
+        c: &CipherText,
+    ) -> Result<SharedSecret, Error> {
+        let pk = opk.unwrap();
+
+        let m = FAME::decrypt(usk, c).ok_or(Error)?;
+        let c2 = FAME::encrypt(pk, c.policy(), &m, &coins(&m, c.policy()));
+
+        // Can save some time by not doing a constant-time comparison
+        // since we can leak whether the decapsulation succeeds/fails.
+        if c.ct_eq(&c2).into() {
+            Ok(SharedSecret::from(&m))
+        } else {
+            Err(Error)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Derive;
+
+    fn attrs(names: &[&str]) -> Vec<Attribute> {
+        names.iter().map(|n| Attribute::derive_str(n)).collect()
+    }
+
+    #[test]
+    fn eq_encaps_decaps() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = FAMEFO::setup(&mut rng);
+        let policy = Policy::parse("email:alice@example.com AND phone:0612345678").unwrap();
+
+        let usk = FAMEFO::extract_usk(
+            None,
+            &sk,
+            &attrs(&["email:alice@example.com", "phone:0612345678"]),
+            &mut rng,
+        );
+        let (ct, k) = FAMEFO::encaps(&pk, &policy, &mut rng);
+
+        assert_eq!(k, FAMEFO::decaps(Some(&pk), &usk, &ct).unwrap());
+    }
+
+    #[test]
+    fn decaps_rejects_unsatisfied_policy() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = FAMEFO::setup(&mut rng);
+        let policy = Policy::parse("email:alice@example.com AND phone:0612345678").unwrap();
+
+        let usk = FAMEFO::extract_usk(None, &sk, &attrs(&["email:alice@example.com"]), &mut rng);
+        let (ct, _) = FAMEFO::encaps(&pk, &policy, &mut rng);
+
+        assert!(FAMEFO::decaps(Some(&pk), &usk, &ct).is_err());
+    }
+
+    #[test]
+    fn decaps_rejects_mauled_ciphertext() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = FAMEFO::setup(&mut rng);
+        let policy = Policy::parse("a OR b").unwrap();
+
+        let usk = FAMEFO::extract_usk(None, &sk, &attrs(&["a"]), &mut rng);
+        let (ct, _) = FAMEFO::encaps(&pk, &policy, &mut rng);
+
+        // Swap the ciphertext rows of both attributes.
+        let mut bytes = ct.to_bytes();
+        let len = bytes.len();
+        let (first, second) = bytes[len - 2 * CT_ROW_BYTES..].split_at_mut(CT_ROW_BYTES);
+        first.swap_with_slice(second);
+
+        let mauled = CipherText::from_bytes(&bytes).unwrap();
+        assert!(FAMEFO::decaps(Some(&pk), &usk, &mauled).is_err());
+    }
+}
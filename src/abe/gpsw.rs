@@ -0,0 +1,497 @@
+//! IND-sAtt-CPA secure key-policy ABE by Goyal, Pandey, Sahai and Waters (GPSW).
+//! * From: "[Attribute-Based Encryption for Fine-Grained Access Control of Encrypted Data](https://eprint.iacr.org/2006/309.pdf)"
+//!
+//! This file contains the large universe construction in the random oracle model, in which the
+//! access tree is replaced by the linear secret sharing scheme of a [`Policy`]. Attributes are
+//! hashed to G1 using RFC 9380 hash-to-curve.
+//!
+//! Ciphertexts are labelled with a set of attributes and grow linearly in its size. User secret
+//! keys embed a policy and grow linearly in the size of the policy.
+//! All structs' byte serialization use compression.
+
+extern crate alloc;
+use alloc::vec::Vec;
+
+use crate::abe::policy::Policy;
+use crate::abe::{Attribute, ABE};
+use crate::util::*;
+use crate::Compress;
+use pg_curve::{
+    multi_miller_loop, pairing, G1Affine, G1Projective, G2Affine, G2Prepared, G2Projective, Gt,
+    Scalar,
+};
+use rand::{CryptoRng, Rng};
+use subtle::{Choice, ConstantTimeEq, CtOption};
+
+/// Domain separation tag for hashing attributes to G1.
+const DST: &[u8] = b"IBE_GPSW_BLS12381G1_XMD:SHA-256_SSWU_RO_";
+
+/// Size of the compressed message in bytes.
+pub const MSG_BYTES: usize = GT_BYTES;
+
+/// Size of the compressed master public key in bytes.
+pub const PK_BYTES: usize = GT_BYTES;
+
+/// Size of the compressed master secret key in bytes.
+pub const SK_BYTES: usize = SCALAR_BYTES;
+
+/// Size of the compressed user secret key component for a single row of the policy in bytes.
+pub const USK_ROW_BYTES: usize = G1_BYTES + G2_BYTES;
+
+/// Size of the compressed ciphertext in bytes, without any attributes.
+pub const CT_BASE_BYTES: usize = G2_BYTES + GT_BYTES + 4;
+
+/// Size of the compressed ciphertext component for a single attribute in bytes.
+pub const CT_ATTR_BYTES: usize = ID_BYTES + G1_BYTES;
+
+/// Public key parameters generated by the PKG used to encrypt messages.
+/// Also known as MPK.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PublicKey {
+    y: Gt,
+}
+
+/// Secret key parameter generated by the PKG used to extract user secret keys.
+/// Also known as MSK.
+///
+/// # Zeroization
+///
+/// With the `zeroize` feature enabled this type derives `Zeroize` but **not**
+/// `ZeroizeOnDrop` (it is `Copy`). Secret material is **not** cleared on drop —
+/// you **MUST** call `.zeroize()` explicitly once done. See the
+/// [crate-level docs](crate#zeroizing-secret-material).
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize))]
+pub struct SecretKey {
+    y: Scalar,
+}
+
+/// Key component for a single row of the policy.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize))]
+struct RowKey {
+    d: G1Affine,
+    r: G2Affine,
+}
+
+/// User secret key. Can be used to decrypt ciphertexts whose attributes satisfy its policy.
+/// Also known as USK_{policy}.
+///
+/// # Zeroization
+///
+/// With the `zeroize` feature enabled this type derives `Zeroize` and `ZeroizeOnDrop`.
+/// The policy is public and is not zeroized.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize, zeroize::ZeroizeOnDrop))]
+pub struct UserSecretKey {
+    #[cfg_attr(feature = "zeroize", zeroize(skip))]
+    policy: Policy,
+    rows: Vec<RowKey>,
+}
+
+/// Encrypted message, labelled with a set of attributes. Can only be decrypted with a user secret
+/// key whose policy is satisfied by these attributes.
+/// Also known as CT_{S}.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CipherText {
+    attrs: Vec<Attribute>,
+    e: G2Affine,
+    es: Vec<G1Affine>,
+    cprime: Gt,
+}
+
+/// A message that can be encrypted using the ABE.
+pub type Msg = Gt;
+
+/// The Goyal-Pandey-Sahai-Waters key-policy attribute-based encryption scheme.
+#[derive(Debug, Clone, Copy)]
+pub struct GPSW;
+
+fn hash_attr(attr: &Attribute) -> G1Projective {
+    hash_to_g1(&attr.0, DST)
+}
+
+impl ABE for GPSW {
+    type Pk = PublicKey;
+    type Sk = SecretKey;
+    type Usk = UserSecretKey;
+    type Ct = CipherText;
+    type Msg = Msg;
+    type KeyAttrs = Policy;
+    type CtAttrs = [Attribute];
+    type RngBytes = [u8; 64];
+
+    const PK_BYTES: usize = PK_BYTES;
+    const SK_BYTES: usize = SK_BYTES;
+    const MSG_BYTES: usize = MSG_BYTES;
+
+    /// Generate a keypair used by the Private Key Generator (PKG).
+    fn setup<R: Rng + CryptoRng>(rng: &mut R) -> (PublicKey, SecretKey) {
+        let y = rand_scalar(rng);
+
+        let pk = pairing(&G1Affine::generator(), &G2Affine::generator()) * y;
+
+        (PublicKey { y: pk }, SecretKey { y })
+    }
+
+    /// Extract a user secret key for a policy.
+    fn extract_usk<R: Rng + CryptoRng>(
+        _pk: Option<&PublicKey>,
+        sk: &SecretKey,
+        policy: &Policy,
+        rng: &mut R,
+    ) -> UserSecretKey {
+        let g1 = G1Projective::generator();
+        let g2 = G2Projective::generator();
+
+        let lsss = policy.to_lsss();
+        let shares = lsss.share(&sk.y, rng);
+
+        let (d, r): (Vec<G1Projective>, Vec<G2Projective>) = lsss
+            .rows()
+            .iter()
+            .zip(shares.iter())
+            .map(|((attr, _), lambda)| {
+                let r = rand_scalar(rng);
+
+                (g1 * lambda + hash_attr(attr) * r, g2 * r)
+            })
+            .unzip();
+
+        let mut d_affine = alloc::vec![G1Affine::default(); d.len()];
+        let mut r_affine = alloc::vec![G2Affine::default(); r.len()];
+        G1Projective::batch_normalize(&d, &mut d_affine);
+        G2Projective::batch_normalize(&r, &mut r_affine);
+
+        let rows = d_affine
+            .into_iter()
+            .zip(r_affine)
+            .map(|(d, r)| RowKey { d, r })
+            .collect();
+
+        UserSecretKey {
+            policy: policy.clone(),
+            rows,
+        }
+    }
+
+    /// Encrypt a message using the PKG public key, labelled with a set of attributes.
+    fn encrypt(pk: &PublicKey, attrs: &[Attribute], message: &Msg, rng: &[u8; 64]) -> CipherText {
+        let s = Scalar::from_bytes_wide(rng);
+
+        let es: Vec<G1Projective> = attrs.iter().map(|attr| hash_attr(attr) * s).collect();
+        let mut es_affine = alloc::vec![G1Affine::default(); es.len()];
+        G1Projective::batch_normalize(&es, &mut es_affine);
+
+        CipherText {
+            attrs: attrs.to_vec(),
+            e: (G2Affine::generator() * s).into(),
+            es: es_affine,
+            cprime: pk.y * s + message,
+        }
+    }
+
+    /// Derive the same message from the CipherText using a UserSecretKey.
+    ///
+    /// Returns `None` if the attributes of the ciphertext do not satisfy the policy.
+    fn decrypt(usk: &UserSecretKey, ct: &CipherText) -> Option<Msg> {
+        let rows = usk.policy.satisfying_rows(&ct.attrs)?;
+
+        let mut d = G1Projective::identity();
+        let mut g1s = Vec::with_capacity(rows.len() + 1);
+        let mut g2s = Vec::with_capacity(rows.len() + 1);
+
+        for (i, attr) in rows {
+            let k = usk.rows.get(i)?;
+            let j = ct.attrs.iter().position(|a| *a == attr)?;
+
+            d += k.d;
+            g1s.push(-ct.es[j]);
+            g2s.push(G2Prepared::from(k.r));
+        }
+
+        g1s.push(G1Affine::from(d));
+        g2s.push(G2Prepared::from(ct.e));
+
+        let terms: Vec<(&G1Affine, &G2Prepared)> = g1s.iter().zip(g2s.iter()).collect();
+        let m = multi_miller_loop(&terms).final_exponentiation();
+
+        Some(ct.cprime - m)
+    }
+}
+
+impl UserSecretKey {
+    /// The policy this user secret key was extracted for.
+    pub fn policy(&self) -> &Policy {
+        &self.policy
+    }
+}
+
+impl CipherText {
+    /// The attributes this ciphertext is labelled with.
+    pub fn attributes(&self) -> &[Attribute] {
+        &self.attrs
+    }
+}
+
+impl ConstantTimeEq for CipherText {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        // The attributes are public and determine the number of elements.
+        if self.attrs != other.attrs || self.es.len() != other.es.len() {
+            return Choice::from(0);
+        }
+
+        let es = self
+            .es
+            .iter()
+            .zip(other.es.iter())
+            .fold(Choice::from(1), |acc, (a, b)| acc & a.ct_eq(b));
+
+        es & self.e.ct_eq(&other.e) & self.cprime.ct_eq(&other.cprime)
+    }
+}
+
+impl Compress for PublicKey {
+    const OUTPUT_SIZE: usize = PK_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; PK_BYTES] {
+        self.y.to_compressed()
+    }
+
+    fn from_bytes(bytes: &[u8; PK_BYTES]) -> CtOption<Self> {
+        Gt::from_compressed(bytes).map(|y| PublicKey { y })
+    }
+}
+
+impl Compress for SecretKey {
+    const OUTPUT_SIZE: usize = SK_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; SK_BYTES] {
+        self.y.to_bytes()
+    }
+
+    fn from_bytes(bytes: &[u8; SK_BYTES]) -> CtOption<Self> {
+        Scalar::from_bytes(bytes).map(|y| SecretKey { y })
+    }
+}
+
+impl Compress for UserSecretKey {
+    /// The size of a user secret key depends on the policy, this is the size of a user secret key
+    /// for a policy of a single attribute.
+    const OUTPUT_SIZE: usize = 1 + ID_BYTES + USK_ROW_BYTES;
+    type Output = Vec<u8>;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut res = Vec::with_capacity(self.policy.bytes_len() + self.rows.len() * USK_ROW_BYTES);
+
+        self.policy.write_bytes(&mut res);
+        for k in self.rows.iter() {
+            res.extend_from_slice(&k.d.to_compressed());
+            res.extend_from_slice(&k.r.to_compressed());
+        }
+
+        res
+    }
+
+    fn from_bytes(bytes: &Vec<u8>) -> CtOption<Self> {
+        let mut res = UserSecretKey {
+            policy: Policy::Or(Vec::new()),
+            rows: Vec::new(),
+        };
+
+        let rows = match Policy::read_bytes(bytes) {
+            Some((policy, rows)) if rows.len() == policy.len() * USK_ROW_BYTES => {
+                res.policy = policy;
+                rows
+            }
+            _ => return CtOption::new(res, Choice::from(0)),
+        };
+
+        let mut is_some = Choice::from(1u8);
+        for chunk in rows.chunks_exact(USK_ROW_BYTES) {
+            let (d, r) = chunk.split_at(G1_BYTES);
+
+            let mut k = RowKey {
+                d: G1Affine::default(),
+                r: G2Affine::default(),
+            };
+            is_some &= G1Affine::from_compressed(d.try_into().unwrap())
+                .map(|el| k.d = el)
+                .is_some();
+            is_some &= G2Affine::from_compressed(r.try_into().unwrap())
+                .map(|el| k.r = el)
+                .is_some();
+
+            res.rows.push(k);
+        }
+
+        CtOption::new(res, is_some)
+    }
+}
+
+impl Compress for CipherText {
+    /// The size of a ciphertext depends on the number of attributes, this is the size of a
+    /// ciphertext without attributes.
+    const OUTPUT_SIZE: usize = CT_BASE_BYTES;
+    type Output = Vec<u8>;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut res = Vec::with_capacity(CT_BASE_BYTES + self.attrs.len() * CT_ATTR_BYTES);
+
+        res.extend_from_slice(&self.e.to_compressed());
+        res.extend_from_slice(&self.cprime.to_compressed());
+
+        res.extend_from_slice(&(self.attrs.len() as u32).to_be_bytes());
+        for (attr, e) in self.attrs.iter().zip(self.es.iter()) {
+            res.extend_from_slice(&attr.0);
+            res.extend_from_slice(&e.to_compressed());
+        }
+
+        res
+    }
+
+    fn from_bytes(bytes: &Vec<u8>) -> CtOption<Self> {
+        let mut res = CipherText {
+            attrs: Vec::new(),
+            e: G2Affine::default(),
+            es: Vec::new(),
+            cprime: Gt::default(),
+        };
+
+        let n = match bytes.get(CT_BASE_BYTES - 4..CT_BASE_BYTES) {
+            Some(n) => u32::from_be_bytes(n.try_into().unwrap()) as usize,
+            None => return CtOption::new(res, Choice::from(0)),
+        };
+
+        let len = n
+            .checked_mul(CT_ATTR_BYTES)
+            .and_then(|len| len.checked_add(CT_BASE_BYTES));
+        if len != Some(bytes.len()) {
+            return CtOption::new(res, Choice::from(0));
+        }
+
+        let (g2, rest) = bytes.split_at(G2_BYTES);
+        let (gt, rest) = rest.split_at(GT_BYTES);
+
+        // Use the checked `from_compressed` variant, which verifies that each point is in the
+        // correct prime-order subgroup (GHSA-25fp-2fjj-g84w).
+        let mut is_some = G2Affine::from_compressed(g2.try_into().unwrap())
+            .map(|el| res.e = el)
+            .is_some();
+        is_some &= Gt::from_compressed(gt.try_into().unwrap())
+            .map(|el| res.cprime = el)
+            .is_some();
+
+        for chunk in rest[4..].chunks_exact(CT_ATTR_BYTES) {
+            let (attr, e) = chunk.split_at(ID_BYTES);
+
+            let mut el = G1Affine::default();
+            is_some &= G1Affine::from_compressed(e.try_into().unwrap())
+                .map(|e| el = e)
+                .is_some();
+
+            res.attrs.push(Attribute(attr.try_into().unwrap()));
+            res.es.push(el);
+        }
+
+        CtOption::new(res, is_some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Derive;
+    use rand::RngCore;
+
+    const POLICY: &str = "dept:finance AND (year:2025 OR year:2026)";
+
+    fn attrs(names: &[&str]) -> Vec<Attribute> {
+        names.iter().map(|n| Attribute::derive_str(n)).collect()
+    }
+
+    fn encrypt_random(pk: &PublicKey, attrs: &[Attribute]) -> (CipherText, Msg) {
+        let mut rng = rand::thread_rng();
+        let mut coins = [0u8; 64];
+        rng.fill_bytes(&mut coins);
+
+        let m = rand_gt(&mut rng);
+
+        (GPSW::encrypt(pk, attrs, &m, &coins), m)
+    }
+
+    #[test]
+    fn eq_encrypt_decrypt() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = GPSW::setup(&mut rng);
+        let usk = GPSW::extract_usk(None, &sk, &Policy::parse(POLICY).unwrap(), &mut rng);
+
+        for set in [
+            &["dept:finance", "year:2026"][..],
+            &["year:2025", "dept:hr", "dept:finance"],
+        ] {
+            let (ct, m) = encrypt_random(&pk, &attrs(set));
+            assert_eq!(GPSW::decrypt(&usk, &ct), Some(m));
+        }
+    }
+
+    #[test]
+    fn unsatisfied_policy_does_not_decrypt() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = GPSW::setup(&mut rng);
+        let usk = GPSW::extract_usk(None, &sk, &Policy::parse(POLICY).unwrap(), &mut rng);
+
+        let (ct, _) = encrypt_random(&pk, &attrs(&["dept:hr", "year:2026"]));
+        assert_eq!(GPSW::decrypt(&usk, &ct), None);
+    }
+
+    #[test]
+    fn colluding_keys_do_not_decrypt() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = GPSW::setup(&mut rng);
+        let policy = Policy::parse("a AND b").unwrap();
+
+        // Combine the row keys of two users with a key for the same policy.
+        let mut usk = GPSW::extract_usk(None, &sk, &policy, &mut rng);
+        let other = GPSW::extract_usk(None, &sk, &policy, &mut rng);
+        usk.rows[1] = other.rows[1];
+
+        let (ct, m) = encrypt_random(&pk, &attrs(&["a", "b"]));
+        assert_ne!(GPSW::decrypt(&usk, &ct), Some(m));
+    }
+
+    #[test]
+    fn eq_serialize_deserialize() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = GPSW::setup(&mut rng);
+        let usk = GPSW::extract_usk(None, &sk, &Policy::parse(POLICY).unwrap(), &mut rng);
+        let (ct, _) = encrypt_random(&pk, &attrs(&["dept:finance", "year:2026"]));
+
+        assert_eq!(pk, PublicKey::from_bytes(&pk.to_bytes()).unwrap());
+        assert_eq!(sk, SecretKey::from_bytes(&sk.to_bytes()).unwrap());
+        assert_eq!(usk, UserSecretKey::from_bytes(&usk.to_bytes()).unwrap());
+        assert_eq!(ct, CipherText::from_bytes(&ct.to_bytes()).unwrap());
+
+        let mut bytes = ct.to_bytes();
+        bytes.pop();
+        assert!(bool::from(CipherText::from_bytes(&bytes).is_none()));
+
+        let mut bytes = usk.to_bytes();
+        bytes.pop();
+        assert!(bool::from(UserSecretKey::from_bytes(&bytes).is_none()));
+    }
+
+    #[test]
+    fn from_bytes_rejects_non_subgroup_point() {
+        let mut rng = rand::thread_rng();
+        let (pk, _) = GPSW::setup(&mut rng);
+        let (ct, _) = encrypt_random(&pk, &attrs(&["a"]));
+
+        let mut bytes = ct.to_bytes();
+        let len = bytes.len();
+        bytes[len - G1_BYTES..].copy_from_slice(&NON_SUBGROUP_G1_COMPRESSED);
+        assert!(bool::from(CipherText::from_bytes(&bytes).is_none()));
+    }
+}
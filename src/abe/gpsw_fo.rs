@@ -0,0 +1,166 @@
+//! IND-sAtt-CCA2 secure key-policy ABKEM from GPSW by Goyal, Pandey, Sahai and Waters.
+//! * From: "[Attribute-Based Encryption for Fine-Grained Access Control of Encrypted Data](https://eprint.iacr.org/2006/309.pdf)"
+//!
+//! CCA security due to a general approach by Fujisaki and Okamoto.
+//! * From: "[A Modular Analysis of the Fujisaki-Okamoto Transformation](https://eprint.iacr.org/2017/604.pdf)"
+//!
+//! Symmetric primitive G instantiated using sha3_512. The coins are bound to the attributes, which
+//! are part of the ciphertext. The shared secret is derived from the message in the same way as
+//! for the identity-based KEMs.
+//!
+//! A drawback of a Fujisaki-Okamoto transform is that we now need the public key to decapsulate.
+
+extern crate alloc;
+use alloc::vec::Vec;
+
+use crate::abe::gpsw::{Msg, GPSW};
+use crate::abe::policy::Policy;
+use crate::abe::{Attribute, ABE, ABKEM};
+use crate::kem::{Error, SharedSecret};
+use crate::util::*;
+use crate::Compress;
+use group::Group;
+use rand::{CryptoRng, Rng};
+use subtle::ConstantTimeEq;
+
+/// These struct are identical for the CCA KEM.
+pub use crate::abe::gpsw::{
+    CipherText, PublicKey, SecretKey, UserSecretKey, CT_ATTR_BYTES, CT_BASE_BYTES, PK_BYTES,
+    SK_BYTES, USK_ROW_BYTES,
+};
+
+/// The CCA2 secure KEM that results by applying the implicit rejection
+/// variant of the Fujisaki-Okamoto transform to the GPSW KP-ABE scheme.
+#[derive(Debug, Clone, Copy)]
+pub struct GPSWFO;
+
+/// Derives the encryption coins from the message and the attributes.
+fn coins(m: &Msg, attrs: &[Attribute]) -> [u8; 64] {
+    let mut pre_coins = Vec::with_capacity(GT_BYTES + attrs.len() * ID_BYTES);
+    pre_coins.extend_from_slice(&m.to_bytes());
+    for attr in attrs {
+        pre_coins.extend_from_slice(&attr.0);
+    }
+
+    sha3_512(&pre_coins)
+}
+
+impl ABKEM for GPSWFO {
+    const IDENTIFIER: &'static str = "gpswfo";
+
+    type Pk = PublicKey;
+    type Sk = SecretKey;
+    type Usk = UserSecretKey;
+    type Ct = CipherText;
+    type KeyAttrs = Policy;
+    type CtAttrs = [Attribute];
+
+    const PK_BYTES: usize = PK_BYTES;
+    const SK_BYTES: usize = SK_BYTES;
+
+    fn setup<R: Rng + CryptoRng>(rng: &mut R) -> (PublicKey, SecretKey) {
+        GPSW::setup(rng)
+    }
+
+    fn extract_usk<R: Rng + CryptoRng>(
+        _pk: Option<&PublicKey>,
+        sk: &SecretKey,
+        policy: &Policy,
+        rng: &mut R,
+    ) -> UserSecretKey {
+        GPSW::extract_usk(None, sk, policy, rng)
+    }
+
+    fn encaps<R: Rng + CryptoRng>(
+        pk: &PublicKey,
+        attrs: &[Attribute],
+        rng: &mut R,
+    ) -> (CipherText, SharedSecret) {
+        let m = Msg::random(rng);
+        let ct = GPSW::encrypt(pk, attrs, &m, &coins(&m, attrs));
+
+        (ct, SharedSecret::from(&m))
+    }
+
+    /// Decapsulate a shared secret from the ciphertext.
+    ///
+    /// # Panics
+    ///
+    /// This scheme **does** requires the master public key due to usage the Fujisaki-Okamoto transform.
+    /// This function panics if no master public key is provided.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an [`Error`] when the attributes of the ciphertext do not satisfy the
+    /// policy, or when an illegitimate ciphertext is encountered (explicit rejection).
+    fn decaps(
+        opk: Option<&PublicKey>,
+        usk: &UserSecretKey,
+        c: &CipherText,
+    ) -> Result<SharedSecret, Error> {
+        let pk = opk.unwrap();
+
+        let m = GPSW::decrypt(usk, c).ok_or(Error)?;
+        let c2 = GPSW::encrypt(pk, c.attributes(), &m, &coins(&m, c.attributes()));
+
+        // Can save some time by not doing a constant-time comparison
+        // since we can leak whether the decapsulation succeeds/fails.
+        if c.ct_eq(&c2).into() {
+            Ok(SharedSecret::from(&m))
+        } else {
+            Err(Error)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Derive;
+
+    fn attrs(names: &[&str]) -> Vec<Attribute> {
+        names.iter().map(|n| Attribute::derive_str(n)).collect()
+    }
+
+    #[test]
+    fn eq_encaps_decaps() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = GPSWFO::setup(&mut rng);
+        let policy = Policy::parse("dept:finance AND year:2026").unwrap();
+
+        let usk = GPSWFO::extract_usk(None, &sk, &policy, &mut rng);
+        let (ct, k) = GPSWFO::encaps(&pk, &attrs(&["dept:finance", "year:2026"]), &mut rng);
+
+        assert_eq!(k, GPSWFO::decaps(Some(&pk), &usk, &ct).unwrap());
+    }
+
+    #[test]
+    fn decaps_rejects_unsatisfied_policy() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = GPSWFO::setup(&mut rng);
+        let policy = Policy::parse("dept:finance AND year:2026").unwrap();
+
+        let usk = GPSWFO::extract_usk(None, &sk, &policy, &mut rng);
+        let (ct, _) = GPSWFO::encaps(&pk, &attrs(&["dept:finance"]), &mut rng);
+
+        assert!(GPSWFO::decaps(Some(&pk), &usk, &ct).is_err());
+    }
+
+    #[test]
+    fn decaps_rejects_relabelled_ciphertext() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = GPSWFO::setup(&mut rng);
+        let policy = Policy::parse("a OR b").unwrap();
+
+        let usk = GPSWFO::extract_usk(None, &sk, &policy, &mut rng);
+        let (ct, _) = GPSWFO::encaps(&pk, &attrs(&["a", "b"]), &mut rng);
+
+        // Drop the last attribute, which still satisfies the policy.
+        let mut bytes = ct.to_bytes();
+        bytes.truncate(bytes.len() - CT_ATTR_BYTES);
+        bytes[CT_BASE_BYTES - 4..CT_BASE_BYTES].copy_from_slice(&1u32.to_be_bytes());
+
+        let relabelled = CipherText::from_bytes(&bytes).unwrap();
+        assert!(GPSWFO::decaps(Some(&pk), &usk, &relabelled).is_err());
+    }
+}
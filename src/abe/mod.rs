@@ -0,0 +1,183 @@
+//! This module contains attribute-based encryption schemes (ABEs) and their key encapsulation
+//! mechanisms.
+//!
+//! In a ciphertext-policy scheme, ciphertexts are encrypted under a [`Policy`] over attributes and
+//! user secret keys are extracted for a set of attributes. A user secret key can decrypt a
+//! ciphertext if its attributes satisfy the policy. In a key-policy scheme these roles are
+//! swapped: ciphertexts are labelled with a set of attributes and user secret keys embed a policy.
+//!
+//! Attributes are derived using [`Derive`](crate::Derive), like the identities of the
+//! identity-based schemes.
+//!
+//! Among the schemes are:
+//!
+//! - FAME (ciphertext-policy), and its Fujisaki-Okamoto transformed KEM, FAMEFO,
+//! - GPSW (key-policy), and its Fujisaki-Okamoto transformed KEM, GPSWFO,
+//! - Sahai-Waters fuzzy IBE, where a user secret key decrypts a ciphertext if their attribute sets
+//!   overlap in at least a threshold number of attributes,
+//! - CGWIPE, inner-product predicate encryption where attributes are vectors, and its CCA secure
+//!   KEM, CGWIPEKV.
+
+pub mod policy;
+
+#[cfg(feature = "cgwipe")]
+#[cfg_attr(docsrs, doc(cfg(feature = "cgwipe")))]
+pub mod cgw_ipe;
+
+#[cfg(feature = "cgwipekv")]
+#[cfg_attr(docsrs, doc(cfg(feature = "cgwipekv")))]
+pub mod cgw_ipe_kv;
+
+#[cfg(feature = "fame")]
+#[cfg_attr(docsrs, doc(cfg(feature = "fame")))]
+pub mod fame;
+
+#[cfg(feature = "famefo")]
+#[cfg_attr(docsrs, doc(cfg(feature = "famefo")))]
+pub mod fame_fo;
+
+#[cfg(feature = "gpsw")]
+#[cfg_attr(docsrs, doc(cfg(feature = "gpsw")))]
+pub mod gpsw;
+
+#[cfg(feature = "gpswfo")]
+#[cfg_attr(docsrs, doc(cfg(feature = "gpswfo")))]
+pub mod gpsw_fo;
+
+#[cfg(feature = "sahai_waters")]
+#[cfg_attr(docsrs, doc(cfg(feature = "sahai_waters")))]
+pub mod sahai_waters;
+
+pub use crate::util::Identity as Attribute;
+pub use policy::{ParseError, Policy};
+
+use crate::kem::{Error, SharedSecret};
+use crate::Compress;
+use group::Group;
+use rand::{CryptoRng, Rng};
+
+/// Attribute-based encryption scheme (ABE).
+pub trait ABE {
+    /// Master public key (Mpk).
+    type Pk: Compress;
+
+    /// Master secret key (Msk).
+    type Sk: Compress;
+
+    /// User secret key (Usk).
+    type Usk: Compress;
+
+    /// Ciphertext (Ct).
+    type Ct: Compress;
+
+    /// Message type (Msg), we require group so that we can draw random messages.
+    type Msg: Compress + Group;
+
+    /// Attributes or policy that a user secret key is extracted for.
+    type KeyAttrs: ?Sized;
+
+    /// Attributes or policy that a message is encrypted under.
+    type CtAttrs: ?Sized;
+
+    /// Randomness required to encrypt a message.
+    type RngBytes: Sized;
+
+    /// Size of the master public key in bytes.
+    const PK_BYTES: usize;
+
+    /// Size of the master secret key in bytes.
+    const SK_BYTES: usize;
+
+    /// Size of the message in bytes.
+    const MSG_BYTES: usize;
+
+    /// Creates an MSK, MPK pair.
+    fn setup<R: Rng + CryptoRng>(rng: &mut R) -> (Self::Pk, Self::Sk);
+
+    /// Extract a user secret key for attributes using the MSK.
+    ///
+    /// Optionally requires the system's public key.
+    fn extract_usk<R: Rng + CryptoRng>(
+        pk: Option<&Self::Pk>,
+        sk: &Self::Sk,
+        attrs: &Self::KeyAttrs,
+        rng: &mut R,
+    ) -> Self::Usk;
+
+    /// Encrypt a message using the MPK under attributes.
+    fn encrypt(
+        pk: &Self::Pk,
+        attrs: &Self::CtAttrs,
+        message: &Self::Msg,
+        rng: &Self::RngBytes,
+    ) -> Self::Ct;
+
+    /// Decrypt a ciphertext using a user secret key to retrieve a message.
+    ///
+    /// Returns `None` if the attributes of the user secret key and the ciphertext do not match.
+    fn decrypt(usk: &Self::Usk, ct: &Self::Ct) -> Option<Self::Msg>;
+}
+
+/// Attribute-based key encapsulation mechanism (ABKEM).
+///
+/// Produces the same [`SharedSecret`] as the identity-based KEMs.
+pub trait ABKEM {
+    /// Scheme identifier.
+    const IDENTIFIER: &'static str;
+
+    /// Master public key (Mpk).
+    type Pk: Compress;
+
+    /// Master secret key (Msk).
+    type Sk: Compress;
+
+    /// User secret key (Usk).
+    type Usk: Compress;
+
+    /// Ciphertext (Ct).
+    type Ct: Compress;
+
+    /// Attributes or policy that a user secret key is extracted for.
+    type KeyAttrs: ?Sized;
+
+    /// Attributes or policy that a shared secret is encapsulated under.
+    type CtAttrs: ?Sized;
+
+    /// Size of the master public key in bytes.
+    const PK_BYTES: usize;
+
+    /// Size of the master secret key in bytes.
+    const SK_BYTES: usize;
+
+    /// Creates an MSK, MPK pair.
+    fn setup<R: Rng + CryptoRng>(rng: &mut R) -> (Self::Pk, Self::Sk);
+
+    /// Extract a user secret key for attributes using the MSK.
+    ///
+    /// Optionally requires the system's public key.
+    fn extract_usk<R: Rng + CryptoRng>(
+        pk: Option<&Self::Pk>,
+        sk: &Self::Sk,
+        attrs: &Self::KeyAttrs,
+        rng: &mut R,
+    ) -> Self::Usk;
+
+    /// Encapsulate a shared secret using the master public key under attributes.
+    fn encaps<R: Rng + CryptoRng>(
+        pk: &Self::Pk,
+        attrs: &Self::CtAttrs,
+        rng: &mut R,
+    ) -> (Self::Ct, SharedSecret);
+
+    /// Decrypt a ciphertext using a user secret key to retrieve the shared secret.
+    ///
+    /// Optionally requires a public key to perform this operation.
+    ///
+    /// This operation fails if the attributes of the user secret key and the ciphertext do not
+    /// match, or when an illegitimate ciphertext is used as input.
+    fn decaps(
+        mpk: Option<&Self::Pk>,
+        usk: &Self::Usk,
+        ct: &Self::Ct,
+    ) -> Result<SharedSecret, Error>;
+}
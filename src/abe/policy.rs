@@ -0,0 +1,601 @@
+//! Monotone boolean access policies and their linear secret sharing schemes (LSSS).
+//!
+//! A policy is a formula of attributes combined with `AND` and `OR`, for example:
+//!
+//! ```text
+//! email:alice@example.com AND (phone:0612345678 OR "name:Alice Example")
+//! ```
+//!
+//! `AND` binds stronger than `OR` and both keywords are case-insensitive. Attributes that contain
+//! whitespace, parentheses or a keyword can be quoted. Every attribute is derived using [`Derive`].
+//!
+//! A policy is compiled to an access matrix using the algorithm of Lewko and Waters.
+//! * From: "[Decentralizing Attribute-Based Encryption](https://eprint.iacr.org/2010/351.pdf)", Appendix G
+//!
+//! All entries of the resulting matrix are `-1`, `0` or `1`, and any minimal set of rows that
+//! satisfies the policy reconstructs the target vector `(1, 0, ..., 0)` with all coefficients equal
+//! to one.
+
+extern crate alloc;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::abe::Attribute;
+use crate::util::{rand_scalar, ID_BYTES};
+use crate::Derive;
+use core::str::FromStr;
+use pg_curve::Scalar;
+use rand::{CryptoRng, Rng};
+
+/// Maximum nesting depth of a policy.
+///
+/// Bounds the recursion when parsing a policy from a string or from bytes. Policies that are
+/// nested deeper cannot be deserialized.
+pub const MAX_DEPTH: usize = 32;
+
+const TAG_ATTR: u8 = 0;
+const TAG_AND: u8 = 1;
+const TAG_OR: u8 = 2;
+
+/// A monotone boolean access policy over attributes.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Policy {
+    /// Satisfied by a single attribute.
+    Attr(Attribute),
+    /// Satisfied if all subpolicies are satisfied.
+    And(Vec<Policy>),
+    /// Satisfied if any of the subpolicies is satisfied.
+    Or(Vec<Policy>),
+}
+
+/// Error that occurs when parsing a policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseError {
+    /// The policy ended while an attribute or closing parenthesis was expected.
+    UnexpectedEnd,
+    /// An unexpected token was found at the given byte offset.
+    UnexpectedToken(usize),
+    /// The quoted attribute starting at the given byte offset has no closing quote.
+    UnterminatedQuote(usize),
+    /// The policy is nested deeper than [`MAX_DEPTH`].
+    TooDeep,
+}
+
+impl Policy {
+    /// Create a policy that consists of a single attribute.
+    pub fn attr(s: &str) -> Policy {
+        Policy::Attr(Attribute::derive_str(s))
+    }
+
+    /// Combine two policies, such that both must be satisfied.
+    pub fn and(self, other: Policy) -> Policy {
+        let mut ps = match self {
+            Policy::And(ps) => ps,
+            p => vec![p],
+        };
+
+        match other {
+            Policy::And(qs) => ps.extend(qs),
+            q => ps.push(q),
+        }
+
+        Policy::And(ps)
+    }
+
+    /// Combine two policies, such that either must be satisfied.
+    pub fn or(self, other: Policy) -> Policy {
+        let mut ps = match self {
+            Policy::Or(ps) => ps,
+            p => vec![p],
+        };
+
+        match other {
+            Policy::Or(qs) => ps.extend(qs),
+            q => ps.push(q),
+        }
+
+        Policy::Or(ps)
+    }
+
+    /// Parse a policy from a string.
+    pub fn parse(s: &str) -> Result<Policy, ParseError> {
+        let tokens = tokenize(s)?;
+        let mut parser = Parser { tokens, pos: 0 };
+
+        let policy = parser.expr(0)?;
+        match parser.tokens.get(parser.pos) {
+            None if policy.depth() > MAX_DEPTH => Err(ParseError::TooDeep),
+            None => Ok(policy),
+            Some((offset, _)) => Err(ParseError::UnexpectedToken(*offset)),
+        }
+    }
+
+    /// The nesting depth of the policy, where a single attribute has depth zero.
+    pub fn depth(&self) -> usize {
+        match self {
+            Policy::Attr(_) => 0,
+            Policy::And(ps) | Policy::Or(ps) => 1 + ps.iter().map(Policy::depth).max().unwrap_or(0),
+        }
+    }
+
+    /// The number of attribute occurrences in the policy, i.e., the number of rows of its access
+    /// matrix.
+    pub fn len(&self) -> usize {
+        match self {
+            Policy::Attr(_) => 1,
+            Policy::And(ps) | Policy::Or(ps) => ps.iter().map(Policy::len).sum(),
+        }
+    }
+
+    /// Whether the policy has no attributes.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Whether the set of attributes satisfies the policy.
+    pub fn is_satisfied_by(&self, attrs: &[Attribute]) -> bool {
+        self.satisfying_rows(attrs).is_some()
+    }
+
+    /// Compiles the policy into an access matrix.
+    pub fn to_lsss(&self) -> AccessMatrix {
+        let mut rows = Vec::with_capacity(self.len());
+        let mut cols = 1;
+
+        self.fill_lsss(vec![1], &mut cols, &mut rows);
+
+        for (_, row) in rows.iter_mut() {
+            row.resize(cols, 0);
+        }
+
+        AccessMatrix { rows, cols }
+    }
+
+    fn fill_lsss(&self, v: Vec<i8>, cols: &mut usize, rows: &mut Vec<(Attribute, Vec<i8>)>) {
+        match self {
+            Policy::Attr(a) => rows.push((*a, v)),
+            Policy::Or(ps) => {
+                for p in ps {
+                    p.fill_lsss(v.clone(), cols, rows);
+                }
+            }
+            Policy::And(ps) => {
+                // The first child gets (v, 1), the last one (0, ..., 0, -1) and every other child
+                // (0, ..., 0, -1, 1), such that the rows of all children sum up to v.
+                let mut carry = v;
+                for (i, p) in ps.iter().enumerate() {
+                    if i + 1 == ps.len() {
+                        p.fill_lsss(carry, cols, rows);
+                        break;
+                    }
+
+                    let mut child = core::mem::take(&mut carry);
+                    child.resize(*cols, 0);
+                    child.push(1);
+
+                    carry = vec![0; *cols];
+                    carry.push(-1);
+                    *cols += 1;
+
+                    p.fill_lsss(child, cols, rows);
+                }
+            }
+        }
+    }
+
+    /// Returns the indices and attributes of a minimal set of rows of the access matrix whose
+    /// attributes are contained in `attrs` and that reconstruct the target vector, if it exists.
+    pub(crate) fn satisfying_rows(&self, attrs: &[Attribute]) -> Option<Vec<(usize, Attribute)>> {
+        let mut rows = Vec::new();
+        let mut offset = 0;
+
+        self.prune(attrs, &mut offset, &mut rows).then_some(rows)
+    }
+
+    fn prune(
+        &self,
+        attrs: &[Attribute],
+        offset: &mut usize,
+        rows: &mut Vec<(usize, Attribute)>,
+    ) -> bool {
+        match self {
+            Policy::Attr(a) => {
+                let i = *offset;
+                *offset += 1;
+
+                let found = attrs.contains(a);
+                if found {
+                    rows.push((i, *a));
+                }
+
+                found
+            }
+            Policy::And(ps) => ps
+                .iter()
+                .fold(!ps.is_empty(), |acc, p| p.prune(attrs, offset, rows) & acc),
+            Policy::Or(ps) => {
+                let mut found = false;
+                for p in ps {
+                    if found {
+                        *offset += p.len();
+                        continue;
+                    }
+
+                    let mark = rows.len();
+                    found = p.prune(attrs, offset, rows);
+                    if !found {
+                        rows.truncate(mark);
+                    }
+                }
+
+                found
+            }
+        }
+    }
+
+    /// The size of the serialized policy in bytes.
+    pub(crate) fn bytes_len(&self) -> usize {
+        match self {
+            Policy::Attr(_) => 1 + ID_BYTES,
+            Policy::And(ps) | Policy::Or(ps) => 5 + ps.iter().map(Policy::bytes_len).sum::<usize>(),
+        }
+    }
+
+    /// Serializes the policy in prefix order.
+    pub(crate) fn write_bytes(&self, out: &mut Vec<u8>) {
+        match self {
+            Policy::Attr(a) => {
+                out.push(TAG_ATTR);
+                out.extend_from_slice(&a.0);
+            }
+            Policy::And(ps) | Policy::Or(ps) => {
+                out.push(if matches!(self, Policy::And(_)) {
+                    TAG_AND
+                } else {
+                    TAG_OR
+                });
+                out.extend_from_slice(&(ps.len() as u32).to_be_bytes());
+                for p in ps {
+                    p.write_bytes(out);
+                }
+            }
+        }
+    }
+
+    /// Serializes the policy.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut res = Vec::with_capacity(self.bytes_len());
+        self.write_bytes(&mut res);
+
+        res
+    }
+
+    /// Deserializes a policy.
+    ///
+    /// Returns `None` if the bytes do not encode a policy or if the policy is nested deeper than
+    /// [`MAX_DEPTH`].
+    pub fn from_bytes(bytes: &[u8]) -> Option<Policy> {
+        match Self::read_bytes(bytes)? {
+            (p, []) => Some(p),
+            _ => None,
+        }
+    }
+
+    /// Deserializes a policy, returning the remaining bytes.
+    pub(crate) fn read_bytes(bytes: &[u8]) -> Option<(Policy, &[u8])> {
+        Self::read_bytes_at(bytes, 0)
+    }
+
+    fn read_bytes_at(bytes: &[u8], depth: usize) -> Option<(Policy, &[u8])> {
+        let (tag, rest) = bytes.split_first()?;
+
+        match *tag {
+            TAG_ATTR => {
+                let (id, rest) = (rest.get(..ID_BYTES)?, &rest[ID_BYTES..]);
+
+                Some((Policy::Attr(Attribute(id.try_into().unwrap())), rest))
+            }
+            TAG_AND | TAG_OR if depth < MAX_DEPTH => {
+                let n = u32::from_be_bytes(rest.get(..4)?.try_into().unwrap()) as usize;
+
+                let mut rest = &rest[4..];
+                let mut ps = Vec::with_capacity(n.min(rest.len() / (1 + ID_BYTES)));
+                for _ in 0..n {
+                    let (p, r) = Self::read_bytes_at(rest, depth + 1)?;
+                    ps.push(p);
+                    rest = r;
+                }
+
+                let p = if *tag == TAG_AND {
+                    Policy::And(ps)
+                } else {
+                    Policy::Or(ps)
+                };
+
+                Some((p, rest))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for Policy {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Policy::parse(s)
+    }
+}
+
+/// Access matrix of a policy, one row per attribute occurrence.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccessMatrix {
+    pub(crate) rows: Vec<(Attribute, Vec<i8>)>,
+    pub(crate) cols: usize,
+}
+
+impl AccessMatrix {
+    /// The rows of the matrix, labelled with their attribute.
+    pub fn rows(&self) -> &[(Attribute, Vec<i8>)] {
+        &self.rows
+    }
+
+    /// The number of columns of the matrix.
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Shares a secret over the rows of the matrix.
+    ///
+    /// The share of row `i` is `M_i · (secret, v_2, ..., v_cols)`, for random `v_j`. The shares of
+    /// the rows returned by a satisfying set of attributes sum up to the secret.
+    pub fn share<R: Rng + CryptoRng>(&self, secret: &Scalar, rng: &mut R) -> Vec<Scalar> {
+        let v: Vec<Scalar> = core::iter::once(*secret)
+            .chain((1..self.cols).map(|_| rand_scalar(rng)))
+            .collect();
+
+        self.rows
+            .iter()
+            .map(|(_, m)| {
+                m.iter()
+                    .zip(v.iter())
+                    .fold(Scalar::zero(), |acc, (mj, vj)| match mj {
+                        1 => acc + vj,
+                        -1 => acc - vj,
+                        _ => acc,
+                    })
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token<'a> {
+    Open,
+    Close,
+    And,
+    Or,
+    Attr(&'a str),
+}
+
+fn tokenize(s: &str) -> Result<Vec<(usize, Token<'_>)>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = s.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '(' => tokens.push((start, Token::Open)),
+            ')' => tokens.push((start, Token::Close)),
+            '"' => {
+                let end = s[start + 1..]
+                    .find('"')
+                    .ok_or(ParseError::UnterminatedQuote(start))?
+                    + start
+                    + 1;
+
+                while chars.next_if(|(i, _)| *i <= end).is_some() {}
+                tokens.push((start, Token::Attr(&s[start + 1..end])));
+            }
+            _ => {
+                let mut end = start + c.len_utf8();
+                while let Some((i, c)) =
+                    chars.next_if(|(_, c)| !c.is_whitespace() && !matches!(c, '(' | ')' | '"'))
+                {
+                    end = i + c.len_utf8();
+                }
+
+                let word = &s[start..end];
+                let token = if word.eq_ignore_ascii_case("and") {
+                    Token::And
+                } else if word.eq_ignore_ascii_case("or") {
+                    Token::Or
+                } else {
+                    Token::Attr(word)
+                };
+
+                tokens.push((start, token));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Recursive descent parser for the grammar:
+///
+/// ```text
+/// expr   := term (OR term)*
+/// term   := factor (AND factor)*
+/// factor := attribute | '(' expr ')'
+/// ```
+struct Parser<'a> {
+    tokens: Vec<(usize, Token<'a>)>,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn next(&mut self) -> Result<(usize, Token<'_>), ParseError> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+
+        token.ok_or(ParseError::UnexpectedEnd)
+    }
+
+    fn eat(&mut self, expected: Token<'_>) -> bool {
+        let found = matches!(self.tokens.get(self.pos), Some((_, t)) if *t == expected);
+        if found {
+            self.pos += 1;
+        }
+
+        found
+    }
+
+    fn expr(&mut self, depth: usize) -> Result<Policy, ParseError> {
+        let mut p = self.term(depth)?;
+        while self.eat(Token::Or) {
+            p = p.or(self.term(depth)?);
+        }
+
+        Ok(p)
+    }
+
+    fn term(&mut self, depth: usize) -> Result<Policy, ParseError> {
+        let mut p = self.factor(depth)?;
+        while self.eat(Token::And) {
+            p = p.and(self.factor(depth)?);
+        }
+
+        Ok(p)
+    }
+
+    fn factor(&mut self, depth: usize) -> Result<Policy, ParseError> {
+        match self.next()? {
+            (_, Token::Attr(s)) => Ok(Policy::attr(s)),
+            (_, Token::Open) if depth < MAX_DEPTH => {
+                let p = self.expr(depth + 1)?;
+
+                match self.next()? {
+                    (_, Token::Close) => Ok(p),
+                    (offset, _) => Err(ParseError::UnexpectedToken(offset)),
+                }
+            }
+            (_, Token::Open) => Err(ParseError::TooDeep),
+            (offset, _) => Err(ParseError::UnexpectedToken(offset)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Sums the given rows of the access matrix.
+    fn sum_rows(m: &AccessMatrix, rows: &[(usize, Attribute)]) -> Vec<i8> {
+        let mut sum = vec![0i8; m.cols];
+        for &(i, _) in rows {
+            for (s, c) in sum.iter_mut().zip(m.rows[i].1.iter()) {
+                *s += c;
+            }
+        }
+
+        sum
+    }
+
+    fn attrs(names: &[&str]) -> Vec<Attribute> {
+        names.iter().map(|n| Attribute::derive_str(n)).collect()
+    }
+
+    #[test]
+    fn parse_respects_precedence() {
+        let p = Policy::parse("a AND b OR c and d").unwrap();
+        let expected = Policy::attr("a")
+            .and(Policy::attr("b"))
+            .or(Policy::attr("c").and(Policy::attr("d")));
+
+        assert_eq!(p, expected);
+        assert_eq!(p.len(), 4);
+    }
+
+    #[test]
+    fn parse_parentheses_and_quotes() {
+        let p: Policy = "email:alice AND (\"name:Alice Example\" OR phone:06)"
+            .parse()
+            .unwrap();
+        let expected = Policy::attr("email:alice")
+            .and(Policy::attr("name:Alice Example").or(Policy::attr("phone:06")));
+
+        assert_eq!(p, expected);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(Policy::parse(""), Err(ParseError::UnexpectedEnd));
+        assert_eq!(Policy::parse("a AND"), Err(ParseError::UnexpectedEnd));
+        assert_eq!(Policy::parse("a b"), Err(ParseError::UnexpectedToken(2)));
+        assert_eq!(Policy::parse("(a OR b"), Err(ParseError::UnexpectedEnd));
+        assert_eq!(
+            Policy::parse("a OR b)"),
+            Err(ParseError::UnexpectedToken(6))
+        );
+        assert_eq!(
+            Policy::parse("a OR \"b"),
+            Err(ParseError::UnterminatedQuote(5))
+        );
+
+        let deep = "(".repeat(MAX_DEPTH + 1) + "a" + &")".repeat(MAX_DEPTH + 1);
+        assert_eq!(Policy::parse(&deep), Err(ParseError::TooDeep));
+    }
+
+    #[test]
+    fn satisfying_rows_reconstruct_target() {
+        let p = Policy::parse("(a AND b AND c) OR (d AND (e OR f)) OR g").unwrap();
+        let m = p.to_lsss();
+
+        assert_eq!(m.rows.len(), p.len());
+        assert_eq!(m.cols, 4);
+
+        let mut target = vec![0i8; m.cols];
+        target[0] = 1;
+
+        for set in [&["a", "b", "c"][..], &["d", "f"], &["g"], &["a", "d", "e"]] {
+            let rows = p.satisfying_rows(&attrs(set)).unwrap();
+            assert!(rows.iter().all(|(i, a)| m.rows[*i].0 == *a));
+            assert_eq!(sum_rows(&m, &rows), target);
+        }
+
+        for set in [&["a", "b"][..], &["d"], &["e", "f"], &[]] {
+            assert!(!p.is_satisfied_by(&attrs(set)));
+        }
+    }
+
+    #[test]
+    fn satisfying_shares_reconstruct_secret() {
+        let mut rng = rand::thread_rng();
+        let p = Policy::parse("(a AND b AND c) OR (d AND (e OR f))").unwrap();
+        let m = p.to_lsss();
+
+        let secret = rand_scalar(&mut rng);
+        let shares = m.share(&secret, &mut rng);
+
+        let rows = p.satisfying_rows(&attrs(&["d", "f"])).unwrap();
+        let sum = rows
+            .iter()
+            .fold(Scalar::zero(), |acc, (i, _)| acc + shares[*i]);
+        assert_eq!(sum, secret);
+
+        let sum = shares[0] + shares[1];
+        assert_ne!(sum, secret);
+    }
+
+    #[test]
+    fn eq_serialize_deserialize() {
+        let p = Policy::parse("a AND (b OR c OR (d AND e))").unwrap();
+
+        let bytes = p.to_bytes();
+        assert_eq!(bytes.len(), p.bytes_len());
+        assert_eq!(Some(p), Policy::from_bytes(&bytes));
+
+        assert!(Policy::from_bytes(&bytes[..bytes.len() - 1]).is_none());
+    }
+}
@@ -0,0 +1,524 @@
+//! IND-sID-CPA secure fuzzy IBE by Sahai and Waters.
+//! * From: "[Fuzzy Identity-Based Encryption](https://eprint.iacr.org/2004/086.pdf)"
+//!
+//! This file contains the large universe construction in the random oracle model. An identity is
+//! a set of attributes. A user secret key for the attributes `W` can decrypt a ciphertext for the
+//! attributes `W'` whenever `|W ∩ W'| ≥ D`, which allows for error-tolerant matching of
+//! identities. The threshold `D` is fixed for the system. Attributes are hashed to G1 using
+//! RFC 9380 hash-to-curve.
+//!
+//! Ciphertexts and user secret keys grow linearly in the number of attributes.
+//! All structs' byte serialization use compression.
+
+extern crate alloc;
+use alloc::vec::Vec;
+
+use crate::abe::{Attribute, ABE};
+use crate::util::*;
+use crate::Compress;
+use pg_curve::{
+    multi_miller_loop, pairing, G1Affine, G1Projective, G2Affine, G2Prepared, G2Projective, Gt,
+    Scalar,
+};
+use rand::{CryptoRng, Rng};
+use subtle::{Choice, CtOption};
+
+/// Domain separation tag for hashing attributes to G1.
+const DST: &[u8] = b"IBE_SAHAI_WATERS_BLS12381G1_XMD:SHA-256_SSWU_RO_";
+
+/// Size of the compressed message in bytes.
+pub const MSG_BYTES: usize = GT_BYTES;
+
+/// Size of the compressed master public key in bytes.
+pub const PK_BYTES: usize = GT_BYTES;
+
+/// Size of the compressed master secret key in bytes.
+pub const SK_BYTES: usize = SCALAR_BYTES;
+
+/// Size of the compressed user secret key in bytes, without any attributes.
+pub const USK_BASE_BYTES: usize = 4;
+
+/// Size of the compressed user secret key component for a single attribute in bytes.
+pub const USK_ATTR_BYTES: usize = ID_BYTES + G1_BYTES + G2_BYTES;
+
+/// Size of the compressed ciphertext in bytes, without any attributes.
+pub const CT_BASE_BYTES: usize = G2_BYTES + GT_BYTES + 4;
+
+/// Size of the compressed ciphertext component for a single attribute in bytes.
+pub const CT_ATTR_BYTES: usize = ID_BYTES + G1_BYTES;
+
+/// Public key parameters generated by the PKG used to encrypt messages.
+/// Also known as MPK.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PublicKey {
+    y: Gt,
+}
+
+/// Secret key parameter generated by the PKG used to extract user secret keys.
+/// Also known as MSK.
+///
+/// # Zeroization
+///
+/// With the `zeroize` feature enabled this type derives `Zeroize` but **not**
+/// `ZeroizeOnDrop` (it is `Copy`). Secret material is **not** cleared on drop —
+/// you **MUST** call `.zeroize()` explicitly once done. See the
+/// [crate-level docs](crate#zeroizing-secret-material).
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize))]
+pub struct SecretKey {
+    y: Scalar,
+}
+
+/// Key component for a single attribute.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize))]
+struct AttributeKey {
+    attr: Attribute,
+    d: G1Affine,
+    r: G2Affine,
+}
+
+/// User secret key. Can be used to decrypt ciphertexts for attributes that overlap in at least
+/// `D` attributes.
+/// Also known as USK_{W}.
+///
+/// # Zeroization
+///
+/// With the `zeroize` feature enabled this type derives `Zeroize` and `ZeroizeOnDrop`.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize, zeroize::ZeroizeOnDrop))]
+pub struct UserSecretKey {
+    ks: Vec<AttributeKey>,
+}
+
+/// Encrypted message. Can only be decrypted with a user secret key for attributes that overlap in
+/// at least `D` attributes.
+/// Also known as CT_{W'}.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CipherText {
+    attrs: Vec<Attribute>,
+    e: G2Affine,
+    es: Vec<G1Affine>,
+    cprime: Gt,
+}
+
+/// A message that can be encrypted using the fuzzy IBE.
+pub type Msg = Gt;
+
+/// The Sahai-Waters fuzzy identity-based encryption scheme with threshold `D`.
+#[derive(Debug, Clone, Copy)]
+pub struct SahaiWaters<const D: usize>;
+
+fn hash_attr(attr: &Attribute) -> G1Projective {
+    hash_to_g1(&attr.0, DST)
+}
+
+impl<const D: usize> ABE for SahaiWaters<D> {
+    type Pk = PublicKey;
+    type Sk = SecretKey;
+    type Usk = UserSecretKey;
+    type Ct = CipherText;
+    type Msg = Msg;
+    type KeyAttrs = [Attribute];
+    type CtAttrs = [Attribute];
+    type RngBytes = [u8; 64];
+
+    const PK_BYTES: usize = PK_BYTES;
+    const SK_BYTES: usize = SK_BYTES;
+    const MSG_BYTES: usize = MSG_BYTES;
+
+    /// Generate a keypair used by the Private Key Generator (PKG).
+    fn setup<R: Rng + CryptoRng>(rng: &mut R) -> (PublicKey, SecretKey) {
+        let y = rand_scalar(rng);
+
+        let pk = pairing(&G1Affine::generator(), &G2Affine::generator()) * y;
+
+        (PublicKey { y: pk }, SecretKey { y })
+    }
+
+    /// Extract a user secret key for a set of attributes.
+    ///
+    /// The master secret is shared with a random polynomial of degree `D - 1`, which is evaluated
+    /// at the attributes. Repeated attributes are only included once.
+    fn extract_usk<R: Rng + CryptoRng>(
+        _pk: Option<&PublicKey>,
+        sk: &SecretKey,
+        attrs: &[Attribute],
+        rng: &mut R,
+    ) -> UserSecretKey {
+        let g1 = G1Projective::generator();
+        let g2 = G2Projective::generator();
+
+        // q(x) = y + a_1 x + ... + a_{D-1} x^{D-1}.
+        let q: Vec<Scalar> = core::iter::once(sk.y)
+            .chain((1..D).map(|_| rand_scalar(rng)))
+            .collect();
+
+        let mut unique: Vec<Attribute> = Vec::with_capacity(attrs.len());
+        for attr in attrs {
+            if !unique.contains(attr) {
+                unique.push(*attr);
+            }
+        }
+
+        let (d, r): (Vec<G1Projective>, Vec<G2Projective>) = unique
+            .iter()
+            .map(|attr| {
+                let x = attr.to_scalar();
+                let qx = q.iter().rev().fold(Scalar::zero(), |acc, a| acc * x + a);
+                let r = rand_scalar(rng);
+
+                (g1 * qx + hash_attr(attr) * r, g2 * r)
+            })
+            .unzip();
+
+        let mut d_affine = alloc::vec![G1Affine::default(); d.len()];
+        let mut r_affine = alloc::vec![G2Affine::default(); r.len()];
+        G1Projective::batch_normalize(&d, &mut d_affine);
+        G2Projective::batch_normalize(&r, &mut r_affine);
+
+        let ks = unique
+            .into_iter()
+            .zip(d_affine.into_iter().zip(r_affine))
+            .map(|(attr, (d, r))| AttributeKey { attr, d, r })
+            .collect();
+
+        UserSecretKey { ks }
+    }
+
+    /// Encrypt a message using the PKG public key for a set of attributes.
+    fn encrypt(pk: &PublicKey, attrs: &[Attribute], message: &Msg, rng: &[u8; 64]) -> CipherText {
+        let s = Scalar::from_bytes_wide(rng);
+
+        let es: Vec<G1Projective> = attrs.iter().map(|attr| hash_attr(attr) * s).collect();
+        let mut es_affine = alloc::vec![G1Affine::default(); es.len()];
+        G1Projective::batch_normalize(&es, &mut es_affine);
+
+        CipherText {
+            attrs: attrs.to_vec(),
+            e: (G2Affine::generator() * s).into(),
+            es: es_affine,
+            cprime: pk.y * s + message,
+        }
+    }
+
+    /// Derive the same message from the CipherText using a UserSecretKey.
+    ///
+    /// Returns `None` if the attributes of the user secret key and the ciphertext overlap in fewer
+    /// than `D` attributes.
+    fn decrypt(usk: &UserSecretKey, ct: &CipherText) -> Option<Msg> {
+        // Select the first D attributes in the overlap.
+        let overlap: Vec<(&AttributeKey, &G1Affine)> = usk
+            .ks
+            .iter()
+            .filter_map(|k| {
+                let j = ct.attrs.iter().position(|a| *a == k.attr)?;
+                Some((k, &ct.es[j]))
+            })
+            .take(D)
+            .collect();
+
+        if D == 0 || overlap.len() < D {
+            return None;
+        }
+
+        let xs: Vec<Scalar> = overlap.iter().map(|(k, _)| k.attr.to_scalar()).collect();
+        let coeffs = lagrange_at(&xs, &Scalar::zero())?;
+
+        let mut d = G1Projective::identity();
+        let mut es = Vec::with_capacity(D);
+        for ((k, e), delta) in overlap.iter().zip(coeffs.iter()) {
+            d += k.d * delta;
+            es.push(-(*e * delta));
+        }
+
+        let mut g1s = alloc::vec![G1Affine::default(); D + 1];
+        es.push(d);
+        G1Projective::batch_normalize(&es, &mut g1s);
+
+        let g2s: Vec<G2Prepared> = overlap
+            .iter()
+            .map(|(k, _)| G2Prepared::from(k.r))
+            .chain(core::iter::once(G2Prepared::from(ct.e)))
+            .collect();
+
+        let terms: Vec<(&G1Affine, &G2Prepared)> = g1s.iter().zip(g2s.iter()).collect();
+        let m = multi_miller_loop(&terms).final_exponentiation();
+
+        Some(ct.cprime - m)
+    }
+}
+
+impl UserSecretKey {
+    /// The attributes this user secret key was extracted for.
+    pub fn attributes(&self) -> impl Iterator<Item = &Attribute> {
+        self.ks.iter().map(|k| &k.attr)
+    }
+}
+
+impl CipherText {
+    /// The attributes this ciphertext was encrypted for.
+    pub fn attributes(&self) -> &[Attribute] {
+        &self.attrs
+    }
+}
+
+impl Compress for PublicKey {
+    const OUTPUT_SIZE: usize = PK_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; PK_BYTES] {
+        self.y.to_compressed()
+    }
+
+    fn from_bytes(bytes: &[u8; PK_BYTES]) -> CtOption<Self> {
+        Gt::from_compressed(bytes).map(|y| PublicKey { y })
+    }
+}
+
+impl Compress for SecretKey {
+    const OUTPUT_SIZE: usize = SK_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; SK_BYTES] {
+        self.y.to_bytes()
+    }
+
+    fn from_bytes(bytes: &[u8; SK_BYTES]) -> CtOption<Self> {
+        Scalar::from_bytes(bytes).map(|y| SecretKey { y })
+    }
+}
+
+impl Compress for UserSecretKey {
+    /// The size of a user secret key depends on the number of attributes, this is the size of a
+    /// user secret key without attributes.
+    const OUTPUT_SIZE: usize = USK_BASE_BYTES;
+    type Output = Vec<u8>;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut res = Vec::with_capacity(USK_BASE_BYTES + self.ks.len() * USK_ATTR_BYTES);
+
+        res.extend_from_slice(&(self.ks.len() as u32).to_be_bytes());
+        for k in self.ks.iter() {
+            res.extend_from_slice(&k.attr.0);
+            res.extend_from_slice(&k.d.to_compressed());
+            res.extend_from_slice(&k.r.to_compressed());
+        }
+
+        res
+    }
+
+    fn from_bytes(bytes: &Vec<u8>) -> CtOption<Self> {
+        let mut res = UserSecretKey::default();
+
+        let n = match bytes.get(..USK_BASE_BYTES) {
+            Some(n) => u32::from_be_bytes(n.try_into().unwrap()) as usize,
+            None => return CtOption::new(res, Choice::from(0)),
+        };
+
+        let len = n
+            .checked_mul(USK_ATTR_BYTES)
+            .and_then(|len| len.checked_add(USK_BASE_BYTES));
+        if len != Some(bytes.len()) {
+            return CtOption::new(res, Choice::from(0));
+        }
+
+        let mut is_some = Choice::from(1u8);
+        for chunk in bytes[USK_BASE_BYTES..].chunks_exact(USK_ATTR_BYTES) {
+            let (attr, rest) = chunk.split_at(ID_BYTES);
+            let (d, r) = rest.split_at(G1_BYTES);
+
+            let mut k = AttributeKey {
+                attr: Attribute(attr.try_into().unwrap()),
+                d: G1Affine::default(),
+                r: G2Affine::default(),
+            };
+            is_some &= G1Affine::from_compressed(d.try_into().unwrap())
+                .map(|el| k.d = el)
+                .is_some();
+            is_some &= G2Affine::from_compressed(r.try_into().unwrap())
+                .map(|el| k.r = el)
+                .is_some();
+
+            res.ks.push(k);
+        }
+
+        CtOption::new(res, is_some)
+    }
+}
+
+impl Compress for CipherText {
+    /// The size of a ciphertext depends on the number of attributes, this is the size of a
+    /// ciphertext without attributes.
+    const OUTPUT_SIZE: usize = CT_BASE_BYTES;
+    type Output = Vec<u8>;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut res = Vec::with_capacity(CT_BASE_BYTES + self.attrs.len() * CT_ATTR_BYTES);
+
+        res.extend_from_slice(&self.e.to_compressed());
+        res.extend_from_slice(&self.cprime.to_compressed());
+
+        res.extend_from_slice(&(self.attrs.len() as u32).to_be_bytes());
+        for (attr, e) in self.attrs.iter().zip(self.es.iter()) {
+            res.extend_from_slice(&attr.0);
+            res.extend_from_slice(&e.to_compressed());
+        }
+
+        res
+    }
+
+    fn from_bytes(bytes: &Vec<u8>) -> CtOption<Self> {
+        let mut res = CipherText {
+            attrs: Vec::new(),
+            e: G2Affine::default(),
+            es: Vec::new(),
+            cprime: Gt::default(),
+        };
+
+        let n = match bytes.get(CT_BASE_BYTES - 4..CT_BASE_BYTES) {
+            Some(n) => u32::from_be_bytes(n.try_into().unwrap()) as usize,
+            None => return CtOption::new(res, Choice::from(0)),
+        };
+
+        let len = n
+            .checked_mul(CT_ATTR_BYTES)
+            .and_then(|len| len.checked_add(CT_BASE_BYTES));
+        if len != Some(bytes.len()) {
+            return CtOption::new(res, Choice::from(0));
+        }
+
+        let (g2, rest) = bytes.split_at(G2_BYTES);
+        let (gt, rest) = rest.split_at(GT_BYTES);
+
+        // Use the checked `from_compressed` variant, which verifies that each point is in the
+        // correct prime-order subgroup (GHSA-25fp-2fjj-g84w).
+        let mut is_some = G2Affine::from_compressed(g2.try_into().unwrap())
+            .map(|el| res.e = el)
+            .is_some();
+        is_some &= Gt::from_compressed(gt.try_into().unwrap())
+            .map(|el| res.cprime = el)
+            .is_some();
+
+        for chunk in rest[4..].chunks_exact(CT_ATTR_BYTES) {
+            let (attr, e) = chunk.split_at(ID_BYTES);
+
+            let mut el = G1Affine::default();
+            is_some &= G1Affine::from_compressed(e.try_into().unwrap())
+                .map(|e| el = e)
+                .is_some();
+
+            res.attrs.push(Attribute(attr.try_into().unwrap()));
+            res.es.push(el);
+        }
+
+        CtOption::new(res, is_some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Derive;
+    use rand::RngCore;
+
+    type SahaiWaters = super::SahaiWaters<3>;
+
+    const ATTRS: [&str; 5] = [
+        "email:alice@example.com",
+        "phone:0612345678",
+        "name:Alice Example",
+        "birthdate:1990-01-01",
+        "city:Nijmegen",
+    ];
+
+    fn attrs(names: &[&str]) -> Vec<Attribute> {
+        names.iter().map(|n| Attribute::derive_str(n)).collect()
+    }
+
+    fn encrypt_random(pk: &PublicKey, attrs: &[Attribute]) -> (CipherText, Msg) {
+        let mut rng = rand::thread_rng();
+        let mut coins = [0u8; 64];
+        rng.fill_bytes(&mut coins);
+
+        let m = rand_gt(&mut rng);
+
+        (SahaiWaters::encrypt(pk, attrs, &m, &coins), m)
+    }
+
+    #[test]
+    fn eq_encrypt_decrypt_with_sufficient_overlap() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = SahaiWaters::setup(&mut rng);
+        let (ct, m) = encrypt_random(&pk, &attrs(&ATTRS));
+
+        for set in [
+            &ATTRS[..3],
+            &ATTRS[2..],
+            &[ATTRS[4], "age:34", ATTRS[0], ATTRS[1]][..],
+            &ATTRS,
+        ] {
+            let usk = SahaiWaters::extract_usk(None, &sk, &attrs(set), &mut rng);
+            assert_eq!(SahaiWaters::decrypt(&usk, &ct), Some(m));
+        }
+    }
+
+    #[test]
+    fn insufficient_overlap_does_not_decrypt() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = SahaiWaters::setup(&mut rng);
+        let (ct, _) = encrypt_random(&pk, &attrs(&ATTRS));
+
+        // Repeated attributes do not count towards the overlap.
+        let set = [ATTRS[0], ATTRS[1], ATTRS[1], "age:34"];
+        let usk = SahaiWaters::extract_usk(None, &sk, &attrs(&set), &mut rng);
+        assert_eq!(SahaiWaters::decrypt(&usk, &ct), None);
+    }
+
+    #[test]
+    fn colluding_keys_do_not_decrypt() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = SahaiWaters::setup(&mut rng);
+        let (ct, m) = encrypt_random(&pk, &attrs(&ATTRS));
+
+        // Combine the attribute keys of two users with an overlap of two attributes each.
+        let mut usk = SahaiWaters::extract_usk(None, &sk, &attrs(&ATTRS[..2]), &mut rng);
+        let other = SahaiWaters::extract_usk(None, &sk, &attrs(&ATTRS[2..4]), &mut rng);
+        usk.ks.push(other.ks[0]);
+
+        assert_ne!(SahaiWaters::decrypt(&usk, &ct), Some(m));
+    }
+
+    #[test]
+    fn eq_serialize_deserialize() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = SahaiWaters::setup(&mut rng);
+        let usk = SahaiWaters::extract_usk(None, &sk, &attrs(&ATTRS[..3]), &mut rng);
+        let (ct, _) = encrypt_random(&pk, &attrs(&ATTRS));
+
+        assert_eq!(pk, PublicKey::from_bytes(&pk.to_bytes()).unwrap());
+        assert_eq!(sk, SecretKey::from_bytes(&sk.to_bytes()).unwrap());
+        assert_eq!(usk, UserSecretKey::from_bytes(&usk.to_bytes()).unwrap());
+        assert_eq!(ct, CipherText::from_bytes(&ct.to_bytes()).unwrap());
+
+        let mut bytes = ct.to_bytes();
+        bytes.pop();
+        assert!(bool::from(CipherText::from_bytes(&bytes).is_none()));
+
+        let mut bytes = usk.to_bytes();
+        bytes.pop();
+        assert!(bool::from(UserSecretKey::from_bytes(&bytes).is_none()));
+    }
+
+    #[test]
+    fn from_bytes_rejects_non_subgroup_point() {
+        let mut rng = rand::thread_rng();
+        let (pk, _) = SahaiWaters::setup(&mut rng);
+        let (ct, _) = encrypt_random(&pk, &attrs(&ATTRS[..1]));
+
+        let mut bytes = ct.to_bytes();
+        let len = bytes.len();
+        bytes[len - G1_BYTES..].copy_from_slice(&NON_SUBGROUP_G1_COMPRESSED);
+        assert!(bool::from(CipherText::from_bytes(&bytes).is_none()));
+    }
+}
@@ -0,0 +1,131 @@
+//! This module contains identity-based authenticated key exchange protocols (ID-AKEs).
+//!
+//! In contrast to the static [`encaps`](crate::kem::IBKEM::encaps) and
+//! [`decaps`](crate::kem::IBKEM::decaps) of the KEMs, both parties contribute an ephemeral key to
+//! the session key. As a result, a session key stays secret even if the user secret keys of both
+//! parties, or the master secret key of the PKG, leak afterwards (forward secrecy).
+//!
+//! A protocol run is modelled as two explicit state machines:
+//!
+//! 1. The initiator calls [`initiate`](AKE::initiate) and sends the [`InitMsg`](AKE::InitMsg).
+//! 2. The responder calls [`respond`](AKE::respond) and sends back the
+//!    [`RespMsg`](AKE::RespMsg).
+//! 3. The initiator calls [`finish_initiator`](AKE::finish_initiator), which authenticates the
+//!    responder, outputs the session key and a [`ConfirmMsg`](AKE::ConfirmMsg).
+//! 4. The responder calls [`finish_responder`](AKE::finish_responder), which authenticates the
+//!    initiator and outputs the same session key.
+//!
+//! The states are consumed by each step, such that an ephemeral key can not be reused.
+//!
+//! Among the schemes are:
+//!
+//! - Smart-Chen-Kudla.
+
+#[cfg(feature = "sck")]
+#[cfg_attr(docsrs, doc(cfg(feature = "sck")))]
+pub mod smart_chen_kudla;
+
+use crate::kem::{AuthenticationError, SharedSecret};
+use crate::{Compress, Derive};
+use core::fmt::Debug;
+use rand::{CryptoRng, Rng};
+
+/// Identity-based authenticated key exchange (ID-AKE) with mutual key confirmation.
+pub trait AKE {
+    /// Master public key (Mpk).
+    type Pk: Compress;
+
+    /// Master secret key (Msk).
+    type Sk: Compress;
+
+    /// User secret key (Usk), used to authenticate to the other party.
+    type Usk: Compress;
+
+    /// Internal identity type (Id).
+    type Id: Copy + Derive;
+
+    /// State of the initiator while waiting for the [`RespMsg`](AKE::RespMsg).
+    type Initiator: Debug;
+
+    /// State of the responder while waiting for the [`ConfirmMsg`](AKE::ConfirmMsg).
+    type Responder: Debug;
+
+    /// First message, from the initiator to the responder.
+    type InitMsg: Compress;
+
+    /// Second message, from the responder to the initiator.
+    type RespMsg: Compress;
+
+    /// Third message, from the initiator to the responder.
+    type ConfirmMsg: Compress;
+
+    /// Size of the master public key in bytes.
+    const PK_BYTES: usize;
+
+    /// Size of the master secret key in bytes.
+    const SK_BYTES: usize;
+
+    /// Size of the user secret key in bytes.
+    const USK_BYTES: usize;
+
+    /// Size of the first message in bytes.
+    const INIT_MSG_BYTES: usize;
+
+    /// Size of the second message in bytes.
+    const RESP_MSG_BYTES: usize;
+
+    /// Size of the third message in bytes.
+    const CONFIRM_MSG_BYTES: usize;
+
+    /// Creates an MSK, MPK pair.
+    fn setup<R: Rng + CryptoRng>(rng: &mut R) -> (Self::Pk, Self::Sk);
+
+    /// Extract a user secret key for an identity using the MSK.
+    ///
+    /// Optionally requires the system's public key.
+    fn extract_usk<R: Rng + CryptoRng>(
+        pk: Option<&Self::Pk>,
+        sk: &Self::Sk,
+        id: &Self::Id,
+        rng: &mut R,
+    ) -> Self::Usk;
+
+    /// Start a protocol run with `peer` as the initiator.
+    fn initiate<R: Rng + CryptoRng>(
+        pk: &Self::Pk,
+        usk: &Self::Usk,
+        peer: &Self::Id,
+        rng: &mut R,
+    ) -> (Self::Initiator, Self::InitMsg);
+
+    /// Respond to a protocol run as the responder.
+    ///
+    /// The identity of the initiator is part of the first message. The caller must check that it
+    /// is willing to establish a session with this identity.
+    ///
+    /// Fails if the first message is malformed.
+    fn respond<R: Rng + CryptoRng>(
+        pk: &Self::Pk,
+        usk: &Self::Usk,
+        msg: &Self::InitMsg,
+        rng: &mut R,
+    ) -> Result<(Self::Responder, Self::RespMsg), AuthenticationError>;
+
+    /// Finish a protocol run as the initiator.
+    ///
+    /// Fails if the responder can not be authenticated as the owner of the user secret key of
+    /// the peer.
+    fn finish_initiator(
+        state: Self::Initiator,
+        msg: &Self::RespMsg,
+    ) -> Result<(Self::ConfirmMsg, SharedSecret), AuthenticationError>;
+
+    /// Finish a protocol run as the responder.
+    ///
+    /// Fails if the initiator can not be authenticated as the owner of the user secret key of
+    /// the identity in the first message.
+    fn finish_responder(
+        state: Self::Responder,
+        msg: &Self::ConfirmMsg,
+    ) -> Result<SharedSecret, AuthenticationError>;
+}
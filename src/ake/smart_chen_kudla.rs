@@ -0,0 +1,510 @@
+//! Identity-based authenticated key exchange by Smart, modified by Chen and Kudla (SCK).
+//! * From: "[Identity Based Authenticated Key Agreement Protocols from Pairings](https://eprint.iacr.org/2002/184.pdf)"
+//! * Published in: CSFW, 2003
+//!
+//! Both parties exchange an ephemeral T_A = a·P and T_B = b·P in G1, and compute the shared
+//! pairing value K = e(P_pub, a·Q_B)·e(T_B, d_A) = e(P_pub, b·Q_A)·e(T_A, d_B). The modification
+//! by Chen and Kudla adds the Diffie-Hellman value a·b·P to the key derivation, such that the
+//! session key also stays secret when the master secret key leaks (master key forward secrecy).
+//!
+//! Identities are hashed to G2 using the hash-to-curve construction of RFC 9380, such that the
+//! ephemeral keys live in the (smaller) G1 group. The session key and the key confirmation tags
+//! are derived using SHAKE256 over the identities, the ephemeral keys, K and a·b·P.
+
+use crate::ake::AKE;
+use crate::kem::{AuthenticationError, SharedSecret, SS_BYTES};
+use crate::util::*;
+use crate::Compress;
+use arrayref::{array_refs, mut_array_refs};
+use pg_curve::{multi_miller_loop, G1Affine, G2Affine, G2Prepared, Gt, Scalar};
+use rand::{CryptoRng, Rng};
+use subtle::{Choice, ConstantTimeEq, CtOption};
+
+/// Domain separation tag used to hash identities to G2.
+const DST: &[u8] = b"AKE_SCK_BLS12381G2_XMD:SHA-256_SSWU_RO_";
+
+/// Size of a key confirmation tag in bytes.
+const TAG_BYTES: usize = 32;
+
+/// Size of the compressed master public key in bytes.
+pub const PK_BYTES: usize = G1_BYTES;
+
+/// Size of the compressed master secret key in bytes.
+pub const SK_BYTES: usize = SCALAR_BYTES;
+
+/// Size of the compressed user secret key in bytes.
+///
+/// The USK includes the identity (needed to derive the session key).
+pub const USK_BYTES: usize = ID_BYTES + G2_BYTES;
+
+/// Size of the compressed first message in bytes.
+pub const INIT_MSG_BYTES: usize = ID_BYTES + G1_BYTES;
+
+/// Size of the compressed second message in bytes.
+pub const RESP_MSG_BYTES: usize = G1_BYTES + TAG_BYTES;
+
+/// Size of the compressed third message in bytes.
+pub const CONFIRM_MSG_BYTES: usize = TAG_BYTES;
+
+/// Public key parameters generated by the PKG used to authenticate the other party.
+/// Also known as P_pub.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct PublicKey {
+    ppub: G1Affine,
+}
+
+/// Secret key parameter generated by the PKG used to extract user secret keys.
+///
+/// # Zeroization
+///
+/// With the `zeroize` feature enabled this type derives `Zeroize` but **not**
+/// `ZeroizeOnDrop` (it is `Copy`). Secret material is **not** cleared on drop —
+/// you **MUST** call `.zeroize()` explicitly once done. See the
+/// [crate-level docs](crate#zeroizing-secret-material).
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize))]
+pub struct SecretKey {
+    s: Scalar,
+}
+
+/// User secret key. Can be used to authenticate as its identity in a protocol run.
+/// Also known as d_id.
+///
+/// # Zeroization
+///
+/// With the `zeroize` feature enabled this type derives `Zeroize` but **not**
+/// `ZeroizeOnDrop` (it is `Copy`). Secret material is **not** cleared on drop —
+/// you **MUST** call `.zeroize()` explicitly once done. See the
+/// [crate-level docs](crate#zeroizing-secret-material).
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize))]
+pub struct UserSecretKey {
+    id: Identity,
+    d: G2Affine,
+}
+
+/// First message, containing the identity and the ephemeral key of the initiator.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InitMessage {
+    id: Identity,
+    t: G1Affine,
+}
+
+/// Second message, containing the ephemeral key of the responder and its key confirmation tag.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct RespMessage {
+    t: G1Affine,
+    tag: [u8; TAG_BYTES],
+}
+
+/// Third message, containing the key confirmation tag of the initiator.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct ConfirmMessage {
+    tag: [u8; TAG_BYTES],
+}
+
+/// State of the initiator, holding its ephemeral secret.
+///
+/// # Zeroization
+///
+/// With the `zeroize` feature enabled this type derives `Zeroize` and `ZeroizeOnDrop`.
+#[derive(Debug)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize, zeroize::ZeroizeOnDrop))]
+pub struct Initiator {
+    usk: UserSecretKey,
+    peer: Identity,
+    a: Scalar,
+    t: G1Affine,
+    k: Gt,
+}
+
+/// State of the responder, holding the session key until the initiator is authenticated.
+///
+/// # Zeroization
+///
+/// With the `zeroize` feature enabled this type derives `Zeroize` and `ZeroizeOnDrop`.
+#[derive(Debug)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize, zeroize::ZeroizeOnDrop))]
+pub struct Responder {
+    ss: SharedSecret,
+    tag: [u8; TAG_BYTES],
+}
+
+/// The Smart-Chen-Kudla identity-based authenticated key exchange.
+#[derive(Debug)]
+pub struct SCK;
+
+/// Hashes an identity to a point on G2, also known as Q_id.
+fn hash_to_point(id: &Identity) -> G2Affine {
+    hash_to_g2(&id.0, DST).into()
+}
+
+/// Derives the session key and the key confirmation tags of the responder and the initiator.
+fn derive(
+    initiator: &Identity,
+    responder: &Identity,
+    ta: &G1Affine,
+    tb: &G1Affine,
+    k: &Gt,
+    dh: &G1Affine,
+) -> (SharedSecret, [u8; TAG_BYTES], [u8; TAG_BYTES]) {
+    let mut pre = [0u8; 2 * ID_BYTES + 3 * G1_BYTES + GT_BYTES];
+    let (pi, pr, pta, ptb, pk, pdh) =
+        mut_array_refs![&mut pre, ID_BYTES, ID_BYTES, G1_BYTES, G1_BYTES, GT_BYTES, G1_BYTES];
+
+    *pi = initiator.0;
+    *pr = responder.0;
+    *pta = ta.to_compressed();
+    *ptb = tb.to_compressed();
+    *pk = k.to_compressed();
+    *pdh = dh.to_compressed();
+
+    let okm = shake256::<{ SS_BYTES + 2 * TAG_BYTES }>(&pre);
+    let (ss, tag_r, tag_i) = array_refs![&okm, SS_BYTES, TAG_BYTES, TAG_BYTES];
+
+    (SharedSecret(*ss), *tag_r, *tag_i)
+}
+
+impl AKE for SCK {
+    type Pk = PublicKey;
+    type Sk = SecretKey;
+    type Usk = UserSecretKey;
+    type Id = Identity;
+    type Initiator = Initiator;
+    type Responder = Responder;
+    type InitMsg = InitMessage;
+    type RespMsg = RespMessage;
+    type ConfirmMsg = ConfirmMessage;
+
+    const PK_BYTES: usize = PK_BYTES;
+    const SK_BYTES: usize = SK_BYTES;
+    const USK_BYTES: usize = USK_BYTES;
+    const INIT_MSG_BYTES: usize = INIT_MSG_BYTES;
+    const RESP_MSG_BYTES: usize = RESP_MSG_BYTES;
+    const CONFIRM_MSG_BYTES: usize = CONFIRM_MSG_BYTES;
+
+    /// Generate a keypair used by the Private Key Generator (PKG).
+    fn setup<R: Rng + CryptoRng>(rng: &mut R) -> (PublicKey, SecretKey) {
+        let s = rand_scalar(rng);
+        let ppub = (G1Affine::generator() * s).into();
+
+        (PublicKey { ppub }, SecretKey { s })
+    }
+
+    /// Extract a user secret key for a given identity.
+    fn extract_usk<R: Rng + CryptoRng>(
+        _opk: Option<&PublicKey>,
+        sk: &SecretKey,
+        id: &Identity,
+        _rng: &mut R,
+    ) -> UserSecretKey {
+        UserSecretKey {
+            id: *id,
+            d: (hash_to_point(id) * sk.s).into(),
+        }
+    }
+
+    /// Generate the ephemeral T_A = a·P and precompute e(P_pub, a·Q_B).
+    fn initiate<R: Rng + CryptoRng>(
+        pk: &PublicKey,
+        usk: &UserSecretKey,
+        peer: &Identity,
+        rng: &mut R,
+    ) -> (Initiator, InitMessage) {
+        let a = rand_scalar(rng);
+        let t = (G1Affine::generator() * a).into();
+        let aq = G2Affine::from(hash_to_point(peer) * a);
+        let k = multi_miller_loop(&[(&pk.ppub, &G2Prepared::from(aq))]).final_exponentiation();
+
+        let state = Initiator {
+            usk: *usk,
+            peer: *peer,
+            a,
+            t,
+            k,
+        };
+
+        (state, InitMessage { id: usk.id, t })
+    }
+
+    /// Generate the ephemeral T_B = b·P, derive the session key and the confirmation tag of the
+    /// responder.
+    fn respond<R: Rng + CryptoRng>(
+        pk: &PublicKey,
+        usk: &UserSecretKey,
+        msg: &InitMessage,
+        rng: &mut R,
+    ) -> Result<(Responder, RespMessage), AuthenticationError> {
+        if bool::from(msg.t.is_identity()) {
+            return Err(AuthenticationError);
+        }
+
+        let b = rand_scalar(rng);
+        let t = (G1Affine::generator() * b).into();
+        let bq = G2Affine::from(hash_to_point(&msg.id) * b);
+
+        let k = multi_miller_loop(&[
+            (&pk.ppub, &G2Prepared::from(bq)),
+            (&msg.t, &G2Prepared::from(usk.d)),
+        ])
+        .final_exponentiation();
+        let dh = (msg.t * b).into();
+
+        let (ss, tag_r, tag_i) = derive(&msg.id, &usk.id, &msg.t, &t, &k, &dh);
+
+        Ok((Responder { ss, tag: tag_i }, RespMessage { t, tag: tag_r }))
+    }
+
+    /// Complete K with e(T_B, d_A), derive the session key and check the confirmation tag of the
+    /// responder.
+    fn finish_initiator(
+        state: Initiator,
+        msg: &RespMessage,
+    ) -> Result<(ConfirmMessage, SharedSecret), AuthenticationError> {
+        if bool::from(msg.t.is_identity()) {
+            return Err(AuthenticationError);
+        }
+
+        let k = state.k
+            + multi_miller_loop(&[(&msg.t, &G2Prepared::from(state.usk.d))]).final_exponentiation();
+        let dh = (msg.t * state.a).into();
+
+        let (ss, tag_r, tag_i) = derive(&state.usk.id, &state.peer, &state.t, &msg.t, &k, &dh);
+
+        if bool::from(tag_r.ct_eq(&msg.tag)) {
+            Ok((ConfirmMessage { tag: tag_i }, ss))
+        } else {
+            Err(AuthenticationError)
+        }
+    }
+
+    /// Check the confirmation tag of the initiator and release the session key.
+    fn finish_responder(
+        state: Responder,
+        msg: &ConfirmMessage,
+    ) -> Result<SharedSecret, AuthenticationError> {
+        if bool::from(state.tag.ct_eq(&msg.tag)) {
+            Ok(state.ss)
+        } else {
+            Err(AuthenticationError)
+        }
+    }
+}
+
+impl Compress for PublicKey {
+    const OUTPUT_SIZE: usize = PK_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; PK_BYTES] {
+        self.ppub.to_compressed()
+    }
+
+    fn from_bytes(bytes: &[u8; PK_BYTES]) -> CtOption<Self> {
+        G1Affine::from_compressed(bytes).map(|ppub| PublicKey { ppub })
+    }
+}
+
+impl Compress for SecretKey {
+    const OUTPUT_SIZE: usize = SK_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; SK_BYTES] {
+        self.s.to_bytes()
+    }
+
+    fn from_bytes(bytes: &[u8; SK_BYTES]) -> CtOption<Self> {
+        Scalar::from_bytes(bytes).map(|s| SecretKey { s })
+    }
+}
+
+impl Compress for UserSecretKey {
+    const OUTPUT_SIZE: usize = USK_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; USK_BYTES] {
+        let mut buf = [0u8; USK_BYTES];
+        let (id, d) = mut_array_refs![&mut buf, ID_BYTES, G2_BYTES];
+
+        *id = self.id.0;
+        *d = self.d.to_compressed();
+
+        buf
+    }
+
+    fn from_bytes(bytes: &[u8; USK_BYTES]) -> CtOption<Self> {
+        let (id, d) = array_refs![bytes, ID_BYTES, G2_BYTES];
+
+        G2Affine::from_compressed(d).map(|d| UserSecretKey {
+            id: Identity(*id),
+            d,
+        })
+    }
+}
+
+impl Compress for InitMessage {
+    const OUTPUT_SIZE: usize = INIT_MSG_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; INIT_MSG_BYTES] {
+        let mut buf = [0u8; INIT_MSG_BYTES];
+        let (id, t) = mut_array_refs![&mut buf, ID_BYTES, G1_BYTES];
+
+        *id = self.id.0;
+        *t = self.t.to_compressed();
+
+        buf
+    }
+
+    fn from_bytes(bytes: &[u8; INIT_MSG_BYTES]) -> CtOption<Self> {
+        let (id, t) = array_refs![bytes, ID_BYTES, G1_BYTES];
+
+        // Use the checked `from_compressed` variant, which verifies that the
+        // point is in the correct prime-order subgroup (GHSA-25fp-2fjj-g84w).
+        G1Affine::from_compressed(t).map(|t| InitMessage {
+            id: Identity(*id),
+            t,
+        })
+    }
+}
+
+impl Compress for RespMessage {
+    const OUTPUT_SIZE: usize = RESP_MSG_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; RESP_MSG_BYTES] {
+        let mut buf = [0u8; RESP_MSG_BYTES];
+        let (t, tag) = mut_array_refs![&mut buf, G1_BYTES, TAG_BYTES];
+
+        *t = self.t.to_compressed();
+        *tag = self.tag;
+
+        buf
+    }
+
+    fn from_bytes(bytes: &[u8; RESP_MSG_BYTES]) -> CtOption<Self> {
+        let (t, tag) = array_refs![bytes, G1_BYTES, TAG_BYTES];
+
+        G1Affine::from_compressed(t).map(|t| RespMessage { t, tag: *tag })
+    }
+}
+
+impl Compress for ConfirmMessage {
+    const OUTPUT_SIZE: usize = CONFIRM_MSG_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; CONFIRM_MSG_BYTES] {
+        self.tag
+    }
+
+    fn from_bytes(bytes: &[u8; CONFIRM_MSG_BYTES]) -> CtOption<Self> {
+        CtOption::new(ConfirmMessage { tag: *bytes }, Choice::from(1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Derive;
+
+    const ALICE: &str = "email:alice@example.com";
+    const BOB: &str = "email:bob@example.com";
+    const CHARLIE: &str = "email:charlie@example.com";
+
+    #[test]
+    fn eq_initiator_responder() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = SCK::setup(&mut rng);
+
+        let (alice, bob) = (Identity::derive_str(ALICE), Identity::derive_str(BOB));
+        let usk_alice = SCK::extract_usk(None, &sk, &alice, &mut rng);
+        let usk_bob = SCK::extract_usk(None, &sk, &bob, &mut rng);
+
+        let (initiator, m1) = SCK::initiate(&pk, &usk_alice, &bob, &mut rng);
+        let (responder, m2) = SCK::respond(&pk, &usk_bob, &m1, &mut rng).unwrap();
+        let (m3, k1) = SCK::finish_initiator(initiator, &m2).unwrap();
+        let k2 = SCK::finish_responder(responder, &m3).unwrap();
+
+        assert_eq!(k1, k2);
+    }
+
+    #[test]
+    fn session_keys_are_fresh() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = SCK::setup(&mut rng);
+
+        let (alice, bob) = (Identity::derive_str(ALICE), Identity::derive_str(BOB));
+        let usk_alice = SCK::extract_usk(None, &sk, &alice, &mut rng);
+        let usk_bob = SCK::extract_usk(None, &sk, &bob, &mut rng);
+
+        let mut run = || {
+            let (initiator, m1) = SCK::initiate(&pk, &usk_alice, &bob, &mut rng);
+            let (_, m2) = SCK::respond(&pk, &usk_bob, &m1, &mut rng).unwrap();
+            SCK::finish_initiator(initiator, &m2).unwrap().1
+        };
+
+        assert_ne!(run(), run());
+    }
+
+    #[test]
+    fn rejects_impersonation() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = SCK::setup(&mut rng);
+
+        let (alice, bob) = (Identity::derive_str(ALICE), Identity::derive_str(BOB));
+        let charlie = Identity::derive_str(CHARLIE);
+        let usk_alice = SCK::extract_usk(None, &sk, &alice, &mut rng);
+        let usk_bob = SCK::extract_usk(None, &sk, &bob, &mut rng);
+        let usk_charlie = SCK::extract_usk(None, &sk, &charlie, &mut rng);
+
+        // Charlie answers a protocol run that Alice started with Bob.
+        let (initiator, m1) = SCK::initiate(&pk, &usk_alice, &bob, &mut rng);
+        let (_, m2) = SCK::respond(&pk, &usk_charlie, &m1, &mut rng).unwrap();
+        assert_eq!(
+            SCK::finish_initiator(initiator, &m2),
+            Err(AuthenticationError)
+        );
+
+        // Charlie claims to be Alice in a protocol run with Bob.
+        let (initiator, mut m1) = SCK::initiate(&pk, &usk_charlie, &bob, &mut rng);
+        m1.id = alice;
+        let (responder, m2) = SCK::respond(&pk, &usk_bob, &m1, &mut rng).unwrap();
+        assert!(SCK::finish_initiator(initiator, &m2).is_err());
+        let m3 = ConfirmMessage { tag: m2.tag };
+        assert!(SCK::finish_responder(responder, &m3).is_err());
+
+        // The identity element is not accepted as an ephemeral key.
+        let (_, mut m1) = SCK::initiate(&pk, &usk_alice, &bob, &mut rng);
+        m1.t = G1Affine::identity();
+        assert!(SCK::respond(&pk, &usk_bob, &m1, &mut rng).is_err());
+    }
+
+    #[test]
+    fn eq_serialize_deserialize() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = SCK::setup(&mut rng);
+
+        let (alice, bob) = (Identity::derive_str(ALICE), Identity::derive_str(BOB));
+        let usk_alice = SCK::extract_usk(None, &sk, &alice, &mut rng);
+        let usk_bob = SCK::extract_usk(None, &sk, &bob, &mut rng);
+
+        let (initiator, m1) = SCK::initiate(&pk, &usk_alice, &bob, &mut rng);
+        let m1 = InitMessage::from_bytes(&m1.to_bytes()).unwrap();
+        let (responder, m2) = SCK::respond(&pk, &usk_bob, &m1, &mut rng).unwrap();
+        let m2 = RespMessage::from_bytes(&m2.to_bytes()).unwrap();
+        let (m3, k1) = SCK::finish_initiator(initiator, &m2).unwrap();
+        let m3 = ConfirmMessage::from_bytes(&m3.to_bytes()).unwrap();
+        let k2 = SCK::finish_responder(responder, &m3).unwrap();
+
+        assert_eq!(k1, k2);
+        assert_eq!(pk, PublicKey::from_bytes(&pk.to_bytes()).unwrap());
+        assert_eq!(sk, SecretKey::from_bytes(&sk.to_bytes()).unwrap());
+        assert_eq!(
+            usk_alice,
+            UserSecretKey::from_bytes(&usk_alice.to_bytes()).unwrap()
+        );
+
+        let mut bytes = m1.to_bytes();
+        bytes[ID_BYTES..].copy_from_slice(&NON_SUBGROUP_G1_COMPRESSED);
+        assert!(bool::from(InitMessage::from_bytes(&bytes).is_none()));
+    }
+}
@@ -0,0 +1,761 @@
+//! IND-sRID-CPA secure revocable IBE following Boldyreva, Goyal and Kumar.
+//! * From: "[Identity-based Encryption with Efficient Revocation](https://eprint.iacr.org/2012/052.pdf)"
+//! * Published in: CCS, 2008
+//!
+//! Users are assigned to the leaves of a complete binary tree of depth `D`. The long-term user
+//! secret key contains a key for every node on the path from its leaf to the root, and every epoch
+//! the PKG publishes a key update for the minimal set of nodes that covers all non-revoked leaves.
+//! With `r` revoked users, a key update contains at most `r · log(N / r)` node keys for `N`
+//! leaves, and only a single node key when no user is revoked.
+//!
+//! Instead of the fuzzy IBE of the paper, the identity and epoch are encoded using two
+//! Boneh-Boyen style hashes, as suggested by Libert and Vergnaud. The random per-node secrets are
+//! derived from a seed in the master secret key, such that the PKG does not need to store them.
+//!
+//! All structs' byte serialization use compression.
+
+extern crate alloc;
+use alloc::vec::Vec;
+
+use crate::ibe::RIBE;
+use crate::util::*;
+use crate::Compress;
+use arrayref::{array_refs, mut_array_refs};
+use pg_curve::{
+    multi_miller_loop, pairing, G1Affine, G2Affine, G2Prepared, G2Projective, Gt, Scalar,
+};
+use rand::{CryptoRng, Rng};
+use subtle::{Choice, CtOption};
+
+/// Size of the compressed message in bytes.
+pub const MSG_BYTES: usize = GT_BYTES;
+
+/// Size of the compressed master public key in bytes.
+pub const PK_BYTES: usize = 4 * G1_BYTES + GT_BYTES;
+
+/// Size of the compressed master secret key in bytes.
+pub const SK_BYTES: usize = 5 * SCALAR_BYTES + SEED_BYTES;
+
+/// Size of the compressed user secret key in bytes, without any node keys.
+pub const USK_BASE_BYTES: usize = 8;
+
+/// Size of the compressed key update in bytes, without any node keys.
+pub const KU_BASE_BYTES: usize = 12;
+
+/// Size of a compressed node key of a user secret key in bytes.
+pub const USK_NODE_BYTES: usize = 2 * G2_BYTES;
+
+/// Size of a compressed node key of a key update in bytes.
+pub const KU_NODE_BYTES: usize = 8 + 2 * G2_BYTES;
+
+/// Size of the compressed decryption key in bytes.
+pub const DK_BYTES: usize = 3 * G2_BYTES;
+
+/// Size of the compressed ciphertext in bytes.
+pub const CT_BYTES: usize = 3 * G1_BYTES + GT_BYTES;
+
+/// Size of the seed from which the per-node secrets are derived.
+const SEED_BYTES: usize = 32;
+
+/// Public key parameters generated by the PKG used to encrypt messages.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct PublicKey {
+    u0: G1Affine,
+    u1: G1Affine,
+    h0: G1Affine,
+    h1: G1Affine,
+    z: Gt,
+}
+
+/// Secret key parameter generated by the PKG used to extract user secret keys and key updates.
+///
+/// # Zeroization
+///
+/// With the `zeroize` feature enabled this type derives `Zeroize` but **not**
+/// `ZeroizeOnDrop` (it is `Copy`). Secret material is **not** cleared on drop —
+/// you **MUST** call `.zeroize()` explicitly once done. See the
+/// [crate-level docs](crate#zeroizing-secret-material).
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize))]
+pub struct SecretKey {
+    alpha: Scalar,
+    a0: Scalar,
+    a1: Scalar,
+    b0: Scalar,
+    b1: Scalar,
+    seed: [u8; SEED_BYTES],
+}
+
+/// Key for a single node of the binary tree.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize))]
+struct NodeKey {
+    node: u64,
+    d: G2Affine,
+    r: G2Affine,
+}
+
+/// Long-term user secret key, containing the node keys on the path from its leaf to the root.
+/// Can be combined with a key update into a decryption key.
+///
+/// # Zeroization
+///
+/// With the `zeroize` feature enabled this type derives `Zeroize` and `ZeroizeOnDrop`.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize, zeroize::ZeroizeOnDrop))]
+pub struct UserSecretKey {
+    leaf: u64,
+    ks: Vec<NodeKey>,
+}
+
+/// Key update for a single epoch, containing the node keys that cover all non-revoked leaves.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyUpdate {
+    epoch: u64,
+    ks: Vec<NodeKey>,
+}
+
+/// Decryption key for an identity and a single epoch.
+///
+/// # Zeroization
+///
+/// With the `zeroize` feature enabled this type derives `Zeroize` but **not**
+/// `ZeroizeOnDrop` (it is `Copy`). Secret material is **not** cleared on drop —
+/// you **MUST** call `.zeroize()` explicitly once done. See the
+/// [crate-level docs](crate#zeroizing-secret-material).
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize))]
+pub struct DecryptionKey {
+    k: G2Affine,
+    r: G2Affine,
+    s: G2Affine,
+}
+
+/// Encrypted message. Can only be decrypted with a decryption key for the same identity and epoch.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct CipherText {
+    c0: G1Affine,
+    c1: G1Affine,
+    c2: G1Affine,
+    cprime: Gt,
+}
+
+/// A message that can be encrypted using the RIBE.
+pub type Msg = Gt;
+
+/// The Boldyreva-Goyal-Kumar revocable identity-based encryption scheme for a binary tree of
+/// depth `D`, i.e., for at most `2^D` user secret keys.
+#[derive(Debug)]
+pub struct BoldyrevaGoyalKumar<const D: usize>;
+
+/// Derives the secret of a node from the seed in the master secret key.
+fn node_secret(sk: &SecretKey, node: u64) -> Scalar {
+    let mut pre = [0u8; SEED_BYTES + 8];
+    let (seed, n) = mut_array_refs![&mut pre, SEED_BYTES, 8];
+
+    *seed = sk.seed;
+    *n = node.to_be_bytes();
+
+    Scalar::from_bytes_wide(&sha3_512(&pre))
+}
+
+/// Computes the minimal set of nodes that covers all leaves that are not revoked.
+///
+/// The nodes are numbered in heap order: the root is node 1, the children of node `x` are `2x`
+/// and `2x + 1`, and leaf `i` is node `2^D + i`.
+fn cover<const D: usize>(revoked: &[u64]) -> Vec<u64> {
+    let mut marked: Vec<u64> = Vec::new();
+    for leaf in revoked.iter().filter(|leaf| **leaf < 1 << D) {
+        let mut node = (1 << D) + leaf;
+        while node > 0 {
+            marked.push(node);
+            node >>= 1;
+        }
+    }
+    marked.sort_unstable();
+    marked.dedup();
+
+    if marked.is_empty() {
+        return alloc::vec![1];
+    }
+
+    let mut nodes: Vec<u64> = marked
+        .iter()
+        .filter(|node| **node < 1 << D)
+        .flat_map(|node| [2 * node, 2 * node + 1])
+        .filter(|child| marked.binary_search(child).is_err())
+        .collect();
+    nodes.sort_unstable();
+
+    nodes
+}
+
+impl<const D: usize> RIBE for BoldyrevaGoyalKumar<D> {
+    type Pk = PublicKey;
+    type Sk = SecretKey;
+    type Usk = UserSecretKey;
+    type KeyUpdate = KeyUpdate;
+    type Dk = DecryptionKey;
+    type Ct = CipherText;
+    type Msg = Msg;
+    type Id = Identity;
+    type RngBytes = [u8; 64];
+
+    const PK_BYTES: usize = PK_BYTES;
+    const SK_BYTES: usize = SK_BYTES;
+    const DK_BYTES: usize = DK_BYTES;
+    const CT_BYTES: usize = CT_BYTES;
+    const MSG_BYTES: usize = MSG_BYTES;
+    const MAX_USERS: u64 = {
+        assert!(D < 63, "D must be smaller than 63");
+        1 << D
+    };
+
+    /// Generate a keypair used by the Private Key Generator (PKG).
+    fn setup<R: Rng + CryptoRng>(rng: &mut R) -> (PublicKey, SecretKey) {
+        let g = G1Affine::generator();
+
+        let mut sk = SecretKey {
+            alpha: rand_scalar(rng),
+            a0: rand_scalar(rng),
+            a1: rand_scalar(rng),
+            b0: rand_scalar(rng),
+            b1: rand_scalar(rng),
+            seed: [0u8; SEED_BYTES],
+        };
+        rng.fill_bytes(&mut sk.seed);
+
+        let pk = PublicKey {
+            u0: (g * sk.a0).into(),
+            u1: (g * sk.a1).into(),
+            h0: (g * sk.b0).into(),
+            h1: (g * sk.b1).into(),
+            z: pairing(&g, &G2Affine::generator()) * sk.alpha,
+        };
+
+        (pk, sk)
+    }
+
+    /// Extract a user secret key for an identity, assigned to a leaf of the binary tree.
+    fn extract_usk<R: Rng + CryptoRng>(
+        _pk: Option<&PublicKey>,
+        sk: &SecretKey,
+        v: &Identity,
+        leaf: u64,
+        rng: &mut R,
+    ) -> Option<UserSecretKey> {
+        if leaf >= Self::MAX_USERS {
+            return None;
+        }
+
+        let h = G2Affine::generator();
+        let id = sk.a0 + sk.a1 * v.to_scalar();
+
+        let ks = (0..=D)
+            .map(|i| {
+                let node = ((1 << D) + leaf) >> i;
+                let r = rand_scalar(rng);
+
+                NodeKey {
+                    node,
+                    d: (h * (node_secret(sk, node) + r * id)).into(),
+                    r: (h * r).into(),
+                }
+            })
+            .collect();
+
+        Some(UserSecretKey { leaf, ks })
+    }
+
+    /// Generate the key update for an epoch. Leaves that do not exist are ignored.
+    fn update_key<R: Rng + CryptoRng>(
+        _pk: Option<&PublicKey>,
+        sk: &SecretKey,
+        epoch: u64,
+        revoked: &[u64],
+        rng: &mut R,
+    ) -> KeyUpdate {
+        let h = G2Affine::generator();
+        let t = sk.b0 + sk.b1 * Scalar::from(epoch);
+
+        let ks = cover::<D>(revoked)
+            .into_iter()
+            .map(|node| {
+                let s = rand_scalar(rng);
+
+                NodeKey {
+                    node,
+                    d: (h * (sk.alpha - node_secret(sk, node) + s * t)).into(),
+                    r: (h * s).into(),
+                }
+            })
+            .collect();
+
+        KeyUpdate { epoch, ks }
+    }
+
+    /// Combine the node keys of the user secret key and the key update for the first node they
+    /// have in common.
+    fn derive_dk(usk: &UserSecretKey, ku: &KeyUpdate) -> Option<DecryptionKey> {
+        usk.ks.iter().find_map(|uk| {
+            let kk = ku.ks.iter().find(|kk| kk.node == uk.node)?;
+
+            Some(DecryptionKey {
+                k: (G2Projective::from(uk.d) + kk.d).into(),
+                r: uk.r,
+                s: kk.r,
+            })
+        })
+    }
+
+    /// Encrypt a message using the PKG public key for an identity and an epoch.
+    fn encrypt(
+        pk: &PublicKey,
+        v: &Identity,
+        epoch: u64,
+        message: &Msg,
+        rng: &[u8; 64],
+    ) -> CipherText {
+        let s = Scalar::from_bytes_wide(rng);
+        let id = v.to_scalar();
+        let t = Scalar::from(epoch);
+
+        CipherText {
+            c0: (G1Affine::generator() * s).into(),
+            c1: (pk.u0 * s + pk.u1 * (s * id)).into(),
+            c2: (pk.h0 * s + pk.h1 * (s * t)).into(),
+            cprime: pk.z * s + message,
+        }
+    }
+
+    /// Derive the same message from the CipherText using a DecryptionKey.
+    fn decrypt(dk: &DecryptionKey, ct: &CipherText) -> Msg {
+        let m = multi_miller_loop(&[
+            (&ct.c0, &G2Prepared::from(dk.k)),
+            (&-ct.c1, &G2Prepared::from(dk.r)),
+            (&-ct.c2, &G2Prepared::from(dk.s)),
+        ])
+        .final_exponentiation();
+
+        ct.cprime - m
+    }
+}
+
+impl UserSecretKey {
+    /// The leaf of the binary tree this user secret key is assigned to.
+    pub fn leaf(&self) -> u64 {
+        self.leaf
+    }
+}
+
+impl KeyUpdate {
+    /// The epoch of this key update.
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    /// The number of node keys in this key update.
+    pub fn len(&self) -> usize {
+        self.ks.len()
+    }
+
+    /// Whether this key update contains no node keys, i.e., all leaves are revoked.
+    pub fn is_empty(&self) -> bool {
+        self.ks.is_empty()
+    }
+}
+
+/// Reads a node key from a chunk, without the node index.
+fn read_node_key(node: u64, chunk: &[u8], is_some: &mut Choice) -> NodeKey {
+    let (d, r) = chunk.split_at(G2_BYTES);
+
+    let mut k = NodeKey {
+        node,
+        d: G2Affine::default(),
+        r: G2Affine::default(),
+    };
+    *is_some &= G2Affine::from_compressed(d.try_into().unwrap())
+        .map(|el| k.d = el)
+        .is_some();
+    *is_some &= G2Affine::from_compressed(r.try_into().unwrap())
+        .map(|el| k.r = el)
+        .is_some();
+
+    k
+}
+
+impl Compress for PublicKey {
+    const OUTPUT_SIZE: usize = PK_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; PK_BYTES] {
+        let mut buf = [0u8; PK_BYTES];
+        let (u0, u1, h0, h1, z) =
+            mut_array_refs![&mut buf, G1_BYTES, G1_BYTES, G1_BYTES, G1_BYTES, GT_BYTES];
+
+        *u0 = self.u0.to_compressed();
+        *u1 = self.u1.to_compressed();
+        *h0 = self.h0.to_compressed();
+        *h1 = self.h1.to_compressed();
+        *z = self.z.to_compressed();
+
+        buf
+    }
+
+    fn from_bytes(bytes: &[u8; PK_BYTES]) -> CtOption<Self> {
+        let (u0, u1, h0, h1, z) =
+            array_refs![bytes, G1_BYTES, G1_BYTES, G1_BYTES, G1_BYTES, GT_BYTES];
+
+        let u0 = G1Affine::from_compressed(u0);
+        let u1 = G1Affine::from_compressed(u1);
+        let h0 = G1Affine::from_compressed(h0);
+        let h1 = G1Affine::from_compressed(h1);
+        let z = Gt::from_compressed(z);
+
+        u0.and_then(|u0| {
+            u1.and_then(|u1| {
+                h0.and_then(|h0| h1.and_then(|h1| z.map(|z| PublicKey { u0, u1, h0, h1, z })))
+            })
+        })
+    }
+}
+
+impl Compress for SecretKey {
+    const OUTPUT_SIZE: usize = SK_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; SK_BYTES] {
+        let mut buf = [0u8; SK_BYTES];
+        let (alpha, a0, a1, b0, b1, seed) = mut_array_refs![
+            &mut buf,
+            SCALAR_BYTES,
+            SCALAR_BYTES,
+            SCALAR_BYTES,
+            SCALAR_BYTES,
+            SCALAR_BYTES,
+            SEED_BYTES
+        ];
+
+        *alpha = self.alpha.to_bytes();
+        *a0 = self.a0.to_bytes();
+        *a1 = self.a1.to_bytes();
+        *b0 = self.b0.to_bytes();
+        *b1 = self.b1.to_bytes();
+        *seed = self.seed;
+
+        buf
+    }
+
+    fn from_bytes(bytes: &[u8; SK_BYTES]) -> CtOption<Self> {
+        let (alpha, a0, a1, b0, b1, seed) = array_refs![
+            bytes,
+            SCALAR_BYTES,
+            SCALAR_BYTES,
+            SCALAR_BYTES,
+            SCALAR_BYTES,
+            SCALAR_BYTES,
+            SEED_BYTES
+        ];
+
+        let alpha = Scalar::from_bytes(alpha);
+        let a0 = Scalar::from_bytes(a0);
+        let a1 = Scalar::from_bytes(a1);
+        let b0 = Scalar::from_bytes(b0);
+        let b1 = Scalar::from_bytes(b1);
+
+        alpha.and_then(|alpha| {
+            a0.and_then(|a0| {
+                a1.and_then(|a1| {
+                    b0.and_then(|b0| {
+                        b1.map(|b1| SecretKey {
+                            alpha,
+                            a0,
+                            a1,
+                            b0,
+                            b1,
+                            seed: *seed,
+                        })
+                    })
+                })
+            })
+        })
+    }
+}
+
+impl Compress for UserSecretKey {
+    /// The size of a user secret key depends on the depth `D` of the binary tree, this is the size
+    /// of a user secret key without node keys. A user secret key contains `D + 1` node keys of
+    /// [`USK_NODE_BYTES`] each.
+    const OUTPUT_SIZE: usize = USK_BASE_BYTES;
+    type Output = Vec<u8>;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut res = Vec::with_capacity(USK_BASE_BYTES + self.ks.len() * USK_NODE_BYTES);
+
+        res.extend_from_slice(&self.leaf.to_be_bytes());
+        for k in self.ks.iter() {
+            res.extend_from_slice(&k.d.to_compressed());
+            res.extend_from_slice(&k.r.to_compressed());
+        }
+
+        res
+    }
+
+    /// Deserializes a user secret key. The node indices follow from the leaf, such that the
+    /// number of node keys determines the depth of the tree.
+    fn from_bytes(bytes: &Vec<u8>) -> CtOption<Self> {
+        let mut res = UserSecretKey::default();
+
+        let leaf = match bytes.get(..USK_BASE_BYTES) {
+            Some(leaf) => u64::from_be_bytes(leaf.try_into().unwrap()),
+            None => return CtOption::new(res, Choice::from(0)),
+        };
+
+        let rest = &bytes[USK_BASE_BYTES..];
+        let depth = rest.len() / USK_NODE_BYTES;
+        if !rest.len().is_multiple_of(USK_NODE_BYTES)
+            || depth == 0
+            || depth > 63
+            || leaf >> (depth - 1) != 0
+        {
+            return CtOption::new(res, Choice::from(0));
+        }
+
+        res.leaf = leaf;
+        let mut node = (1 << (depth - 1)) + leaf;
+
+        let mut is_some = Choice::from(1u8);
+        for chunk in rest.chunks_exact(USK_NODE_BYTES) {
+            res.ks.push(read_node_key(node, chunk, &mut is_some));
+            node >>= 1;
+        }
+
+        CtOption::new(res, is_some)
+    }
+}
+
+impl Compress for KeyUpdate {
+    /// The size of a key update depends on the number of revoked leaves, this is the size of a
+    /// key update without node keys.
+    const OUTPUT_SIZE: usize = KU_BASE_BYTES;
+    type Output = Vec<u8>;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut res = Vec::with_capacity(KU_BASE_BYTES + self.ks.len() * KU_NODE_BYTES);
+
+        res.extend_from_slice(&self.epoch.to_be_bytes());
+        res.extend_from_slice(&(self.ks.len() as u32).to_be_bytes());
+        for k in self.ks.iter() {
+            res.extend_from_slice(&k.node.to_be_bytes());
+            res.extend_from_slice(&k.d.to_compressed());
+            res.extend_from_slice(&k.r.to_compressed());
+        }
+
+        res
+    }
+
+    fn from_bytes(bytes: &Vec<u8>) -> CtOption<Self> {
+        let mut res = KeyUpdate {
+            epoch: 0,
+            ks: Vec::new(),
+        };
+
+        let (epoch, n) = match bytes.get(..KU_BASE_BYTES) {
+            Some(base) => (
+                u64::from_be_bytes(base[..8].try_into().unwrap()),
+                u32::from_be_bytes(base[8..].try_into().unwrap()) as usize,
+            ),
+            None => return CtOption::new(res, Choice::from(0)),
+        };
+
+        let len = n
+            .checked_mul(KU_NODE_BYTES)
+            .and_then(|len| len.checked_add(KU_BASE_BYTES));
+        if len != Some(bytes.len()) {
+            return CtOption::new(res, Choice::from(0));
+        }
+
+        res.epoch = epoch;
+
+        let mut is_some = Choice::from(1u8);
+        for chunk in bytes[KU_BASE_BYTES..].chunks_exact(KU_NODE_BYTES) {
+            let (node, rest) = chunk.split_at(8);
+            let node = u64::from_be_bytes(node.try_into().unwrap());
+
+            res.ks.push(read_node_key(node, rest, &mut is_some));
+        }
+
+        CtOption::new(res, is_some)
+    }
+}
+
+impl Compress for DecryptionKey {
+    const OUTPUT_SIZE: usize = DK_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; DK_BYTES] {
+        let mut buf = [0u8; DK_BYTES];
+        let (k, r, s) = mut_array_refs![&mut buf, G2_BYTES, G2_BYTES, G2_BYTES];
+
+        *k = self.k.to_compressed();
+        *r = self.r.to_compressed();
+        *s = self.s.to_compressed();
+
+        buf
+    }
+
+    fn from_bytes(bytes: &[u8; DK_BYTES]) -> CtOption<Self> {
+        let (k, r, s) = array_refs![bytes, G2_BYTES, G2_BYTES, G2_BYTES];
+
+        let k = G2Affine::from_compressed(k);
+        let r = G2Affine::from_compressed(r);
+        let s = G2Affine::from_compressed(s);
+
+        k.and_then(|k| r.and_then(|r| s.map(|s| DecryptionKey { k, r, s })))
+    }
+}
+
+impl Compress for CipherText {
+    const OUTPUT_SIZE: usize = CT_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; CT_BYTES] {
+        let mut buf = [0u8; CT_BYTES];
+        let (c0, c1, c2, cprime) =
+            mut_array_refs![&mut buf, G1_BYTES, G1_BYTES, G1_BYTES, GT_BYTES];
+
+        *c0 = self.c0.to_compressed();
+        *c1 = self.c1.to_compressed();
+        *c2 = self.c2.to_compressed();
+        *cprime = self.cprime.to_compressed();
+
+        buf
+    }
+
+    fn from_bytes(bytes: &[u8; CT_BYTES]) -> CtOption<Self> {
+        let (c0, c1, c2, cprime) = array_refs![bytes, G1_BYTES, G1_BYTES, G1_BYTES, GT_BYTES];
+
+        // Use the checked `from_compressed` variant, which verifies that each point is in the
+        // correct prime-order subgroup (GHSA-25fp-2fjj-g84w).
+        let c0 = G1Affine::from_compressed(c0);
+        let c1 = G1Affine::from_compressed(c1);
+        let c2 = G1Affine::from_compressed(c2);
+        let cprime = Gt::from_compressed(cprime);
+
+        c0.and_then(|c0| {
+            c1.and_then(|c1| {
+                c2.and_then(|c2| cprime.map(|cprime| CipherText { c0, c1, c2, cprime }))
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Derive;
+    use rand::RngCore;
+
+    type Scheme = BoldyrevaGoyalKumar<4>;
+
+    const ALICE: &str = "email:alice@example.com";
+    const BOB: &str = "email:bob@example.com";
+
+    fn encrypt_random(pk: &PublicKey, id: &Identity, epoch: u64) -> (CipherText, Msg) {
+        let mut rng = rand::thread_rng();
+        let mut coins = [0u8; 64];
+        rng.fill_bytes(&mut coins);
+
+        let m = rand_gt(&mut rng);
+
+        (Scheme::encrypt(pk, id, epoch, &m, &coins), m)
+    }
+
+    #[test]
+    fn eq_encrypt_decrypt() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = Scheme::setup(&mut rng);
+
+        let alice = Identity::derive_str(ALICE);
+        let ku = Scheme::update_key(None, &sk, 7, &[], &mut rng);
+        let (ct, m) = encrypt_random(&pk, &alice, 7);
+
+        for leaf in [0, 5, 15] {
+            let usk = Scheme::extract_usk(None, &sk, &alice, leaf, &mut rng).unwrap();
+            let dk = Scheme::derive_dk(&usk, &ku).unwrap();
+
+            assert_eq!(Scheme::decrypt(&dk, &ct), m);
+        }
+    }
+
+    #[test]
+    fn revoked_user_cannot_derive_decryption_key() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = Scheme::setup(&mut rng);
+
+        let (alice, bob) = (Identity::derive_str(ALICE), Identity::derive_str(BOB));
+        let usk_alice = Scheme::extract_usk(None, &sk, &alice, 3, &mut rng).unwrap();
+        let usk_bob = Scheme::extract_usk(None, &sk, &bob, 4, &mut rng).unwrap();
+
+        let ku1 = Scheme::update_key(None, &sk, 1, &[], &mut rng);
+        let ku2 = Scheme::update_key(None, &sk, 2, &[3, 9], &mut rng);
+        let (ct, m) = encrypt_random(&pk, &bob, 2);
+
+        assert!(Scheme::derive_dk(&usk_alice, &ku1).is_some());
+        assert!(Scheme::derive_dk(&usk_alice, &ku2).is_none());
+
+        let dk = Scheme::derive_dk(&usk_bob, &ku2).unwrap();
+        assert_eq!(Scheme::decrypt(&dk, &ct), m);
+
+        // A decryption key for another epoch does not decrypt.
+        let dk = Scheme::derive_dk(&usk_bob, &ku1).unwrap();
+        assert_ne!(Scheme::decrypt(&dk, &ct), m);
+    }
+
+    #[test]
+    fn key_update_covers_non_revoked_leaves() {
+        assert_eq!(cover::<4>(&[]), [1]);
+        assert_eq!(cover::<4>(&[0]), [3, 5, 9, 17]);
+        assert_eq!(cover::<4>(&[0, 1]), [3, 5, 9]);
+        assert!(cover::<2>(&[0, 1, 2, 3]).is_empty());
+
+        // Leaves outside of the tree are ignored.
+        assert_eq!(cover::<4>(&[16]), [1]);
+    }
+
+    #[test]
+    fn eq_serialize_deserialize() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = Scheme::setup(&mut rng);
+
+        let alice = Identity::derive_str(ALICE);
+        let usk = Scheme::extract_usk(None, &sk, &alice, 6, &mut rng).unwrap();
+        let ku = Scheme::update_key(None, &sk, 1, &[2, 11], &mut rng);
+        let dk = Scheme::derive_dk(&usk, &ku).unwrap();
+        let (ct, _) = encrypt_random(&pk, &alice, 1);
+
+        assert_eq!(pk, PublicKey::from_bytes(&pk.to_bytes()).unwrap());
+        assert_eq!(sk, SecretKey::from_bytes(&sk.to_bytes()).unwrap());
+        assert_eq!(usk, UserSecretKey::from_bytes(&usk.to_bytes()).unwrap());
+        assert_eq!(ku, KeyUpdate::from_bytes(&ku.to_bytes()).unwrap());
+        assert_eq!(dk, DecryptionKey::from_bytes(&dk.to_bytes()).unwrap());
+        assert_eq!(ct, CipherText::from_bytes(&ct.to_bytes()).unwrap());
+
+        assert!(Scheme::extract_usk(None, &sk, &alice, 16, &mut rng).is_none());
+
+        let mut bytes = ku.to_bytes();
+        bytes.pop();
+        assert!(bool::from(KeyUpdate::from_bytes(&bytes).is_none()));
+    }
+
+    #[test]
+    fn from_bytes_rejects_non_subgroup_point() {
+        let mut rng = rand::thread_rng();
+        let (pk, _) = Scheme::setup(&mut rng);
+        let (ct, _) = encrypt_random(&pk, &Identity::derive_str(ALICE), 1);
+
+        let mut bytes = ct.to_bytes();
+        bytes[G1_BYTES..2 * G1_BYTES].copy_from_slice(&NON_SUBGROUP_G1_COMPRESSED);
+        assert!(bool::from(CipherText::from_bytes(&bytes).is_none()));
+    }
+}
@@ -0,0 +1,309 @@
+//! IND-sID-CPA secure IBE from the Boneh & Boyen BB1 scheme.
+//! * From: "[Efficient Selective-ID Secure Identity-Based Encryption Without Random Oracles](https://link.springer.com/chapter/10.1007/978-3-540-24676-3_14)"
+//! * Published in: EUROCRYPT, 2004
+//!
+//! The implementation is based on BB1, the scheme underlying
+//! [RFC 5409](https://www.rfc-editor.org/rfc/rfc5409) and IEEE 1363.3, but is **not**
+//! interoperable with them: it uses a target group element as message instead of a bitstring, and
+//! its own hashing of identities and byte encodings.
+
+use crate::util::*;
+use crate::{ibe::IBE, Compress};
+use arrayref::{array_refs, mut_array_refs};
+use pg_curve::{multi_miller_loop, pairing, G1Affine, G2Affine, G2Prepared, Gt, Scalar};
+use rand::{CryptoRng, Rng};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
+
+/// Size of the compressed message in bytes.
+pub const MSG_BYTES: usize = GT_BYTES;
+
+/// Size of the compressed master public key in bytes.
+pub const PK_BYTES: usize = 2 * G1_BYTES + GT_BYTES;
+
+/// Size of the compressed master secret key in bytes.
+pub const SK_BYTES: usize = 3 * SCALAR_BYTES;
+
+/// Size of the compressed user secret key in bytes.
+pub const USK_BYTES: usize = 2 * G2_BYTES;
+
+/// Size of the compressed ciphertext key in bytes.
+pub const CT_BYTES: usize = 2 * G1_BYTES + GT_BYTES;
+
+/// Public key parameters generated by the PKG used to encrypt messages.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct PublicKey {
+    p1: G1Affine,
+    p3: G1Affine,
+    v: Gt,
+}
+
+/// Secret key parameter generated by the PKG used to extract user secret keys.
+///
+/// # Zeroization
+///
+/// With the `zeroize` feature enabled this type derives `Zeroize` but **not**
+/// `ZeroizeOnDrop` (it is `Copy`). Secret material is **not** cleared on drop —
+/// you **MUST** call `.zeroize()` explicitly once done. See the
+/// [crate-level docs](crate#zeroizing-secret-material).
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize))]
+pub struct SecretKey {
+    alpha: Scalar,
+    beta: Scalar,
+    gamma: Scalar,
+}
+
+/// Points on G2 that form the user secret key.
+///
+/// # Zeroization
+///
+/// With the `zeroize` feature enabled this type derives `Zeroize` but **not**
+/// `ZeroizeOnDrop` (it is `Copy`). Secret material is **not** cleared on drop —
+/// you **MUST** call `.zeroize()` explicitly once done. See the
+/// [crate-level docs](crate#zeroizing-secret-material).
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize))]
+pub struct UserSecretKey {
+    d0: G2Affine,
+    d1: G2Affine,
+}
+
+/// Encrypted message. Can only be decrypted with a corresponding user secret key.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub struct CipherText {
+    c0: G1Affine,
+    c1: G1Affine,
+    cprime: Gt,
+}
+
+/// A message that can be encrypted using the PKE.
+pub type Msg = Gt;
+
+/// The Boneh & Boyen (BB1) identity-based encryption scheme.
+#[derive(Debug)]
+pub struct BonehBoyen;
+
+impl IBE for BonehBoyen {
+    type Pk = PublicKey;
+    type Sk = SecretKey;
+    type Usk = UserSecretKey;
+    type Ct = CipherText;
+    type Msg = Msg;
+    type Id = Identity;
+    type RngBytes = [u8; 64];
+
+    const PK_BYTES: usize = PK_BYTES;
+    const SK_BYTES: usize = SK_BYTES;
+    const USK_BYTES: usize = USK_BYTES;
+    const CT_BYTES: usize = CT_BYTES;
+    const MSG_BYTES: usize = MSG_BYTES;
+
+    /// Generate a keypair used by the Private Key Generator (PKG).
+    fn setup<R: Rng + CryptoRng>(rng: &mut R) -> (PublicKey, SecretKey) {
+        let g = G1Affine::generator();
+        let h = G2Affine::generator();
+
+        let alpha = rand_scalar(rng);
+        let beta = rand_scalar(rng);
+        let gamma = rand_scalar(rng);
+
+        let p1 = (g * alpha).into();
+        let p3 = (g * gamma).into();
+        let v = pairing(&g, &h) * (alpha * beta);
+
+        (PublicKey { p1, p3, v }, SecretKey { alpha, beta, gamma })
+    }
+
+    /// Extract a user secret key for a given identity.
+    fn extract_usk<R: Rng + CryptoRng>(
+        _opk: Option<&PublicKey>,
+        sk: &SecretKey,
+        v: &Identity,
+        rng: &mut R,
+    ) -> UserSecretKey {
+        let h = G2Affine::generator();
+
+        let r = rand_scalar(rng);
+        let id = v.to_scalar();
+
+        let d0 = (h * (sk.alpha * sk.beta + r * (sk.alpha * id + sk.gamma))).into();
+        let d1 = (h * r).into();
+
+        UserSecretKey { d0, d1 }
+    }
+
+    /// Encrypt a message using the PKG public key and an identity.
+    fn encrypt(pk: &PublicKey, v: &Identity, message: &Msg, rng: &Self::RngBytes) -> CipherText {
+        let s = Scalar::from_bytes_wide(rng);
+        let id = v.to_scalar();
+
+        let c0 = (G1Affine::generator() * s).into();
+        let c1 = (pk.p1 * (s * id) + pk.p3 * s).into();
+        let cprime = pk.v * s + message;
+
+        CipherText { c0, c1, cprime }
+    }
+
+    /// Derive the same message from the CipherText using a UserSecretKey.
+    fn decrypt(usk: &UserSecretKey, ct: &CipherText) -> Msg {
+        ct.cprime
+            - multi_miller_loop(&[
+                (&ct.c0, &G2Prepared::from(usk.d0)),
+                (&-ct.c1, &G2Prepared::from(usk.d1)),
+            ])
+            .final_exponentiation()
+    }
+}
+
+impl Compress for PublicKey {
+    const OUTPUT_SIZE: usize = PK_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; PK_BYTES] {
+        let mut res = [0u8; PK_BYTES];
+        let (p1, p3, v) = mut_array_refs![&mut res, G1_BYTES, G1_BYTES, GT_BYTES];
+
+        *p1 = self.p1.to_compressed();
+        *p3 = self.p3.to_compressed();
+        *v = self.v.to_compressed();
+
+        res
+    }
+
+    fn from_bytes(bytes: &[u8; PK_BYTES]) -> CtOption<Self> {
+        let (p1, p3, v) = array_refs![bytes, G1_BYTES, G1_BYTES, GT_BYTES];
+
+        // Use the checked `from_compressed` variant, which verifies that each
+        // point is in the correct prime-order subgroup. Public keys may be
+        // deserialized from an unauthenticated source before their integrity is
+        // verified out-of-band; skipping the subgroup check would let an
+        // adversary embed a point outside the prime-order subgroup (BLS12-381
+        // has a large, non-trivial cofactor) and mount a small-subgroup attack
+        // (GHSA-25fp-2fjj-g84w).
+        let p1 = G1Affine::from_compressed(p1);
+        let p3 = G1Affine::from_compressed(p3);
+        let v = Gt::from_compressed(v);
+
+        p1.and_then(|p1| p3.and_then(|p3| v.map(|v| PublicKey { p1, p3, v })))
+    }
+}
+
+impl Compress for SecretKey {
+    const OUTPUT_SIZE: usize = SK_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; SK_BYTES] {
+        let mut res = [0u8; SK_BYTES];
+        let (alpha, beta, gamma) =
+            mut_array_refs![&mut res, SCALAR_BYTES, SCALAR_BYTES, SCALAR_BYTES];
+
+        *alpha = self.alpha.to_bytes();
+        *beta = self.beta.to_bytes();
+        *gamma = self.gamma.to_bytes();
+
+        res
+    }
+
+    fn from_bytes(bytes: &[u8; SK_BYTES]) -> CtOption<Self> {
+        let (alpha, beta, gamma) = array_refs![bytes, SCALAR_BYTES, SCALAR_BYTES, SCALAR_BYTES];
+
+        let alpha = Scalar::from_bytes(alpha);
+        let beta = Scalar::from_bytes(beta);
+        let gamma = Scalar::from_bytes(gamma);
+
+        alpha.and_then(|alpha| {
+            beta.and_then(|beta| gamma.map(|gamma| SecretKey { alpha, beta, gamma }))
+        })
+    }
+}
+
+impl Compress for UserSecretKey {
+    const OUTPUT_SIZE: usize = USK_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; USK_BYTES] {
+        let mut res = [0u8; USK_BYTES];
+        let (d0, d1) = mut_array_refs![&mut res, G2_BYTES, G2_BYTES];
+
+        *d0 = self.d0.to_compressed();
+        *d1 = self.d1.to_compressed();
+
+        res
+    }
+
+    fn from_bytes(bytes: &[u8; USK_BYTES]) -> CtOption<Self> {
+        let (d0, d1) = array_refs![bytes, G2_BYTES, G2_BYTES];
+
+        let d0 = G2Affine::from_compressed(d0);
+        let d1 = G2Affine::from_compressed(d1);
+
+        d0.and_then(|d0| d1.map(|d1| UserSecretKey { d0, d1 }))
+    }
+}
+
+impl Compress for CipherText {
+    const OUTPUT_SIZE: usize = CT_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; CT_BYTES] {
+        let mut res = [0u8; CT_BYTES];
+        let (c0, c1, cprime) = mut_array_refs![&mut res, G1_BYTES, G1_BYTES, GT_BYTES];
+
+        *c0 = self.c0.to_compressed();
+        *c1 = self.c1.to_compressed();
+        *cprime = self.cprime.to_compressed();
+
+        res
+    }
+
+    fn from_bytes(bytes: &[u8; CT_BYTES]) -> CtOption<Self> {
+        let (c0, c1, cprime) = array_refs![bytes, G1_BYTES, G1_BYTES, GT_BYTES];
+
+        let c0 = G1Affine::from_compressed(c0);
+        let c1 = G1Affine::from_compressed(c1);
+        let cprime = Gt::from_compressed(cprime);
+
+        c0.and_then(|c0| c1.and_then(|c1| cprime.map(|cprime| CipherText { c0, c1, cprime })))
+    }
+}
+
+impl ConstantTimeEq for CipherText {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.c0.ct_eq(&other.c0) & self.c1.ct_eq(&other.c1) & self.cprime.ct_eq(&other.cprime)
+    }
+}
+
+impl ConditionallySelectable for CipherText {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        CipherText {
+            c0: G1Affine::conditional_select(&a.c0, &b.c0, choice),
+            c1: G1Affine::conditional_select(&a.c1, &b.c1, choice),
+            cprime: Gt::conditional_select(&a.cprime, &b.cprime, choice),
+        }
+    }
+}
+
+impl ConditionallySelectable for UserSecretKey {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        UserSecretKey {
+            d0: G2Affine::conditional_select(&a.d0, &b.d0, choice),
+            d1: G2Affine::conditional_select(&a.d1, &b.d1, choice),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    test_ibe!(BonehBoyen);
+
+    // Regression test for GHSA-25fp-2fjj-g84w. A public key whose first G1
+    // component encodes a valid on-curve point that lies outside the prime-order
+    // subgroup must be rejected by the checked `from_bytes` deserialization.
+    #[test]
+    fn from_bytes_rejects_non_subgroup_point() {
+        let pk = perform_default().pk;
+        let mut bytes = pk.to_bytes();
+        bytes[..G1_BYTES].copy_from_slice(&NON_SUBGROUP_G1_COMPRESSED);
+        assert!(bool::from(PublicKey::from_bytes(&bytes).is_none()));
+    }
+}
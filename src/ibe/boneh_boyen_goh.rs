@@ -0,0 +1,515 @@
+//! IND-sID-CPA secure HIBE with constant size ciphertext from the Boneh, Boyen & Goh scheme.
+//! * From: "[Hierarchical Identity Based Encryption with Constant Size Ciphertext](https://eprint.iacr.org/2005/015.pdf)"
+//! * Published in: EUROCRYPT, 2005
+//!
+//! The ciphertext consists of two elements of G1 and one element of Gt, regardless of the depth of
+//! the recipient identity path. The maximum depth `D` is configurable and determines the size of
+//! the public key and the user secret keys. Note that this scheme is not anonymous.
+//!
+//! The byte serialisation of the keys depends on `D` and is returned as a vector.
+//! The structure of the byte serialisation is not guaranteed to remain constant between releases
+//! of this library.
+
+extern crate alloc;
+use alloc::vec::Vec;
+
+use core::convert::TryInto;
+
+use crate::ibe::{IdentityPath, HIBE, IBE};
+use crate::util::*;
+use crate::Compress;
+use arrayref::{array_refs, mut_array_refs};
+use pg_curve::{
+    multi_miller_loop, pairing, G1Affine, G1Projective, G2Affine, G2Prepared, G2Projective, Gt,
+    Scalar,
+};
+use rand::{CryptoRng, Rng};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
+
+/// Size of the compressed message in bytes.
+pub const MSG_BYTES: usize = GT_BYTES;
+
+/// Size of the compressed ciphertext in bytes.
+pub const CT_BYTES: usize = 2 * G1_BYTES + GT_BYTES;
+
+/// Public key parameters generated by the PKG used to encrypt messages.
+///
+/// The identity hashing parameters are present in both source groups, the former to encrypt and
+/// the latter to delegate user secret keys.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PublicKey<const D: usize> {
+    pub(super) omega: Gt,
+    pub(super) g3: G1Affine,
+    pub(super) h: [G1Affine; D],
+    g3_hat: G2Affine,
+    h_hat: [G2Affine; D],
+}
+
+/// Secret key parameter generated by the PKG used to extract user secret keys.
+///
+/// # Zeroization
+///
+/// With the `zeroize` feature enabled this type derives `Zeroize` but **not**
+/// `ZeroizeOnDrop` (it is `Copy`). Secret material is **not** cleared on drop —
+/// you **MUST** call `.zeroize()` explicitly once done. See the
+/// [crate-level docs](crate#zeroizing-secret-material).
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize))]
+pub struct SecretKey<const D: usize> {
+    alpha: Scalar,
+    y3: Scalar,
+    y: [Scalar; D],
+}
+
+impl<const D: usize> ConstantTimeEq for SecretKey<D> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.alpha.ct_eq(&other.alpha) & self.y3.ct_eq(&other.y3) & self.y[..].ct_eq(&other.y[..])
+    }
+}
+
+impl<const D: usize> PartialEq for SecretKey<D> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+/// Points on G2 that form the user secret key, together with its identity path.
+///
+/// The delegation components `b` of the levels of the identity path itself are unused and set to
+/// the identity.
+///
+/// # Zeroization
+///
+/// With the `zeroize` feature enabled this type derives `Zeroize` but **not**
+/// `ZeroizeOnDrop` (it is `Copy`). Secret material is **not** cleared on drop —
+/// you **MUST** call `.zeroize()` explicitly once done. See the
+/// [crate-level docs](crate#zeroizing-secret-material).
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize))]
+pub struct UserSecretKey<const D: usize> {
+    pub(super) id: IdentityPath<D>,
+    pub(super) a0: G2Affine,
+    pub(super) a1: G2Affine,
+    pub(super) b: [G2Affine; D],
+}
+
+/// Encrypted message. Can only be decrypted with a corresponding user secret key.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub struct CipherText {
+    c0: G1Affine,
+    c1: G1Affine,
+    cprime: Gt,
+}
+
+/// A message that can be encrypted using the PKE.
+pub type Msg = Gt;
+
+/// The Boneh, Boyen & Goh hierarchical identity-based encryption scheme, supporting identity paths
+/// up to depth `D`.
+#[derive(Debug)]
+pub struct BonehBoyenGoh<const D: usize>;
+
+impl<const D: usize> IBE for BonehBoyenGoh<D> {
+    type Pk = PublicKey<D>;
+    type Sk = SecretKey<D>;
+    type Usk = UserSecretKey<D>;
+    type Ct = CipherText;
+    type Msg = Msg;
+    type Id = IdentityPath<D>;
+    type RngBytes = [u8; 64];
+
+    const PK_BYTES: usize = GT_BYTES + (1 + D) * (G1_BYTES + G2_BYTES);
+    const SK_BYTES: usize = (2 + D) * SCALAR_BYTES;
+    const USK_BYTES: usize = 1 + D * ID_BYTES + (2 + D) * G2_BYTES;
+    const CT_BYTES: usize = CT_BYTES;
+    const MSG_BYTES: usize = MSG_BYTES;
+
+    /// Generate a keypair used by the Private Key Generator (PKG).
+    fn setup<R: Rng + CryptoRng>(rng: &mut R) -> (PublicKey<D>, SecretKey<D>) {
+        let g = G1Affine::generator();
+        let h = G2Affine::generator();
+
+        let alpha = rand_scalar(rng);
+        let y3 = rand_scalar(rng);
+        let mut y = [Scalar::zero(); D];
+        for yi in y.iter_mut() {
+            *yi = rand_scalar(rng);
+        }
+
+        let omega = pairing(&g, &h) * alpha;
+
+        let batch: Vec<G1Projective> = y.iter().map(|yi| g * yi).collect();
+        let mut hs = [G1Affine::default(); D];
+        G1Projective::batch_normalize(&batch, &mut hs);
+
+        let batch: Vec<G2Projective> = y.iter().map(|yi| h * yi).collect();
+        let mut hs_hat = [G2Affine::default(); D];
+        G2Projective::batch_normalize(&batch, &mut hs_hat);
+
+        (
+            PublicKey {
+                omega,
+                g3: (g * y3).into(),
+                h: hs,
+                g3_hat: (h * y3).into(),
+                h_hat: hs_hat,
+            },
+            SecretKey { alpha, y3, y },
+        )
+    }
+
+    /// Extract a user secret key for a given identity path.
+    fn extract_usk<R: Rng + CryptoRng>(
+        _opk: Option<&PublicKey<D>>,
+        sk: &SecretKey<D>,
+        v: &IdentityPath<D>,
+        rng: &mut R,
+    ) -> UserSecretKey<D> {
+        let h = G2Affine::generator();
+        let r = rand_scalar(rng);
+
+        let f = v
+            .components()
+            .iter()
+            .zip(sk.y.iter())
+            .fold(sk.y3, |acc, (id, yi)| acc + yi * id.to_scalar());
+
+        let batch: Vec<G2Projective> = [sk.alpha + r * f, r]
+            .into_iter()
+            .chain(sk.y.iter().skip(v.len()).map(|yi| r * yi))
+            .map(|e| h * e)
+            .collect();
+
+        let mut res = UserSecretKey {
+            id: *v,
+            a0: G2Affine::identity(),
+            a1: G2Affine::identity(),
+            b: [G2Affine::identity(); D],
+        };
+
+        let mut out = [G2Affine::identity(); 2];
+        G2Projective::batch_normalize(&batch[..2], &mut out);
+        G2Projective::batch_normalize(&batch[2..], &mut res.b[v.len()..]);
+        [res.a0, res.a1] = out;
+
+        res
+    }
+
+    /// Encrypt a message using the PKG public key and an identity path.
+    fn encrypt(
+        pk: &PublicKey<D>,
+        v: &IdentityPath<D>,
+        m: &Msg,
+        rng: &Self::RngBytes,
+    ) -> CipherText {
+        let s = Scalar::from_bytes_wide(rng);
+
+        let f = v
+            .components()
+            .iter()
+            .zip(pk.h.iter())
+            .fold(G1Projective::from(pk.g3), |acc, (id, hi)| {
+                acc + hi * id.to_scalar()
+            });
+
+        let mut c = [G1Affine::default(); 2];
+        G1Projective::batch_normalize(&[G1Affine::generator() * s, f * s], &mut c);
+
+        CipherText {
+            c0: c[0],
+            c1: c[1],
+            cprime: pk.omega * s + m,
+        }
+    }
+
+    /// Decrypt ciphertext to a message using a user secret key.
+    fn decrypt(usk: &UserSecretKey<D>, ct: &CipherText) -> Msg {
+        ct.cprime
+            - multi_miller_loop(&[
+                (&ct.c0, &G2Prepared::from(usk.a0)),
+                (&-ct.c1, &G2Prepared::from(usk.a1)),
+            ])
+            .final_exponentiation()
+    }
+}
+
+impl<const D: usize> HIBE for BonehBoyenGoh<D> {
+    type IdComponent = Identity;
+
+    const MAX_DEPTH: usize = D;
+
+    /// Delegate a user secret key for a child identity, re-randomizing all components.
+    fn delegate<R: Rng + CryptoRng>(
+        pk: &PublicKey<D>,
+        usk: &UserSecretKey<D>,
+        id: &Identity,
+        rng: &mut R,
+    ) -> Option<UserSecretKey<D>> {
+        let child = usk.id.push(id)?;
+        let k = usk.id.len();
+        let t = rand_scalar(rng);
+
+        let f = child
+            .components()
+            .iter()
+            .zip(pk.h_hat.iter())
+            .fold(G2Projective::from(pk.g3_hat), |acc, (id, hi)| {
+                acc + hi * id.to_scalar()
+            });
+
+        let mut batch: Vec<G2Projective> = Vec::with_capacity(2 + D - k - 1);
+        batch.push(usk.a0 + usk.b[k] * id.to_scalar() + f * t);
+        batch.push(usk.a1 + G2Affine::generator() * t);
+        batch.extend(
+            usk.b
+                .iter()
+                .zip(pk.h_hat.iter())
+                .skip(k + 1)
+                .map(|(bi, hi)| bi + hi * t),
+        );
+
+        let mut res = UserSecretKey {
+            id: child,
+            a0: G2Affine::identity(),
+            a1: G2Affine::identity(),
+            b: [G2Affine::identity(); D],
+        };
+
+        let mut out = [G2Affine::identity(); 2];
+        G2Projective::batch_normalize(&batch[..2], &mut out);
+        G2Projective::batch_normalize(&batch[2..], &mut res.b[k + 1..]);
+        [res.a0, res.a1] = out;
+
+        Some(res)
+    }
+}
+
+impl<const D: usize> Compress for PublicKey<D> {
+    const OUTPUT_SIZE: usize = <BonehBoyenGoh<D> as IBE>::PK_BYTES;
+    type Output = Vec<u8>;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut res = Vec::with_capacity(Self::OUTPUT_SIZE);
+
+        res.extend_from_slice(&self.omega.to_compressed());
+        for el in core::iter::once(&self.g3).chain(self.h.iter()) {
+            res.extend_from_slice(&el.to_compressed());
+        }
+        for el in core::iter::once(&self.g3_hat).chain(self.h_hat.iter()) {
+            res.extend_from_slice(&el.to_compressed());
+        }
+
+        res
+    }
+
+    fn from_bytes(bytes: &Vec<u8>) -> CtOption<Self> {
+        let mut res = PublicKey {
+            omega: Gt::identity(),
+            g3: G1Affine::identity(),
+            h: [G1Affine::identity(); D],
+            g3_hat: G2Affine::identity(),
+            h_hat: [G2Affine::identity(); D],
+        };
+
+        if bytes.len() != Self::OUTPUT_SIZE {
+            return CtOption::new(res, Choice::from(0));
+        }
+
+        let (omega, rest) = bytes.split_at(GT_BYTES);
+        let (g1s, g2s) = rest.split_at((1 + D) * G1_BYTES);
+
+        // Use the checked `from_compressed` variant, which verifies that each
+        // point is in the correct prime-order subgroup. Public keys may be
+        // deserialized from an unauthenticated source before their integrity is
+        // verified out-of-band; skipping the subgroup check would let an
+        // adversary embed a point outside the prime-order subgroup (BLS12-381
+        // has a large, non-trivial cofactor) and mount a small-subgroup attack
+        // (GHSA-25fp-2fjj-g84w).
+        let mut is_some = Gt::from_compressed(omega.try_into().unwrap())
+            .map(|el| res.omega = el)
+            .is_some();
+
+        let slots = core::iter::once(&mut res.g3).chain(res.h.iter_mut());
+        for (slot, chunk) in slots.zip(g1s.chunks_exact(G1_BYTES)) {
+            is_some &= G1Affine::from_compressed(chunk.try_into().unwrap())
+                .map(|el| *slot = el)
+                .is_some();
+        }
+
+        let slots = core::iter::once(&mut res.g3_hat).chain(res.h_hat.iter_mut());
+        for (slot, chunk) in slots.zip(g2s.chunks_exact(G2_BYTES)) {
+            is_some &= G2Affine::from_compressed(chunk.try_into().unwrap())
+                .map(|el| *slot = el)
+                .is_some();
+        }
+
+        CtOption::new(res, is_some)
+    }
+}
+
+impl<const D: usize> Compress for SecretKey<D> {
+    const OUTPUT_SIZE: usize = <BonehBoyenGoh<D> as IBE>::SK_BYTES;
+    type Output = Vec<u8>;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let scalars = [self.alpha, self.y3].into_iter().chain(self.y);
+
+        scalars.flat_map(|s| s.to_bytes()).collect()
+    }
+
+    fn from_bytes(bytes: &Vec<u8>) -> CtOption<Self> {
+        let mut res = SecretKey {
+            alpha: Scalar::zero(),
+            y3: Scalar::zero(),
+            y: [Scalar::zero(); D],
+        };
+
+        if bytes.len() != Self::OUTPUT_SIZE {
+            return CtOption::new(res, Choice::from(0));
+        }
+
+        let mut is_some = Choice::from(1u8);
+        let slots = [&mut res.alpha, &mut res.y3]
+            .into_iter()
+            .chain(res.y.iter_mut());
+        for (slot, chunk) in slots.zip(bytes.chunks_exact(SCALAR_BYTES)) {
+            is_some &= Scalar::from_bytes(chunk.try_into().unwrap())
+                .map(|s| *slot = s)
+                .is_some();
+        }
+
+        CtOption::new(res, is_some)
+    }
+}
+
+impl<const D: usize> Compress for UserSecretKey<D> {
+    const OUTPUT_SIZE: usize = <BonehBoyenGoh<D> as IBE>::USK_BYTES;
+    type Output = Vec<u8>;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        const { assert!(D <= u8::MAX as usize, "D must fit in a byte") };
+
+        let mut res = Vec::with_capacity(Self::OUTPUT_SIZE);
+
+        res.push(self.id.len() as u8);
+        for i in 0..D {
+            match self.id.components().get(i) {
+                Some(id) => res.extend_from_slice(&id.0),
+                None => res.extend_from_slice(&[0u8; ID_BYTES]),
+            }
+        }
+        for el in [&self.a0, &self.a1].into_iter().chain(self.b.iter()) {
+            res.extend_from_slice(&el.to_compressed());
+        }
+
+        res
+    }
+
+    fn from_bytes(bytes: &Vec<u8>) -> CtOption<Self> {
+        let mut res = UserSecretKey {
+            id: IdentityPath::new(&[]).unwrap(),
+            a0: G2Affine::identity(),
+            a1: G2Affine::identity(),
+            b: [G2Affine::identity(); D],
+        };
+
+        if bytes.len() != Self::OUTPUT_SIZE || bytes[0] as usize > D {
+            return CtOption::new(res, Choice::from(0));
+        }
+
+        let (ids, g2s) = bytes[1..].split_at(D * ID_BYTES);
+
+        let ids: Vec<Identity> = ids
+            .chunks_exact(ID_BYTES)
+            .take(bytes[0] as usize)
+            .map(|chunk| Identity(chunk.try_into().unwrap()))
+            .collect();
+        res.id = IdentityPath::new(&ids).unwrap();
+
+        let mut is_some = Choice::from(1u8);
+        let slots = [&mut res.a0, &mut res.a1]
+            .into_iter()
+            .chain(res.b.iter_mut());
+        for (slot, chunk) in slots.zip(g2s.chunks_exact(G2_BYTES)) {
+            is_some &= G2Affine::from_compressed(chunk.try_into().unwrap())
+                .map(|el| *slot = el)
+                .is_some();
+        }
+
+        CtOption::new(res, is_some)
+    }
+}
+
+impl Compress for CipherText {
+    const OUTPUT_SIZE: usize = CT_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; CT_BYTES] {
+        let mut res = [0u8; CT_BYTES];
+        let (c0, c1, cprime) = mut_array_refs![&mut res, G1_BYTES, G1_BYTES, GT_BYTES];
+
+        *c0 = self.c0.to_compressed();
+        *c1 = self.c1.to_compressed();
+        *cprime = self.cprime.to_compressed();
+
+        res
+    }
+
+    fn from_bytes(bytes: &[u8; CT_BYTES]) -> CtOption<Self> {
+        let (c0, c1, cprime) = array_refs![bytes, G1_BYTES, G1_BYTES, GT_BYTES];
+
+        let c0 = G1Affine::from_compressed(c0);
+        let c1 = G1Affine::from_compressed(c1);
+        let cprime = Gt::from_compressed(cprime);
+
+        c0.and_then(|c0| c1.and_then(|c1| cprime.map(|cprime| CipherText { c0, c1, cprime })))
+    }
+}
+
+impl ConstantTimeEq for CipherText {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.c0.ct_eq(&other.c0) & self.c1.ct_eq(&other.c1) & self.cprime.ct_eq(&other.cprime)
+    }
+}
+
+impl ConditionallySelectable for CipherText {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        CipherText {
+            c0: G1Affine::conditional_select(&a.c0, &b.c0, choice),
+            c1: G1Affine::conditional_select(&a.c1, &b.c1, choice),
+            cprime: Gt::conditional_select(&a.cprime, &b.cprime, choice),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    type BonehBoyenGoh = super::BonehBoyenGoh<3>;
+    type PublicKey = super::PublicKey<3>;
+    type SecretKey = super::SecretKey<3>;
+    type UserSecretKey = super::UserSecretKey<3>;
+
+    test_ibe!(BonehBoyenGoh);
+
+    test_hibe!(BonehBoyenGoh);
+
+    // Regression test for GHSA-25fp-2fjj-g84w. A public key whose first G1
+    // component encodes a valid on-curve point that lies outside the prime-order
+    // subgroup must be rejected by the checked `from_bytes`.
+    #[test]
+    fn from_bytes_rejects_non_subgroup_point() {
+        let pk = perform_default().pk;
+        let mut bytes = pk.to_bytes();
+        // Layout: omega (Gt) precedes g3 (first G1 component).
+        bytes[GT_BYTES..GT_BYTES + G1_BYTES].copy_from_slice(&NON_SUBGROUP_G1_COMPRESSED);
+        assert!(bool::from(PublicKey::from_bytes(&bytes).is_none()));
+    }
+
+    #[test]
+    fn ciphertext_size_is_independent_of_depth() {
+        assert_eq!(
+            <super::BonehBoyenGoh<1> as IBE>::CT_BYTES,
+            <super::BonehBoyenGoh<8> as IBE>::CT_BYTES
+        );
+    }
+}
@@ -0,0 +1,382 @@
+//! IND-sWID-CPA secure WIBE on top of the Boneh, Boyen & Goh HIBE, by Abdalla, Catalano, Dent,
+//! Malone-Lee, Neven and Smart.
+//! * From: "[Identity-Based Encryption Gone Wild](https://eprint.iacr.org/2006/304.pdf)"
+//! * Published in: ICALP, 2006
+//!
+//! A message is encrypted for a [`Pattern`], such as `(example.com, *, admin)`, and can be
+//! decrypted by any user secret key for a matching identity path. The master keys and user secret
+//! keys are those of the [`BonehBoyenGoh`] HIBE, so that the same keys can be used to decrypt both
+//! kinds of ciphertexts.
+//!
+//! The ciphertext contains the pattern, and one element of G1 for each wildcard on top of the
+//! constant size ciphertext of the HIBE. Note that this scheme is not anonymous.
+//!
+//! The byte serialisation of the ciphertext depends on the pattern and is returned as a vector.
+//! The structure of the byte serialisation is not guaranteed to remain constant between releases
+//! of this library.
+
+extern crate alloc;
+use alloc::vec::Vec;
+
+use core::convert::TryInto;
+
+use crate::ibe::{Pattern, WIBE};
+use crate::util::*;
+use crate::Compress;
+use pg_curve::{
+    multi_miller_loop, G1Affine, G1Projective, G2Affine, G2Prepared, G2Projective, Gt, Scalar,
+};
+use subtle::{Choice, CtOption};
+
+/// These structs are identical for the WIBE.
+pub use crate::ibe::boneh_boyen_goh::{
+    BonehBoyenGoh, Msg, PublicKey, SecretKey, UserSecretKey, MSG_BYTES,
+};
+
+/// Size of the compressed pattern ciphertext in bytes, for the empty pattern.
+pub const PATTERN_CT_BASE_BYTES: usize = 2 * G1_BYTES + GT_BYTES + 1;
+
+/// Size of the compressed pattern ciphertext component for an identity component in bytes.
+pub const PATTERN_CT_ID_BYTES: usize = 1 + ID_BYTES;
+
+/// Size of the compressed pattern ciphertext component for a wildcard in bytes.
+pub const PATTERN_CT_WILDCARD_BYTES: usize = 1 + G1_BYTES;
+
+/// Encrypted message. Can only be decrypted with a user secret key whose identity path matches the
+/// pattern.
+///
+/// The wildcard components `c` of the non-wildcard levels are unused and set to the identity.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PatternCipherText<const D: usize> {
+    pattern: Pattern<D>,
+    c0: G1Affine,
+    c1: G1Affine,
+    c: [G1Affine; D],
+    cprime: Gt,
+}
+
+impl<const D: usize> WIBE for BonehBoyenGoh<D> {
+    type Pattern = Pattern<D>;
+    type PatternCt = PatternCipherText<D>;
+
+    /// Encrypt a message using the PKG public key and a pattern.
+    fn encrypt_pattern(
+        pk: &PublicKey<D>,
+        pattern: &Pattern<D>,
+        m: &Msg,
+        rng: &[u8; 64],
+    ) -> PatternCipherText<D> {
+        let s = Scalar::from_bytes_wide(rng);
+
+        let f = pattern
+            .components()
+            .iter()
+            .zip(pk.h.iter())
+            .filter_map(|(p, hi)| p.map(|id| hi * id.to_scalar()))
+            .fold(G1Projective::from(pk.g3), |acc, el| acc + el);
+
+        let batch: Vec<G1Projective> = [G1Affine::generator() * s, f * s]
+            .into_iter()
+            .chain(
+                pattern
+                    .components()
+                    .iter()
+                    .zip(pk.h.iter())
+                    .map(|(p, hi)| match p {
+                        Some(_) => G1Projective::identity(),
+                        None => hi * s,
+                    }),
+            )
+            .collect();
+
+        let mut res = PatternCipherText {
+            pattern: *pattern,
+            c0: G1Affine::identity(),
+            c1: G1Affine::identity(),
+            c: [G1Affine::identity(); D],
+            cprime: pk.omega * s + m,
+        };
+
+        let mut out = [G1Affine::identity(); 2];
+        G1Projective::batch_normalize(&batch[..2], &mut out);
+        G1Projective::batch_normalize(&batch[2..], &mut res.c[..pattern.len()]);
+        [res.c0, res.c1] = out;
+
+        res
+    }
+
+    /// Decrypt a pattern ciphertext to a message using a user secret key.
+    ///
+    /// The wildcards of the pattern at the levels of the identity path are filled in with its
+    /// components. The user secret key is delegated to the remaining components of the pattern,
+    /// where the wildcards are left out.
+    fn decrypt_pattern(usk: &UserSecretKey<D>, ct: &PatternCipherText<D>) -> Option<Msg> {
+        if !ct.pattern.matches(&usk.id) {
+            return None;
+        }
+
+        let k = usk.id.len();
+        let components = ct.pattern.components();
+
+        let c1 = components[..k]
+            .iter()
+            .zip(usk.id.components())
+            .zip(ct.c.iter())
+            .filter(|((p, _), _)| p.is_none())
+            .fold(G1Projective::from(ct.c1), |acc, ((_, id), ci)| {
+                acc + ci * id.to_scalar()
+            });
+
+        let a0 = components[k..]
+            .iter()
+            .zip(usk.b[k..].iter())
+            .filter_map(|(p, bi)| p.map(|id| bi * id.to_scalar()))
+            .fold(G2Projective::from(usk.a0), |acc, el| acc + el);
+
+        Some(
+            ct.cprime
+                - multi_miller_loop(&[
+                    (&ct.c0, &G2Prepared::from(G2Affine::from(a0))),
+                    (&-G1Affine::from(c1), &G2Prepared::from(usk.a1)),
+                ])
+                .final_exponentiation(),
+        )
+    }
+}
+
+impl<const D: usize> PatternCipherText<D> {
+    /// The pattern this ciphertext was encrypted for.
+    pub fn pattern(&self) -> &Pattern<D> {
+        &self.pattern
+    }
+}
+
+impl<const D: usize> Compress for PatternCipherText<D> {
+    /// The size of a pattern ciphertext depends on the pattern, this is the size of a ciphertext
+    /// for the empty pattern.
+    const OUTPUT_SIZE: usize = PATTERN_CT_BASE_BYTES;
+    type Output = Vec<u8>;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        const { assert!(D <= u8::MAX as usize, "D must fit in a byte") };
+
+        let mut res =
+            Vec::with_capacity(PATTERN_CT_BASE_BYTES + self.pattern.len() * PATTERN_CT_ID_BYTES);
+
+        res.extend_from_slice(&self.c0.to_compressed());
+        res.extend_from_slice(&self.c1.to_compressed());
+        res.extend_from_slice(&self.cprime.to_compressed());
+
+        res.push(self.pattern.len() as u8);
+        for (p, ci) in self.pattern.components().iter().zip(self.c.iter()) {
+            match p {
+                Some(id) => {
+                    res.push(1);
+                    res.extend_from_slice(&id.0);
+                }
+                None => {
+                    res.push(0);
+                    res.extend_from_slice(&ci.to_compressed());
+                }
+            }
+        }
+
+        res
+    }
+
+    fn from_bytes(bytes: &Vec<u8>) -> CtOption<Self> {
+        let mut res = PatternCipherText {
+            pattern: Pattern::new(&[]).unwrap(),
+            c0: G1Affine::identity(),
+            c1: G1Affine::identity(),
+            c: [G1Affine::identity(); D],
+            cprime: Gt::identity(),
+        };
+
+        if bytes.len() < PATTERN_CT_BASE_BYTES || bytes[PATTERN_CT_BASE_BYTES - 1] as usize > D {
+            return CtOption::new(res, Choice::from(0));
+        }
+
+        let (c0, rest) = bytes.split_at(G1_BYTES);
+        let (c1, rest) = rest.split_at(G1_BYTES);
+        let (cprime, rest) = rest.split_at(GT_BYTES);
+        let (len, mut rest) = (rest[0] as usize, &rest[1..]);
+
+        // Use the checked `from_compressed` variant, which verifies that each point is in the
+        // correct prime-order subgroup (GHSA-25fp-2fjj-g84w).
+        let mut is_some = G1Affine::from_compressed(c0.try_into().unwrap())
+            .map(|el| res.c0 = el)
+            .is_some();
+        is_some &= G1Affine::from_compressed(c1.try_into().unwrap())
+            .map(|el| res.c1 = el)
+            .is_some();
+        is_some &= Gt::from_compressed(cprime.try_into().unwrap())
+            .map(|el| res.cprime = el)
+            .is_some();
+
+        let mut components = [None; D];
+        for (slot, ci) in components.iter_mut().zip(res.c.iter_mut()).take(len) {
+            match rest.split_first() {
+                Some((1, tail)) if tail.len() >= ID_BYTES => {
+                    let (id, tail) = tail.split_at(ID_BYTES);
+                    *slot = Some(Identity(id.try_into().unwrap()));
+                    rest = tail;
+                }
+                Some((0, tail)) if tail.len() >= G1_BYTES => {
+                    let (el, tail) = tail.split_at(G1_BYTES);
+                    is_some &= G1Affine::from_compressed(el.try_into().unwrap())
+                        .map(|el| *ci = el)
+                        .is_some();
+                    rest = tail;
+                }
+                _ => return CtOption::new(res, Choice::from(0)),
+            }
+        }
+
+        if !rest.is_empty() {
+            return CtOption::new(res, Choice::from(0));
+        }
+
+        res.pattern = Pattern::new(&components[..len]).unwrap();
+
+        CtOption::new(res, is_some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ibe::{HIBE, IBE};
+    use crate::Derive;
+    use rand::RngCore;
+
+    type BonehBoyenGoh = super::BonehBoyenGoh<3>;
+    type PatternCipherText = super::PatternCipherText<3>;
+    type Pattern = crate::ibe::Pattern<3>;
+    type IdentityPath = crate::ibe::IdentityPath<3>;
+
+    fn encrypt_random(pk: &super::PublicKey<3>, pattern: &Pattern) -> (PatternCipherText, Msg) {
+        let mut rng = rand::thread_rng();
+        let mut coins = [0u8; 64];
+        rng.fill_bytes(&mut coins);
+
+        let m = rand_gt(&mut rng);
+
+        (BonehBoyenGoh::encrypt_pattern(pk, pattern, &m, &coins), m)
+    }
+
+    fn path(components: &[&str]) -> IdentityPath {
+        let ids: Vec<Identity> = components.iter().map(|c| Identity::derive_str(c)).collect();
+        IdentityPath::new(&ids).unwrap()
+    }
+
+    fn pattern(components: &[Option<&str>]) -> Pattern {
+        let ids: Vec<Option<Identity>> = components
+            .iter()
+            .map(|c| c.map(Identity::derive_str))
+            .collect();
+        Pattern::new(&ids).unwrap()
+    }
+
+    #[test]
+    fn eq_encrypt_decrypt_matching_pattern() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = BonehBoyenGoh::setup(&mut rng);
+        let p = pattern(&[Some("example.com"), None, Some("admin")]);
+        let (ct, m) = encrypt_random(&pk, &p);
+
+        for id in [
+            path(&["example.com", "sales", "admin"]),
+            path(&["example.com", "support", "admin"]),
+            path(&["example.com", "support"]),
+            path(&["example.com"]),
+        ] {
+            assert!(p.matches(&id));
+            let usk = BonehBoyenGoh::extract_usk(None, &sk, &id, &mut rng);
+            assert_eq!(BonehBoyenGoh::decrypt_pattern(&usk, &ct), Some(m));
+        }
+    }
+
+    #[test]
+    fn eq_delegate_decrypt() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = BonehBoyenGoh::setup(&mut rng);
+        let (ct, m) = encrypt_random(&pk, &pattern(&[Some("example.com"), None, None]));
+
+        let usk = BonehBoyenGoh::extract_usk(None, &sk, &path(&["example.com"]), &mut rng);
+        let usk = BonehBoyenGoh::delegate(&pk, &usk, &Identity::derive_str("sales"), &mut rng);
+        let usk =
+            BonehBoyenGoh::delegate(&pk, &usk.unwrap(), &Identity::derive_str("bob"), &mut rng);
+
+        assert_eq!(BonehBoyenGoh::decrypt_pattern(&usk.unwrap(), &ct), Some(m));
+    }
+
+    #[test]
+    fn non_matching_usk_does_not_decrypt() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = BonehBoyenGoh::setup(&mut rng);
+        let (ct, m) = encrypt_random(&pk, &pattern(&[Some("example.com"), None, Some("admin")]));
+
+        let id = path(&["example.com", "sales", "intern"]);
+        let mut usk = BonehBoyenGoh::extract_usk(None, &sk, &id, &mut rng);
+        assert_eq!(BonehBoyenGoh::decrypt_pattern(&usk, &ct), None);
+
+        // Relabelling the user secret key with a matching identity path does not help.
+        usk.id = path(&["example.com", "sales", "admin"]);
+        assert_ne!(BonehBoyenGoh::decrypt_pattern(&usk, &ct), Some(m));
+    }
+
+    #[test]
+    fn pattern_without_wildcards_is_hibe() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = BonehBoyenGoh::setup(&mut rng);
+        let id = path(&["example.com", "alice"]);
+        let (ct, m) = encrypt_random(&pk, &Pattern::from(id));
+
+        let usk = BonehBoyenGoh::extract_usk(None, &sk, &id, &mut rng);
+        assert_eq!(BonehBoyenGoh::decrypt_pattern(&usk, &ct), Some(m));
+
+        // The ciphertext is the same as that of the HIBE, apart from the pattern.
+        let c = BonehBoyenGoh::encrypt(&pk, &id, &m, &[7u8; 64]);
+        let ct = BonehBoyenGoh::encrypt_pattern(&pk, &Pattern::from(id), &m, &[7u8; 64]);
+        assert_eq!(c.to_bytes()[..], ct.to_bytes()[..PATTERN_CT_BASE_BYTES - 1]);
+    }
+
+    #[test]
+    fn eq_serialize_deserialize() {
+        let mut rng = rand::thread_rng();
+        let (pk, _) = BonehBoyenGoh::setup(&mut rng);
+        let (ct, _) = encrypt_random(&pk, &pattern(&[Some("example.com"), None, Some("admin")]));
+
+        let bytes = ct.to_bytes();
+        assert_eq!(
+            bytes.len(),
+            PATTERN_CT_BASE_BYTES + 2 * PATTERN_CT_ID_BYTES + PATTERN_CT_WILDCARD_BYTES
+        );
+        assert_eq!(ct, PatternCipherText::from_bytes(&bytes).unwrap());
+
+        let mut truncated = bytes.clone();
+        truncated.pop();
+        assert!(bool::from(
+            PatternCipherText::from_bytes(&truncated).is_none()
+        ));
+
+        let mut extended = bytes;
+        extended.push(0);
+        assert!(bool::from(
+            PatternCipherText::from_bytes(&extended).is_none()
+        ));
+    }
+
+    #[test]
+    fn from_bytes_rejects_non_subgroup_point() {
+        let mut rng = rand::thread_rng();
+        let (pk, _) = BonehBoyenGoh::setup(&mut rng);
+        let (ct, _) = encrypt_random(&pk, &pattern(&[Some("example.com"), None]));
+
+        let mut bytes = ct.to_bytes();
+        let len = bytes.len();
+        bytes[len - G1_BYTES..].copy_from_slice(&NON_SUBGROUP_G1_COMPRESSED);
+        assert!(bool::from(PatternCipherText::from_bytes(&bytes).is_none()));
+    }
+}
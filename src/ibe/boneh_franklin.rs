@@ -0,0 +1,252 @@
+//! IND-ID-CPA secure IBE from the Boneh & Franklin scheme (BasicIdent).
+//! * From: "[Identity-Based Encryption from the Weil Pairing](https://link.springer.com/chapter/10.1007/3-540-44647-8_13)"
+//! * Published in: CRYPTO, 2001
+//!
+//! Identities are hashed to G2 using the hash-to-curve construction of RFC 9380, such that
+//! the master public key and the ciphertext live in the (smaller) G1 group.
+//!
+//! The messages are elements of the target group, instead of the bitstrings in the original
+//! paper. Apply the Fujisaki-Okamoto transform to obtain FullIdent, see
+//! [`bf_fo`](crate::kem::bf_fo).
+
+use crate::util::*;
+use crate::{ibe::IBE, Compress};
+use arrayref::{array_refs, mut_array_refs};
+use pg_curve::{pairing, G1Affine, G2Affine, Gt, Scalar};
+use rand::{CryptoRng, Rng};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
+
+/// Domain separation tag used to hash identities to G2.
+const DST: &[u8] = b"IBE_BF_BLS12381G2_XMD:SHA-256_SSWU_RO_";
+
+/// Size of the compressed message in bytes.
+pub const MSG_BYTES: usize = GT_BYTES;
+
+/// Size of the compressed master public key in bytes.
+pub const PK_BYTES: usize = G1_BYTES;
+
+/// Size of the compressed master secret key in bytes.
+pub const SK_BYTES: usize = SCALAR_BYTES;
+
+/// Size of the compressed user secret key in bytes.
+pub const USK_BYTES: usize = G2_BYTES;
+
+/// Size of the compressed ciphertext key in bytes.
+pub const CT_BYTES: usize = G1_BYTES + GT_BYTES;
+
+/// Public key parameters generated by the PKG used to encrypt messages.
+/// Also known as P_pub.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct PublicKey {
+    pub(crate) ppub: G1Affine,
+}
+
+/// Secret key parameter generated by the PKG used to extract user secret keys.
+///
+/// # Zeroization
+///
+/// With the `zeroize` feature enabled this type derives `Zeroize` but **not**
+/// `ZeroizeOnDrop` (it is `Copy`). Secret material is **not** cleared on drop —
+/// you **MUST** call `.zeroize()` explicitly once done. See the
+/// [crate-level docs](crate#zeroizing-secret-material).
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize))]
+pub struct SecretKey {
+    pub(super) s: Scalar,
+}
+
+/// Point on G2 that forms the user secret key.
+///
+/// # Zeroization
+///
+/// With the `zeroize` feature enabled this type derives `Zeroize` but **not**
+/// `ZeroizeOnDrop` (it is `Copy`). Secret material is **not** cleared on drop —
+/// you **MUST** call `.zeroize()` explicitly once done. See the
+/// [crate-level docs](crate#zeroizing-secret-material).
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize))]
+pub struct UserSecretKey {
+    pub(crate) d: G2Affine,
+}
+
+/// Encrypted message. Can only be decrypted with a corresponding user secret key.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub struct CipherText {
+    pub(super) u: G1Affine,
+    pub(super) v: Gt,
+}
+
+/// A message that can be encrypted using the PKE.
+pub type Msg = Gt;
+
+/// Hashes an identity to a point on G2, also known as Q_id.
+pub(crate) fn hash_to_point(v: &Identity) -> G2Affine {
+    hash_to_g2(&v.0, DST).into()
+}
+
+/// The Boneh & Franklin identity-based encryption scheme.
+#[derive(Debug)]
+pub struct BonehFranklin;
+
+impl IBE for BonehFranklin {
+    type Pk = PublicKey;
+    type Sk = SecretKey;
+    type Usk = UserSecretKey;
+    type Ct = CipherText;
+    type Msg = Msg;
+    type Id = Identity;
+    type RngBytes = [u8; 64];
+
+    const PK_BYTES: usize = PK_BYTES;
+    const SK_BYTES: usize = SK_BYTES;
+    const USK_BYTES: usize = USK_BYTES;
+    const CT_BYTES: usize = CT_BYTES;
+    const MSG_BYTES: usize = MSG_BYTES;
+
+    /// Generate a keypair used by the Private Key Generator (PKG).
+    fn setup<R: Rng + CryptoRng>(rng: &mut R) -> (PublicKey, SecretKey) {
+        let s = rand_scalar(rng);
+        let ppub = (G1Affine::generator() * s).into();
+
+        (PublicKey { ppub }, SecretKey { s })
+    }
+
+    /// Extract a user secret key for a given identity.
+    fn extract_usk<R: Rng + CryptoRng>(
+        _opk: Option<&PublicKey>,
+        sk: &SecretKey,
+        v: &Identity,
+        _rng: &mut R,
+    ) -> UserSecretKey {
+        UserSecretKey {
+            d: (hash_to_point(v) * sk.s).into(),
+        }
+    }
+
+    /// Encrypt a message using the PKG public key and an identity.
+    fn encrypt(pk: &PublicKey, v: &Identity, message: &Msg, rng: &Self::RngBytes) -> CipherText {
+        let r = Scalar::from_bytes_wide(rng);
+
+        let u = (G1Affine::generator() * r).into();
+        let v = pairing(&pk.ppub, &hash_to_point(v)) * r + message;
+
+        CipherText { u, v }
+    }
+
+    /// Derive the same message from the CipherText using a UserSecretKey.
+    fn decrypt(usk: &UserSecretKey, ct: &CipherText) -> Msg {
+        ct.v - pairing(&ct.u, &usk.d)
+    }
+}
+
+impl Compress for PublicKey {
+    const OUTPUT_SIZE: usize = PK_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; PK_BYTES] {
+        self.ppub.to_compressed()
+    }
+
+    fn from_bytes(bytes: &[u8; PK_BYTES]) -> CtOption<Self> {
+        // Use the checked `from_compressed` variant, which verifies that the
+        // point is in the correct prime-order subgroup (GHSA-25fp-2fjj-g84w).
+        G1Affine::from_compressed(bytes).map(|ppub| PublicKey { ppub })
+    }
+}
+
+impl Compress for SecretKey {
+    const OUTPUT_SIZE: usize = SK_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; SK_BYTES] {
+        self.s.to_bytes()
+    }
+
+    fn from_bytes(bytes: &[u8; SK_BYTES]) -> CtOption<Self> {
+        Scalar::from_bytes(bytes).map(|s| SecretKey { s })
+    }
+}
+
+impl Compress for UserSecretKey {
+    const OUTPUT_SIZE: usize = USK_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; USK_BYTES] {
+        self.d.to_compressed()
+    }
+
+    fn from_bytes(bytes: &[u8; USK_BYTES]) -> CtOption<Self> {
+        G2Affine::from_compressed(bytes).map(|d| UserSecretKey { d })
+    }
+}
+
+impl Compress for CipherText {
+    const OUTPUT_SIZE: usize = CT_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; CT_BYTES] {
+        let mut res = [0u8; CT_BYTES];
+        let (u, v) = mut_array_refs![&mut res, G1_BYTES, GT_BYTES];
+
+        *u = self.u.to_compressed();
+        *v = self.v.to_compressed();
+
+        res
+    }
+
+    fn from_bytes(bytes: &[u8; CT_BYTES]) -> CtOption<Self> {
+        let (u, v) = array_refs![bytes, G1_BYTES, GT_BYTES];
+
+        let u = G1Affine::from_compressed(u);
+        let v = Gt::from_compressed(v);
+
+        u.and_then(|u| v.map(|v| CipherText { u, v }))
+    }
+}
+
+impl ConstantTimeEq for CipherText {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.u.ct_eq(&other.u) & self.v.ct_eq(&other.v)
+    }
+}
+
+impl ConditionallySelectable for CipherText {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        CipherText {
+            u: G1Affine::conditional_select(&a.u, &b.u, choice),
+            v: Gt::conditional_select(&a.v, &b.v, choice),
+        }
+    }
+}
+
+impl ConditionallySelectable for UserSecretKey {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        UserSecretKey {
+            d: G2Affine::conditional_select(&a.d, &b.d, choice),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    test_ibe!(BonehFranklin);
+
+    // Regression test for GHSA-25fp-2fjj-g84w. A public key that encodes a valid
+    // on-curve point outside the prime-order subgroup must be rejected by the
+    // checked `from_bytes` deserialization.
+    #[test]
+    fn from_bytes_rejects_non_subgroup_point() {
+        assert!(bool::from(
+            PublicKey::from_bytes(&NON_SUBGROUP_G1_COMPRESSED).is_none()
+        ));
+    }
+
+    #[test]
+    fn distinct_identities_hash_to_distinct_points() {
+        let a = hash_to_point(&Identity::derive_str("alice@example.com"));
+        let b = hash_to_point(&Identity::derive_str("bob@example.com"));
+
+        assert_ne!(a, b);
+        assert_eq!(a, hash_to_point(&Identity::derive_str("alice@example.com")));
+    }
+}
@@ -0,0 +1,624 @@
+//! IND-sID-CPA secure anonymous HIBE from the Boyen & Waters scheme.
+//! * From: "[Anonymous Hierarchical Identity-Based Encryption (Without Random Oracles)](https://link.springer.com/content/pdf/10.1007/11818175_17.pdf)"
+//!
+//! This is the multi-level variant of [`boyen_waters`](crate::ibe::boyen_waters), with a
+//! configurable maximum depth `D`. Every level of the hierarchy has its own identity hashing
+//! parameters, while the linear splitting of the ciphertext is shared among the levels.
+//!
+//! Ciphertexts always contain `D` identity components, where unused levels are filled with random
+//! group elements, such that neither the recipient path nor its depth is revealed. Note that the
+//! delegation components of a user secret key allow its holder to test the identity components of
+//! a ciphertext at the levels below its own depth.
+//!
+//! The byte serialisation of all structures depends on `D` and is returned as a vector.
+//! The structure of the byte serialisation is not guaranteed to remain constant between releases
+//! of this library.
+
+extern crate alloc;
+use alloc::vec::Vec;
+
+use core::convert::TryInto;
+
+use crate::ibe::{IdentityPath, HIBE, IBE};
+use crate::util::*;
+use crate::Compress;
+use pg_curve::{
+    multi_miller_loop, pairing, G1Affine, G1Projective, G2Affine, G2Prepared, G2Projective, Gt,
+    Scalar,
+};
+use rand::{CryptoRng, Rng};
+use subtle::{Choice, ConstantTimeEq, CtOption};
+
+/// Size of the compressed message in bytes.
+pub const MSG_BYTES: usize = GT_BYTES;
+
+/// Number of group elements of the delegation components per level.
+const B: usize = 10;
+
+/// Public key parameters generated by the PKG used to encrypt messages.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PublicKey<const D: usize> {
+    omega: Gt,
+    v: [G1Affine; 4],
+    z: [[G1Affine; 2]; D],
+}
+
+/// Secret key parameter generated by the PKG used to extract user secret keys.
+///
+/// # Zeroization
+///
+/// With the `zeroize` feature enabled this type derives `Zeroize` but **not**
+/// `ZeroizeOnDrop` (it is `Copy`). Secret material is **not** cleared on drop —
+/// you **MUST** call `.zeroize()` explicitly once done. See the
+/// [crate-level docs](crate#zeroizing-secret-material).
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize))]
+pub struct SecretKey<const D: usize> {
+    alpha: Scalar,
+    t: [Scalar; 4],
+    z: [[Scalar; 2]; D],
+}
+
+impl<const D: usize> ConstantTimeEq for SecretKey<D> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.z
+            .iter()
+            .zip(other.z.iter())
+            .fold(self.alpha.ct_eq(&other.alpha), |acc, (a, b)| {
+                acc & a[..].ct_eq(&b[..])
+            })
+            & self.t[..].ct_eq(&other.t[..])
+    }
+}
+
+impl<const D: usize> PartialEq for SecretKey<D> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+/// Points on the paired curves that form the user secret key.
+///
+/// Besides the decryption components, the user secret key contains per level of the hierarchy
+/// either the components to re-randomize the key (for the levels of its identity path) or the
+/// components to delegate keys (for the levels below).
+///
+/// # Zeroization
+///
+/// With the `zeroize` feature enabled this type derives `Zeroize` but **not**
+/// `ZeroizeOnDrop` (it is `Copy`). Secret material is **not** cleared on drop —
+/// you **MUST** call `.zeroize()` explicitly once done. See the
+/// [crate-level docs](crate#zeroizing-secret-material).
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize))]
+pub struct UserSecretKey<const D: usize> {
+    len: usize,
+    d0: [G2Affine; D],
+    d: [G2Affine; 4],
+    b: [[G2Affine; B]; D],
+}
+
+impl<const D: usize> ConstantTimeEq for UserSecretKey<D> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.b
+            .iter()
+            .zip(other.b.iter())
+            .fold(self.len.ct_eq(&other.len), |acc, (a, b)| {
+                acc & a[..].ct_eq(&b[..])
+            })
+            & self.d0[..].ct_eq(&other.d0[..])
+            & self.d[..].ct_eq(&other.d[..])
+    }
+}
+
+impl<const D: usize> PartialEq for UserSecretKey<D> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+/// Encrypted message. Can only be decrypted with an user secret key.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CipherText<const D: usize> {
+    c0: [G1Affine; D],
+    c: [G1Affine; 4],
+    cprime: Gt,
+}
+
+/// A message that can be encrypted using the PKE.
+pub type Msg = Gt;
+
+/// The multi-level Boyen & Waters anonymous hierarchical identity-based encryption scheme,
+/// supporting identity paths up to depth `D`.
+#[derive(Debug)]
+pub struct BoyenWatersHIBE<const D: usize>;
+
+/// Batch multiply the generator of G2 with scalars.
+fn batch_g2<const N: usize>(scalars: &[Scalar; N]) -> [G2Affine; N] {
+    let h = G2Projective::generator();
+    let batch: Vec<G2Projective> = scalars.iter().map(|s| h * s).collect();
+
+    let mut out = [G2Affine::default(); N];
+    G2Projective::batch_normalize(&batch, &mut out);
+
+    out
+}
+
+/// Scales the components of a level by `lambda1` (the first chain) and `lambda2` (the second
+/// chain), which re-randomizes them while keeping them consistent.
+fn scale(b: &mut [G2Affine; B], lambda1: &Scalar, lambda2: &Scalar) {
+    let batch: Vec<G2Projective> = b
+        .iter()
+        .enumerate()
+        .map(|(i, el)| match i {
+            0 | 2 | 3 | 6 | 7 => el * lambda1,
+            _ => el * lambda2,
+        })
+        .collect();
+
+    G2Projective::batch_normalize(&batch, b);
+}
+
+impl<const D: usize> IBE for BoyenWatersHIBE<D> {
+    type Pk = PublicKey<D>;
+    type Sk = SecretKey<D>;
+    type Usk = UserSecretKey<D>;
+    type Ct = CipherText<D>;
+    type Msg = Msg;
+    type Id = IdentityPath<D>;
+    type RngBytes = [u8; 192];
+
+    const PK_BYTES: usize = GT_BYTES + (4 + 2 * D) * G1_BYTES;
+    const SK_BYTES: usize = (5 + 2 * D) * SCALAR_BYTES;
+    const USK_BYTES: usize = 1 + (4 + D + B * D) * G2_BYTES;
+    const CT_BYTES: usize = (4 + D) * G1_BYTES + GT_BYTES;
+    const MSG_BYTES: usize = MSG_BYTES;
+
+    /// Generate a keypair used by the Private Key Generator (PKG).
+    fn setup<R: Rng + CryptoRng>(rng: &mut R) -> (PublicKey<D>, SecretKey<D>) {
+        let g = G1Affine::generator();
+        let h = G2Affine::generator();
+
+        let alpha = rand_scalar(rng);
+        let t = [
+            rand_scalar(rng),
+            rand_scalar(rng),
+            rand_scalar(rng),
+            rand_scalar(rng),
+        ];
+
+        let mut z = [[Scalar::zero(); 2]; D];
+        for zl in z.iter_mut() {
+            *zl = [rand_scalar(rng), rand_scalar(rng)];
+        }
+
+        let omega = pairing(&g, &h) * (alpha * t[0] * t[1]);
+
+        let mut v = [G1Affine::default(); 4];
+        let batch: Vec<G1Projective> = t.iter().map(|ti| g * ti).collect();
+        G1Projective::batch_normalize(&batch, &mut v);
+
+        let mut gz = [[G1Affine::default(); 2]; D];
+        for (gzl, zl) in gz.iter_mut().zip(z.iter()) {
+            *gzl = [(g * zl[0]).into(), (g * zl[1]).into()];
+        }
+
+        (PublicKey { omega, v, z: gz }, SecretKey { alpha, t, z })
+    }
+
+    /// Extract an user secret key for a given identity path.
+    fn extract_usk<R: Rng + CryptoRng>(
+        _opk: Option<&PublicKey<D>>,
+        sk: &SecretKey<D>,
+        v: &IdentityPath<D>,
+        rng: &mut R,
+    ) -> UserSecretKey<D> {
+        let [t1, t2, t3, t4] = sk.t;
+        let (t12, t34) = (t1 * t2, t3 * t4);
+
+        let mut d0 = [G2Affine::identity(); D];
+        let mut b = [[G2Affine::identity(); B]; D];
+
+        // The exponents of d1, d2, d3 and d4, respectively.
+        let mut e = [
+            -sk.alpha * t2,
+            -sk.alpha * t1,
+            Scalar::zero(),
+            Scalar::zero(),
+        ];
+
+        for (l, zl) in sk.z.iter().enumerate() {
+            let rho1 = rand_scalar(rng);
+            let rho2 = rand_scalar(rng);
+
+            if let Some(id) = v.components().get(l) {
+                let x = zl[0] + zl[1] * id.to_scalar();
+
+                let r1 = rand_scalar(rng);
+                let r2 = rand_scalar(rng);
+
+                d0[l] = (G2Affine::generator() * (r1 * t12 + r2 * t34)).into();
+
+                e[0] -= x * r1 * t2;
+                e[1] -= x * r1 * t1;
+                e[2] -= x * r2 * t4;
+                e[3] -= x * r2 * t3;
+
+                b[l][..6].copy_from_slice(&batch_g2(&[
+                    rho1 * t12,
+                    rho2 * t34,
+                    -rho1 * x * t2,
+                    -rho1 * x * t1,
+                    -rho2 * x * t4,
+                    -rho2 * x * t3,
+                ]));
+            } else {
+                b[l] = batch_g2(&[
+                    rho1 * t12,
+                    rho2 * t34,
+                    -rho1 * zl[0] * t2,
+                    -rho1 * zl[0] * t1,
+                    -rho2 * zl[0] * t4,
+                    -rho2 * zl[0] * t3,
+                    -rho1 * zl[1] * t2,
+                    -rho1 * zl[1] * t1,
+                    -rho2 * zl[1] * t4,
+                    -rho2 * zl[1] * t3,
+                ]);
+            }
+        }
+
+        UserSecretKey {
+            len: v.len(),
+            d0,
+            d: batch_g2(&e),
+            b,
+        }
+    }
+
+    /// Encrypt a message using the PKG public key and an identity path.
+    fn encrypt(
+        pk: &PublicKey<D>,
+        v: &IdentityPath<D>,
+        m: &Msg,
+        rng_bytes: &Self::RngBytes,
+    ) -> CipherText<D> {
+        const { assert!(D <= u8::MAX as usize, "D must fit in a byte") };
+
+        let s = Scalar::from_bytes_wide(rng_bytes[0..64].try_into().unwrap());
+        let s1 = Scalar::from_bytes_wide(rng_bytes[64..128].try_into().unwrap());
+        let s2 = Scalar::from_bytes_wide(rng_bytes[128..192].try_into().unwrap());
+
+        let batch: Vec<G1Projective> =
+            pk.z.iter()
+                .enumerate()
+                .map(|(l, zl)| match v.components().get(l) {
+                    Some(id) => (zl[0] + zl[1] * id.to_scalar()) * s,
+                    None => {
+                        // Fill the unused levels with random elements derived from the coins.
+                        let mut pre = [0u8; 193];
+                        pre[..192].copy_from_slice(rng_bytes);
+                        pre[192] = l as u8;
+
+                        G1Affine::generator() * Scalar::from_bytes_wide(&sha3_512(&pre))
+                    }
+                })
+                .collect();
+
+        let mut c0 = [G1Affine::default(); D];
+        G1Projective::batch_normalize(&batch, &mut c0);
+
+        let batch = [
+            pk.v[0] * (s - s1),
+            pk.v[1] * s1,
+            pk.v[2] * (s - s2),
+            pk.v[3] * s2,
+        ];
+        let mut c = [G1Affine::default(); 4];
+        G1Projective::batch_normalize(&batch, &mut c);
+
+        CipherText {
+            c0,
+            c,
+            cprime: pk.omega * s + m,
+        }
+    }
+
+    /// Decrypt ciphertext to a message using a user secret key.
+    fn decrypt(usk: &UserSecretKey<D>, ct: &CipherText<D>) -> Msg {
+        let prepared: Vec<(G1Affine, G2Prepared)> =
+            ct.c.iter()
+                .zip(usk.d.iter())
+                .chain(ct.c0.iter().zip(usk.d0.iter()).take(usk.len))
+                .map(|(c, d)| (*c, G2Prepared::from(*d)))
+                .collect();
+
+        let terms: Vec<(&G1Affine, &G2Prepared)> = prepared.iter().map(|(c, d)| (c, d)).collect();
+
+        ct.cprime + multi_miller_loop(&terms).final_exponentiation()
+    }
+}
+
+impl<const D: usize> HIBE for BoyenWatersHIBE<D> {
+    type IdComponent = Identity;
+
+    const MAX_DEPTH: usize = D;
+
+    /// Delegate a user secret key for a child identity, re-randomizing all components.
+    fn delegate<R: Rng + CryptoRng>(
+        _pk: &PublicKey<D>,
+        usk: &UserSecretKey<D>,
+        id: &Identity,
+        rng: &mut R,
+    ) -> Option<UserSecretKey<D>> {
+        if usk.len >= D {
+            return None;
+        }
+
+        let mut res = *usk;
+        let mut d: [G2Projective; 4] = [
+            usk.d[0].into(),
+            usk.d[1].into(),
+            usk.d[2].into(),
+            usk.d[3].into(),
+        ];
+
+        // Fix the identity at the new level, such that its delegation components
+        // become re-randomization components.
+        let x = id.to_scalar();
+        let bl = &usk.b[usk.len];
+        let batch = [
+            bl[2] + bl[6] * x,
+            bl[3] + bl[7] * x,
+            bl[4] + bl[8] * x,
+            bl[5] + bl[9] * x,
+        ];
+        let mut fixed = [G2Affine::default(); 4];
+        G2Projective::batch_normalize(&batch, &mut fixed);
+
+        res.b[usk.len] = [G2Affine::identity(); B];
+        res.b[usk.len][..2].copy_from_slice(&bl[..2]);
+        res.b[usk.len][2..6].copy_from_slice(&fixed);
+        res.len += 1;
+
+        for (l, bl) in res.b.iter_mut().enumerate() {
+            if l < res.len {
+                // Re-randomize the decryption components of this level.
+                let mu1 = rand_scalar(rng);
+                let mu2 = rand_scalar(rng);
+
+                let d0 = G2Projective::from(res.d0[l]) + bl[0] * mu1 + bl[1] * mu2;
+                res.d0[l] = d0.into();
+
+                d[0] += bl[2] * mu1;
+                d[1] += bl[3] * mu1;
+                d[2] += bl[4] * mu2;
+                d[3] += bl[5] * mu2;
+            }
+
+            scale(bl, &rand_scalar(rng), &rand_scalar(rng));
+        }
+
+        G2Projective::batch_normalize(&d, &mut res.d);
+
+        Some(res)
+    }
+}
+
+impl<const D: usize> Compress for PublicKey<D> {
+    const OUTPUT_SIZE: usize = <BoyenWatersHIBE<D> as IBE>::PK_BYTES;
+    type Output = Vec<u8>;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut res = Vec::with_capacity(Self::OUTPUT_SIZE);
+
+        res.extend_from_slice(&self.omega.to_compressed());
+        for vi in self.v.iter() {
+            res.extend_from_slice(&vi.to_compressed());
+        }
+        for zl in self.z.iter() {
+            res.extend_from_slice(&zl[0].to_compressed());
+            res.extend_from_slice(&zl[1].to_compressed());
+        }
+
+        res
+    }
+
+    fn from_bytes(bytes: &Vec<u8>) -> CtOption<Self> {
+        let mut res = PublicKey {
+            omega: Gt::identity(),
+            v: [G1Affine::identity(); 4],
+            z: [[G1Affine::identity(); 2]; D],
+        };
+
+        if bytes.len() != Self::OUTPUT_SIZE {
+            return CtOption::new(res, Choice::from(0));
+        }
+
+        // Use the checked `from_compressed` variant, which verifies that each
+        // point is in the correct prime-order subgroup. Public keys may be
+        // deserialized from an unauthenticated source before their integrity is
+        // verified out-of-band; skipping the subgroup check would let an
+        // adversary embed a point outside the prime-order subgroup (BLS12-381
+        // has a large, non-trivial cofactor) and mount a small-subgroup attack
+        // (GHSA-25fp-2fjj-g84w).
+        let mut is_some = Gt::from_compressed(bytes[..GT_BYTES].try_into().unwrap())
+            .map(|el| res.omega = el)
+            .is_some();
+
+        let g1s = bytes[GT_BYTES..].chunks_exact(G1_BYTES);
+        let slots = res.v.iter_mut().chain(res.z.iter_mut().flatten());
+        for (slot, chunk) in slots.zip(g1s) {
+            is_some &= G1Affine::from_compressed(chunk.try_into().unwrap())
+                .map(|el| *slot = el)
+                .is_some();
+        }
+
+        CtOption::new(res, is_some)
+    }
+}
+
+impl<const D: usize> Compress for SecretKey<D> {
+    const OUTPUT_SIZE: usize = <BoyenWatersHIBE<D> as IBE>::SK_BYTES;
+    type Output = Vec<u8>;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let scalars = core::iter::once(&self.alpha)
+            .chain(self.t.iter())
+            .chain(self.z.iter().flatten());
+
+        scalars.flat_map(|s| s.to_bytes()).collect()
+    }
+
+    fn from_bytes(bytes: &Vec<u8>) -> CtOption<Self> {
+        let mut res = SecretKey {
+            alpha: Scalar::zero(),
+            t: [Scalar::zero(); 4],
+            z: [[Scalar::zero(); 2]; D],
+        };
+
+        if bytes.len() != Self::OUTPUT_SIZE {
+            return CtOption::new(res, Choice::from(0));
+        }
+
+        let mut is_some = Choice::from(1u8);
+        let slots = core::iter::once(&mut res.alpha)
+            .chain(res.t.iter_mut())
+            .chain(res.z.iter_mut().flatten());
+        for (slot, chunk) in slots.zip(bytes.chunks_exact(SCALAR_BYTES)) {
+            is_some &= Scalar::from_bytes(chunk.try_into().unwrap())
+                .map(|s| *slot = s)
+                .is_some();
+        }
+
+        CtOption::new(res, is_some)
+    }
+}
+
+impl<const D: usize> Compress for UserSecretKey<D> {
+    const OUTPUT_SIZE: usize = <BoyenWatersHIBE<D> as IBE>::USK_BYTES;
+    type Output = Vec<u8>;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        const { assert!(D <= u8::MAX as usize, "D must fit in a byte") };
+
+        let mut res = Vec::with_capacity(Self::OUTPUT_SIZE);
+
+        res.push(self.len as u8);
+        let els = self
+            .d
+            .iter()
+            .chain(self.d0.iter())
+            .chain(self.b.iter().flatten());
+        for el in els {
+            res.extend_from_slice(&el.to_compressed());
+        }
+
+        res
+    }
+
+    fn from_bytes(bytes: &Vec<u8>) -> CtOption<Self> {
+        let mut res = UserSecretKey {
+            len: 0,
+            d0: [G2Affine::identity(); D],
+            d: [G2Affine::identity(); 4],
+            b: [[G2Affine::identity(); B]; D],
+        };
+
+        if bytes.len() != Self::OUTPUT_SIZE || bytes[0] as usize > D {
+            return CtOption::new(res, Choice::from(0));
+        }
+
+        res.len = bytes[0] as usize;
+
+        let mut is_some = Choice::from(1u8);
+        let slots = res
+            .d
+            .iter_mut()
+            .chain(res.d0.iter_mut())
+            .chain(res.b.iter_mut().flatten());
+        for (slot, chunk) in slots.zip(bytes[1..].chunks_exact(G2_BYTES)) {
+            is_some &= G2Affine::from_compressed(chunk.try_into().unwrap())
+                .map(|el| *slot = el)
+                .is_some();
+        }
+
+        CtOption::new(res, is_some)
+    }
+}
+
+impl<const D: usize> Compress for CipherText<D> {
+    const OUTPUT_SIZE: usize = <BoyenWatersHIBE<D> as IBE>::CT_BYTES;
+    type Output = Vec<u8>;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut res = Vec::with_capacity(Self::OUTPUT_SIZE);
+
+        for el in self.c.iter().chain(self.c0.iter()) {
+            res.extend_from_slice(&el.to_compressed());
+        }
+        res.extend_from_slice(&self.cprime.to_compressed());
+
+        res
+    }
+
+    fn from_bytes(bytes: &Vec<u8>) -> CtOption<Self> {
+        let mut res = CipherText {
+            c0: [G1Affine::identity(); D],
+            c: [G1Affine::identity(); 4],
+            cprime: Gt::identity(),
+        };
+
+        if bytes.len() != Self::OUTPUT_SIZE {
+            return CtOption::new(res, Choice::from(0));
+        }
+
+        let (g1s, gt) = bytes.split_at(Self::OUTPUT_SIZE - GT_BYTES);
+
+        let mut is_some = Gt::from_compressed(gt.try_into().unwrap())
+            .map(|el| res.cprime = el)
+            .is_some();
+
+        let slots = res.c.iter_mut().chain(res.c0.iter_mut());
+        for (slot, chunk) in slots.zip(g1s.chunks_exact(G1_BYTES)) {
+            is_some &= G1Affine::from_compressed(chunk.try_into().unwrap())
+                .map(|el| *slot = el)
+                .is_some();
+        }
+
+        CtOption::new(res, is_some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    type BoyenWatersHIBE = super::BoyenWatersHIBE<3>;
+    type PublicKey = super::PublicKey<3>;
+    type SecretKey = super::SecretKey<3>;
+    type UserSecretKey = super::UserSecretKey<3>;
+    type CipherText = super::CipherText<3>;
+
+    test_ibe!(BoyenWatersHIBE);
+
+    test_hibe!(BoyenWatersHIBE);
+
+    // Regression test for GHSA-25fp-2fjj-g84w. A public key whose first G1
+    // component encodes a valid on-curve point that lies outside the prime-order
+    // subgroup must be rejected by the checked `from_bytes`.
+    #[test]
+    fn from_bytes_rejects_non_subgroup_point() {
+        let pk = perform_default().pk;
+        let mut bytes = pk.to_bytes();
+        // Layout: omega (Gt) precedes v1 (first G1 component).
+        bytes[GT_BYTES..GT_BYTES + G1_BYTES].copy_from_slice(&NON_SUBGROUP_G1_COMPRESSED);
+        assert!(bool::from(PublicKey::from_bytes(&bytes).is_none()));
+    }
+
+    #[test]
+    fn from_bytes_rejects_wrong_length() {
+        let pk = perform_default().pk;
+        let mut bytes = pk.to_bytes();
+        bytes.pop();
+        assert!(bool::from(PublicKey::from_bytes(&bytes).is_none()));
+    }
+}
@@ -0,0 +1,560 @@
+//! IND-ID-CPA secure accountable-authority IBE by Goyal.
+//! * From: "[Reducing Trust in the PKG in Identity Based Cryptosystems](https://www.iacr.org/archive/crypto2007/46220430/46220430.pdf)"
+//! * Published in: CRYPTO, 2007
+//!
+//! The scheme builds on the IBE by Gentry. A user secret key for an identity is a triple
+//! (r, t, K = (h0 · h1^t · g2^{-r})^{1/(α - id)}), such that every identity has one key family
+//! for every t. During extraction, the user commits to a random t' by sending R = h1^{t'} along
+//! with a Schnorr proof of knowledge of t'. The PKG picks r and t'' and returns the key for
+//! t = t' + t'', of which it does not learn t. A user that knows two keys with different t for the
+//! same identity can compute the discrete logarithm of h1, so the user can only leak the key it
+//! obtained, and a leaked key with another t must originate from the PKG.
+//!
+//! The Schnorr proof is made non-interactive using the Fiat-Shamir heuristic, where the challenge
+//! is computed using sha3_512 over the identity and the commitments.
+
+use crate::ibe::{Culprit, AIBE};
+use crate::util::*;
+use crate::Compress;
+use arrayref::{array_refs, mut_array_refs};
+use group::Group;
+use pg_curve::{multi_miller_loop, pairing, G1Affine, G2Affine, G2Prepared, Gt, Scalar};
+use rand::{CryptoRng, Rng};
+use subtle::CtOption;
+
+/// Size of the compressed message in bytes.
+pub const MSG_BYTES: usize = GT_BYTES;
+
+/// Size of the compressed master public key in bytes.
+pub const PK_BYTES: usize = G1_BYTES + 2 * G2_BYTES + 2 * GT_BYTES;
+
+/// Size of the compressed master secret key in bytes.
+pub const SK_BYTES: usize = SCALAR_BYTES;
+
+/// Size of the compressed user secret key in bytes.
+pub const USK_BYTES: usize = 2 * SCALAR_BYTES + G2_BYTES;
+
+/// Size of the compressed ciphertext in bytes.
+pub const CT_BYTES: usize = G1_BYTES + 3 * GT_BYTES;
+
+/// Size of the compressed extraction request in bytes.
+pub const KEY_REQUEST_BYTES: usize = G2_BYTES + 2 * SCALAR_BYTES;
+
+/// Size of the compressed extraction response in bytes.
+pub const KEY_RESPONSE_BYTES: usize = 2 * SCALAR_BYTES + G2_BYTES;
+
+/// Public key parameters generated by the PKG used to encrypt messages.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct PublicKey {
+    g1: G1Affine,
+    h0: G2Affine,
+    h1: G2Affine,
+    z0: Gt,
+    z1: Gt,
+}
+
+/// Secret key parameter generated by the PKG used to answer extraction requests.
+///
+/// # Zeroization
+///
+/// With the `zeroize` feature enabled this type derives `Zeroize` but **not**
+/// `ZeroizeOnDrop` (it is `Copy`). Secret material is **not** cleared on drop —
+/// you **MUST** call `.zeroize()` explicitly once done. See the
+/// [crate-level docs](crate#zeroizing-secret-material).
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize))]
+pub struct SecretKey {
+    alpha: Scalar,
+}
+
+/// User secret key. Can be used to decrypt the corresponding ciphertext.
+///
+/// # Zeroization
+///
+/// With the `zeroize` feature enabled this type derives `Zeroize` but **not**
+/// `ZeroizeOnDrop` (it is `Copy`). Secret material is **not** cleared on drop —
+/// you **MUST** call `.zeroize()` explicitly once done. See the
+/// [crate-level docs](crate#zeroizing-secret-material).
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize))]
+pub struct UserSecretKey {
+    r: Scalar,
+    t: Scalar,
+    k: G2Affine,
+}
+
+/// Encrypted message. Can only be decrypted with a corresponding user secret key.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct CipherText {
+    c1: G1Affine,
+    c2: Gt,
+    c3: Gt,
+    c4: Gt,
+}
+
+/// State of the user during extraction, holding its share t' of t.
+///
+/// # Zeroization
+///
+/// With the `zeroize` feature enabled this type derives `Zeroize` and `ZeroizeOnDrop`.
+#[derive(Debug)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize, zeroize::ZeroizeOnDrop))]
+pub struct UserState {
+    id: Identity,
+    t: Scalar,
+}
+
+/// Extraction request, containing R = h1^{t'} and a proof of knowledge of t'.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct KeyRequest {
+    r: G2Affine,
+    c: Scalar,
+    s: Scalar,
+}
+
+/// Extraction response, containing the user secret key for t' + t'', without t'.
+///
+/// # Zeroization
+///
+/// With the `zeroize` feature enabled this type derives `Zeroize` but **not**
+/// `ZeroizeOnDrop` (it is `Copy`). Secret material is **not** cleared on drop —
+/// you **MUST** call `.zeroize()` explicitly once done. See the
+/// [crate-level docs](crate#zeroizing-secret-material).
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize))]
+pub struct KeyResponse {
+    r: Scalar,
+    t: Scalar,
+    k: G2Affine,
+}
+
+/// A message that can be encrypted using the A-IBE.
+pub type Msg = Gt;
+
+/// The accountable-authority identity-based encryption scheme by Goyal.
+#[derive(Debug)]
+pub struct Goyal;
+
+/// Computes the Fiat-Shamir challenge c = H(id, R, A) of the proof of knowledge of t'.
+fn challenge(id: &Identity, r: &G2Affine, a: &G2Affine) -> Scalar {
+    let mut pre = [0u8; ID_BYTES + 2 * G2_BYTES];
+    let (pid, pr, pa) = mut_array_refs![&mut pre, ID_BYTES, G2_BYTES, G2_BYTES];
+
+    *pid = id.0;
+    *pr = r.to_compressed();
+    *pa = a.to_compressed();
+
+    Scalar::from_bytes_wide(&sha3_512(&pre))
+}
+
+/// Checks that e(g1 · g^{-id}, K) = e(g, h0 · h1^t · g2^{-r}).
+fn verify_usk(pk: &PublicKey, id: &Identity, usk: &UserSecretKey) -> bool {
+    let c = G1Affine::from(pk.g1 - G1Affine::generator() * id.to_scalar());
+    let d = G2Affine::from(pk.h0 + pk.h1 * usk.t - G2Affine::generator() * usk.r);
+
+    multi_miller_loop(&[
+        (&c, &G2Prepared::from(usk.k)),
+        (&-G1Affine::generator(), &G2Prepared::from(d)),
+    ])
+    .final_exponentiation()
+        == Gt::identity()
+}
+
+impl AIBE for Goyal {
+    type Pk = PublicKey;
+    type Sk = SecretKey;
+    type Usk = UserSecretKey;
+    type Ct = CipherText;
+    type Msg = Msg;
+    type Id = Identity;
+    type RngBytes = [u8; 64];
+    type UserState = UserState;
+    type KeyRequest = KeyRequest;
+    type KeyResponse = KeyResponse;
+
+    const PK_BYTES: usize = PK_BYTES;
+    const SK_BYTES: usize = SK_BYTES;
+    const USK_BYTES: usize = USK_BYTES;
+    const CT_BYTES: usize = CT_BYTES;
+    const MSG_BYTES: usize = MSG_BYTES;
+    const KEY_REQUEST_BYTES: usize = KEY_REQUEST_BYTES;
+    const KEY_RESPONSE_BYTES: usize = KEY_RESPONSE_BYTES;
+
+    /// Generate a keypair used by the Private Key Generator (PKG).
+    fn setup<R: Rng + CryptoRng>(rng: &mut R) -> (PublicKey, SecretKey) {
+        let g = G1Affine::generator();
+
+        let alpha = rand_scalar(rng);
+        let g1 = (g * alpha).into();
+        let h0 = rand_g2(rng).into();
+        let h1 = rand_g2(rng).into();
+
+        let z0 = pairing(&g, &h0);
+        let z1 = pairing(&g, &h1);
+
+        (PublicKey { g1, h0, h1, z0, z1 }, SecretKey { alpha })
+    }
+
+    /// Pick t', and prove knowledge of t' in R = h1^{t'} using A = h1^w and s = w + c·t'.
+    fn request_usk<R: Rng + CryptoRng>(
+        pk: &PublicKey,
+        id: &Identity,
+        rng: &mut R,
+    ) -> (UserState, KeyRequest) {
+        let t = rand_scalar(rng);
+        let w = rand_scalar(rng);
+
+        let r = (pk.h1 * t).into();
+        let a = (pk.h1 * w).into();
+        let c = challenge(id, &r, &a);
+
+        (UserState { id: *id, t }, KeyRequest { r, c, s: w + c * t })
+    }
+
+    /// Verify the proof of knowledge, pick r and t'', and compute
+    /// K = (h0 · R · h1^{t''} · g2^{-r})^{1/(α - id)}.
+    fn issue_usk<R: Rng + CryptoRng>(
+        pk: &PublicKey,
+        sk: &SecretKey,
+        id: &Identity,
+        req: &KeyRequest,
+        rng: &mut R,
+    ) -> Option<KeyResponse> {
+        let a = (pk.h1 * req.s - req.r * req.c).into();
+        if challenge(id, &req.r, &a) != req.c {
+            return None;
+        }
+
+        let r = rand_scalar(rng);
+        let t = rand_scalar(rng);
+
+        Option::<Scalar>::from((sk.alpha - id.to_scalar()).invert()).map(|e| KeyResponse {
+            r,
+            t,
+            k: ((pk.h1 * t + pk.h0 + req.r - G2Affine::generator() * r) * e).into(),
+        })
+    }
+
+    /// Combine t = t' + t'' and check the resulting user secret key.
+    fn finish_usk(pk: &PublicKey, state: UserState, resp: &KeyResponse) -> Option<UserSecretKey> {
+        let usk = UserSecretKey {
+            r: resp.r,
+            t: state.t + resp.t,
+            k: resp.k,
+        };
+
+        verify_usk(pk, &state.id, &usk).then_some(usk)
+    }
+
+    /// Encrypt a message using the PKG public key and an identity.
+    fn encrypt(pk: &PublicKey, id: &Identity, message: &Msg, rng: &[u8; 64]) -> CipherText {
+        let s = Scalar::from_bytes_wide(rng);
+
+        CipherText {
+            c1: ((pk.g1 - G1Affine::generator() * id.to_scalar()) * s).into(),
+            c2: Gt::generator() * s,
+            c3: message - pk.z0 * s,
+            c4: pk.z1 * s,
+        }
+    }
+
+    /// Derive the same message from the ciphertext using a user secret key.
+    fn decrypt(usk: &UserSecretKey, ct: &CipherText) -> Msg {
+        ct.c3 + pairing(&ct.c1, &usk.k) + ct.c2 * usk.r - ct.c4 * usk.t
+    }
+
+    /// Blame the user if the leaked key has the same t as the key of the user, and the PKG
+    /// otherwise.
+    fn trace(
+        pk: &PublicKey,
+        id: &Identity,
+        leaked: &UserSecretKey,
+        usk: &UserSecretKey,
+    ) -> Option<Culprit> {
+        if !verify_usk(pk, id, leaked) || !verify_usk(pk, id, usk) {
+            return None;
+        }
+
+        if leaked.t == usk.t {
+            Some(Culprit::User)
+        } else {
+            Some(Culprit::Pkg)
+        }
+    }
+}
+
+impl Compress for PublicKey {
+    const OUTPUT_SIZE: usize = PK_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; PK_BYTES] {
+        let mut res = [0u8; PK_BYTES];
+        let (g1, h0, h1, z0, z1) =
+            mut_array_refs![&mut res, G1_BYTES, G2_BYTES, G2_BYTES, GT_BYTES, GT_BYTES];
+
+        *g1 = self.g1.to_compressed();
+        *h0 = self.h0.to_compressed();
+        *h1 = self.h1.to_compressed();
+        *z0 = self.z0.to_compressed();
+        *z1 = self.z1.to_compressed();
+
+        res
+    }
+
+    fn from_bytes(bytes: &[u8; PK_BYTES]) -> CtOption<Self> {
+        let (g1, h0, h1, z0, z1) =
+            array_refs![bytes, G1_BYTES, G2_BYTES, G2_BYTES, GT_BYTES, GT_BYTES];
+
+        // Use the checked `from_compressed` variant, which verifies that each point is in the
+        // correct prime-order subgroup (GHSA-25fp-2fjj-g84w).
+        let g1 = G1Affine::from_compressed(g1);
+        let h0 = G2Affine::from_compressed(h0);
+        let h1 = G2Affine::from_compressed(h1);
+        let z0 = Gt::from_compressed(z0);
+        let z1 = Gt::from_compressed(z1);
+
+        g1.and_then(|g1| {
+            h0.and_then(|h0| {
+                h1.and_then(|h1| z0.and_then(|z0| z1.map(|z1| PublicKey { g1, h0, h1, z0, z1 })))
+            })
+        })
+    }
+}
+
+impl Compress for SecretKey {
+    const OUTPUT_SIZE: usize = SK_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; SK_BYTES] {
+        self.alpha.to_bytes()
+    }
+
+    fn from_bytes(bytes: &[u8; SK_BYTES]) -> CtOption<Self> {
+        Scalar::from_bytes(bytes).map(|alpha| SecretKey { alpha })
+    }
+}
+
+impl Compress for UserSecretKey {
+    const OUTPUT_SIZE: usize = USK_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; USK_BYTES] {
+        let mut res = [0u8; USK_BYTES];
+        let (r, t, k) = mut_array_refs![&mut res, SCALAR_BYTES, SCALAR_BYTES, G2_BYTES];
+
+        *r = self.r.to_bytes();
+        *t = self.t.to_bytes();
+        *k = self.k.to_compressed();
+
+        res
+    }
+
+    fn from_bytes(bytes: &[u8; USK_BYTES]) -> CtOption<Self> {
+        let (r, t, k) = array_refs![bytes, SCALAR_BYTES, SCALAR_BYTES, G2_BYTES];
+
+        let r = Scalar::from_bytes(r);
+        let t = Scalar::from_bytes(t);
+        let k = G2Affine::from_compressed(k);
+
+        r.and_then(|r| t.and_then(|t| k.map(|k| UserSecretKey { r, t, k })))
+    }
+}
+
+impl Compress for CipherText {
+    const OUTPUT_SIZE: usize = CT_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; CT_BYTES] {
+        let mut res = [0u8; CT_BYTES];
+        let (c1, c2, c3, c4) = mut_array_refs![&mut res, G1_BYTES, GT_BYTES, GT_BYTES, GT_BYTES];
+
+        *c1 = self.c1.to_compressed();
+        *c2 = self.c2.to_compressed();
+        *c3 = self.c3.to_compressed();
+        *c4 = self.c4.to_compressed();
+
+        res
+    }
+
+    fn from_bytes(bytes: &[u8; CT_BYTES]) -> CtOption<Self> {
+        let (c1, c2, c3, c4) = array_refs![bytes, G1_BYTES, GT_BYTES, GT_BYTES, GT_BYTES];
+
+        let c1 = G1Affine::from_compressed(c1);
+        let c2 = Gt::from_compressed(c2);
+        let c3 = Gt::from_compressed(c3);
+        let c4 = Gt::from_compressed(c4);
+
+        c1.and_then(|c1| {
+            c2.and_then(|c2| c3.and_then(|c3| c4.map(|c4| CipherText { c1, c2, c3, c4 })))
+        })
+    }
+}
+
+impl Compress for KeyRequest {
+    const OUTPUT_SIZE: usize = KEY_REQUEST_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; KEY_REQUEST_BYTES] {
+        let mut res = [0u8; KEY_REQUEST_BYTES];
+        let (r, c, s) = mut_array_refs![&mut res, G2_BYTES, SCALAR_BYTES, SCALAR_BYTES];
+
+        *r = self.r.to_compressed();
+        *c = self.c.to_bytes();
+        *s = self.s.to_bytes();
+
+        res
+    }
+
+    fn from_bytes(bytes: &[u8; KEY_REQUEST_BYTES]) -> CtOption<Self> {
+        let (r, c, s) = array_refs![bytes, G2_BYTES, SCALAR_BYTES, SCALAR_BYTES];
+
+        let r = G2Affine::from_compressed(r);
+        let c = Scalar::from_bytes(c);
+        let s = Scalar::from_bytes(s);
+
+        r.and_then(|r| c.and_then(|c| s.map(|s| KeyRequest { r, c, s })))
+    }
+}
+
+impl Compress for KeyResponse {
+    const OUTPUT_SIZE: usize = KEY_RESPONSE_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; KEY_RESPONSE_BYTES] {
+        let mut res = [0u8; KEY_RESPONSE_BYTES];
+        let (r, t, k) = mut_array_refs![&mut res, SCALAR_BYTES, SCALAR_BYTES, G2_BYTES];
+
+        *r = self.r.to_bytes();
+        *t = self.t.to_bytes();
+        *k = self.k.to_compressed();
+
+        res
+    }
+
+    fn from_bytes(bytes: &[u8; KEY_RESPONSE_BYTES]) -> CtOption<Self> {
+        let (r, t, k) = array_refs![bytes, SCALAR_BYTES, SCALAR_BYTES, G2_BYTES];
+
+        let r = Scalar::from_bytes(r);
+        let t = Scalar::from_bytes(t);
+        let k = G2Affine::from_compressed(k);
+
+        r.and_then(|r| t.and_then(|t| k.map(|k| KeyResponse { r, t, k })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Derive;
+    use group::Group;
+    use rand::RngCore;
+
+    const ALICE: &str = "email:alice@example.com";
+    const BOB: &str = "email:bob@example.com";
+
+    fn extract(pk: &PublicKey, sk: &SecretKey, id: &Identity) -> UserSecretKey {
+        let mut rng = rand::thread_rng();
+
+        let (state, req) = Goyal::request_usk(pk, id, &mut rng);
+        let resp = Goyal::issue_usk(pk, sk, id, &req, &mut rng).unwrap();
+
+        Goyal::finish_usk(pk, state, &resp).unwrap()
+    }
+
+    fn encrypt_random(pk: &PublicKey, id: &Identity) -> (CipherText, Msg) {
+        let mut rng = rand::thread_rng();
+        let mut coins = [0u8; 64];
+        rng.fill_bytes(&mut coins);
+
+        let m = Msg::random(&mut rng);
+
+        (Goyal::encrypt(pk, id, &m, &coins), m)
+    }
+
+    #[test]
+    fn eq_encrypt_decrypt() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = Goyal::setup(&mut rng);
+
+        let (alice, bob) = (Identity::derive_str(ALICE), Identity::derive_str(BOB));
+        let usk = extract(&pk, &sk, &alice);
+
+        let (ct, m) = encrypt_random(&pk, &alice);
+        assert_eq!(Goyal::decrypt(&usk, &ct), m);
+
+        let (ct, m) = encrypt_random(&pk, &bob);
+        assert_ne!(Goyal::decrypt(&usk, &ct), m);
+    }
+
+    #[test]
+    fn trace_blames_the_leaker() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = Goyal::setup(&mut rng);
+
+        let (alice, bob) = (Identity::derive_str(ALICE), Identity::derive_str(BOB));
+        let usk = extract(&pk, &sk, &alice);
+
+        // Any key the PKG creates by itself decrypts, but belongs to another key family.
+        let pkg_usk = extract(&pk, &sk, &alice);
+        let (ct, m) = encrypt_random(&pk, &alice);
+        assert_eq!(Goyal::decrypt(&pkg_usk, &ct), m);
+
+        assert_eq!(Goyal::trace(&pk, &alice, &usk, &usk), Some(Culprit::User));
+        assert_eq!(
+            Goyal::trace(&pk, &alice, &pkg_usk, &usk),
+            Some(Culprit::Pkg)
+        );
+
+        let bob_usk = extract(&pk, &sk, &bob);
+        assert_eq!(Goyal::trace(&pk, &alice, &bob_usk, &usk), None);
+    }
+
+    #[test]
+    fn extraction_rejects_tampering() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = Goyal::setup(&mut rng);
+
+        let (alice, bob) = (Identity::derive_str(ALICE), Identity::derive_str(BOB));
+
+        // The proof of knowledge is bound to the identity.
+        let (_, req) = Goyal::request_usk(&pk, &alice, &mut rng);
+        assert!(Goyal::issue_usk(&pk, &sk, &bob, &req, &mut rng).is_none());
+
+        let forged = KeyRequest {
+            r: (pk.h1 * rand_scalar(&mut rng)).into(),
+            ..req
+        };
+        assert!(Goyal::issue_usk(&pk, &sk, &alice, &forged, &mut rng).is_none());
+
+        let (state, req) = Goyal::request_usk(&pk, &alice, &mut rng);
+        let resp = Goyal::issue_usk(&pk, &sk, &alice, &req, &mut rng).unwrap();
+        let tampered = KeyResponse {
+            t: resp.t + Scalar::one(),
+            ..resp
+        };
+        assert!(Goyal::finish_usk(&pk, state, &tampered).is_none());
+    }
+
+    #[test]
+    fn eq_serialize_deserialize() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = Goyal::setup(&mut rng);
+
+        let alice = Identity::derive_str(ALICE);
+        let (state, req) = Goyal::request_usk(&pk, &alice, &mut rng);
+        let req = KeyRequest::from_bytes(&req.to_bytes()).unwrap();
+        let resp = Goyal::issue_usk(&pk, &sk, &alice, &req, &mut rng).unwrap();
+        let resp = KeyResponse::from_bytes(&resp.to_bytes()).unwrap();
+        let usk = Goyal::finish_usk(&pk, state, &resp).unwrap();
+        let (ct, _) = encrypt_random(&pk, &alice);
+
+        assert_eq!(pk, PublicKey::from_bytes(&pk.to_bytes()).unwrap());
+        assert_eq!(sk, SecretKey::from_bytes(&sk.to_bytes()).unwrap());
+        assert_eq!(usk, UserSecretKey::from_bytes(&usk.to_bytes()).unwrap());
+        assert_eq!(ct, CipherText::from_bytes(&ct.to_bytes()).unwrap());
+
+        let mut bytes = ct.to_bytes();
+        bytes[..G1_BYTES].copy_from_slice(&NON_SUBGROUP_G1_COMPRESSED);
+        assert!(bool::from(CipherText::from_bytes(&bytes).is_none()));
+    }
+}
@@ -0,0 +1,235 @@
+//! IND-ID-CPA secure identity-based proxy re-encryption by Green and Ateniese (IBP1).
+//! * From: "[Identity-Based Proxy Re-encryption](https://eprint.iacr.org/2006/473.pdf)"
+//! * Published in: ACNS, 2007
+//!
+//! The scheme extends [Boneh-Franklin](crate::ibe::boneh_franklin), such that ciphertexts and user
+//! secret keys are shared with that scheme. A re-encryption key from `alice` to `bob` consists of
+//! `-USK_{alice} + H(X)` for a random target group element `X`, together with an encryption of `X`
+//! for `bob`. The proxy learns neither the message nor `X`.
+//!
+//! The scheme is **not** collusion resistant: `bob` can decrypt `X`, such that a proxy colluding
+//! with `bob` recovers `USK_{alice} = H(X) - r` from the re-encryption key. Delegating a mailbox
+//! with this scheme therefore amounts to handing the user secret key of `alice` to the proxy and
+//! `bob` jointly. Only delegate to a delegatee that may be trusted with that key, or to a proxy
+//! that is trusted not to cooperate with the delegatee.
+//!
+//! Re-encryption is single-hop: a re-encrypted ciphertext can not be re-encrypted again. Elements
+//! of the target group are hashed to G2 using the hash-to-curve construction of RFC 9380.
+
+use crate::ibe::boneh_franklin::CT_BYTES;
+use crate::ibe::{IBE, IBPRE};
+use crate::util::*;
+use crate::Compress;
+use arrayref::{array_refs, mut_array_refs};
+use pg_curve::{pairing, G1Affine, G2Affine, G2Projective, Gt};
+use rand::{CryptoRng, Rng};
+use subtle::CtOption;
+
+/// These structs are identical for proxy re-encryption.
+pub use crate::ibe::boneh_franklin::{
+    BonehFranklin, CipherText, Msg, PublicKey, SecretKey, UserSecretKey,
+};
+
+/// Domain separation tag used to hash target group elements to G2.
+const DST: &[u8] = b"IBE_GREEN_ATENIESE_BLS12381G2_XMD:SHA-256_SSWU_RO_";
+
+/// Size of the compressed re-encryption key in bytes.
+pub const REKEY_BYTES: usize = G2_BYTES + CT_BYTES;
+
+/// Size of the compressed re-encrypted ciphertext in bytes.
+pub const RECT_BYTES: usize = G1_BYTES + GT_BYTES + CT_BYTES;
+
+/// Re-encryption key from the delegator to the delegatee. Can be handed to an untrusted proxy.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub struct ReKey {
+    r: G2Affine,
+    cx: CipherText,
+}
+
+/// Re-encrypted ciphertext. Can only be decrypted with the user secret key of the delegatee.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub struct ReCipherText {
+    u: G1Affine,
+    v: Gt,
+    cx: CipherText,
+}
+
+/// Hashes a target group element to a point on G2.
+fn hash_to_point(x: &Gt) -> G2Projective {
+    hash_to_g2(&x.to_compressed(), DST)
+}
+
+impl IBPRE for BonehFranklin {
+    type ReKey = ReKey;
+    type ReCt = ReCipherText;
+
+    const REKEY_BYTES: usize = REKEY_BYTES;
+    const RECT_BYTES: usize = RECT_BYTES;
+
+    /// Generate a re-encryption key, which encrypts a random X for the delegatee.
+    fn rekey<R: Rng + CryptoRng>(
+        pk: &PublicKey,
+        usk: &UserSecretKey,
+        to: &Identity,
+        rng: &mut R,
+    ) -> ReKey {
+        let x = rand_gt(rng);
+
+        let mut coins = [0u8; 64];
+        rng.fill_bytes(&mut coins);
+
+        ReKey {
+            r: (hash_to_point(&x) - usk.d).into(),
+            cx: BonehFranklin::encrypt(pk, to, &x, &coins),
+        }
+    }
+
+    /// Re-encrypt a ciphertext, such that the mask e(P_pub, Q_id)^r is replaced by e(P, H(X))^r.
+    fn reencrypt(rk: &ReKey, ct: &CipherText) -> ReCipherText {
+        ReCipherText {
+            u: ct.u,
+            v: ct.v + pairing(&ct.u, &rk.r),
+            cx: rk.cx,
+        }
+    }
+
+    /// Recover X using the user secret key of the delegatee, and use it to unmask the message.
+    fn decrypt_reencrypted(usk: &UserSecretKey, ct: &ReCipherText) -> Msg {
+        let x = BonehFranklin::decrypt(usk, &ct.cx);
+
+        ct.v - pairing(&ct.u, &G2Affine::from(hash_to_point(&x)))
+    }
+}
+
+impl Compress for ReKey {
+    const OUTPUT_SIZE: usize = REKEY_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; REKEY_BYTES] {
+        let mut res = [0u8; REKEY_BYTES];
+        let (r, cx) = mut_array_refs![&mut res, G2_BYTES, CT_BYTES];
+
+        *r = self.r.to_compressed();
+        *cx = self.cx.to_bytes();
+
+        res
+    }
+
+    fn from_bytes(bytes: &[u8; REKEY_BYTES]) -> CtOption<Self> {
+        let (r, cx) = array_refs![bytes, G2_BYTES, CT_BYTES];
+
+        // Use the checked `from_compressed` variant, which verifies that the
+        // point is in the correct prime-order subgroup (GHSA-25fp-2fjj-g84w).
+        let r = G2Affine::from_compressed(r);
+        let cx = CipherText::from_bytes(cx);
+
+        r.and_then(|r| cx.map(|cx| ReKey { r, cx }))
+    }
+}
+
+impl Compress for ReCipherText {
+    const OUTPUT_SIZE: usize = RECT_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; RECT_BYTES] {
+        let mut res = [0u8; RECT_BYTES];
+        let (u, v, cx) = mut_array_refs![&mut res, G1_BYTES, GT_BYTES, CT_BYTES];
+
+        *u = self.u.to_compressed();
+        *v = self.v.to_compressed();
+        *cx = self.cx.to_bytes();
+
+        res
+    }
+
+    fn from_bytes(bytes: &[u8; RECT_BYTES]) -> CtOption<Self> {
+        let (u, v, cx) = array_refs![bytes, G1_BYTES, GT_BYTES, CT_BYTES];
+
+        let u = G1Affine::from_compressed(u);
+        let v = Gt::from_compressed(v);
+        let cx = CipherText::from_bytes(cx);
+
+        u.and_then(|u| v.and_then(|v| cx.map(|cx| ReCipherText { u, v, cx })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Derive;
+    use rand::RngCore;
+
+    const ALICE: &str = "email:alice@example.com";
+    const BOB: &str = "email:bob@example.com";
+    const CHARLIE: &str = "email:charlie@example.com";
+
+    fn encrypt_random(pk: &PublicKey, id: &Identity) -> (CipherText, Msg) {
+        let mut rng = rand::thread_rng();
+        let mut coins = [0u8; 64];
+        rng.fill_bytes(&mut coins);
+
+        let m = rand_gt(&mut rng);
+
+        (BonehFranklin::encrypt(pk, id, &m, &coins), m)
+    }
+
+    #[test]
+    fn eq_reencrypt_decrypt() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = BonehFranklin::setup(&mut rng);
+
+        let (alice, bob) = (Identity::derive_str(ALICE), Identity::derive_str(BOB));
+        let usk_alice = BonehFranklin::extract_usk(None, &sk, &alice, &mut rng);
+        let usk_bob = BonehFranklin::extract_usk(None, &sk, &bob, &mut rng);
+
+        let (ct, m) = encrypt_random(&pk, &alice);
+        let rk = BonehFranklin::rekey(&pk, &usk_alice, &bob, &mut rng);
+        let rct = BonehFranklin::reencrypt(&rk, &ct);
+
+        assert_eq!(BonehFranklin::decrypt_reencrypted(&usk_bob, &rct), m);
+        assert_ne!(BonehFranklin::decrypt(&usk_bob, &ct), m);
+    }
+
+    #[test]
+    fn reencrypted_ciphertext_only_decrypts_for_delegatee() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = BonehFranklin::setup(&mut rng);
+
+        let (alice, bob) = (Identity::derive_str(ALICE), Identity::derive_str(BOB));
+        let charlie = Identity::derive_str(CHARLIE);
+        let usk_alice = BonehFranklin::extract_usk(None, &sk, &alice, &mut rng);
+        let usk_bob = BonehFranklin::extract_usk(None, &sk, &bob, &mut rng);
+        let usk_charlie = BonehFranklin::extract_usk(None, &sk, &charlie, &mut rng);
+
+        let rk = BonehFranklin::rekey(&pk, &usk_alice, &bob, &mut rng);
+
+        let (ct, m) = encrypt_random(&pk, &alice);
+        let rct = BonehFranklin::reencrypt(&rk, &ct);
+        assert_ne!(BonehFranklin::decrypt_reencrypted(&usk_charlie, &rct), m);
+
+        // The re-encryption key does not transform ciphertexts for other identities.
+        let (ct, m) = encrypt_random(&pk, &charlie);
+        let rct = BonehFranklin::reencrypt(&rk, &ct);
+        assert_ne!(BonehFranklin::decrypt_reencrypted(&usk_bob, &rct), m);
+    }
+
+    #[test]
+    fn eq_serialize_deserialize() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = BonehFranklin::setup(&mut rng);
+
+        let (alice, bob) = (Identity::derive_str(ALICE), Identity::derive_str(BOB));
+        let usk_alice = BonehFranklin::extract_usk(None, &sk, &alice, &mut rng);
+
+        let (ct, _) = encrypt_random(&pk, &alice);
+        let rk = BonehFranklin::rekey(&pk, &usk_alice, &bob, &mut rng);
+        let rct = BonehFranklin::reencrypt(&rk, &ct);
+
+        assert_eq!(rk, ReKey::from_bytes(&rk.to_bytes()).unwrap());
+        assert_eq!(rct, ReCipherText::from_bytes(&rct.to_bytes()).unwrap());
+
+        let mut bytes = rct.to_bytes();
+        bytes[..G1_BYTES].copy_from_slice(&NON_SUBGROUP_G1_COMPRESSED);
+        assert!(bool::from(ReCipherText::from_bytes(&bytes).is_none()));
+    }
+}
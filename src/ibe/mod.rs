@@ -7,17 +7,78 @@
 //! Some scheme's API slightly differ; some require the system's public key for extraction.  These
 //! parameters are made optional.
 //!
+//! Hierarchical schemes additionally implement [`HIBE`], which allows the holder of a user
+//! secret key to delegate keys for descendant identities. Some hierarchical schemes also implement
+//! [`WIBE`], which allows encrypting for a [`Pattern`] of identity components and wildcards.
+//!
+//! Proxy re-encryption schemes implement [`IBPRE`], which allows the holder of a user secret key
+//! to delegate decryption of their ciphertexts to another identity through an untrusted proxy.
+//!
+//! Some schemes implement [`ObliviousKeyOracle`], through which the PKG grants a user secret key
+//! for any identity without learning which. It is **not** a replacement for extraction by the PKG,
+//! as the PKG can not enforce which identity a request is for.
+//!
+//! Revocable schemes implement [`RIBE`], in which user secret keys are combined with a key update
+//! that the PKG publishes each epoch, such that keys can be revoked without a new master key pair.
+//!
+//! Accountable-authority schemes implement [`AIBE`], in which the user takes part in the
+//! extraction of its user secret key, such that a judge can tell whether a leaked key originates
+//! from the PKG or from the user.
+//!
 //! Among the schemes are:
 //!
+//! - Boneh-Franklin, its proxy re-encryption extension by Green and Ateniese, and an oblivious key
+//!   oracle for its user secret keys,
+//! - Boneh-Boyen (BB1),
+//! - Goyal accountable-authority IBE,
+//! - Boldyreva-Goyal-Kumar revocable IBE,
+//! - Boneh-Boyen-Goh HIBE, and its wildcarded extension,
 //! - Waters,
 //! - Waters-Naccache,
 //! - Boyen-Waters,
+//! - Boyen-Waters anonymous HIBE,
 //! - Chen-Gay-Wee (or short, CGW).
 
+#[cfg(feature = "boneh_boyen")]
+#[cfg_attr(docsrs, doc(cfg(feature = "boneh_boyen")))]
+pub mod boneh_boyen;
+
+#[cfg(feature = "boldyreva_goyal_kumar")]
+#[cfg_attr(docsrs, doc(cfg(feature = "boldyreva_goyal_kumar")))]
+pub mod boldyreva_goyal_kumar;
+
+#[cfg(feature = "boneh_boyen_goh")]
+#[cfg_attr(docsrs, doc(cfg(feature = "boneh_boyen_goh")))]
+pub mod boneh_boyen_goh;
+
+#[cfg(feature = "goyal")]
+#[cfg_attr(docsrs, doc(cfg(feature = "goyal")))]
+pub mod goyal;
+
+#[cfg(feature = "boneh_boyen_goh_wibe")]
+#[cfg_attr(docsrs, doc(cfg(feature = "boneh_boyen_goh_wibe")))]
+pub mod boneh_boyen_goh_wibe;
+
+#[cfg(feature = "boneh_franklin")]
+#[cfg_attr(docsrs, doc(cfg(feature = "boneh_franklin")))]
+pub mod boneh_franklin;
+
+#[cfg(feature = "green_ateniese")]
+#[cfg_attr(docsrs, doc(cfg(feature = "green_ateniese")))]
+pub mod green_ateniese;
+
+#[cfg(feature = "oblivious_key_oracle")]
+#[cfg_attr(docsrs, doc(cfg(feature = "oblivious_key_oracle")))]
+pub mod oblivious_key_oracle;
+
 #[cfg(feature = "boyen_waters")]
 #[cfg_attr(docsrs, doc(cfg(feature = "boyen_waters")))]
 pub mod boyen_waters;
 
+#[cfg(feature = "boyen_waters_hibe")]
+#[cfg_attr(docsrs, doc(cfg(feature = "boyen_waters_hibe")))]
+pub mod boyen_waters_hibe;
+
 #[cfg(feature = "cgw")]
 #[cfg_attr(docsrs, doc(cfg(feature = "cgw")))]
 pub mod cgw;
@@ -30,7 +91,9 @@ pub mod waters;
 #[cfg_attr(docsrs, doc(cfg(feature = "waters_naccache")))]
 pub mod waters_naccache;
 
+use crate::util::Identity;
 use crate::{Compress, Derive};
+use core::fmt::Debug;
 use group::Group;
 use rand::{CryptoRng, Rng};
 
@@ -92,3 +155,492 @@ pub trait IBE {
     /// Decrypt a ciphertext using a user secret key to retrieve a message.
     fn decrypt(usk: &Self::Usk, ct: &Self::Ct) -> Self::Msg;
 }
+
+/// Hierarchical identity-based encryption scheme (HIBE).
+///
+/// The identity ([`IBE::Id`]) of a HIBE is a path of identity components. The holder of a user
+/// secret key for a path can delegate user secret keys for all its descendants, without the
+/// involvement of the PKG.
+pub trait HIBE: IBE {
+    /// A single component of a hierarchical identity.
+    type IdComponent: Copy + Derive;
+
+    /// Maximum depth of the hierarchy.
+    const MAX_DEPTH: usize;
+
+    /// Delegate a user secret key for a child identity from the user secret key of its parent.
+    ///
+    /// Returns `None` if the parent user secret key is already at the maximum depth.
+    fn delegate<R: Rng + CryptoRng>(
+        pk: &Self::Pk,
+        usk: &Self::Usk,
+        id: &Self::IdComponent,
+        rng: &mut R,
+    ) -> Option<Self::Usk>;
+}
+
+/// Wildcarded identity-based encryption scheme (WIBE).
+///
+/// A WIBE is a HIBE in which a message can also be encrypted for a [`Pattern`], in which some
+/// components are wildcards. The holder of a user secret key for an identity path that matches the
+/// pattern can decrypt, which allows for encrypting to a group of recipients with a single
+/// ciphertext.
+pub trait WIBE: HIBE {
+    /// Pattern of identity components and wildcards.
+    type Pattern: Copy;
+
+    /// Ciphertext for a pattern.
+    type PatternCt: Compress;
+
+    /// Encrypt a message using the MPK for a pattern.
+    fn encrypt_pattern(
+        pk: &Self::Pk,
+        pattern: &Self::Pattern,
+        message: &Self::Msg,
+        rng: &Self::RngBytes,
+    ) -> Self::PatternCt;
+
+    /// Decrypt a pattern ciphertext using a user secret key to retrieve a message.
+    ///
+    /// Returns `None` if the identity path of the user secret key does not match the pattern.
+    fn decrypt_pattern(usk: &Self::Usk, ct: &Self::PatternCt) -> Option<Self::Msg>;
+}
+
+/// Identity-based proxy re-encryption scheme (IBPRE).
+///
+/// The holder of a user secret key for an identity can issue a re-encryption key for another
+/// identity. Using this key, an untrusted proxy can transform ciphertexts for the former identity
+/// into ciphertexts for the latter, without learning the message. Implementations are not
+/// necessarily collusion resistant, see the documentation of the scheme.
+pub trait IBPRE: IBE {
+    /// Re-encryption key from one identity to another.
+    type ReKey: Compress;
+
+    /// Re-encrypted ciphertext.
+    type ReCt: Compress;
+
+    /// Size of the re-encryption key in bytes.
+    const REKEY_BYTES: usize;
+
+    /// Size of the re-encrypted ciphertext in bytes.
+    const RECT_BYTES: usize;
+
+    /// Generate a re-encryption key for an identity using the user secret key of the delegator.
+    fn rekey<R: Rng + CryptoRng>(
+        pk: &Self::Pk,
+        usk: &Self::Usk,
+        to: &Self::Id,
+        rng: &mut R,
+    ) -> Self::ReKey;
+
+    /// Re-encrypt a ciphertext for the delegator into a ciphertext for the delegatee.
+    fn reencrypt(rk: &Self::ReKey, ct: &Self::Ct) -> Self::ReCt;
+
+    /// Decrypt a re-encrypted ciphertext using the user secret key of the delegatee.
+    fn decrypt_reencrypted(usk: &Self::Usk, ct: &Self::ReCt) -> Self::Msg;
+}
+
+/// Oblivious key oracle that grants a user secret key for any identity.
+///
+/// The user [`blind`](ObliviousKeyOracle::blind)s an identity, the PKG answers the request with
+/// [`oblivious_extract_usk`](ObliviousKeyOracle::oblivious_extract_usk) and the user
+/// [`unblind`](ObliviousKeyOracle::unblind)s the response into an ordinary user secret key of the
+/// underlying [`IBE`]. The PKG does not learn the identity for which it extracts a key.
+///
+/// The PKG can **not** enforce which identity a request is for: whoever may send requests obtains
+/// the user secret key of every identity of its choice. Answering a request is therefore only
+/// appropriate if the requester may decrypt for all identities of the system.
+pub trait ObliviousKeyOracle: IBE {
+    /// State of the user, needed to unblind the response.
+    type BlindState: Debug;
+
+    /// Blinded extraction request.
+    type BlindRequest: Compress;
+
+    /// Blinded extraction response.
+    type BlindResponse: Compress;
+
+    /// Size of the blinded extraction request in bytes.
+    const BLIND_REQUEST_BYTES: usize;
+
+    /// Size of the blinded extraction response in bytes.
+    const BLIND_RESPONSE_BYTES: usize;
+
+    /// Blind an identity into an extraction request.
+    fn blind<R: Rng + CryptoRng>(
+        pk: &Self::Pk,
+        id: &Self::Id,
+        rng: &mut R,
+    ) -> (Self::BlindState, Self::BlindRequest);
+
+    /// Answer a blinded extraction request using the MSK.
+    ///
+    /// Optionally requires the system's public key.
+    fn oblivious_extract_usk<R: Rng + CryptoRng>(
+        pk: Option<&Self::Pk>,
+        sk: &Self::Sk,
+        req: &Self::BlindRequest,
+        rng: &mut R,
+    ) -> Self::BlindResponse;
+
+    /// Unblind the response of the PKG into a user secret key for the blinded identity.
+    ///
+    /// Returns `None` if the response does not result in a valid user secret key.
+    fn unblind(
+        pk: &Self::Pk,
+        state: Self::BlindState,
+        resp: &Self::BlindResponse,
+    ) -> Option<Self::Usk>;
+}
+
+/// Revocable identity-based encryption scheme (RIBE).
+///
+/// Ciphertexts are encrypted for an identity and an epoch. A user secret key is assigned to a leaf
+/// of a binary tree of at most [`RIBE::MAX_USERS`] leaves and is valid for all epochs. Each epoch,
+/// the PKG publishes a key update that covers exactly the leaves that are not revoked. A user
+/// combines their user secret key with the key update into a decryption key for that epoch, which
+/// is no longer possible once their leaf has been revoked.
+///
+/// The PKG is responsible for assigning distinct leaves to user secret keys and for keeping track
+/// of the revoked leaves.
+pub trait RIBE {
+    /// Master public key (Mpk).
+    type Pk: Compress;
+
+    /// Master secret key (Msk).
+    type Sk: Compress;
+
+    /// Long-term user secret key (Usk).
+    type Usk: Compress;
+
+    /// Key update for a single epoch.
+    type KeyUpdate: Compress;
+
+    /// Decryption key for an identity and a single epoch (Dk).
+    type Dk: Compress;
+
+    /// Ciphertext (Ct).
+    type Ct: Compress;
+
+    /// Message type (Msg), we require group so that we can draw random messages.
+    type Msg: Compress + Group;
+
+    /// Internal identity type (Id).
+    type Id: Copy + Derive;
+
+    /// Randomness required to encrypt a message.
+    type RngBytes: Sized;
+
+    /// Size of the master public key in bytes.
+    const PK_BYTES: usize;
+
+    /// Size of the master secret key in bytes.
+    const SK_BYTES: usize;
+
+    /// Size of the decryption key in bytes.
+    const DK_BYTES: usize;
+
+    /// Size of the ciphertext in bytes.
+    const CT_BYTES: usize;
+
+    /// Size of the message in bytes.
+    const MSG_BYTES: usize;
+
+    /// Maximum number of user secret keys, i.e., the number of leaves of the binary tree.
+    const MAX_USERS: u64;
+
+    /// Creates an MSK, MPK pair.
+    fn setup<R: Rng + CryptoRng>(rng: &mut R) -> (Self::Pk, Self::Sk);
+
+    /// Extract a long-term user secret key for an identity, assigned to a leaf of the binary tree.
+    ///
+    /// Returns `None` if the leaf is not smaller than [`RIBE::MAX_USERS`].
+    fn extract_usk<R: Rng + CryptoRng>(
+        pk: Option<&Self::Pk>,
+        sk: &Self::Sk,
+        id: &Self::Id,
+        leaf: u64,
+        rng: &mut R,
+    ) -> Option<Self::Usk>;
+
+    /// Generate the key update for an epoch, given the leaves that are revoked.
+    fn update_key<R: Rng + CryptoRng>(
+        pk: Option<&Self::Pk>,
+        sk: &Self::Sk,
+        epoch: u64,
+        revoked: &[u64],
+        rng: &mut R,
+    ) -> Self::KeyUpdate;
+
+    /// Combine a user secret key with a key update into a decryption key for the epoch of the key
+    /// update.
+    ///
+    /// Returns `None` if the leaf of the user secret key is revoked in the key update.
+    fn derive_dk(usk: &Self::Usk, ku: &Self::KeyUpdate) -> Option<Self::Dk>;
+
+    /// Encrypt a message using the MPK for an identity and an epoch.
+    fn encrypt(
+        pk: &Self::Pk,
+        id: &Self::Id,
+        epoch: u64,
+        message: &Self::Msg,
+        rng: &Self::RngBytes,
+    ) -> Self::Ct;
+
+    /// Decrypt a ciphertext using a decryption key to retrieve a message.
+    fn decrypt(dk: &Self::Dk, ct: &Self::Ct) -> Self::Msg;
+}
+
+/// The party that is to blame for a leaked user secret key, see [`AIBE::trace`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Culprit {
+    /// The leaked key is not the key of the user, so it must have been created by the PKG.
+    Pkg,
+
+    /// The leaked key is the key of the user.
+    User,
+}
+
+/// Accountable-authority identity-based encryption scheme (A-IBE).
+///
+/// Extraction is a two-party protocol between the user and the PKG. The user starts with
+/// [`request_usk`](AIBE::request_usk), the PKG answers with [`issue_usk`](AIBE::issue_usk) and
+/// the user completes its key with [`finish_usk`](AIBE::finish_usk). Every identity has many valid
+/// user secret keys and the PKG does not learn which one the user ends up with, while the user can
+/// not compute a second one. Given a leaked key and the key of the user, a judge can therefore tell
+/// who leaked it.
+pub trait AIBE {
+    /// Master public key (Mpk).
+    type Pk: Compress;
+
+    /// Master secret key (Msk).
+    type Sk: Compress;
+
+    /// User secret key (Usk).
+    type Usk: Compress;
+
+    /// Ciphertext (Ct).
+    type Ct: Compress;
+
+    /// Message type (Msg), we require group so that we can draw random messages.
+    type Msg: Compress + Group;
+
+    /// Internal identity type (Id).
+    type Id: Copy + Derive;
+
+    /// Randomness required to encrypt a message.
+    type RngBytes: Sized;
+
+    /// State of the user during extraction.
+    type UserState: Debug;
+
+    /// Extraction request, from the user to the PKG.
+    type KeyRequest: Compress;
+
+    /// Extraction response, from the PKG to the user.
+    type KeyResponse: Compress;
+
+    /// Size of the master public key in bytes.
+    const PK_BYTES: usize;
+
+    /// Size of the master secret key in bytes.
+    const SK_BYTES: usize;
+
+    /// Size of the user secret key in bytes.
+    const USK_BYTES: usize;
+
+    /// Size of the ciphertext in bytes.
+    const CT_BYTES: usize;
+
+    /// Size of the message in bytes.
+    const MSG_BYTES: usize;
+
+    /// Size of the extraction request in bytes.
+    const KEY_REQUEST_BYTES: usize;
+
+    /// Size of the extraction response in bytes.
+    const KEY_RESPONSE_BYTES: usize;
+
+    /// Creates an MSK, MPK pair.
+    fn setup<R: Rng + CryptoRng>(rng: &mut R) -> (Self::Pk, Self::Sk);
+
+    /// Start the extraction of a user secret key for an identity.
+    fn request_usk<R: Rng + CryptoRng>(
+        pk: &Self::Pk,
+        id: &Self::Id,
+        rng: &mut R,
+    ) -> (Self::UserState, Self::KeyRequest);
+
+    /// Answer an extraction request for an identity using the MSK.
+    ///
+    /// The PKG must check that the requester owns the identity. Returns `None` if the request
+    /// is malformed.
+    fn issue_usk<R: Rng + CryptoRng>(
+        pk: &Self::Pk,
+        sk: &Self::Sk,
+        id: &Self::Id,
+        req: &Self::KeyRequest,
+        rng: &mut R,
+    ) -> Option<Self::KeyResponse>;
+
+    /// Complete the extraction of a user secret key.
+    ///
+    /// Returns `None` if the response of the PKG does not result in a valid user secret key.
+    fn finish_usk(
+        pk: &Self::Pk,
+        state: Self::UserState,
+        resp: &Self::KeyResponse,
+    ) -> Option<Self::Usk>;
+
+    /// Encrypt a message using the MPK and an identity.
+    fn encrypt(pk: &Self::Pk, id: &Self::Id, message: &Self::Msg, rng: &Self::RngBytes)
+        -> Self::Ct;
+
+    /// Decrypt a ciphertext using a user secret key to retrieve a message.
+    fn decrypt(usk: &Self::Usk, ct: &Self::Ct) -> Self::Msg;
+
+    /// Decide who is to blame for a leaked user secret key of an identity, given the user secret
+    /// key that the user obtained through extraction.
+    ///
+    /// Returns `None` if one of the keys is not a valid user secret key for the identity.
+    fn trace(pk: &Self::Pk, id: &Self::Id, leaked: &Self::Usk, usk: &Self::Usk) -> Option<Culprit>;
+}
+
+/// Hierarchical identity consisting of at most `D` identity components.
+///
+/// Deriving an identity path from a byte slice results in a path of depth one.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize))]
+pub struct IdentityPath<const D: usize> {
+    ids: [Identity; D],
+    len: usize,
+}
+
+impl<const D: usize> IdentityPath<D> {
+    /// Create an identity path from its components, starting at the top of the hierarchy.
+    ///
+    /// Returns `None` if more than `D` components are given.
+    pub fn new(components: &[Identity]) -> Option<Self> {
+        if components.len() > D {
+            return None;
+        }
+
+        let mut ids = [Identity::default(); D];
+        ids[..components.len()].copy_from_slice(components);
+
+        Some(IdentityPath {
+            ids,
+            len: components.len(),
+        })
+    }
+
+    /// Extend the identity path with a child component.
+    ///
+    /// Returns `None` if the path is already at depth `D`.
+    pub fn push(&self, id: &Identity) -> Option<Self> {
+        if self.len >= D {
+            return None;
+        }
+
+        let mut res = *self;
+        res.ids[self.len] = *id;
+        res.len += 1;
+
+        Some(res)
+    }
+
+    /// The components of the identity path.
+    pub fn components(&self) -> &[Identity] {
+        &self.ids[..self.len]
+    }
+
+    /// The depth of the identity path.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether this is the empty identity path (the root of the hierarchy).
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+/// Pattern of at most `D` components, each of which is either an identity component or a
+/// wildcard (`None`).
+///
+/// An identity path matches a pattern if it is not longer than the pattern and each of its
+/// components is equal to the corresponding component of the pattern, or that component is a
+/// wildcard. Note that a prefix of a matching identity path also matches, as its holder can
+/// delegate a user secret key for the full path.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Pattern<const D: usize> {
+    ids: [Option<Identity>; D],
+    len: usize,
+}
+
+impl<const D: usize> Pattern<D> {
+    /// Create a pattern from its components, starting at the top of the hierarchy.
+    ///
+    /// Returns `None` if more than `D` components are given.
+    pub fn new(components: &[Option<Identity>]) -> Option<Self> {
+        if components.len() > D {
+            return None;
+        }
+
+        let mut ids = [None; D];
+        ids[..components.len()].copy_from_slice(components);
+
+        Some(Pattern {
+            ids,
+            len: components.len(),
+        })
+    }
+
+    /// The components of the pattern, where wildcards are `None`.
+    pub fn components(&self) -> &[Option<Identity>] {
+        &self.ids[..self.len]
+    }
+
+    /// The depth of the pattern.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether this is the empty pattern.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Whether the identity path matches this pattern.
+    pub fn matches(&self, id: &IdentityPath<D>) -> bool {
+        id.len() <= self.len
+            && id
+                .components()
+                .iter()
+                .zip(self.components())
+                .all(|(id, p)| p.is_none_or(|p| p == *id))
+    }
+}
+
+impl<const D: usize> From<IdentityPath<D>> for Pattern<D> {
+    /// The pattern without wildcards that only matches the identity path and its prefixes.
+    fn from(id: IdentityPath<D>) -> Self {
+        let mut ids = [None; D];
+        for (slot, id) in ids.iter_mut().zip(id.components()) {
+            *slot = Some(*id);
+        }
+
+        Pattern { ids, len: id.len() }
+    }
+}
+
+impl<const D: usize> Derive for IdentityPath<D> {
+    /// Derive an identity path of depth one from a byte slice.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `D` is zero.
+    fn derive(b: &[u8]) -> Self {
+        IdentityPath::new(&[Identity::derive(b)]).unwrap()
+    }
+}
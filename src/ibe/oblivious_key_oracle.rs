@@ -0,0 +1,216 @@
+//! Oblivious key oracle for Boneh-Franklin user secret keys, which grants a key for any identity.
+//!
+//! A [Boneh-Franklin](crate::ibe::boneh_franklin) user secret key d = s·Q_id is a BLS signature
+//! on the identity, which can be extracted obliviously in the same way as a blind BLS signature.
+//! The user sends B = β·Q_id for a random β, the PKG returns s·B, and the user unblinds
+//! d = β^{-1}·s·B. As β is uniformly random, B is independent of the identity.
+//!
+//! The PKG can **not** enforce which identity a request is for, as identities are hashed to G2.
+//! Therefore, anyone whose requests are answered can compute the user secret key of any identity,
+//! not only the PKG. This is **not** the commitment-based blind extraction of Green and
+//! Hohenberger, "[Blind Identity-Based Encryption and Simulatable Oblivious
+//! Transfer](https://eprint.iacr.org/2007/235.pdf)" (ASIACRYPT, 2007), in which the PKG verifies a
+//! proof about the requested identity, and must not be used in place of extraction by the PKG.
+//!
+//! The user checks that e(P1, d) = e(P_pub, Q_id) after unblinding, such that a PKG that answers
+//! incorrectly is detected.
+
+use crate::ibe::boneh_franklin::hash_to_point;
+use crate::ibe::ObliviousKeyOracle;
+use crate::util::*;
+use crate::Compress;
+use pg_curve::{multi_miller_loop, G1Affine, G2Affine, G2Prepared, Gt, Scalar};
+use rand::{CryptoRng, Rng};
+use subtle::CtOption;
+
+/// These structs are identical for oblivious extraction.
+pub use crate::ibe::boneh_franklin::{
+    BonehFranklin, CipherText, Msg, PublicKey, SecretKey, UserSecretKey,
+};
+
+/// Size of the compressed blinded extraction request in bytes.
+pub const BLIND_REQUEST_BYTES: usize = G2_BYTES;
+
+/// Size of the compressed blinded extraction response in bytes.
+pub const BLIND_RESPONSE_BYTES: usize = G2_BYTES;
+
+/// State of the user, holding the identity and the inverse of the blinding factor.
+///
+/// # Zeroization
+///
+/// With the `zeroize` feature enabled this type derives `Zeroize` and `ZeroizeOnDrop`.
+#[derive(Debug)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize, zeroize::ZeroizeOnDrop))]
+pub struct BlindState {
+    id: Identity,
+    beta_inv: Scalar,
+}
+
+/// Blinded extraction request, B = β·Q_id.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct BlindRequest {
+    b: G2Affine,
+}
+
+/// Blinded extraction response, s·B.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct BlindResponse {
+    d: G2Affine,
+}
+
+impl ObliviousKeyOracle for BonehFranklin {
+    type BlindState = BlindState;
+    type BlindRequest = BlindRequest;
+    type BlindResponse = BlindResponse;
+
+    const BLIND_REQUEST_BYTES: usize = BLIND_REQUEST_BYTES;
+    const BLIND_RESPONSE_BYTES: usize = BLIND_RESPONSE_BYTES;
+
+    /// Blind Q_id using a random non-zero β.
+    fn blind<R: Rng + CryptoRng>(
+        _pk: &PublicKey,
+        id: &Identity,
+        rng: &mut R,
+    ) -> (BlindState, BlindRequest) {
+        let (beta, beta_inv) = loop {
+            let beta = rand_scalar(rng);
+            if let Some(beta_inv) = Option::<Scalar>::from(beta.invert()) {
+                break (beta, beta_inv);
+            }
+        };
+
+        let b = (hash_to_point(id) * beta).into();
+
+        (BlindState { id: *id, beta_inv }, BlindRequest { b })
+    }
+
+    /// Multiply the blinded request by the master secret.
+    fn oblivious_extract_usk<R: Rng + CryptoRng>(
+        _opk: Option<&PublicKey>,
+        sk: &SecretKey,
+        req: &BlindRequest,
+        _rng: &mut R,
+    ) -> BlindResponse {
+        BlindResponse {
+            d: (req.b * sk.s).into(),
+        }
+    }
+
+    /// Unblind the response and check that e(P1, d) = e(P_pub, Q_id).
+    fn unblind(pk: &PublicKey, state: BlindState, resp: &BlindResponse) -> Option<UserSecretKey> {
+        let d = (resp.d * state.beta_inv).into();
+
+        let valid = multi_miller_loop(&[
+            (&G1Affine::generator(), &G2Prepared::from(d)),
+            (&-pk.ppub, &G2Prepared::from(hash_to_point(&state.id))),
+        ])
+        .final_exponentiation()
+            == Gt::identity();
+
+        valid.then_some(UserSecretKey { d })
+    }
+}
+
+impl Compress for BlindRequest {
+    const OUTPUT_SIZE: usize = BLIND_REQUEST_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; BLIND_REQUEST_BYTES] {
+        self.b.to_compressed()
+    }
+
+    fn from_bytes(bytes: &[u8; BLIND_REQUEST_BYTES]) -> CtOption<Self> {
+        // Use the checked `from_compressed` variant, which verifies that the
+        // point is in the correct prime-order subgroup (GHSA-25fp-2fjj-g84w).
+        G2Affine::from_compressed(bytes).map(|b| BlindRequest { b })
+    }
+}
+
+impl Compress for BlindResponse {
+    const OUTPUT_SIZE: usize = BLIND_RESPONSE_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; BLIND_RESPONSE_BYTES] {
+        self.d.to_compressed()
+    }
+
+    fn from_bytes(bytes: &[u8; BLIND_RESPONSE_BYTES]) -> CtOption<Self> {
+        G2Affine::from_compressed(bytes).map(|d| BlindResponse { d })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ibe::IBE;
+    use crate::Derive;
+    use rand::RngCore;
+
+    const ALICE: &str = "email:alice@example.com";
+    const BOB: &str = "email:bob@example.com";
+
+    #[test]
+    fn eq_blind_extract() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = BonehFranklin::setup(&mut rng);
+
+        let alice = Identity::derive_str(ALICE);
+        let (state, req) = BonehFranklin::blind(&pk, &alice, &mut rng);
+        let resp = BonehFranklin::oblivious_extract_usk(None, &sk, &req, &mut rng);
+        let usk = BonehFranklin::unblind(&pk, state, &resp).unwrap();
+
+        assert_eq!(usk, BonehFranklin::extract_usk(None, &sk, &alice, &mut rng));
+
+        let mut coins = [0u8; 64];
+        rng.fill_bytes(&mut coins);
+        let m = rand_gt(&mut rng);
+        let ct = BonehFranklin::encrypt(&pk, &alice, &m, &coins);
+
+        assert_eq!(BonehFranklin::decrypt(&usk, &ct), m);
+    }
+
+    #[test]
+    fn requests_are_unlinkable() {
+        let mut rng = rand::thread_rng();
+        let (pk, _) = BonehFranklin::setup(&mut rng);
+
+        let alice = Identity::derive_str(ALICE);
+        let (_, req1) = BonehFranklin::blind(&pk, &alice, &mut rng);
+        let (_, req2) = BonehFranklin::blind(&pk, &alice, &mut rng);
+
+        assert_ne!(req1, req2);
+        assert_ne!(req1.b, hash_to_point(&alice));
+    }
+
+    #[test]
+    fn unblind_rejects_invalid_response() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = BonehFranklin::setup(&mut rng);
+        let (_, other_sk) = BonehFranklin::setup(&mut rng);
+
+        let (alice, bob) = (Identity::derive_str(ALICE), Identity::derive_str(BOB));
+
+        let (state, req) = BonehFranklin::blind(&pk, &alice, &mut rng);
+        let resp = BonehFranklin::oblivious_extract_usk(None, &other_sk, &req, &mut rng);
+        assert!(BonehFranklin::unblind(&pk, state, &resp).is_none());
+
+        // A response to a request for another identity.
+        let (state, _) = BonehFranklin::blind(&pk, &alice, &mut rng);
+        let (_, req) = BonehFranklin::blind(&pk, &bob, &mut rng);
+        let resp = BonehFranklin::oblivious_extract_usk(None, &sk, &req, &mut rng);
+        assert!(BonehFranklin::unblind(&pk, state, &resp).is_none());
+    }
+
+    #[test]
+    fn eq_serialize_deserialize() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = BonehFranklin::setup(&mut rng);
+
+        let alice = Identity::derive_str(ALICE);
+        let (_, req) = BonehFranklin::blind(&pk, &alice, &mut rng);
+        let resp = BonehFranklin::oblivious_extract_usk(None, &sk, &req, &mut rng);
+
+        assert_eq!(req, BlindRequest::from_bytes(&req.to_bytes()).unwrap());
+        assert_eq!(resp, BlindResponse::from_bytes(&resp.to_bytes()).unwrap());
+    }
+}
@@ -0,0 +1,302 @@
+//! EUF-ID-CMA secure IBS by Cha and Cheon.
+//! * From: "[An Identity-Based Signature from Gap Diffie-Hellman Groups](https://eprint.iacr.org/2002/018.pdf)"
+//! * Published in: PKC, 2003
+//!
+//! Identities are hashed to G1 using the hash-to-curve construction of RFC 9380, such that the
+//! user secret keys and the signatures live in the (smaller) G1 group, while the master public
+//! key lives in G2. A signature consists of two G1 elements.
+//!
+//! The hash H2 is instantiated using sha3_512 over the identity, the commitment and the message.
+
+extern crate alloc;
+use alloc::vec::Vec;
+
+use crate::ibs::IBS;
+use crate::util::*;
+use crate::Compress;
+use arrayref::{array_refs, mut_array_refs};
+use pg_curve::{multi_miller_loop, G1Affine, G2Affine, G2Prepared, Gt, Scalar};
+use rand::{CryptoRng, Rng};
+use subtle::CtOption;
+
+/// Domain separation tag used to hash identities to G1.
+const DST: &[u8] = b"IBS_CHA_CHEON_BLS12381G1_XMD:SHA-256_SSWU_RO_";
+
+/// Size of the compressed master public key in bytes.
+pub const PK_BYTES: usize = G2_BYTES;
+
+/// Size of the compressed master secret key in bytes.
+pub const SK_BYTES: usize = SCALAR_BYTES;
+
+/// Size of the compressed user secret key in bytes.
+///
+/// The USK includes the identity (needed to compute the challenge).
+pub const USK_BYTES: usize = ID_BYTES + G1_BYTES;
+
+/// Size of the compressed signature in bytes.
+pub const SIG_BYTES: usize = 2 * G1_BYTES;
+
+/// Public key parameters generated by the PKG used to verify signatures.
+/// Also known as P_pub.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct PublicKey {
+    ppub: G2Affine,
+}
+
+/// Secret key parameter generated by the PKG used to extract user secret keys.
+///
+/// # Zeroization
+///
+/// With the `zeroize` feature enabled this type derives `Zeroize` but **not**
+/// `ZeroizeOnDrop` (it is `Copy`). Secret material is **not** cleared on drop —
+/// you **MUST** call `.zeroize()` explicitly once done. See the
+/// [crate-level docs](crate#zeroizing-secret-material).
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize))]
+pub struct SecretKey {
+    s: Scalar,
+}
+
+/// User secret key. Can be used to sign messages on behalf of its identity.
+/// Also known as D_id.
+///
+/// # Zeroization
+///
+/// With the `zeroize` feature enabled this type derives `Zeroize` but **not**
+/// `ZeroizeOnDrop` (it is `Copy`). Secret material is **not** cleared on drop —
+/// you **MUST** call `.zeroize()` explicitly once done. See the
+/// [crate-level docs](crate#zeroizing-secret-material).
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize))]
+pub struct UserSecretKey {
+    id: Identity,
+    d: G1Affine,
+}
+
+/// Signature on a message. Can be verified using the identity of the signer.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub struct Signature {
+    u: G1Affine,
+    v: G1Affine,
+}
+
+/// The Cha-Cheon identity-based signature scheme.
+#[derive(Debug)]
+pub struct ChaCheon;
+
+/// Hashes an identity to a point on G1, also known as Q_id.
+fn hash_to_point(id: &Identity) -> G1Affine {
+    hash_to_g1(&id.0, DST).into()
+}
+
+/// Computes the challenge h = H2(id, U, m).
+fn challenge(id: &Identity, u: &G1Affine, message: &[u8]) -> Scalar {
+    let mut pre = Vec::with_capacity(ID_BYTES + G1_BYTES + message.len());
+    pre.extend_from_slice(&id.0);
+    pre.extend_from_slice(&u.to_compressed());
+    pre.extend_from_slice(message);
+
+    Scalar::from_bytes_wide(&sha3_512(&pre))
+}
+
+impl IBS for ChaCheon {
+    type Pk = PublicKey;
+    type Sk = SecretKey;
+    type Usk = UserSecretKey;
+    type Sig = Signature;
+    type Id = Identity;
+
+    const PK_BYTES: usize = PK_BYTES;
+    const SK_BYTES: usize = SK_BYTES;
+    const USK_BYTES: usize = USK_BYTES;
+    const SIG_BYTES: usize = SIG_BYTES;
+
+    /// Generate a keypair used by the Private Key Generator (PKG).
+    fn setup<R: Rng + CryptoRng>(rng: &mut R) -> (PublicKey, SecretKey) {
+        let s = rand_scalar(rng);
+        let ppub = (G2Affine::generator() * s).into();
+
+        (PublicKey { ppub }, SecretKey { s })
+    }
+
+    /// Extract a user secret key for a given identity.
+    fn extract_usk<R: Rng + CryptoRng>(
+        _opk: Option<&PublicKey>,
+        sk: &SecretKey,
+        id: &Identity,
+        _rng: &mut R,
+    ) -> UserSecretKey {
+        UserSecretKey {
+            id: *id,
+            d: (hash_to_point(id) * sk.s).into(),
+        }
+    }
+
+    /// Sign a message, such that U = r·Q_id and V = (r + h)·D_id.
+    fn sign<R: Rng + CryptoRng>(usk: &UserSecretKey, message: &[u8], rng: &mut R) -> Signature {
+        let r = rand_scalar(rng);
+
+        let u = (hash_to_point(&usk.id) * r).into();
+        let h = challenge(&usk.id, &u, message);
+        let v = (usk.d * (r + h)).into();
+
+        Signature { u, v }
+    }
+
+    /// Verify a signature by checking that e(V, g2) = e(U + h·Q_id, P_pub).
+    fn verify(pk: &PublicKey, id: &Identity, message: &[u8], sig: &Signature) -> bool {
+        let h = challenge(id, &sig.u, message);
+        let w: G1Affine = (sig.u + hash_to_point(id) * h).into();
+
+        multi_miller_loop(&[
+            (&sig.v, &G2Prepared::from(-G2Affine::generator())),
+            (&w, &G2Prepared::from(pk.ppub)),
+        ])
+        .final_exponentiation()
+            == Gt::identity()
+    }
+}
+
+impl Compress for PublicKey {
+    const OUTPUT_SIZE: usize = PK_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; PK_BYTES] {
+        self.ppub.to_compressed()
+    }
+
+    fn from_bytes(bytes: &[u8; PK_BYTES]) -> CtOption<Self> {
+        G2Affine::from_compressed(bytes).map(|ppub| PublicKey { ppub })
+    }
+}
+
+impl Compress for SecretKey {
+    const OUTPUT_SIZE: usize = SK_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; SK_BYTES] {
+        self.s.to_bytes()
+    }
+
+    fn from_bytes(bytes: &[u8; SK_BYTES]) -> CtOption<Self> {
+        Scalar::from_bytes(bytes).map(|s| SecretKey { s })
+    }
+}
+
+impl Compress for UserSecretKey {
+    const OUTPUT_SIZE: usize = USK_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; USK_BYTES] {
+        let mut buf = [0u8; USK_BYTES];
+        let (id, d) = mut_array_refs![&mut buf, ID_BYTES, G1_BYTES];
+
+        *id = self.id.0;
+        *d = self.d.to_compressed();
+
+        buf
+    }
+
+    fn from_bytes(bytes: &[u8; USK_BYTES]) -> CtOption<Self> {
+        let (id, d) = array_refs![bytes, ID_BYTES, G1_BYTES];
+
+        G1Affine::from_compressed(d).map(|d| UserSecretKey {
+            id: Identity(*id),
+            d,
+        })
+    }
+}
+
+impl Compress for Signature {
+    const OUTPUT_SIZE: usize = SIG_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; SIG_BYTES] {
+        let mut buf = [0u8; SIG_BYTES];
+        let (u, v) = mut_array_refs![&mut buf, G1_BYTES, G1_BYTES];
+
+        *u = self.u.to_compressed();
+        *v = self.v.to_compressed();
+
+        buf
+    }
+
+    fn from_bytes(bytes: &[u8; SIG_BYTES]) -> CtOption<Self> {
+        let (u, v) = array_refs![bytes, G1_BYTES, G1_BYTES];
+
+        // Use the checked `from_compressed` variant, which verifies that each point is in the
+        // correct prime-order subgroup (GHSA-25fp-2fjj-g84w).
+        let u = G1Affine::from_compressed(u);
+        let v = G1Affine::from_compressed(v);
+
+        u.and_then(|u| v.map(|v| Signature { u, v }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Derive;
+
+    const MSG: &[u8] = b"this came from alice@example.com";
+
+    #[test]
+    fn eq_sign_verify() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = ChaCheon::setup(&mut rng);
+
+        let alice = Identity::derive_str("alice@example.com");
+        let usk = ChaCheon::extract_usk(None, &sk, &alice, &mut rng);
+        let sig = ChaCheon::sign(&usk, MSG, &mut rng);
+
+        assert!(ChaCheon::verify(&pk, &alice, MSG, &sig));
+    }
+
+    #[test]
+    fn verify_rejects_forgeries() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = ChaCheon::setup(&mut rng);
+        let (other_pk, _) = ChaCheon::setup(&mut rng);
+
+        let alice = Identity::derive_str("alice@example.com");
+        let bob = Identity::derive_str("bob@example.com");
+        let usk = ChaCheon::extract_usk(None, &sk, &alice, &mut rng);
+        let sig = ChaCheon::sign(&usk, MSG, &mut rng);
+
+        assert!(!ChaCheon::verify(&pk, &bob, MSG, &sig));
+        assert!(!ChaCheon::verify(&pk, &alice, b"this came from bob", &sig));
+        assert!(!ChaCheon::verify(&other_pk, &alice, MSG, &sig));
+
+        let swapped = Signature { u: sig.v, v: sig.u };
+        assert!(!ChaCheon::verify(&pk, &alice, MSG, &swapped));
+    }
+
+    #[test]
+    fn eq_serialize_deserialize() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = ChaCheon::setup(&mut rng);
+
+        let alice = Identity::derive_str("alice@example.com");
+        let usk = ChaCheon::extract_usk(None, &sk, &alice, &mut rng);
+        let sig = ChaCheon::sign(&usk, MSG, &mut rng);
+
+        assert_eq!(pk, PublicKey::from_bytes(&pk.to_bytes()).unwrap());
+        assert_eq!(sk, SecretKey::from_bytes(&sk.to_bytes()).unwrap());
+        assert_eq!(usk, UserSecretKey::from_bytes(&usk.to_bytes()).unwrap());
+        assert_eq!(sig, Signature::from_bytes(&sig.to_bytes()).unwrap());
+    }
+
+    #[test]
+    fn from_bytes_rejects_non_subgroup_point() {
+        let mut rng = rand::thread_rng();
+        let (_, sk) = ChaCheon::setup(&mut rng);
+
+        let alice = Identity::derive_str("alice@example.com");
+        let usk = ChaCheon::extract_usk(None, &sk, &alice, &mut rng);
+        let sig = ChaCheon::sign(&usk, MSG, &mut rng);
+
+        let mut bytes = sig.to_bytes();
+        bytes[..G1_BYTES].copy_from_slice(&NON_SUBGROUP_G1_COMPRESSED);
+        assert!(bool::from(Signature::from_bytes(&bytes).is_none()));
+    }
+}
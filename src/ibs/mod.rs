@@ -0,0 +1,69 @@
+//! This module contains identity-based signature schemes (IBSs).
+//!
+//! A signature is verified using the master public key of the PKG and the identity of the signer,
+//! such that no certificates are required. Identities are derived using [`Derive`], like the
+//! identities of the identity-based encryption schemes.
+//!
+//! The master key pair of a signature scheme is independent of those of the encryption schemes,
+//! but a PKG can run both side-by-side and extract a signing key and a decryption key for the
+//! same identity.
+//!
+//! Among the schemes are:
+//!
+//! - Cha-Cheon.
+
+#[cfg(feature = "cha_cheon")]
+#[cfg_attr(docsrs, doc(cfg(feature = "cha_cheon")))]
+pub mod cha_cheon;
+
+use crate::{Compress, Derive};
+use rand::{CryptoRng, Rng};
+
+/// Identity-based signature scheme (IBS).
+pub trait IBS {
+    /// Master public key (Mpk).
+    type Pk: Compress;
+
+    /// Master secret key (Msk).
+    type Sk: Compress;
+
+    /// User secret key (Usk), used to sign messages.
+    type Usk: Compress;
+
+    /// Signature (Sig).
+    type Sig: Compress;
+
+    /// Internal identity type (Id).
+    type Id: Copy + Derive;
+
+    /// Size of the master public key in bytes.
+    const PK_BYTES: usize;
+
+    /// Size of the master secret key in bytes.
+    const SK_BYTES: usize;
+
+    /// Size of the user secret key in bytes.
+    const USK_BYTES: usize;
+
+    /// Size of the signature in bytes.
+    const SIG_BYTES: usize;
+
+    /// Creates an MSK, MPK pair.
+    fn setup<R: Rng + CryptoRng>(rng: &mut R) -> (Self::Pk, Self::Sk);
+
+    /// Extract a user secret key for an identity using the MSK.
+    ///
+    /// Optionally requires the system's public key.
+    fn extract_usk<R: Rng + CryptoRng>(
+        pk: Option<&Self::Pk>,
+        sk: &Self::Sk,
+        id: &Self::Id,
+        rng: &mut R,
+    ) -> Self::Usk;
+
+    /// Sign a message using a user secret key.
+    fn sign<R: Rng + CryptoRng>(usk: &Self::Usk, message: &[u8], rng: &mut R) -> Self::Sig;
+
+    /// Verify a signature on a message using the MPK and the identity of the signer.
+    fn verify(pk: &Self::Pk, id: &Self::Id, message: &[u8], sig: &Self::Sig) -> bool;
+}
@@ -0,0 +1,476 @@
+//! IND-CCA2 secure certificateless KEM by Al-Riyami and Paterson (FullCL-PKE).
+//! * From: "[Certificateless Public Key Cryptography](https://eprint.iacr.org/2003/126.pdf)"
+//! * Published in: ASIACRYPT, 2003
+//!
+//! The scheme builds on the keys of [Boneh-Franklin](crate::ibe::boneh_franklin): the partial key
+//! of an identity is the Boneh-Franklin user secret key d = s·Q_id. A user picks a secret value x
+//! and publishes the user public key (X, Y) = (x·P2, x·P_pub). The user secret key is x·d, and a
+//! ciphertext is a Boneh-Franklin ciphertext under the "master public key" Y, i.e., its mask is
+//! e(Y, Q_id)^r = e(P_pub, Q_id)^{rx}. Decapsulation requires both d and x, such that the PKG can
+//! not decapsulate, and a replaced user public key does not help without d.
+//!
+//! Before encapsulation, the sender checks that e(P_pub, X) = e(Y, P2). As BLS12-381 has an
+//! asymmetric pairing, X lives in G2 and Y lives in G1.
+//!
+//! CCA security due to a general approach by Fujisaki and Okamoto.
+//! * From: "[A Modular Analysis of the Fujisaki-Okamoto Transformation](https://eprint.iacr.org/2017/604.pdf)"
+//!
+//! Symmetric primitives G and H instantiated using sha3_512 and SHAKE256, respectively.
+
+use crate::ibe::boneh_franklin::{hash_to_point, BonehFranklin, USK_BYTES as BF_USK_BYTES};
+use crate::ibe::IBE;
+use crate::kem::{Error, SharedSecret, CLKEM};
+use crate::util::*;
+use crate::Compress;
+use arrayref::{array_refs, mut_array_refs};
+use group::Group;
+use pg_curve::{multi_miller_loop, G1Affine, G2Affine, G2Prepared, Gt, Scalar};
+use rand::{CryptoRng, Rng};
+use subtle::{ConstantTimeEq, CtOption};
+
+/// These structs are identical for the certificateless KEM.
+pub use crate::ibe::boneh_franklin::{
+    CipherText, PublicKey, SecretKey, CT_BYTES, MSG_BYTES, PK_BYTES, SK_BYTES,
+};
+
+use crate::ibe::boneh_franklin::{Msg, UserSecretKey as BFUserSecretKey};
+
+/// Size of the compressed partial key in bytes.
+pub const PARTIAL_KEY_BYTES: usize = ID_BYTES + BF_USK_BYTES;
+
+/// Size of the compressed secret value in bytes.
+pub const SECRET_VALUE_BYTES: usize = SCALAR_BYTES;
+
+/// Size of the compressed user public key in bytes.
+pub const UPK_BYTES: usize = G2_BYTES + G1_BYTES;
+
+/// Size of the compressed user secret key in bytes.
+///
+/// The USK includes the identity and the user public key (needed for re-encryption).
+pub const USK_BYTES: usize = ID_BYTES + BF_USK_BYTES + UPK_BYTES;
+
+/// Partial key issued by the PKG. Can not be used to decapsulate on its own.
+/// Also known as D_id.
+///
+/// # Zeroization
+///
+/// With the `zeroize` feature enabled this type derives `Zeroize` but **not**
+/// `ZeroizeOnDrop` (it is `Copy`). Secret material is **not** cleared on drop —
+/// you **MUST** call `.zeroize()` explicitly once done. See the
+/// [crate-level docs](crate#zeroizing-secret-material).
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize))]
+pub struct PartialKey {
+    id: Identity,
+    d: BFUserSecretKey,
+}
+
+/// Secret value generated by the user.
+/// Also known as x_id.
+///
+/// # Zeroization
+///
+/// With the `zeroize` feature enabled this type derives `Zeroize` but **not**
+/// `ZeroizeOnDrop` (it is `Copy`). Secret material is **not** cleared on drop —
+/// you **MUST** call `.zeroize()` explicitly once done. See the
+/// [crate-level docs](crate#zeroizing-secret-material).
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize))]
+pub struct SecretValue {
+    x: Scalar,
+}
+
+/// User public key, published by the user. Does not require a certificate.
+/// Also known as (X_id, Y_id).
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub struct UserPublicKey {
+    x: G2Affine,
+    y: G1Affine,
+}
+
+/// User secret key. Can be used to decaps the corresponding ciphertext.
+/// Also known as S_id.
+///
+/// # Zeroization
+///
+/// With the `zeroize` feature enabled this type derives `Zeroize` but **not**
+/// `ZeroizeOnDrop` (it is `Copy`). Secret material is **not** cleared on drop —
+/// you **MUST** call `.zeroize()` explicitly once done. See the
+/// [crate-level docs](crate#zeroizing-secret-material).
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize))]
+pub struct UserSecretKey {
+    id: Identity,
+    s: BFUserSecretKey,
+    #[cfg_attr(feature = "zeroize", zeroize(skip))]
+    upk: UserPublicKey,
+}
+
+/// The CCA2 secure certificateless KEM by Al-Riyami and Paterson, on top of the keys of
+/// Boneh-Franklin.
+#[derive(Debug, Clone, Copy)]
+pub struct AlRiyamiPaterson;
+
+/// Computes the coins of the Fujisaki-Okamoto transform, G(m, id, upk).
+fn coins(m: &Msg, id: &Identity, upk: &UserPublicKey) -> [u8; 64] {
+    let mut pre_coins = [0u8; MSG_BYTES + ID_BYTES + UPK_BYTES];
+    let (pm, pid, pupk) = mut_array_refs![&mut pre_coins, MSG_BYTES, ID_BYTES, UPK_BYTES];
+
+    *pm = m.to_bytes();
+    *pid = id.0;
+    *pupk = upk.to_bytes();
+
+    sha3_512(&pre_coins)
+}
+
+/// Encrypts a message as a Boneh-Franklin ciphertext under Y.
+fn encrypt(id: &Identity, upk: &UserPublicKey, m: &Msg, coins: &[u8; 64]) -> CipherText {
+    BonehFranklin::encrypt(&PublicKey { ppub: upk.y }, id, m, coins)
+}
+
+impl CLKEM for AlRiyamiPaterson {
+    const IDENTIFIER: &'static str = "al_riyami_paterson";
+
+    type Pk = PublicKey;
+    type Sk = SecretKey;
+    type PartialKey = PartialKey;
+    type SecretValue = SecretValue;
+    type Upk = UserPublicKey;
+    type Usk = UserSecretKey;
+    type Ct = CipherText;
+    type Id = Identity;
+
+    const PK_BYTES: usize = PK_BYTES;
+    const SK_BYTES: usize = SK_BYTES;
+    const PARTIAL_KEY_BYTES: usize = PARTIAL_KEY_BYTES;
+    const SECRET_VALUE_BYTES: usize = SECRET_VALUE_BYTES;
+    const UPK_BYTES: usize = UPK_BYTES;
+    const USK_BYTES: usize = USK_BYTES;
+    const CT_BYTES: usize = CT_BYTES;
+
+    fn setup<R: Rng + CryptoRng>(rng: &mut R) -> (PublicKey, SecretKey) {
+        BonehFranklin::setup(rng)
+    }
+
+    fn extract_partial_key<R: Rng + CryptoRng>(
+        _pk: Option<&PublicKey>,
+        sk: &SecretKey,
+        id: &Identity,
+        rng: &mut R,
+    ) -> PartialKey {
+        let d = BonehFranklin::extract_usk(None, sk, id, rng);
+
+        PartialKey { id: *id, d }
+    }
+
+    fn generate_user_keys<R: Rng + CryptoRng>(
+        pk: &PublicKey,
+        rng: &mut R,
+    ) -> (UserPublicKey, SecretValue) {
+        let x = rand_scalar(rng);
+
+        let upk = UserPublicKey {
+            x: (G2Affine::generator() * x).into(),
+            y: (pk.ppub * x).into(),
+        };
+
+        (upk, SecretValue { x })
+    }
+
+    /// Combine the partial key and the secret value into S_id = x·D_id.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an [`Error`] if e(P1, D_id) ≠ e(P_pub, Q_id), or if the user public
+    /// key is not (x·P2, x·P_pub).
+    fn combine_usk(
+        pk: &PublicKey,
+        partial: &PartialKey,
+        sv: &SecretValue,
+        upk: &UserPublicKey,
+    ) -> Result<UserSecretKey, Error> {
+        let valid_partial = multi_miller_loop(&[
+            (&G1Affine::generator(), &G2Prepared::from(partial.d.d)),
+            (&-pk.ppub, &G2Prepared::from(hash_to_point(&partial.id))),
+        ])
+        .final_exponentiation()
+            == Gt::identity();
+
+        let valid_upk = G2Affine::from(G2Affine::generator() * sv.x) == upk.x
+            && G1Affine::from(pk.ppub * sv.x) == upk.y;
+
+        if !valid_partial || !valid_upk {
+            return Err(Error);
+        }
+
+        Ok(UserSecretKey {
+            id: partial.id,
+            s: BFUserSecretKey {
+                d: (partial.d.d * sv.x).into(),
+            },
+            upk: *upk,
+        })
+    }
+
+    /// Encapsulate a shared secret for an identity and its user public key.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an [`Error`] if Y is the identity, or if e(P_pub, X) ≠ e(Y, P2).
+    fn encaps<R: Rng + CryptoRng>(
+        pk: &PublicKey,
+        id: &Identity,
+        upk: &UserPublicKey,
+        rng: &mut R,
+    ) -> Result<(CipherText, SharedSecret), Error> {
+        let valid_upk = !bool::from(upk.y.is_identity())
+            && multi_miller_loop(&[
+                (&pk.ppub, &G2Prepared::from(upk.x)),
+                (&-upk.y, &G2Prepared::from(G2Affine::generator())),
+            ])
+            .final_exponentiation()
+                == Gt::identity();
+
+        if !valid_upk {
+            return Err(Error);
+        }
+
+        let m = Msg::random(rng);
+        let ct = encrypt(id, upk, &m, &coins(&m, id, upk));
+
+        Ok((ct, SharedSecret::from(&m)))
+    }
+
+    /// Decapsulate a shared secret from the ciphertext.
+    ///
+    /// This scheme does not require the master public key, as the user secret key includes the
+    /// user public key that is needed for the Fujisaki-Okamoto transform.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an [`Error`] when an illegitimate ciphertext is encountered (explicit rejection).
+    fn decaps(
+        _opk: Option<&PublicKey>,
+        usk: &UserSecretKey,
+        c: &CipherText,
+    ) -> Result<SharedSecret, Error> {
+        let m = BonehFranklin::decrypt(&usk.s, c);
+        let c2 = encrypt(&usk.id, &usk.upk, &m, &coins(&m, &usk.id, &usk.upk));
+
+        // Can save some time by not doing a constant-time comparison
+        // since we can leak whether the decapsulation succeeds/fails.
+        if c.ct_eq(&c2).into() {
+            Ok(SharedSecret::from(&m))
+        } else {
+            Err(Error)
+        }
+    }
+}
+
+impl Compress for PartialKey {
+    const OUTPUT_SIZE: usize = PARTIAL_KEY_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; PARTIAL_KEY_BYTES] {
+        let mut buf = [0u8; PARTIAL_KEY_BYTES];
+        let (id, d) = mut_array_refs![&mut buf, ID_BYTES, BF_USK_BYTES];
+
+        *id = self.id.0;
+        *d = self.d.to_bytes();
+
+        buf
+    }
+
+    fn from_bytes(bytes: &[u8; PARTIAL_KEY_BYTES]) -> CtOption<Self> {
+        let (id, d) = array_refs![bytes, ID_BYTES, BF_USK_BYTES];
+
+        BFUserSecretKey::from_bytes(d).map(|d| PartialKey {
+            id: Identity(*id),
+            d,
+        })
+    }
+}
+
+impl Compress for SecretValue {
+    const OUTPUT_SIZE: usize = SECRET_VALUE_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; SECRET_VALUE_BYTES] {
+        self.x.to_bytes()
+    }
+
+    fn from_bytes(bytes: &[u8; SECRET_VALUE_BYTES]) -> CtOption<Self> {
+        Scalar::from_bytes(bytes).map(|x| SecretValue { x })
+    }
+}
+
+impl Compress for UserPublicKey {
+    const OUTPUT_SIZE: usize = UPK_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; UPK_BYTES] {
+        let mut buf = [0u8; UPK_BYTES];
+        let (x, y) = mut_array_refs![&mut buf, G2_BYTES, G1_BYTES];
+
+        *x = self.x.to_compressed();
+        *y = self.y.to_compressed();
+
+        buf
+    }
+
+    fn from_bytes(bytes: &[u8; UPK_BYTES]) -> CtOption<Self> {
+        let (x, y) = array_refs![bytes, G2_BYTES, G1_BYTES];
+
+        // Use the checked `from_compressed` variant, which verifies that each point is in the
+        // correct prime-order subgroup (GHSA-25fp-2fjj-g84w).
+        let x = G2Affine::from_compressed(x);
+        let y = G1Affine::from_compressed(y);
+
+        x.and_then(|x| y.map(|y| UserPublicKey { x, y }))
+    }
+}
+
+impl Compress for UserSecretKey {
+    const OUTPUT_SIZE: usize = USK_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; USK_BYTES] {
+        let mut buf = [0u8; USK_BYTES];
+        let (id, s, upk) = mut_array_refs![&mut buf, ID_BYTES, BF_USK_BYTES, UPK_BYTES];
+
+        *id = self.id.0;
+        *s = self.s.to_bytes();
+        *upk = self.upk.to_bytes();
+
+        buf
+    }
+
+    fn from_bytes(bytes: &[u8; USK_BYTES]) -> CtOption<Self> {
+        let (id, s, x, y) = array_refs![bytes, ID_BYTES, BF_USK_BYTES, G2_BYTES, G1_BYTES];
+
+        let s = BFUserSecretKey::from_bytes(s);
+        let x = G2Affine::from_compressed(x);
+        let y = G1Affine::from_compressed(y);
+
+        s.and_then(|s| {
+            x.and_then(|x| {
+                y.map(|y| UserSecretKey {
+                    id: Identity(*id),
+                    s,
+                    upk: UserPublicKey { x, y },
+                })
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Derive;
+
+    type Scheme = AlRiyamiPaterson;
+
+    const ALICE: &str = "email:alice@example.com";
+    const BOB: &str = "email:bob@example.com";
+
+    fn keys(pk: &PublicKey, sk: &SecretKey, id: &Identity) -> (UserPublicKey, UserSecretKey) {
+        let mut rng = rand::thread_rng();
+
+        let partial = Scheme::extract_partial_key(Some(pk), sk, id, &mut rng);
+        let (upk, sv) = Scheme::generate_user_keys(pk, &mut rng);
+        let usk = Scheme::combine_usk(pk, &partial, &sv, &upk).unwrap();
+
+        (upk, usk)
+    }
+
+    #[test]
+    fn eq_encaps_decaps() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = Scheme::setup(&mut rng);
+
+        let alice = Identity::derive_str(ALICE);
+        let (upk, usk) = keys(&pk, &sk, &alice);
+
+        let (ct, k) = Scheme::encaps(&pk, &alice, &upk, &mut rng).unwrap();
+        let k2 = Scheme::decaps(Some(&pk), &usk, &ct).unwrap();
+
+        assert_eq!(k, k2);
+    }
+
+    #[test]
+    fn no_key_escrow() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = Scheme::setup(&mut rng);
+
+        let alice = Identity::derive_str(ALICE);
+        let (upk, _) = keys(&pk, &sk, &alice);
+        let (ct, k) = Scheme::encaps(&pk, &alice, &upk, &mut rng).unwrap();
+
+        // The PKG knows the partial key, but not the secret value of the user.
+        let partial = Scheme::extract_partial_key(Some(&pk), &sk, &alice, &mut rng);
+        let (pkg_upk, pkg_sv) = Scheme::generate_user_keys(&pk, &mut rng);
+        let pkg_usk = Scheme::combine_usk(&pk, &partial, &pkg_sv, &pkg_upk).unwrap();
+        assert!(Scheme::decaps(Some(&pk), &pkg_usk, &ct).is_err());
+
+        let forged = UserSecretKey { upk, ..pkg_usk };
+        assert!(Scheme::decaps(Some(&pk), &forged, &ct).is_err());
+        assert_ne!(
+            SharedSecret::from(&BonehFranklin::decrypt(&partial.d, &ct)),
+            k
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_keys() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = Scheme::setup(&mut rng);
+        let (other_pk, other_sk) = Scheme::setup(&mut rng);
+
+        let (alice, bob) = (Identity::derive_str(ALICE), Identity::derive_str(BOB));
+        let (upk, usk) = keys(&pk, &sk, &alice);
+
+        // A user public key that is not of the form (x·P2, x·P_pub).
+        let (other_upk, _) = Scheme::generate_user_keys(&other_pk, &mut rng);
+        let mixed = UserPublicKey {
+            x: upk.x,
+            y: other_upk.y,
+        };
+        assert!(Scheme::encaps(&pk, &alice, &mixed, &mut rng).is_err());
+        assert!(Scheme::encaps(&pk, &alice, &UserPublicKey::default(), &mut rng).is_err());
+
+        // A partial key issued by another PKG.
+        let partial = Scheme::extract_partial_key(None, &other_sk, &alice, &mut rng);
+        let (upk2, sv) = Scheme::generate_user_keys(&pk, &mut rng);
+        assert!(Scheme::combine_usk(&pk, &partial, &sv, &upk2).is_err());
+        assert!(Scheme::combine_usk(&pk, &partial, &sv, &upk).is_err());
+
+        // A ciphertext for another identity.
+        let (bob_upk, _) = keys(&pk, &sk, &bob);
+        let (ct, _) = Scheme::encaps(&pk, &bob, &bob_upk, &mut rng).unwrap();
+        assert!(Scheme::decaps(None, &usk, &ct).is_err());
+    }
+
+    #[test]
+    fn eq_serialize_deserialize() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = Scheme::setup(&mut rng);
+
+        let alice = Identity::derive_str(ALICE);
+        let partial = Scheme::extract_partial_key(Some(&pk), &sk, &alice, &mut rng);
+        let (upk, sv) = Scheme::generate_user_keys(&pk, &mut rng);
+        let usk = Scheme::combine_usk(&pk, &partial, &sv, &upk).unwrap();
+
+        assert_eq!(
+            partial,
+            PartialKey::from_bytes(&partial.to_bytes()).unwrap()
+        );
+        assert_eq!(sv, SecretValue::from_bytes(&sv.to_bytes()).unwrap());
+        assert_eq!(upk, UserPublicKey::from_bytes(&upk.to_bytes()).unwrap());
+        assert_eq!(usk, UserSecretKey::from_bytes(&usk.to_bytes()).unwrap());
+
+        let mut bytes = upk.to_bytes();
+        bytes[G2_BYTES..].copy_from_slice(&NON_SUBGROUP_G1_COMPRESSED);
+        assert!(bool::from(UserPublicKey::from_bytes(&bytes).is_none()));
+    }
+}
@@ -0,0 +1,184 @@
+//! IND-sID-CCA2 secure IBKEM by a scheme by Boneh and Boyen (BB1).
+//! * From: "[Efficient Selective-ID Secure Identity-Based Encryption Without Random Oracles](https://link.springer.com/chapter/10.1007/978-3-540-24676-3_14)"
+//!
+//! CCA security due to a general approach by Fujisaki and Okamoto.
+//! * From: "[A Modular Analysis of the Fujisaki-Okamoto Transformation](https://eprint.iacr.org/2017/604.pdf)"
+//!
+//! Symmetric primitives G and H instantiated using sha3_512 and sha3_256, respectively.
+//!
+//! A drawback of a Fujisaki-Okamoto transform is that we now need the public key to decapsulate.
+
+use crate::ibe::boneh_boyen::{BonehBoyen, CipherText, Msg, USK_BYTES as CPA_USK_BYTES};
+use crate::ibe::IBE;
+use crate::kem::{Error, SharedSecret, IBKEM};
+use crate::util::*;
+use crate::Compress;
+use arrayref::{array_refs, mut_array_refs};
+use group::Group;
+use rand::{CryptoRng, Rng};
+use subtle::{ConstantTimeEq, CtOption};
+
+/// These struct are identical for the CCA KEM.
+pub use crate::ibe::boneh_boyen::{PublicKey, SecretKey, CT_BYTES, MSG_BYTES, PK_BYTES, SK_BYTES};
+
+/// Size of the compressed user secret key in bytes.
+///
+/// The USK includes the identity (needed for re-encryption).
+pub const USK_BYTES: usize = CPA_USK_BYTES + ID_BYTES;
+
+/// User secret key. Can be used to decaps the corresponding ciphertext.
+/// Also known as USK_{id}.
+///
+/// # Zeroization
+///
+/// With the `zeroize` feature enabled this type derives `Zeroize` but **not**
+/// `ZeroizeOnDrop` (it is `Copy`). Secret material is **not** cleared on drop —
+/// you **MUST** call `.zeroize()` explicitly once done. See the
+/// [crate-level docs](crate#zeroizing-secret-material).
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize))]
+pub struct UserSecretKey {
+    usk: crate::ibe::boneh_boyen::UserSecretKey,
+    id: Identity,
+}
+
+impl Compress for UserSecretKey {
+    const OUTPUT_SIZE: usize = USK_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; USK_BYTES] {
+        let mut buf = [0u8; USK_BYTES];
+        let (usk, id) = mut_array_refs![&mut buf, CPA_USK_BYTES, ID_BYTES];
+
+        *usk = self.usk.to_bytes();
+        id.copy_from_slice(&self.id.0);
+
+        buf
+    }
+
+    fn from_bytes(bytes: &[u8; USK_BYTES]) -> CtOption<Self> {
+        let (usk, rid) = array_refs![&bytes, CPA_USK_BYTES, ID_BYTES];
+
+        let usk = crate::ibe::boneh_boyen::UserSecretKey::from_bytes(usk);
+        let id = Identity(*rid);
+
+        usk.map(|usk| UserSecretKey { usk, id })
+    }
+}
+
+/// The CCA2 secure KEM that results by applying the implicit rejection
+/// variant of the Fujisaki-Okamoto transform to the Boneh-Boyen (BB1) IBE scheme.
+#[derive(Debug, Clone, Copy)]
+pub struct BBFO;
+
+impl IBKEM for BBFO {
+    const IDENTIFIER: &'static str = "bbfo";
+
+    type Pk = PublicKey;
+    type Sk = SecretKey;
+    type Usk = UserSecretKey;
+    type Ct = CipherText;
+    type Id = Identity;
+
+    const PK_BYTES: usize = PK_BYTES;
+    const USK_BYTES: usize = USK_BYTES;
+    const SK_BYTES: usize = SK_BYTES;
+    const CT_BYTES: usize = CT_BYTES;
+
+    fn setup<R: Rng + CryptoRng>(rng: &mut R) -> (PublicKey, SecretKey) {
+        BonehBoyen::setup(rng)
+    }
+
+    fn extract_usk<R: Rng + CryptoRng>(
+        _pk: Option<&PublicKey>,
+        sk: &SecretKey,
+        id: &Identity,
+        rng: &mut R,
+    ) -> UserSecretKey {
+        let usk = BonehBoyen::extract_usk(None, sk, id, rng);
+
+        UserSecretKey { usk, id: *id }
+    }
+
+    fn encaps<R: Rng + CryptoRng>(
+        pk: &PublicKey,
+        id: &Identity,
+        rng: &mut R,
+    ) -> (CipherText, SharedSecret) {
+        let m = Msg::random(rng);
+
+        let mut pre_coins = [0u8; MSG_BYTES + ID_BYTES];
+        pre_coins[..MSG_BYTES].copy_from_slice(&m.to_bytes());
+        pre_coins[MSG_BYTES..].copy_from_slice(&id.0);
+
+        let coins = sha3_512(&pre_coins);
+
+        let ct = BonehBoyen::encrypt(pk, id, &m, &coins);
+
+        (ct, SharedSecret::from(&m))
+    }
+
+    /// Decapsulate a shared secret from the ciphertext.
+    ///
+    /// # Panics
+    ///
+    /// This scheme **does** requires the master public key due to usage the Fujisaki-Okamoto transform.
+    /// This function panics if no master public key is provided.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an [`Error`] when an illegitimate ciphertext is encountered (explicit rejection).
+    fn decaps(
+        opk: Option<&PublicKey>,
+        usk: &UserSecretKey,
+        c: &CipherText,
+    ) -> Result<SharedSecret, Error> {
+        let pk = opk.unwrap();
+
+        let m = BonehBoyen::decrypt(&usk.usk, c);
+
+        let mut pre_coins = [0u8; MSG_BYTES + ID_BYTES];
+        pre_coins[..MSG_BYTES].copy_from_slice(&m.to_bytes());
+        pre_coins[MSG_BYTES..].copy_from_slice(&usk.id.0);
+
+        let coins = sha3_512(&pre_coins);
+
+        let c2 = BonehBoyen::encrypt(pk, &usk.id, &m, &coins);
+
+        // Can save some time by not doing a constant-time comparison
+        // since we can leak whether the decapsulation succeeds/fails.
+        if c.ct_eq(&c2).into() {
+            Ok(SharedSecret::from(&m))
+        } else {
+            Err(Error)
+        }
+    }
+}
+
+#[cfg(feature = "mkem")]
+impl crate::kem::mkem::MultiRecipient for BBFO {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Derive;
+
+    test_kem!(BBFO);
+
+    #[cfg(feature = "mkem")]
+    test_multi_kem!(BBFO);
+
+    #[test]
+    fn decaps_rejects_ciphertext_for_other_identity() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = BBFO::setup(&mut rng);
+
+        let alice = Identity::derive_str("alice@example.com");
+        let bob = Identity::derive_str("bob@example.com");
+
+        let usk = BBFO::extract_usk(None, &sk, &alice, &mut rng);
+        let (ct, _k) = BBFO::encaps(&pk, &bob, &mut rng);
+
+        assert!(BBFO::decaps(Some(&pk), &usk, &ct).is_err());
+    }
+}
@@ -0,0 +1,503 @@
+//! IND-sID-CCA2 secure forward-secure IBKEM from the Boneh, Boyen & Goh HIBE.
+//! * From: "[Hierarchical Identity Based Encryption with Constant Size Ciphertext](https://eprint.iacr.org/2005/015.pdf)"
+//! * Published in: EUROCRYPT, 2005
+//!
+//! The time tree construction is due to Canetti, Halevi and Katz.
+//! * From: "[A Forward-Secure Public-Key Encryption Scheme](https://eprint.iacr.org/2003/083.pdf)"
+//!
+//! CCA security due to a general approach by Fujisaki and Okamoto.
+//! * From: "[A Modular Analysis of the Fujisaki-Okamoto Transformation](https://eprint.iacr.org/2017/604.pdf)"
+//!
+//! The identity is the first level of a HIBE of depth `D`, the remaining `D - 1` levels form a
+//! binary tree of which the leaves are the `2^(D - 1)` epochs. A ciphertext for an epoch is
+//! encrypted for the path from the identity to the leaf of that epoch, and therefore has constant
+//! size. The user secret key for an epoch consists of the key of its leaf and the keys of the
+//! right siblings along the path to the root, which together cover exactly the current and all
+//! later epochs. Updating a user secret key delegates keys for the new epoch from these nodes, and
+//! erases the keys of the nodes that only cover earlier epochs. Since delegation only works
+//! downwards in the tree, the erased keys can not be re-derived from the updated key.
+//!
+//! A user secret key only decapsulates ciphertexts for its current epoch, to decapsulate a
+//! ciphertext for a later epoch the key has to be updated first.
+//!
+//! With the `zeroize` feature enabled, erased keys are zeroized. Without this feature the erased
+//! keys are merely dropped, and may remain in memory.
+//!
+//! Symmetric primitive G is instantiated using sha3_512.
+//!
+//! A drawback of a Fujisaki-Okamoto transform is that we now need the public key to decapsulate.
+
+extern crate alloc;
+use alloc::vec::Vec;
+
+use crate::ibe::boneh_boyen_goh::{BonehBoyenGoh, Msg};
+use crate::ibe::{IdentityPath, HIBE, IBE};
+use crate::kem::{Error, SharedSecret, FSKEM};
+use crate::util::*;
+use crate::{Compress, Derive};
+use arrayref::{array_refs, mut_array_refs};
+use group::Group;
+use rand::{CryptoRng, Rng};
+use subtle::{Choice, ConstantTimeEq, CtOption};
+
+/// These structs are identical for the forward-secure KEM.
+pub use crate::ibe::boneh_boyen_goh::{PublicKey, SecretKey, MSG_BYTES};
+
+/// Size of the compressed ciphertext in bytes.
+///
+/// The ciphertext includes its epoch (needed for decapsulation).
+pub const CT_BYTES: usize = crate::ibe::boneh_boyen_goh::CT_BYTES + 8;
+
+/// Key of a node of the time tree, covering the epochs of which the first `len` bits equal
+/// `prefix`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize))]
+struct Node<const D: usize> {
+    prefix: u64,
+    len: u8,
+    usk: crate::ibe::boneh_boyen_goh::UserSecretKey<D>,
+}
+
+/// User secret key at an epoch. Can be used to decaps ciphertexts for this epoch, and can be
+/// updated to later epochs.
+///
+/// # Zeroization
+///
+/// With the `zeroize` feature enabled this type derives `Zeroize` and `ZeroizeOnDrop`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize, zeroize::ZeroizeOnDrop))]
+pub struct UserSecretKey<const D: usize> {
+    id: Identity,
+    epoch: u64,
+    nodes: Vec<Node<D>>,
+}
+
+/// Encapsulated shared secret for an identity at an epoch.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct CipherText {
+    ct: crate::ibe::boneh_boyen_goh::CipherText,
+    epoch: u64,
+}
+
+impl ConstantTimeEq for CipherText {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.ct.ct_eq(&other.ct) & self.epoch.ct_eq(&other.epoch)
+    }
+}
+
+/// The CCA2 secure forward-secure KEM that results by applying the Fujisaki-Okamoto transform to
+/// the Boneh, Boyen & Goh HIBE with a time tree of depth `D - 1`, i.e. `2^(D - 1)` epochs.
+#[derive(Debug, Clone, Copy)]
+pub struct BBGFS<const D: usize>;
+
+/// Identity component for the left (`false`) or right (`true`) child in the time tree.
+fn bit(b: bool) -> Identity {
+    Identity::derive(if b { b"1" } else { b"0" })
+}
+
+impl<const D: usize> Node<D> {
+    /// Depth of the time tree.
+    const LEVELS: u8 = (D - 1) as u8;
+
+    /// Whether the epoch is one of the leaves below this node.
+    fn covers(&self, epoch: u64) -> bool {
+        epoch >> (Self::LEVELS - self.len) == self.prefix
+    }
+
+    /// The first epoch that is a leaf below this node.
+    fn start(&self) -> u64 {
+        self.prefix << (Self::LEVELS - self.len)
+    }
+}
+
+/// Computes the HIBE identity path of the node of the time tree below an identity.
+fn path<const D: usize>(id: &Identity, prefix: u64, len: u8) -> IdentityPath<D> {
+    let mut res = [*id; D];
+    for i in 0..len {
+        res[1 + i as usize] = bit((prefix >> (len - 1 - i)) & 1 == 1);
+    }
+
+    IdentityPath::new(&res[..1 + len as usize]).unwrap()
+}
+
+/// Delegates the key of a node down to the leaf of the epoch, collecting the keys of the right
+/// siblings along the path. The keys of the intermediate nodes are erased.
+fn expand<R: Rng + CryptoRng, const D: usize>(
+    pk: &PublicKey<D>,
+    node: &Node<D>,
+    epoch: u64,
+    out: &mut Vec<Node<D>>,
+    rng: &mut R,
+) {
+    let mut current = *node;
+
+    while current.len < Node::<D>::LEVELS {
+        let len = current.len + 1;
+        let b = (epoch >> (Node::<D>::LEVELS - len)) & 1 == 1;
+
+        if !b {
+            out.push(Node {
+                prefix: (current.prefix << 1) | 1,
+                len,
+                usk: BonehBoyenGoh::delegate(pk, &current.usk, &bit(true), rng).unwrap(),
+            });
+        }
+
+        let child = Node {
+            prefix: (current.prefix << 1) | b as u64,
+            len,
+            usk: BonehBoyenGoh::delegate(pk, &current.usk, &bit(b), rng).unwrap(),
+        };
+
+        #[cfg(feature = "zeroize")]
+        zeroize::Zeroize::zeroize(&mut current);
+
+        current = child;
+    }
+
+    out.push(current);
+}
+
+/// Computes the coins G(m, id, epoch) of the Fujisaki-Okamoto transform.
+fn coins(m: &Msg, id: &Identity, epoch: u64) -> [u8; 64] {
+    let mut pre_coins = [0u8; MSG_BYTES + ID_BYTES + 8];
+    let (pm, pid, pe) = mut_array_refs![&mut pre_coins, MSG_BYTES, ID_BYTES, 8];
+
+    *pm = m.to_compressed();
+    *pid = id.0;
+    *pe = epoch.to_be_bytes();
+
+    sha3_512(&pre_coins)
+}
+
+impl<const D: usize> FSKEM for BBGFS<D> {
+    const IDENTIFIER: &'static str = "bbgfs";
+
+    type Pk = PublicKey<D>;
+    type Sk = SecretKey<D>;
+    type Usk = UserSecretKey<D>;
+    type Ct = CipherText;
+    type Id = Identity;
+
+    const PK_BYTES: usize = <BonehBoyenGoh<D> as IBE>::PK_BYTES;
+    const SK_BYTES: usize = <BonehBoyenGoh<D> as IBE>::SK_BYTES;
+    const USK_BYTES: usize = ID_BYTES + 8 + D * UserSecretKey::<D>::NODE_BYTES;
+    const CT_BYTES: usize = CT_BYTES;
+    const EPOCHS: u64 = 1 << (D - 1);
+
+    fn setup<R: Rng + CryptoRng>(rng: &mut R) -> (PublicKey<D>, SecretKey<D>) {
+        BonehBoyenGoh::setup(rng)
+    }
+
+    /// Extract a user secret key for the first epoch, which consists of the key of its leaf and
+    /// the keys of all right children along the left-most path.
+    fn extract_usk<R: Rng + CryptoRng>(
+        _pk: Option<&PublicKey<D>>,
+        sk: &SecretKey<D>,
+        id: &Identity,
+        rng: &mut R,
+    ) -> UserSecretKey<D> {
+        let mut nodes = Vec::with_capacity(D);
+        nodes.push(Node {
+            prefix: 0,
+            len: Node::<D>::LEVELS,
+            usk: BonehBoyenGoh::extract_usk(None, sk, &path(id, 0, Node::<D>::LEVELS), rng),
+        });
+        for len in (1..=Node::<D>::LEVELS).rev() {
+            nodes.push(Node {
+                prefix: 1,
+                len,
+                usk: BonehBoyenGoh::extract_usk(None, sk, &path(id, 1, len), rng),
+            });
+        }
+
+        UserSecretKey {
+            id: *id,
+            epoch: 0,
+            nodes,
+        }
+    }
+
+    fn epoch(usk: &UserSecretKey<D>) -> u64 {
+        usk.epoch
+    }
+
+    /// Update the user secret key by expanding the node that covers the new epoch, and erasing
+    /// the nodes that only cover earlier epochs.
+    fn update<R: Rng + CryptoRng>(
+        pk: &PublicKey<D>,
+        usk: &mut UserSecretKey<D>,
+        epoch: u64,
+        rng: &mut R,
+    ) -> Result<(), Error> {
+        if epoch < usk.epoch || epoch >= Self::EPOCHS {
+            return Err(Error);
+        }
+
+        let mut nodes = Vec::with_capacity(D);
+        for node in usk.nodes.iter() {
+            if node.covers(epoch) {
+                expand(pk, node, epoch, &mut nodes, rng);
+            } else if node.start() > epoch {
+                nodes.push(*node);
+            }
+        }
+        nodes.sort_by_key(|node| node.start());
+
+        #[cfg(feature = "zeroize")]
+        zeroize::Zeroize::zeroize(&mut usk.nodes);
+
+        usk.nodes = nodes;
+        usk.epoch = epoch;
+
+        Ok(())
+    }
+
+    fn encaps<R: Rng + CryptoRng>(
+        pk: &PublicKey<D>,
+        id: &Identity,
+        epoch: u64,
+        rng: &mut R,
+    ) -> Result<(CipherText, SharedSecret), Error> {
+        if epoch >= Self::EPOCHS {
+            return Err(Error);
+        }
+
+        let m = Msg::random(rng);
+        let leaf = path(id, epoch, Node::<D>::LEVELS);
+        let ct = BonehBoyenGoh::encrypt(pk, &leaf, &m, &coins(&m, id, epoch));
+
+        Ok((CipherText { ct, epoch }, SharedSecret::from(&m)))
+    }
+
+    /// Decapsulate a shared secret from the ciphertext.
+    ///
+    /// # Panics
+    ///
+    /// This scheme **does** requires the master public key due to usage the Fujisaki-Okamoto transform.
+    /// This function panics if no master public key is provided.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an [`Error`] if the ciphertext is not for the epoch of the user secret
+    /// key, or when an illegitimate ciphertext is encountered (explicit rejection).
+    fn decaps(
+        opk: Option<&PublicKey<D>>,
+        usk: &UserSecretKey<D>,
+        ct: &CipherText,
+    ) -> Result<SharedSecret, Error> {
+        let pk = opk.unwrap();
+
+        if ct.epoch != usk.epoch {
+            return Err(Error);
+        }
+
+        let node = usk
+            .nodes
+            .iter()
+            .find(|node| node.len == Node::<D>::LEVELS && node.prefix == ct.epoch)
+            .ok_or(Error)?;
+
+        let m = BonehBoyenGoh::decrypt(&node.usk, &ct.ct);
+
+        let leaf = path(&usk.id, ct.epoch, Node::<D>::LEVELS);
+        let ct2 = BonehBoyenGoh::encrypt(pk, &leaf, &m, &coins(&m, &usk.id, ct.epoch));
+
+        // Can save some time by not doing a constant-time comparison
+        // since we can leak whether the decapsulation succeeds/fails.
+        if ct.ct.ct_eq(&ct2).into() {
+            Ok(SharedSecret::from(&m))
+        } else {
+            Err(Error)
+        }
+    }
+}
+
+impl<const D: usize> UserSecretKey<D> {
+    /// Size of a compressed node of the time tree in bytes.
+    pub const NODE_BYTES: usize = 8 + 1 + <BonehBoyenGoh<D> as IBE>::USK_BYTES;
+}
+
+impl<const D: usize> Compress for UserSecretKey<D> {
+    /// The size of a user secret key depends on its number of nodes, this is the size of a user
+    /// secret key without nodes. Every node adds [`NODE_BYTES`](Self::NODE_BYTES), up to
+    /// [`BBGFS::USK_BYTES`](FSKEM::USK_BYTES) in total.
+    const OUTPUT_SIZE: usize = ID_BYTES + 8;
+    type Output = Vec<u8>;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut res = Vec::with_capacity(ID_BYTES + 8 + self.nodes.len() * Self::NODE_BYTES);
+
+        res.extend_from_slice(&self.id.0);
+        res.extend_from_slice(&self.epoch.to_be_bytes());
+        for node in self.nodes.iter() {
+            res.extend_from_slice(&node.prefix.to_be_bytes());
+            res.push(node.len);
+            res.extend_from_slice(&node.usk.to_bytes());
+        }
+
+        res
+    }
+
+    fn from_bytes(bytes: &Vec<u8>) -> CtOption<Self> {
+        let mut res = UserSecretKey {
+            id: Identity::default(),
+            epoch: 0,
+            nodes: Vec::new(),
+        };
+
+        if bytes.len() < ID_BYTES + 8
+            || !(bytes.len() - ID_BYTES - 8).is_multiple_of(Self::NODE_BYTES)
+        {
+            return CtOption::new(res, Choice::from(0));
+        }
+
+        let (id, rest) = bytes.split_at(ID_BYTES);
+        let (epoch, nodes) = rest.split_at(8);
+
+        res.id = Identity(id.try_into().unwrap());
+        res.epoch = u64::from_be_bytes(epoch.try_into().unwrap());
+
+        for chunk in nodes.chunks_exact(Self::NODE_BYTES) {
+            let prefix = u64::from_be_bytes(chunk[..8].try_into().unwrap());
+            let len = chunk[8];
+
+            if len > Node::<D>::LEVELS || prefix >> len != 0 {
+                return CtOption::new(res, Choice::from(0));
+            }
+
+            // Nodes that only cover earlier epochs must have been erased by an update.
+            let node_start = prefix << (Node::<D>::LEVELS - len);
+            if node_start < res.epoch {
+                return CtOption::new(res, Choice::from(0));
+            }
+
+            let usk = crate::ibe::boneh_boyen_goh::UserSecretKey::from_bytes(&chunk[9..].to_vec());
+            match Option::from(usk) {
+                Some(usk) => res.nodes.push(Node { prefix, len, usk }),
+                None => return CtOption::new(res, Choice::from(0)),
+            }
+        }
+
+        CtOption::new(res, Choice::from(1))
+    }
+}
+
+impl Compress for CipherText {
+    const OUTPUT_SIZE: usize = CT_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; CT_BYTES] {
+        let mut res = [0u8; CT_BYTES];
+        let (ct, epoch) = mut_array_refs![&mut res, CT_BYTES - 8, 8];
+
+        *ct = self.ct.to_bytes();
+        *epoch = self.epoch.to_be_bytes();
+
+        res
+    }
+
+    fn from_bytes(bytes: &[u8; CT_BYTES]) -> CtOption<Self> {
+        let (ct, epoch) = array_refs![bytes, CT_BYTES - 8, 8];
+        let epoch = u64::from_be_bytes(*epoch);
+
+        crate::ibe::boneh_boyen_goh::CipherText::from_bytes(ct).map(|ct| CipherText { ct, epoch })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Scheme = BBGFS<4>;
+
+    const ALICE: &str = "email:alice@example.com";
+
+    #[test]
+    fn eq_encaps_decaps() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = Scheme::setup(&mut rng);
+
+        let alice = Identity::derive_str(ALICE);
+        let mut usk = Scheme::extract_usk(None, &sk, &alice, &mut rng);
+
+        for epoch in [0, 5, Scheme::EPOCHS - 1] {
+            let (ct, k) = Scheme::encaps(&pk, &alice, epoch, &mut rng).unwrap();
+            Scheme::update(&pk, &mut usk, epoch, &mut rng).unwrap();
+
+            assert_eq!(Scheme::decaps(Some(&pk), &usk, &ct).unwrap(), k);
+        }
+    }
+
+    #[test]
+    fn update_erases_earlier_epochs() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = Scheme::setup(&mut rng);
+
+        let alice = Identity::derive_str(ALICE);
+        let mut usk = Scheme::extract_usk(None, &sk, &alice, &mut rng);
+
+        let cts: Vec<_> = (0..Scheme::EPOCHS)
+            .map(|epoch| Scheme::encaps(&pk, &alice, epoch, &mut rng).unwrap())
+            .collect();
+
+        for epoch in [1, 2, 5, 7] {
+            Scheme::update(&pk, &mut usk, epoch, &mut rng).unwrap();
+            assert_eq!(Scheme::epoch(&usk), epoch);
+
+            for (i, (ct, k)) in cts.iter().enumerate() {
+                let res = Scheme::decaps(Some(&pk), &usk, ct);
+                if (i as u64) == epoch {
+                    assert_eq!(res.unwrap(), *k);
+                } else {
+                    assert!(res.is_err());
+                }
+            }
+
+            // The erased keys can not be recovered from the serialized key either.
+            assert!(usk.nodes.iter().all(|node| node.start() >= epoch));
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_epochs() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = Scheme::setup(&mut rng);
+
+        let alice = Identity::derive_str(ALICE);
+        let mut usk = Scheme::extract_usk(None, &sk, &alice, &mut rng);
+
+        assert!(Scheme::encaps(&pk, &alice, Scheme::EPOCHS, &mut rng).is_err());
+        assert!(Scheme::update(&pk, &mut usk, Scheme::EPOCHS, &mut rng).is_err());
+
+        Scheme::update(&pk, &mut usk, 3, &mut rng).unwrap();
+        assert!(Scheme::update(&pk, &mut usk, 2, &mut rng).is_err());
+        assert_eq!(Scheme::epoch(&usk), 3);
+    }
+
+    #[test]
+    fn eq_serialize_deserialize() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = Scheme::setup(&mut rng);
+
+        let alice = Identity::derive_str(ALICE);
+        let mut usk = Scheme::extract_usk(None, &sk, &alice, &mut rng);
+        assert_eq!(usk.to_bytes().len(), Scheme::USK_BYTES);
+
+        Scheme::update(&pk, &mut usk, 3, &mut rng).unwrap();
+        let (ct, _) = Scheme::encaps(&pk, &alice, 3, &mut rng).unwrap();
+
+        assert_eq!(usk, UserSecretKey::from_bytes(&usk.to_bytes()).unwrap());
+        assert_eq!(ct, CipherText::from_bytes(&ct.to_bytes()).unwrap());
+
+        // A key for the first epoch that claims to be for a later epoch still holds the nodes of
+        // the earlier epochs.
+        let mut bytes = Scheme::extract_usk(None, &sk, &alice, &mut rng).to_bytes();
+        bytes[ID_BYTES..ID_BYTES + 8].copy_from_slice(&3u64.to_be_bytes());
+        assert!(bool::from(UserSecretKey::<4>::from_bytes(&bytes).is_none()));
+
+        let mut bytes = ct.to_bytes();
+        bytes[..G1_BYTES].copy_from_slice(&NON_SUBGROUP_G1_COMPRESSED);
+        assert!(bool::from(CipherText::from_bytes(&bytes).is_none()));
+    }
+}
@@ -0,0 +1,211 @@
+//! Identity-based signcryption KEM by Barreto, Libert, McCullagh and Quisquater (BLMQ).
+//! * From: "[Efficient and Provably-Secure Identity-Based Signatures and Signcryption from Bilinear Maps](https://eprint.iacr.org/2005/027.pdf)"
+//! * Published in: ASIACRYPT, 2005
+//!
+//! The scheme uses the exponent inversion keys of [SK-KEM](crate::kem::sakai_kasahara), such that
+//! a PKG can issue a single user secret key that is used to decapsulate, signcrypt and
+//! unsigncrypt. Instead of encrypting a message, the random target group element of the
+//! signcryption is used as the shared secret.
+//!
+//! A signcrypted ciphertext consists of a G1 and a G2 element, which is shorter than an SK-KEM
+//! ciphertext together with a separate identity-based signature.
+
+use crate::kem::{AuthenticationError, SharedSecret, Signcryption};
+use crate::util::*;
+use crate::Compress;
+use arrayref::{array_refs, mut_array_refs};
+use group::Group;
+use pg_curve::{pairing, G1Affine, G2Affine, Gt, Scalar};
+use rand::{CryptoRng, Rng};
+use subtle::CtOption;
+
+/// These structs are identical for signcryption.
+pub use crate::kem::sakai_kasahara::{PublicKey, SecretKey, UserSecretKey, SKKEM};
+
+/// Size of the compressed signcrypted ciphertext in bytes.
+pub const SIGNCRYPTION_CT_BYTES: usize = G1_BYTES + G2_BYTES;
+
+/// Signcrypted ciphertext. Can only be decapsulated with the user secret key of the recipient, and
+/// is authenticated as coming from the sender.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub struct SigncryptionCipherText {
+    t: G1Affine,
+    s: G2Affine,
+}
+
+/// Computes T = x * (id * P1 + Z).
+fn encode(pk: &PublicKey, id: &Identity, x: &Scalar) -> G1Affine {
+    ((G1Affine::generator() * (id.to_scalar() * x)) + (pk.z * x)).into()
+}
+
+/// Computes the challenge h = H(r, T, sender, recipient).
+fn challenge(r: &Gt, t: &G1Affine, sender: &Identity, recipient: &Identity) -> Scalar {
+    let mut pre = [0u8; GT_BYTES + G1_BYTES + 2 * ID_BYTES];
+    let (pr, pt, ps, pi) = mut_array_refs![&mut pre, GT_BYTES, G1_BYTES, ID_BYTES, ID_BYTES];
+
+    *pr = r.to_compressed();
+    *pt = t.to_compressed();
+    *ps = sender.0;
+    *pi = recipient.0;
+
+    Scalar::from_bytes_wide(&sha3_512(&pre))
+}
+
+impl Signcryption for SKKEM {
+    type SigncryptionCt = SigncryptionCipherText;
+
+    const SIGNCRYPTION_CT_BYTES: usize = SIGNCRYPTION_CT_BYTES;
+
+    /// Signcrypt a shared secret, such that T = x * (id * P1 + Z) and S = (x + h) * USK_{sender}.
+    fn signcrypt<R: Rng + CryptoRng>(
+        pk: &PublicKey,
+        usk: &UserSecretKey,
+        id: &Identity,
+        rng: &mut R,
+    ) -> (SigncryptionCipherText, SharedSecret) {
+        let x = rand_scalar(rng);
+
+        let r = Gt::generator() * x;
+        let t = encode(pk, id, &x);
+        let h = challenge(&r, &t, &usk.id, id);
+        let s = (usk.d * (x + h)).into();
+
+        (SigncryptionCipherText { t, s }, SharedSecret::from(&r))
+    }
+
+    /// Unsigncrypt a shared secret, by computing r = e(T, USK_{id}) and checking that
+    /// r = e(sender * P1 + Z, S) / g^h.
+    fn unsigncrypt(
+        pk: &PublicKey,
+        usk: &UserSecretKey,
+        sender: &Identity,
+        ct: &SigncryptionCipherText,
+    ) -> Result<SharedSecret, AuthenticationError> {
+        let r = pairing(&ct.t, &usk.d);
+        let h = challenge(&r, &ct.t, sender, &usk.id);
+
+        let q = encode(pk, sender, &Scalar::one());
+        let expected = pairing(&q, &ct.s) - Gt::generator() * h;
+
+        // Can save some time by not doing a constant-time comparison
+        // since we can leak whether the authentication succeeds/fails.
+        if r == expected {
+            Ok(SharedSecret::from(&r))
+        } else {
+            Err(AuthenticationError)
+        }
+    }
+}
+
+impl Compress for SigncryptionCipherText {
+    const OUTPUT_SIZE: usize = SIGNCRYPTION_CT_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; SIGNCRYPTION_CT_BYTES] {
+        let mut res = [0u8; SIGNCRYPTION_CT_BYTES];
+        let (t, s) = mut_array_refs![&mut res, G1_BYTES, G2_BYTES];
+
+        *t = self.t.to_compressed();
+        *s = self.s.to_compressed();
+
+        res
+    }
+
+    fn from_bytes(bytes: &[u8; SIGNCRYPTION_CT_BYTES]) -> CtOption<Self> {
+        let (t, s) = array_refs![bytes, G1_BYTES, G2_BYTES];
+
+        // Use the checked `from_compressed` variant, which verifies that each point is in the
+        // correct prime-order subgroup (GHSA-25fp-2fjj-g84w).
+        let t = G1Affine::from_compressed(t);
+        let s = G2Affine::from_compressed(s);
+
+        t.and_then(|t| s.map(|s| SigncryptionCipherText { t, s }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kem::IBKEM;
+    use crate::Derive;
+
+    const ALICE: &str = "email:alice@example.com";
+    const BOB: &str = "email:bob@example.com";
+    const CHARLIE: &str = "email:charlie@example.com";
+
+    #[test]
+    fn eq_signcrypt_unsigncrypt() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = SKKEM::setup(&mut rng);
+
+        let (alice, bob) = (Identity::derive_str(ALICE), Identity::derive_str(BOB));
+        let usk_alice = SKKEM::extract_usk(None, &sk, &alice, &mut rng);
+        let usk_bob = SKKEM::extract_usk(None, &sk, &bob, &mut rng);
+
+        let (ct, k) = SKKEM::signcrypt(&pk, &usk_alice, &bob, &mut rng);
+        let k2 = SKKEM::unsigncrypt(&pk, &usk_bob, &alice, &ct).unwrap();
+
+        assert_eq!(k, k2);
+    }
+
+    #[test]
+    fn unsigncrypt_rejects_wrong_sender_or_recipient() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = SKKEM::setup(&mut rng);
+
+        let alice = Identity::derive_str(ALICE);
+        let bob = Identity::derive_str(BOB);
+        let charlie = Identity::derive_str(CHARLIE);
+        let usk_alice = SKKEM::extract_usk(None, &sk, &alice, &mut rng);
+        let usk_bob = SKKEM::extract_usk(None, &sk, &bob, &mut rng);
+        let usk_charlie = SKKEM::extract_usk(None, &sk, &charlie, &mut rng);
+
+        let (ct, _) = SKKEM::signcrypt(&pk, &usk_alice, &bob, &mut rng);
+
+        assert_eq!(
+            SKKEM::unsigncrypt(&pk, &usk_bob, &charlie, &ct),
+            Err(AuthenticationError)
+        );
+        assert_eq!(
+            SKKEM::unsigncrypt(&pk, &usk_charlie, &alice, &ct),
+            Err(AuthenticationError)
+        );
+    }
+
+    #[test]
+    fn unsigncrypt_rejects_mixed_ciphertexts() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = SKKEM::setup(&mut rng);
+
+        let (alice, bob) = (Identity::derive_str(ALICE), Identity::derive_str(BOB));
+        let usk_alice = SKKEM::extract_usk(None, &sk, &alice, &mut rng);
+        let usk_bob = SKKEM::extract_usk(None, &sk, &bob, &mut rng);
+
+        let (ct1, _) = SKKEM::signcrypt(&pk, &usk_alice, &bob, &mut rng);
+        let (ct2, _) = SKKEM::signcrypt(&pk, &usk_alice, &bob, &mut rng);
+        let mixed = SigncryptionCipherText { t: ct1.t, s: ct2.s };
+
+        assert!(SKKEM::unsigncrypt(&pk, &usk_bob, &alice, &mixed).is_err());
+    }
+
+    #[test]
+    fn eq_serialize_deserialize() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = SKKEM::setup(&mut rng);
+
+        let (alice, bob) = (Identity::derive_str(ALICE), Identity::derive_str(BOB));
+        let usk_alice = SKKEM::extract_usk(None, &sk, &alice, &mut rng);
+        let (ct, _) = SKKEM::signcrypt(&pk, &usk_alice, &bob, &mut rng);
+
+        assert_eq!(
+            ct,
+            SigncryptionCipherText::from_bytes(&ct.to_bytes()).unwrap()
+        );
+
+        let mut bytes = ct.to_bytes();
+        bytes[..G1_BYTES].copy_from_slice(&NON_SUBGROUP_G1_COMPRESSED);
+        assert!(bool::from(
+            SigncryptionCipherText::from_bytes(&bytes).is_none()
+        ));
+    }
+}
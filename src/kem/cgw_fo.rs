@@ -16,6 +16,8 @@ use crate::util::*;
 use crate::Compress;
 use arrayref::{array_refs, mut_array_refs};
 use group::Group;
+#[cfg(feature = "threshold")]
+use pg_curve::{Gt, Scalar};
 use rand::{CryptoRng, Rng};
 use subtle::{ConstantTimeEq, CtOption};
 
@@ -36,7 +38,7 @@ pub const USK_BYTES: usize = CPA_USK_BYTES + ID_BYTES;
 /// `ZeroizeOnDrop` (it is `Copy`). Secret material is **not** cleared on drop —
 /// you **MUST** call `.zeroize()` explicitly once done. See the
 /// [crate-level docs](crate#zeroizing-secret-material).
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
 #[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize))]
 pub struct UserSecretKey {
     usk: crate::ibe::cgw::UserSecretKey,
@@ -138,27 +140,76 @@ impl IBKEM for CGWFO {
 
         let m = CGW::decrypt(&usk.usk, c);
 
-        let mut pre_coins = [0u8; MSG_BYTES + ID_BYTES];
-        pre_coins[..MSG_BYTES].copy_from_slice(&m.to_bytes());
-        pre_coins[MSG_BYTES..].copy_from_slice(&usk.id.0);
-
-        let coins = sha3_512(&pre_coins);
-
-        let c2 = CGW::encrypt(pk, &usk.id, &m, &coins);
+        reencrypt(pk, &usk.id, c, &m)
+    }
+}
 
-        // Can save some time by not doing a constant-time comparison
-        // since we can leak whether the decapsulation succeeds/fails.
-        if c.ct_eq(&c2).into() {
-            Ok(SharedSecret::from(&m))
-        } else {
-            Err(Error)
-        }
+/// Re-encrypts the decrypted message and checks that it results in the same ciphertext.
+fn reencrypt(
+    pk: &PublicKey,
+    id: &Identity,
+    c: &CipherText,
+    m: &Msg,
+) -> Result<SharedSecret, Error> {
+    let mut pre_coins = [0u8; MSG_BYTES + ID_BYTES];
+    pre_coins[..MSG_BYTES].copy_from_slice(&m.to_bytes());
+    pre_coins[MSG_BYTES..].copy_from_slice(&id.0);
+
+    let coins = sha3_512(&pre_coins);
+
+    let c2 = CGW::encrypt(pk, id, m, &coins);
+
+    // Can save some time by not doing a constant-time comparison
+    // since we can leak whether the decapsulation succeeds/fails.
+    if c.ct_eq(&c2).into() {
+        Ok(SharedSecret::from(m))
+    } else {
+        Err(Error)
     }
 }
 
 #[cfg(feature = "mkem")]
 impl crate::kem::mkem::MultiRecipient for CGWFO {}
 
+#[cfg(feature = "threshold")]
+impl crate::kem::threshold::ThresholdDecaps for CGWFO {
+    fn random_usk<R: Rng + CryptoRng>(rng: &mut R) -> UserSecretKey {
+        UserSecretKey {
+            usk: crate::ibe::cgw::UserSecretKey(crate::pe::cgw::UserSecretKey::random(rng)),
+            id: Identity::default(),
+        }
+    }
+
+    /// The identity of the result is taken from `other`.
+    fn usk_mul_add(usk: &UserSecretKey, x: &Scalar, other: &UserSecretKey) -> UserSecretKey {
+        UserSecretKey {
+            usk: crate::ibe::cgw::UserSecretKey(usk.usk.0.mul_add(x, &other.usk.0)),
+            id: other.id,
+        }
+    }
+
+    fn decaps_linear(usk: &UserSecretKey, c: &CipherText) -> Gt {
+        crate::ibe::cgw::unblinding(&usk.usk, c)
+    }
+
+    /// # Panics
+    ///
+    /// This scheme **does** requires the master public key due to usage the Fujisaki-Okamoto transform.
+    /// This function panics if no master public key is provided.
+    fn decaps_finish(
+        opk: Option<&PublicKey>,
+        id: &Identity,
+        c: &CipherText,
+        k: &Gt,
+    ) -> Result<SharedSecret, Error> {
+        let pk = opk.unwrap();
+
+        let m = c.cprime + k;
+
+        reencrypt(pk, id, c, &m)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -168,4 +219,7 @@ mod tests {
 
     #[cfg(feature = "mkem")]
     test_multi_kem!(CGWFO);
+
+    #[cfg(feature = "threshold")]
+    test_threshold_kem!(CGWFO);
 }
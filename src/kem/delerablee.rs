@@ -0,0 +1,501 @@
+//! IND-sID-CCA2 secure identity-based broadcast KEM by a scheme by Delerablée.
+//! * From: "[Identity-Based Broadcast Encryption with Constant Size Ciphertexts and Private Keys](https://link.springer.com/chapter/10.1007/978-3-540-76900-2_12)"
+//!
+//! CCA security due to a general approach by Fujisaki and Okamoto.
+//! * From: "[A Modular Analysis of the Fujisaki-Okamoto Transformation](https://eprint.iacr.org/2017/604.pdf)"
+//!
+//! A single ciphertext encapsulates a shared secret for a set of up to `M` identities. The size
+//! of the ciphertext and the user secret keys is constant, while the master public key grows
+//! linearly in `M`. Both encapsulation and decapsulation take time quadratic in the number of
+//! recipients. The set of recipients is required to decapsulate and must be transmitted
+//! alongside the ciphertext.
+//!
+//! The symmetric primitive G is instantiated using sha3_512. All structs' byte serialization use
+//! compression.
+//!
+//! A drawback of a Fujisaki-Okamoto transform is that we now need the public key to decapsulate.
+
+extern crate alloc;
+use alloc::vec::Vec;
+
+use crate::kem::{Error, SharedSecret, IBBKEM};
+use crate::util::*;
+use crate::Compress;
+use arrayref::{array_refs, mut_array_refs};
+use pg_curve::{
+    multi_miller_loop, pairing, G1Affine, G1Projective, G2Affine, G2Prepared, G2Projective, Gt,
+    Scalar,
+};
+use rand::{CryptoRng, Rng};
+use subtle::{Choice, ConstantTimeEq, CtOption};
+
+/// Size of the compressed master public key in bytes, without the powers of `h`.
+pub const PK_BASE_BYTES: usize = G1_BYTES + GT_BYTES;
+
+/// Size of the compressed master secret key in bytes.
+pub const SK_BYTES: usize = G1_BYTES + SCALAR_BYTES;
+
+/// Size of the compressed user secret key in bytes.
+///
+/// The USK includes the identity (needed to find its position in the set of recipients).
+pub const USK_BYTES: usize = ID_BYTES + G1_BYTES;
+
+/// Size of the compressed ciphertext in bytes.
+pub const CT_BYTES: usize = G1_BYTES + G2_BYTES + GT_BYTES;
+
+/// Public key parameters generated by the PKG used to encapsulate shared secrets.
+/// Also known as MPK.
+///
+/// Contains `h^(γ^i)` for `0 ≤ i ≤ M`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PublicKey<const M: usize> {
+    w: G1Affine,
+    v: Gt,
+    hs: Vec<G2Affine>,
+}
+
+/// Secret key parameter generated by the PKG used to extract user secret keys.
+/// Also known as MSK.
+///
+/// # Zeroization
+///
+/// With the `zeroize` feature enabled this type derives `Zeroize` but **not**
+/// `ZeroizeOnDrop` (it is `Copy`). Secret material is **not** cleared on drop —
+/// you **MUST** call `.zeroize()` explicitly once done. See the
+/// [crate-level docs](crate#zeroizing-secret-material).
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize))]
+pub struct SecretKey {
+    g: G1Affine,
+    gamma: Scalar,
+}
+
+/// User secret key. Can be used to decapsulate ciphertexts for sets that contain its identity.
+/// Also known as USK_{id}.
+///
+/// # Zeroization
+///
+/// With the `zeroize` feature enabled this type derives `Zeroize` but **not**
+/// `ZeroizeOnDrop` (it is `Copy`). Secret material is **not** cleared on drop —
+/// you **MUST** call `.zeroize()` explicitly once done. See the
+/// [crate-level docs](crate#zeroizing-secret-material).
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize))]
+pub struct UserSecretKey {
+    id: Identity,
+    d: G1Affine,
+}
+
+/// Encapsulated shared secret for a set of identities.
+///
+/// The ciphertext does not include the set of recipients.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct CipherText {
+    c1: G1Affine,
+    c2: G2Affine,
+    cprime: Gt,
+}
+
+impl ConstantTimeEq for CipherText {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.c1.ct_eq(&other.c1) & self.c2.ct_eq(&other.c2) & self.cprime.ct_eq(&other.cprime)
+    }
+}
+
+/// The CCA2 secure identity-based broadcast KEM that results by applying the implicit rejection
+/// variant of the Fujisaki-Okamoto transform to the Delerablée IBBE scheme, for at most `M`
+/// recipients per ciphertext.
+#[derive(Debug, Clone, Copy)]
+pub struct Delerablee<const M: usize>;
+
+/// Computes the coefficients of `(X + h_1) ... (X + h_n)`, starting at the constant term.
+fn poly_from_roots<'a>(hs: impl Iterator<Item = &'a Scalar>) -> Vec<Scalar> {
+    let mut coeffs = alloc::vec![Scalar::one()];
+
+    for h in hs {
+        coeffs.push(Scalar::zero());
+        for t in (0..coeffs.len()).rev() {
+            let prev = if t > 0 { coeffs[t - 1] } else { Scalar::zero() };
+            coeffs[t] = coeffs[t] * h + prev;
+        }
+    }
+
+    coeffs
+}
+
+fn hash_ids(ids: &[Identity]) -> Vec<Scalar> {
+    ids.iter().map(|id| id.to_scalar()).collect()
+}
+
+fn encrypt<const M: usize>(
+    pk: &PublicKey<M>,
+    ids: &[Identity],
+    m: &Gt,
+    coins: &[u8; 64],
+) -> CipherText {
+    let k = Scalar::from_bytes_wide(coins);
+    let coeffs = poly_from_roots(hash_ids(ids).iter());
+
+    let c2 = coeffs
+        .iter()
+        .zip(pk.hs.iter())
+        .fold(G2Projective::identity(), |acc, (c, h)| acc + h * (c * k));
+
+    CipherText {
+        c1: (-(pk.w * k)).into(),
+        c2: c2.into(),
+        cprime: pk.v * k + m,
+    }
+}
+
+fn decrypt<const M: usize>(
+    pk: &PublicKey<M>,
+    usk: &UserSecretKey,
+    ids: &[Identity],
+    ct: &CipherText,
+) -> Option<Gt> {
+    let i = ids.iter().position(|id| *id == usk.id)?;
+
+    let hs = hash_ids(ids);
+    let coeffs = poly_from_roots(hs[..i].iter().chain(hs[i + 1..].iter()));
+
+    // Evaluates (p(γ) - p(0)) / γ in the exponent of h, where p(X) = Π_{j ≠ i} (X + h_j).
+    let hp = coeffs[1..]
+        .iter()
+        .zip(pk.hs.iter())
+        .fold(G2Projective::identity(), |acc, (c, h)| acc + h * c);
+    let inv: Option<Scalar> = coeffs[0].invert().into();
+
+    let k = multi_miller_loop(&[
+        (&ct.c1, &G2Prepared::from(G2Affine::from(hp))),
+        (&usk.d, &G2Prepared::from(ct.c2)),
+    ])
+    .final_exponentiation();
+
+    Some(ct.cprime - k * inv?)
+}
+
+fn coins(m: &Gt, ids: &[Identity]) -> [u8; 64] {
+    let mut pre_coins = Vec::with_capacity(GT_BYTES + ids.len() * ID_BYTES);
+    pre_coins.extend_from_slice(&m.to_compressed());
+    for id in ids {
+        pre_coins.extend_from_slice(&id.0);
+    }
+
+    sha3_512(&pre_coins)
+}
+
+impl<const M: usize> IBBKEM for Delerablee<M> {
+    const IDENTIFIER: &'static str = "delerablee";
+
+    type Pk = PublicKey<M>;
+    type Sk = SecretKey;
+    type Usk = UserSecretKey;
+    type Ct = CipherText;
+    type Id = Identity;
+
+    const PK_BYTES: usize = PK_BASE_BYTES + (M + 1) * G2_BYTES;
+    const SK_BYTES: usize = SK_BYTES;
+    const USK_BYTES: usize = USK_BYTES;
+    const CT_BYTES: usize = CT_BYTES;
+
+    const MAX_RECIPIENTS: usize = M;
+
+    fn setup<R: Rng + CryptoRng>(rng: &mut R) -> (PublicKey<M>, SecretKey) {
+        let g: G1Affine = (G1Projective::generator() * rand_scalar(rng)).into();
+        let h = G2Projective::generator() * rand_scalar(rng);
+        let gamma = rand_scalar(rng);
+
+        let mut powers = Vec::with_capacity(M + 1);
+        powers.push(h);
+        for i in 0..M {
+            powers.push(powers[i] * gamma);
+        }
+
+        let mut hs = alloc::vec![G2Affine::default(); M + 1];
+        G2Projective::batch_normalize(&powers, &mut hs);
+
+        let pk = PublicKey {
+            w: (g * gamma).into(),
+            v: pairing(&g, &hs[0]),
+            hs,
+        };
+
+        (pk, SecretKey { g, gamma })
+    }
+
+    fn extract_usk<R: Rng + CryptoRng>(
+        _pk: Option<&PublicKey<M>>,
+        sk: &SecretKey,
+        id: &Identity,
+        _rng: &mut R,
+    ) -> UserSecretKey {
+        // The denominator is zero with negligible probability.
+        let inv = (sk.gamma + id.to_scalar()).invert().unwrap();
+
+        UserSecretKey {
+            id: *id,
+            d: (sk.g * inv).into(),
+        }
+    }
+
+    fn encaps<R: Rng + CryptoRng>(
+        pk: &PublicKey<M>,
+        ids: &[Identity],
+        rng: &mut R,
+    ) -> Result<(CipherText, SharedSecret), Error> {
+        if ids.is_empty() || ids.len() > M {
+            return Err(Error);
+        }
+
+        let m = rand_gt(rng);
+        let ct = encrypt(pk, ids, &m, &coins(&m, ids));
+
+        Ok((ct, SharedSecret::from(&m)))
+    }
+
+    /// Decapsulate a shared secret from the ciphertext.
+    ///
+    /// # Panics
+    ///
+    /// This scheme **does** requires the master public key due to usage the Fujisaki-Okamoto transform.
+    /// This function panics if no master public key is provided.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an [`Error`] when the identity of the user secret key is not in the
+    /// set of recipients, or when an illegitimate ciphertext is encountered (explicit rejection).
+    fn decaps(
+        opk: Option<&PublicKey<M>>,
+        usk: &UserSecretKey,
+        ids: &[Identity],
+        ct: &CipherText,
+    ) -> Result<SharedSecret, Error> {
+        let pk = opk.unwrap();
+
+        if ids.len() > M {
+            return Err(Error);
+        }
+
+        let m = decrypt(pk, usk, ids, ct).ok_or(Error)?;
+        let ct2 = encrypt(pk, ids, &m, &coins(&m, ids));
+
+        // Can save some time by not doing a constant-time comparison
+        // since we can leak whether the decapsulation succeeds/fails.
+        if ct.ct_eq(&ct2).into() {
+            Ok(SharedSecret::from(&m))
+        } else {
+            Err(Error)
+        }
+    }
+}
+
+impl<const M: usize> Compress for PublicKey<M> {
+    /// The size of a public key depends on the maximum number of recipients `M`, this is the size
+    /// of a public key without the powers of `h`. The full size is
+    /// [`Delerablee::PK_BYTES`](IBBKEM::PK_BYTES).
+    const OUTPUT_SIZE: usize = PK_BASE_BYTES;
+    type Output = Vec<u8>;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut res = Vec::with_capacity(PK_BASE_BYTES + self.hs.len() * G2_BYTES);
+
+        res.extend_from_slice(&self.w.to_compressed());
+        res.extend_from_slice(&self.v.to_compressed());
+        for h in self.hs.iter() {
+            res.extend_from_slice(&h.to_compressed());
+        }
+
+        res
+    }
+
+    fn from_bytes(bytes: &Vec<u8>) -> CtOption<Self> {
+        let mut res = PublicKey {
+            w: G1Affine::default(),
+            v: Gt::default(),
+            hs: alloc::vec![G2Affine::default(); M + 1],
+        };
+
+        if bytes.len() != PK_BASE_BYTES + (M + 1) * G2_BYTES {
+            return CtOption::new(res, Choice::from(0));
+        }
+
+        let (w, rest) = bytes.split_at(G1_BYTES);
+        let (v, rest) = rest.split_at(GT_BYTES);
+
+        let mut is_some = G1Affine::from_compressed(w.try_into().unwrap())
+            .map(|el| res.w = el)
+            .is_some();
+        is_some &= Gt::from_compressed(v.try_into().unwrap())
+            .map(|el| res.v = el)
+            .is_some();
+        for (h, chunk) in res.hs.iter_mut().zip(rest.chunks_exact(G2_BYTES)) {
+            is_some &= G2Affine::from_compressed(chunk.try_into().unwrap())
+                .map(|el| *h = el)
+                .is_some();
+        }
+
+        CtOption::new(res, is_some)
+    }
+}
+
+impl Compress for SecretKey {
+    const OUTPUT_SIZE: usize = SK_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; SK_BYTES] {
+        let mut buf = [0u8; SK_BYTES];
+        let (g, gamma) = mut_array_refs![&mut buf, G1_BYTES, SCALAR_BYTES];
+
+        *g = self.g.to_compressed();
+        *gamma = self.gamma.to_bytes();
+
+        buf
+    }
+
+    fn from_bytes(bytes: &[u8; SK_BYTES]) -> CtOption<Self> {
+        let (g, gamma) = array_refs![bytes, G1_BYTES, SCALAR_BYTES];
+
+        let g = G1Affine::from_compressed(g);
+        let gamma = Scalar::from_bytes(gamma);
+
+        g.and_then(|g| gamma.map(|gamma| SecretKey { g, gamma }))
+    }
+}
+
+impl Compress for UserSecretKey {
+    const OUTPUT_SIZE: usize = USK_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; USK_BYTES] {
+        let mut buf = [0u8; USK_BYTES];
+        let (id, d) = mut_array_refs![&mut buf, ID_BYTES, G1_BYTES];
+
+        *id = self.id.0;
+        *d = self.d.to_compressed();
+
+        buf
+    }
+
+    fn from_bytes(bytes: &[u8; USK_BYTES]) -> CtOption<Self> {
+        let (id, d) = array_refs![bytes, ID_BYTES, G1_BYTES];
+
+        G1Affine::from_compressed(d).map(|d| UserSecretKey {
+            id: Identity(*id),
+            d,
+        })
+    }
+}
+
+impl Compress for CipherText {
+    const OUTPUT_SIZE: usize = CT_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; CT_BYTES] {
+        let mut buf = [0u8; CT_BYTES];
+        let (c1, c2, cprime) = mut_array_refs![&mut buf, G1_BYTES, G2_BYTES, GT_BYTES];
+
+        *c1 = self.c1.to_compressed();
+        *c2 = self.c2.to_compressed();
+        *cprime = self.cprime.to_compressed();
+
+        buf
+    }
+
+    fn from_bytes(bytes: &[u8; CT_BYTES]) -> CtOption<Self> {
+        let (c1, c2, cprime) = array_refs![bytes, G1_BYTES, G2_BYTES, GT_BYTES];
+
+        let c1 = G1Affine::from_compressed(c1);
+        let c2 = G2Affine::from_compressed(c2);
+        let cprime = Gt::from_compressed(cprime);
+
+        c1.and_then(|c1| c2.and_then(|c2| cprime.map(|cprime| CipherText { c1, c2, cprime })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Derive;
+
+    type Scheme = Delerablee<8>;
+
+    fn ids(n: usize) -> Vec<Identity> {
+        (0..n)
+            .map(|i| Identity::derive(&(i as u32).to_be_bytes()))
+            .collect()
+    }
+
+    #[test]
+    fn eq_encaps_decaps_for_all_recipients() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = Scheme::setup(&mut rng);
+
+        for n in [1, 2, 5, 8] {
+            let ids = ids(n);
+            let (ct, k) = Scheme::encaps(&pk, &ids, &mut rng).unwrap();
+
+            for id in ids.iter() {
+                let usk = Scheme::extract_usk(None, &sk, id, &mut rng);
+                assert_eq!(Scheme::decaps(Some(&pk), &usk, &ids, &ct).unwrap(), k);
+            }
+        }
+    }
+
+    #[test]
+    fn decaps_rejects_non_recipient() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = Scheme::setup(&mut rng);
+
+        let ids = ids(4);
+        let other = Identity::derive_str("mallory@example.com");
+        let (ct, _) = Scheme::encaps(&pk, &ids, &mut rng).unwrap();
+        let usk = Scheme::extract_usk(None, &sk, &other, &mut rng);
+
+        assert!(Scheme::decaps(Some(&pk), &usk, &ids, &ct).is_err());
+
+        // Claiming to be part of the set does not help either.
+        let mut claimed = ids.clone();
+        claimed[0] = other;
+        assert!(Scheme::decaps(Some(&pk), &usk, &claimed, &ct).is_err());
+    }
+
+    #[test]
+    fn encaps_rejects_invalid_number_of_recipients() {
+        let mut rng = rand::thread_rng();
+        let (pk, _) = Scheme::setup(&mut rng);
+
+        assert!(Scheme::encaps(&pk, &[], &mut rng).is_err());
+        assert!(Scheme::encaps(&pk, &ids(9), &mut rng).is_err());
+    }
+
+    #[test]
+    fn eq_serialize_deserialize() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = Scheme::setup(&mut rng);
+        let ids = ids(3);
+        let usk = Scheme::extract_usk(None, &sk, &ids[0], &mut rng);
+        let (ct, _) = Scheme::encaps(&pk, &ids, &mut rng).unwrap();
+
+        assert_eq!(pk.to_bytes().len(), Scheme::PK_BYTES);
+        assert_eq!(pk, PublicKey::from_bytes(&pk.to_bytes()).unwrap());
+        assert_eq!(sk, SecretKey::from_bytes(&sk.to_bytes()).unwrap());
+        assert_eq!(usk, UserSecretKey::from_bytes(&usk.to_bytes()).unwrap());
+        assert_eq!(ct, CipherText::from_bytes(&ct.to_bytes()).unwrap());
+
+        let mut bytes = pk.to_bytes();
+        bytes.pop();
+        assert!(bool::from(PublicKey::<8>::from_bytes(&bytes).is_none()));
+    }
+
+    #[test]
+    fn from_bytes_rejects_non_subgroup_point() {
+        let mut rng = rand::thread_rng();
+        let (_, sk) = Scheme::setup(&mut rng);
+        let usk = Scheme::extract_usk(None, &sk, &ids(1)[0], &mut rng);
+
+        let mut bytes = usk.to_bytes();
+        bytes[ID_BYTES..].copy_from_slice(&NON_SUBGROUP_G1_COMPRESSED);
+        assert!(bool::from(UserSecretKey::from_bytes(&bytes).is_none()));
+    }
+}
@@ -0,0 +1,602 @@
+//! IND-PUN-ATK secure puncturable KEM by Green and Miers.
+//! * From: "[Forward Secure Asynchronous Messaging from Puncturable Encryption](https://isi.jhu.edu/~mgreen/forward_sec.pdf)"
+//! * Published in: IEEE S&P, 2015
+//!
+//! CCA security due to a general approach by Fujisaki and Okamoto.
+//! * From: "[A Modular Analysis of the Fujisaki-Okamoto Transformation](https://eprint.iacr.org/2017/604.pdf)"
+//!
+//! The scheme uses the non-monotonic ABE technique by Ostrovsky, Sahai and Waters. Public key
+//! parameters define V(x) = g^{q(x)} for a random polynomial q of degree `D` with q(0) = β. A
+//! secret key consists of shares of the master secret α, each share labelled with a tag t_j,
+//! and a ciphertext with tags t_1, ..., t_D contains V(t_i)^s. A share can only be used to
+//! decapsulate by interpolating q(0) from its own tag and the tags of the ciphertext, which fails
+//! when its tag is one of them. Puncturing on a tag re-randomizes the first share and adds a new
+//! share for the tag.
+//!
+//! The secret key is generated by the recipient, not by a PKG. To obtain forward secrecy for
+//! identity-based ciphertexts, encapsulate both using an [`IBKEM`](crate::kem::IBKEM) and using
+//! this scheme, and combine the two shared secrets and ciphertexts using
+//! [`combine_pkem`](crate::kem::combine_pkem). Do not combine the shared secrets using XOR, which
+//! is not CCA secure.
+//!
+//! The symmetric primitive G is instantiated using sha3_512. All structs' byte serialization use
+//! compression.
+//!
+//! A drawback of a Fujisaki-Okamoto transform is that we now need the public key to decapsulate.
+
+extern crate alloc;
+use alloc::vec::Vec;
+
+use crate::kem::{Error, SharedSecret, PKEM};
+use crate::util::*;
+use crate::Compress;
+use group::Group;
+use pg_curve::{
+    multi_miller_loop, pairing, G1Affine, G1Projective, G2Affine, G2Prepared, G2Projective, Gt,
+    Scalar,
+};
+use rand::{CryptoRng, Rng};
+use subtle::{Choice, ConstantTimeEq, CtOption};
+
+/// Size of the compressed public key in bytes, without the points of V.
+pub const PK_BASE_BYTES: usize = GT_BYTES;
+
+/// Size of a compressed share of the secret key in bytes.
+pub const SHARE_BYTES: usize = 3 * G2_BYTES + SCALAR_BYTES;
+
+/// Size of the compressed ciphertext in bytes, without the tags.
+pub const CT_BASE_BYTES: usize = GT_BYTES + G1_BYTES;
+
+/// Size of a compressed tag of a ciphertext in bytes.
+pub const CT_TAG_BYTES: usize = G1_BYTES + SCALAR_BYTES;
+
+/// Public key used to encapsulate shared secrets.
+///
+/// Contains V(i) in G1 and G2 for `0 ≤ i ≤ D`, and z = e(g1, g2)^{αβ}.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PublicKey<const D: usize> {
+    z: Gt,
+    v1: Vec<G1Affine>,
+    v2: Vec<G2Affine>,
+}
+
+/// Share of the secret key, labelled with a tag.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize))]
+struct Share {
+    a: G2Affine,
+    b: G2Affine,
+    c: G2Affine,
+    t: Scalar,
+}
+
+/// Secret key, which can be punctured on tags.
+///
+/// A secret key always holds at least one share, which is why this type does not implement
+/// `Default`.
+///
+/// # Zeroization
+///
+/// With the `zeroize` feature enabled this type derives `Zeroize` and `ZeroizeOnDrop`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize, zeroize::ZeroizeOnDrop))]
+pub struct SecretKey {
+    shares: Vec<Share>,
+}
+
+/// Encapsulated shared secret, including its `D` tags.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CipherText<const D: usize> {
+    c1: Gt,
+    c2: G1Affine,
+    c3: Vec<G1Affine>,
+    tags: Vec<Scalar>,
+}
+
+impl<const D: usize> ConstantTimeEq for CipherText<D> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.c3.iter().zip(other.c3.iter()).fold(
+            self.c1.ct_eq(&other.c1) & self.c2.ct_eq(&other.c2),
+            |acc, (a, b)| acc & a.ct_eq(b),
+        ) & Choice::from((self.c3.len() == other.c3.len()) as u8)
+            & Choice::from((self.tags == other.tags) as u8)
+    }
+}
+
+/// The CCA2 secure puncturable KEM that results by applying the Fujisaki-Okamoto transform to the
+/// Green-Miers puncturable encryption scheme, for `D` tags per ciphertext.
+#[derive(Debug, Clone, Copy)]
+pub struct GreenMiers<const D: usize>;
+
+/// The points 0, ..., D at which V is given in the public key.
+fn nodes<const D: usize>() -> Vec<Scalar> {
+    (0..=D as u64).map(Scalar::from).collect()
+}
+
+/// Evaluates V(t) in G1.
+fn v1<const D: usize>(pk: &PublicKey<D>, t: &Scalar) -> G1Affine {
+    let ls = lagrange_at(&nodes::<D>(), t).unwrap();
+
+    pk.v1
+        .iter()
+        .zip(ls.iter())
+        .fold(G1Projective::identity(), |acc, (v, l)| acc + v * l)
+        .into()
+}
+
+/// Evaluates V(t) in G2.
+fn v2<const D: usize>(pk: &PublicKey<D>, t: &Scalar) -> G2Affine {
+    let ls = lagrange_at(&nodes::<D>(), t).unwrap();
+
+    pk.v2
+        .iter()
+        .zip(ls.iter())
+        .fold(G2Projective::identity(), |acc, (v, l)| acc + v * l)
+        .into()
+}
+
+/// Creates the share (g2^{β(λ + r)}, V(t)^r, g2^r, t) of λ.
+fn share<const D: usize>(pk: &PublicKey<D>, lambda: &Scalar, r: &Scalar, t: &Scalar) -> Share {
+    Share {
+        a: (pk.v2[0] * (lambda + r)).into(),
+        b: (v2(pk, t) * r).into(),
+        c: (G2Affine::generator() * r).into(),
+        t: *t,
+    }
+}
+
+fn encrypt<const D: usize>(
+    pk: &PublicKey<D>,
+    tags: &[Scalar],
+    m: &Gt,
+    coins: &[u8; 64],
+) -> CipherText<D> {
+    let s = Scalar::from_bytes_wide(coins);
+
+    CipherText {
+        c1: m + pk.z * s,
+        c2: (G1Affine::generator() * s).into(),
+        c3: tags.iter().map(|t| (v1(pk, t) * s).into()).collect(),
+        tags: tags.to_vec(),
+    }
+}
+
+/// Computes z^s, using every share of the secret key.
+///
+/// Returns `None` if the tag of a share is one of the tags of the ciphertext.
+fn decrypt<const D: usize>(sk: &SecretKey, ct: &CipherText<D>) -> Option<Gt> {
+    let mut g1s = Vec::with_capacity(3 * sk.shares.len());
+    let mut g2s = Vec::with_capacity(3 * sk.shares.len());
+
+    for share in sk.shares.iter() {
+        let mut xs = Vec::with_capacity(D + 1);
+        xs.push(share.t);
+        xs.extend_from_slice(&ct.tags);
+
+        let ws = lagrange_at(&xs, &Scalar::zero())?;
+        let c3 = ct
+            .c3
+            .iter()
+            .zip(ws[1..].iter())
+            .fold(G1Projective::identity(), |acc, (c, w)| acc + c * w);
+
+        g1s.push(ct.c2);
+        g1s.push(G1Affine::from(-(ct.c2 * ws[0])));
+        g1s.push(G1Affine::from(-c3));
+
+        g2s.push(G2Prepared::from(share.a));
+        g2s.push(G2Prepared::from(share.b));
+        g2s.push(G2Prepared::from(share.c));
+    }
+
+    let pairs: Vec<_> = g1s.iter().zip(g2s.iter()).collect();
+
+    Some(multi_miller_loop(&pairs).final_exponentiation())
+}
+
+/// Computes the coins G(m, tags) of the Fujisaki-Okamoto transform.
+fn coins(m: &Gt, tags: &[Scalar]) -> [u8; 64] {
+    let mut pre_coins = Vec::with_capacity(GT_BYTES + tags.len() * SCALAR_BYTES);
+    pre_coins.extend_from_slice(&m.to_compressed());
+    for t in tags.iter() {
+        pre_coins.extend_from_slice(&t.to_bytes());
+    }
+
+    sha3_512(&pre_coins)
+}
+
+impl<const D: usize> PKEM for GreenMiers<D> {
+    const IDENTIFIER: &'static str = "green_miers";
+
+    type Pk = PublicKey<D>;
+    type Sk = SecretKey;
+    type Ct = CipherText<D>;
+    type Tag = Identity;
+
+    const PK_BYTES: usize = PK_BASE_BYTES + (D + 1) * (G1_BYTES + G2_BYTES);
+    const SK_BYTES: usize = SHARE_BYTES;
+    const CT_BYTES: usize = CT_BASE_BYTES + D * CT_TAG_BYTES;
+    const TAGS: usize = D;
+
+    /// Generate a public key, and a secret key with a single share of α for a random tag t_0.
+    fn keygen<R: Rng + CryptoRng>(rng: &mut R) -> (PublicKey<D>, SecretKey) {
+        let alpha = rand_scalar(rng);
+        let beta = rand_scalar(rng);
+
+        // q(x) = β + q_1·x + ... + q_D·x^D.
+        let mut q: Vec<Scalar> = (0..=D).map(|_| rand_scalar(rng)).collect();
+        q[0] = beta;
+
+        let qs: Vec<Scalar> = nodes::<D>()
+            .iter()
+            .map(|x| q.iter().rev().fold(Scalar::zero(), |acc, qi| acc * x + qi))
+            .collect();
+
+        let pk = PublicKey {
+            z: pairing(&G1Affine::generator(), &G2Affine::generator()) * (alpha * beta),
+            v1: qs
+                .iter()
+                .map(|qi| (G1Affine::generator() * qi).into())
+                .collect(),
+            v2: qs
+                .iter()
+                .map(|qi| (G2Affine::generator() * qi).into())
+                .collect(),
+        };
+
+        let t0 = rand_scalar(rng);
+        let r = rand_scalar(rng);
+        let sk = SecretKey {
+            shares: alloc::vec![share(&pk, &alpha, &r, &t0)],
+        };
+
+        (pk, sk)
+    }
+
+    fn encaps<R: Rng + CryptoRng>(
+        pk: &PublicKey<D>,
+        tags: &[Identity],
+        rng: &mut R,
+    ) -> Result<(CipherText<D>, SharedSecret), Error> {
+        let tags: Vec<Scalar> = tags.iter().map(|t| t.to_scalar()).collect();
+        if tags.len() != D || lagrange_at(&tags, &Scalar::zero()).is_none() {
+            return Err(Error);
+        }
+
+        let m = Gt::random(rng);
+        let ct = encrypt(pk, &tags, &m, &coins(&m, &tags));
+
+        Ok((ct, SharedSecret::from(&m)))
+    }
+
+    /// Decapsulate a shared secret from the ciphertext.
+    ///
+    /// # Panics
+    ///
+    /// This scheme **does** requires the public key due to usage the Fujisaki-Okamoto transform.
+    /// This function panics if no public key is provided.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an [`Error`] if the secret key is punctured on one of the tags of
+    /// the ciphertext, or when an illegitimate ciphertext is encountered (explicit rejection).
+    fn decaps(
+        opk: Option<&PublicKey<D>>,
+        sk: &SecretKey,
+        ct: &CipherText<D>,
+    ) -> Result<SharedSecret, Error> {
+        let pk = opk.unwrap();
+
+        let m = ct.c1 - decrypt(sk, ct).ok_or(Error)?;
+        let ct2 = encrypt(pk, &ct.tags, &m, &coins(&m, &ct.tags));
+
+        // Can save some time by not doing a constant-time comparison
+        // since we can leak whether the decapsulation succeeds/fails.
+        if ct.ct_eq(&ct2).into() {
+            Ok(SharedSecret::from(&m))
+        } else {
+            Err(Error)
+        }
+    }
+
+    /// Move a random λ' from the first share to a new share for the tag, and re-randomize the
+    /// first share.
+    fn puncture<R: Rng + CryptoRng>(
+        pk: &PublicKey<D>,
+        sk: &mut SecretKey,
+        tag: &Identity,
+        rng: &mut R,
+    ) {
+        let lambda = rand_scalar(rng);
+        let r0 = rand_scalar(rng);
+        let r1 = rand_scalar(rng);
+
+        let first = &mut sk.shares[0];
+        let delta = share(pk, &-lambda, &r0, &first.t);
+        first.a = (first.a + G2Projective::from(delta.a)).into();
+        first.b = (first.b + G2Projective::from(delta.b)).into();
+        first.c = (first.c + G2Projective::from(delta.c)).into();
+
+        sk.shares.push(share(pk, &lambda, &r1, &tag.to_scalar()));
+    }
+}
+
+impl<const D: usize> Compress for PublicKey<D> {
+    /// The size of a public key depends on the number of tags `D`, this is the size of a public
+    /// key without the points of V. The full size is [`GreenMiers::PK_BYTES`](PKEM::PK_BYTES).
+    const OUTPUT_SIZE: usize = PK_BASE_BYTES;
+    type Output = Vec<u8>;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut res = Vec::with_capacity(PK_BASE_BYTES + (D + 1) * (G1_BYTES + G2_BYTES));
+
+        res.extend_from_slice(&self.z.to_compressed());
+        for v in self.v1.iter() {
+            res.extend_from_slice(&v.to_compressed());
+        }
+        for v in self.v2.iter() {
+            res.extend_from_slice(&v.to_compressed());
+        }
+
+        res
+    }
+
+    fn from_bytes(bytes: &Vec<u8>) -> CtOption<Self> {
+        let mut res = PublicKey {
+            z: Gt::default(),
+            v1: alloc::vec![G1Affine::default(); D + 1],
+            v2: alloc::vec![G2Affine::default(); D + 1],
+        };
+
+        if bytes.len() != PK_BASE_BYTES + (D + 1) * (G1_BYTES + G2_BYTES) {
+            return CtOption::new(res, Choice::from(0));
+        }
+
+        let (z, rest) = bytes.split_at(GT_BYTES);
+        let (v1s, v2s) = rest.split_at((D + 1) * G1_BYTES);
+
+        let mut is_some = Gt::from_compressed(z.try_into().unwrap())
+            .map(|el| res.z = el)
+            .is_some();
+        for (v, chunk) in res.v1.iter_mut().zip(v1s.chunks_exact(G1_BYTES)) {
+            is_some &= G1Affine::from_compressed(chunk.try_into().unwrap())
+                .map(|el| *v = el)
+                .is_some();
+        }
+        for (v, chunk) in res.v2.iter_mut().zip(v2s.chunks_exact(G2_BYTES)) {
+            is_some &= G2Affine::from_compressed(chunk.try_into().unwrap())
+                .map(|el| *v = el)
+                .is_some();
+        }
+
+        CtOption::new(res, is_some)
+    }
+}
+
+impl Compress for SecretKey {
+    /// The size of a secret key depends on the number of punctures, this is the size of a secret
+    /// key that is not punctured. Every puncture adds a share of [`SHARE_BYTES`].
+    const OUTPUT_SIZE: usize = SHARE_BYTES;
+    type Output = Vec<u8>;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut res = Vec::with_capacity(self.shares.len() * SHARE_BYTES);
+
+        for share in self.shares.iter() {
+            res.extend_from_slice(&share.a.to_compressed());
+            res.extend_from_slice(&share.b.to_compressed());
+            res.extend_from_slice(&share.c.to_compressed());
+            res.extend_from_slice(&share.t.to_bytes());
+        }
+
+        res
+    }
+
+    fn from_bytes(bytes: &Vec<u8>) -> CtOption<Self> {
+        let mut res = SecretKey { shares: Vec::new() };
+
+        if bytes.is_empty() || !bytes.len().is_multiple_of(SHARE_BYTES) {
+            return CtOption::new(res, Choice::from(0));
+        }
+
+        let mut is_some = Choice::from(1u8);
+        for chunk in bytes.chunks_exact(SHARE_BYTES) {
+            let (a, rest) = chunk.split_at(G2_BYTES);
+            let (b, rest) = rest.split_at(G2_BYTES);
+            let (c, t) = rest.split_at(G2_BYTES);
+
+            let mut share = Share::default();
+            is_some &= G2Affine::from_compressed(a.try_into().unwrap())
+                .map(|el| share.a = el)
+                .is_some();
+            is_some &= G2Affine::from_compressed(b.try_into().unwrap())
+                .map(|el| share.b = el)
+                .is_some();
+            is_some &= G2Affine::from_compressed(c.try_into().unwrap())
+                .map(|el| share.c = el)
+                .is_some();
+            is_some &= Scalar::from_bytes(t.try_into().unwrap())
+                .map(|el| share.t = el)
+                .is_some();
+
+            res.shares.push(share);
+        }
+
+        CtOption::new(res, is_some)
+    }
+}
+
+impl<const D: usize> Compress for CipherText<D> {
+    /// The size of a ciphertext depends on the number of tags `D`, this is the size of a
+    /// ciphertext without tags. The full size is [`GreenMiers::CT_BYTES`](PKEM::CT_BYTES).
+    const OUTPUT_SIZE: usize = CT_BASE_BYTES;
+    type Output = Vec<u8>;
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut res = Vec::with_capacity(CT_BASE_BYTES + D * CT_TAG_BYTES);
+
+        res.extend_from_slice(&self.c1.to_compressed());
+        res.extend_from_slice(&self.c2.to_compressed());
+        for (c, t) in self.c3.iter().zip(self.tags.iter()) {
+            res.extend_from_slice(&c.to_compressed());
+            res.extend_from_slice(&t.to_bytes());
+        }
+
+        res
+    }
+
+    fn from_bytes(bytes: &Vec<u8>) -> CtOption<Self> {
+        let mut res = CipherText {
+            c1: Gt::default(),
+            c2: G1Affine::default(),
+            c3: alloc::vec![G1Affine::default(); D],
+            tags: alloc::vec![Scalar::zero(); D],
+        };
+
+        if bytes.len() != CT_BASE_BYTES + D * CT_TAG_BYTES {
+            return CtOption::new(res, Choice::from(0));
+        }
+
+        let (c1, rest) = bytes.split_at(GT_BYTES);
+        let (c2, rest) = rest.split_at(G1_BYTES);
+
+        let mut is_some = Gt::from_compressed(c1.try_into().unwrap())
+            .map(|el| res.c1 = el)
+            .is_some();
+        is_some &= G1Affine::from_compressed(c2.try_into().unwrap())
+            .map(|el| res.c2 = el)
+            .is_some();
+        for ((c, t), chunk) in res
+            .c3
+            .iter_mut()
+            .zip(res.tags.iter_mut())
+            .zip(rest.chunks_exact(CT_TAG_BYTES))
+        {
+            let (cc, tc) = chunk.split_at(G1_BYTES);
+            is_some &= G1Affine::from_compressed(cc.try_into().unwrap())
+                .map(|el| *c = el)
+                .is_some();
+            is_some &= Scalar::from_bytes(tc.try_into().unwrap())
+                .map(|el| *t = el)
+                .is_some();
+        }
+
+        CtOption::new(res, is_some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Derive;
+
+    type Scheme = GreenMiers<2>;
+
+    fn tags(a: &str, b: &str) -> [Identity; 2] {
+        [Identity::derive_str(a), Identity::derive_str(b)]
+    }
+
+    #[test]
+    fn eq_encaps_decaps() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = Scheme::keygen(&mut rng);
+
+        let (ct, k) = Scheme::encaps(&pk, &tags("msg:1", "chat:1"), &mut rng).unwrap();
+        let k2 = Scheme::decaps(Some(&pk), &sk, &ct).unwrap();
+
+        assert_eq!(k, k2);
+    }
+
+    #[test]
+    fn punctured_key_rejects_tag() {
+        let mut rng = rand::thread_rng();
+        let (pk, mut sk) = Scheme::keygen(&mut rng);
+
+        let (ct1, _) = Scheme::encaps(&pk, &tags("msg:1", "chat:1"), &mut rng).unwrap();
+        let (ct2, k2) = Scheme::encaps(&pk, &tags("msg:2", "chat:1"), &mut rng).unwrap();
+        let (ct3, k3) = Scheme::encaps(&pk, &tags("msg:3", "chat:2"), &mut rng).unwrap();
+
+        Scheme::puncture(&pk, &mut sk, &Identity::derive_str("msg:1"), &mut rng);
+        assert!(Scheme::decaps(Some(&pk), &sk, &ct1).is_err());
+        assert_eq!(Scheme::decaps(Some(&pk), &sk, &ct2).unwrap(), k2);
+
+        // Puncturing on a tag shared by several ciphertexts removes access to all of them.
+        Scheme::puncture(&pk, &mut sk, &Identity::derive_str("chat:1"), &mut rng);
+        assert!(Scheme::decaps(Some(&pk), &sk, &ct2).is_err());
+        assert_eq!(Scheme::decaps(Some(&pk), &sk, &ct3).unwrap(), k3);
+    }
+
+    #[test]
+    fn secret_key_is_never_empty() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = Scheme::keygen(&mut rng);
+
+        // A secret key without shares can not be deserialized, so puncturing never finds it empty.
+        assert!(bool::from(SecretKey::from_bytes(&Vec::new()).is_none()));
+
+        let mut sk = SecretKey::from_bytes(&sk.to_bytes()).unwrap();
+        let (ct, k) = Scheme::encaps(&pk, &tags("msg:2", "chat:1"), &mut rng).unwrap();
+        Scheme::puncture(&pk, &mut sk, &Identity::derive_str("msg:1"), &mut rng);
+        assert_eq!(Scheme::decaps(Some(&pk), &sk, &ct).unwrap(), k);
+    }
+
+    #[test]
+    fn encaps_rejects_invalid_tags() {
+        let mut rng = rand::thread_rng();
+        let (pk, _) = Scheme::keygen(&mut rng);
+
+        let tag = Identity::derive_str("msg:1");
+        assert!(Scheme::encaps(&pk, &[tag], &mut rng).is_err());
+        assert!(Scheme::encaps(&pk, &[tag, tag], &mut rng).is_err());
+    }
+
+    #[cfg(feature = "kv1")]
+    #[test]
+    fn eq_combine_pkem() {
+        use crate::kem::kiltz_vahlis_one::KV1;
+        use crate::kem::{combine_pkem, IBKEM};
+
+        let mut rng = rand::thread_rng();
+        let id = Identity::derive_str("email:alice@example.com");
+        let (mpk, msk) = KV1::setup(&mut rng);
+        let usk = KV1::extract_usk(Some(&mpk), &msk, &id, &mut rng);
+        let (pk, sk) = Scheme::keygen(&mut rng);
+
+        let (ct1, k1) = KV1::encaps(&mpk, &id, &mut rng);
+        let (ct2, k2) = Scheme::encaps(&pk, &tags("msg:1", "chat:1"), &mut rng).unwrap();
+        let k = combine_pkem::<KV1, Scheme>(&k1, &ct1, &k2, &ct2);
+
+        let k1 = KV1::decaps(None, &usk, &ct1).unwrap();
+        let k2 = Scheme::decaps(Some(&pk), &sk, &ct2).unwrap();
+        assert_eq!(combine_pkem::<KV1, Scheme>(&k1, &ct1, &k2, &ct2), k);
+
+        // The combined shared secret depends on both shared secrets and both ciphertexts.
+        let (ct3, k3) = Scheme::encaps(&pk, &tags("msg:2", "chat:1"), &mut rng).unwrap();
+        assert_ne!(combine_pkem::<KV1, Scheme>(&k1, &ct1, &k3, &ct2), k);
+        assert_ne!(combine_pkem::<KV1, Scheme>(&k1, &ct1, &k2, &ct3), k);
+        assert_ne!(combine_pkem::<KV1, Scheme>(&k2, &ct1, &k1, &ct2), k);
+
+        let (ct4, _) = KV1::encaps(&mpk, &id, &mut rng);
+        assert_ne!(combine_pkem::<KV1, Scheme>(&k1, &ct4, &k2, &ct2), k);
+    }
+
+    #[test]
+    fn eq_serialize_deserialize() {
+        let mut rng = rand::thread_rng();
+        let (pk, mut sk) = Scheme::keygen(&mut rng);
+        Scheme::puncture(&pk, &mut sk, &Identity::derive_str("msg:1"), &mut rng);
+
+        let (ct, _) = Scheme::encaps(&pk, &tags("msg:2", "chat:1"), &mut rng).unwrap();
+
+        assert_eq!(pk, PublicKey::from_bytes(&pk.to_bytes()).unwrap());
+        assert_eq!(sk, SecretKey::from_bytes(&sk.to_bytes()).unwrap());
+        assert_eq!(ct, CipherText::from_bytes(&ct.to_bytes()).unwrap());
+        assert_eq!(pk.to_bytes().len(), Scheme::PK_BYTES);
+        assert_eq!(ct.to_bytes().len(), Scheme::CT_BYTES);
+        assert_eq!(sk.to_bytes().len(), 2 * Scheme::SK_BYTES);
+
+        let mut bytes = ct.to_bytes();
+        bytes[GT_BYTES..CT_BASE_BYTES].copy_from_slice(&NON_SUBGROUP_G1_COMPRESSED);
+        assert!(bool::from(CipherText::<2>::from_bytes(&bytes).is_none()));
+    }
+}
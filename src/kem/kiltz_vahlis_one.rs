@@ -65,7 +65,7 @@ pub struct SecretKey {
 /// `ZeroizeOnDrop` (it is `Copy`). Secret material is **not** cleared on drop —
 /// you **MUST** call `.zeroize()` explicitly once done. See the
 /// [crate-level docs](crate#zeroizing-secret-material).
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
 #[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize))]
 pub struct UserSecretKey {
     d1: G1Affine,
@@ -93,6 +93,18 @@ fn hash_g2_to_scalar(x: G2Affine) -> Scalar {
     Scalar::from_bytes_wide(&buf)
 }
 
+/// Computes the target group element of a decapsulation, which is linear in the user secret key.
+fn unblinding(usk: &UserSecretKey, c: &CipherText) -> Gt {
+    let t = hash_g2_to_scalar(c.c1);
+    let x: G1Affine = (usk.d1 + (usk.d3 * t)).into();
+
+    multi_miller_loop(&[
+        (&x, &G2Prepared::from(c.c1)),
+        (&c.c2, &G2Prepared::from(usk.d2)),
+    ])
+    .final_exponentiation()
+}
+
 impl IBKEM for KV1 {
     const IDENTIFIER: &'static str = "kv1";
 
@@ -177,14 +189,7 @@ impl IBKEM for KV1 {
         usk: &UserSecretKey,
         c: &CipherText,
     ) -> Result<SharedSecret, Error> {
-        let t = hash_g2_to_scalar(c.c1);
-        let x: G1Affine = (usk.d1 + (usk.d3 * t)).into();
-
-        let k = multi_miller_loop(&[
-            (&x, &G2Prepared::from(c.c1)),
-            (&c.c2, &G2Prepared::from(usk.d2)),
-        ])
-        .final_exponentiation();
+        let k = unblinding(usk, c);
 
         Ok(SharedSecret::from(&k))
     }
@@ -357,6 +362,38 @@ impl ConditionallySelectable for CipherText {
 #[cfg(feature = "mkem")]
 impl crate::kem::mkem::MultiRecipient for KV1 {}
 
+#[cfg(feature = "threshold")]
+impl crate::kem::threshold::ThresholdDecaps for KV1 {
+    fn random_usk<R: Rng + CryptoRng>(rng: &mut R) -> UserSecretKey {
+        UserSecretKey {
+            d1: rand_g1(rng).into(),
+            d2: rand_g2(rng).into(),
+            d3: rand_g1(rng).into(),
+        }
+    }
+
+    fn usk_mul_add(usk: &UserSecretKey, x: &Scalar, other: &UserSecretKey) -> UserSecretKey {
+        UserSecretKey {
+            d1: (usk.d1 * x + other.d1).into(),
+            d2: (usk.d2 * x + other.d2).into(),
+            d3: (usk.d3 * x + other.d3).into(),
+        }
+    }
+
+    fn decaps_linear(usk: &UserSecretKey, c: &CipherText) -> Gt {
+        unblinding(usk, c)
+    }
+
+    fn decaps_finish(
+        _opk: Option<&PublicKey>,
+        _id: &Identity,
+        _c: &CipherText,
+        k: &Gt,
+    ) -> Result<SharedSecret, Error> {
+        Ok(SharedSecret::from(k))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -367,6 +404,9 @@ mod tests {
     #[cfg(feature = "mkem")]
     test_multi_kem!(KV1);
 
+    #[cfg(feature = "threshold")]
+    test_threshold_kem!(KV1);
+
     // Regression test for GHSA-25fp-2fjj-g84w. A public key whose `hzero` G1
     // component encodes a valid on-curve point that lies outside the prime-order
     // subgroup must be rejected by the checked `from_bytes` deserialization.
@@ -45,6 +45,12 @@ use aes_gcm::{AeadInPlace, Aes128Gcm, KeyInit};
 use hkdf::Hkdf;
 use sha2::Sha256;
 
+#[cfg(feature = "bbfo")]
+use crate::kem::bb_fo::BBFO;
+
+#[cfg(feature = "bffo")]
+use crate::kem::bf_fo::BFFO;
+
 #[cfg(feature = "cgwfo")]
 use crate::kem::cgw_fo::CGWFO;
 
@@ -54,6 +60,9 @@ use crate::kem::cgw_kv::CGWKV;
 #[cfg(feature = "kv1")]
 use crate::kem::kiltz_vahlis_one::KV1;
 
+#[cfg(feature = "skkem")]
+use crate::kem::sakai_kasahara::SKKEM;
+
 const TAG_SIZE: usize = 16;
 const NONCE_SIZE: usize = 12;
 const KEY_SIZE: usize = 16;
@@ -230,6 +239,15 @@ impl_mkemct_compress!(CGWFO);
 #[cfg(feature = "kv1")]
 impl_mkemct_compress!(KV1);
 
+#[cfg(feature = "bffo")]
+impl_mkemct_compress!(BFFO);
+
+#[cfg(feature = "skkem")]
+impl_mkemct_compress!(SKKEM);
+
+#[cfg(feature = "bbfo")]
+impl_mkemct_compress!(BBFO);
+
 #[cfg(test)]
 mod tests {
     use super::*;
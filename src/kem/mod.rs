@@ -2,13 +2,39 @@
 //!
 //! Among the schemes are:
 //! - Kiltz-Vahlis IBE1,
+//! - BFFO (Boneh-Franklin FullIdent, CCA security through FO-transform),
+//! - SK-KEM (Sakai-Kasahara, pairing-free encapsulation),
+//! - BBFO (Boneh-Boyen BB1, CCA security through FO-transform),
 //! - CGWFO (CCA security through FO-transform),
-//! - CGWKV (CCA security due to technique by Kiltz-Vahlis applied to CGW).
+//! - CGWKV (CCA security due to technique by Kiltz-Vahlis applied to CGW),
+//! - Delerablée (identity-based broadcast KEM with constant size ciphertexts, CCA security through
+//!   FO-transform),
+//! - BLMQ (signcryption using the keys of SK-KEM),
+//! - Al-Riyami-Paterson (certificateless KEM on the keys of Boneh-Franklin, CCA security through
+//!   FO-transform),
+//! - Green-Miers (puncturable KEM, CCA security through FO-transform),
+//! - BBGFS (forward-secure KEM on the Boneh-Boyen-Goh HIBE, CCA security through FO-transform),
+//! - Tlock (timelock KEM against a BLS randomness beacon, CCA security through FO-transform).
+//!
+//! The `threshold` module splits the user secret key of KV1, CGWKV or CGWFO over multiple
+//! custodians, such that decapsulation requires the cooperation of a threshold number of them.
 
 #[cfg(feature = "kv1")]
 #[cfg_attr(docsrs, doc(cfg(feature = "kv1")))]
 pub mod kiltz_vahlis_one;
 
+#[cfg(feature = "bbfo")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bbfo")))]
+pub mod bb_fo;
+
+#[cfg(feature = "bffo")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bffo")))]
+pub mod bf_fo;
+
+#[cfg(feature = "skkem")]
+#[cfg_attr(docsrs, doc(cfg(feature = "skkem")))]
+pub mod sakai_kasahara;
+
 #[cfg(feature = "cgwfo")]
 #[cfg_attr(docsrs, doc(cfg(feature = "cgwfo")))]
 pub mod cgw_fo;
@@ -17,10 +43,38 @@ pub mod cgw_fo;
 #[cfg_attr(docsrs, doc(cfg(feature = "cgwkv")))]
 pub mod cgw_kv;
 
+#[cfg(feature = "blmq")]
+#[cfg_attr(docsrs, doc(cfg(feature = "blmq")))]
+pub mod blmq;
+
+#[cfg(feature = "delerablee")]
+#[cfg_attr(docsrs, doc(cfg(feature = "delerablee")))]
+pub mod delerablee;
+
+#[cfg(feature = "al_riyami_paterson")]
+#[cfg_attr(docsrs, doc(cfg(feature = "al_riyami_paterson")))]
+pub mod al_riyami_paterson;
+
+#[cfg(feature = "green_miers")]
+#[cfg_attr(docsrs, doc(cfg(feature = "green_miers")))]
+pub mod green_miers;
+
+#[cfg(feature = "bbgfs")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bbgfs")))]
+pub mod bbg_fs;
+
+#[cfg(feature = "tlock")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tlock")))]
+pub mod tlock;
+
 #[cfg(feature = "mkem")]
 #[cfg_attr(docsrs, doc(cfg(feature = "mkem")))]
 pub mod mkem;
 
+#[cfg(feature = "threshold")]
+#[cfg_attr(docsrs, doc(cfg(feature = "threshold")))]
+pub mod threshold;
+
 use crate::util::*;
 use crate::{Compress, Derive};
 use core::ops::BitXorAssign;
@@ -79,6 +133,12 @@ impl BitXorAssign for SharedSecret {
 #[derive(Debug)]
 pub struct Error;
 
+/// Error in case a signcrypted ciphertext could not be authenticated as coming from the claimed
+/// sender identity, see [`Signcryption`]. Also used by the authenticated key exchange protocols
+/// when the other party can not be authenticated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuthenticationError;
+
 /// Identity-based key encapsulation mechanism (IBKEM).
 pub trait IBKEM: Clone {
     /// Scheme identifier.
@@ -143,3 +203,473 @@ pub trait IBKEM: Clone {
         ct: &Self::Ct,
     ) -> Result<SharedSecret, Error>;
 }
+
+/// Identity-based broadcast key encapsulation mechanism (IBBKEM).
+///
+/// Encapsulates a single shared secret for a set of identities, such that the size of the
+/// ciphertext does not depend on the number of recipients. The set of recipients is not part of
+/// the ciphertext and must be transmitted separately, as it is required for decapsulation.
+pub trait IBBKEM: Clone {
+    /// Scheme identifier.
+    const IDENTIFIER: &'static str;
+
+    /// Master public key (Mpk).
+    type Pk: Compress;
+
+    /// Master secret key (Msk).
+    type Sk: Compress;
+
+    /// User secret key (Usk).
+    type Usk: Compress;
+
+    /// Ciphertext (Ct).
+    type Ct: Compress;
+
+    /// Identity.
+    type Id: Copy + Default + Derive;
+
+    /// Size of the master public key in bytes.
+    const PK_BYTES: usize;
+
+    /// Size of the master secret key in bytes.
+    const SK_BYTES: usize;
+
+    /// Size of the user secret key in bytes.
+    const USK_BYTES: usize;
+
+    /// Size of the ciphertext in bytes, regardless of the number of recipients.
+    const CT_BYTES: usize;
+
+    /// Maximum number of recipients of a single ciphertext.
+    const MAX_RECIPIENTS: usize;
+
+    /// Creates a MSK, MPK pair.
+    fn setup<R: Rng + CryptoRng>(rng: &mut R) -> (Self::Pk, Self::Sk);
+
+    /// Extract a user secret key for an identity using the MSK.
+    ///
+    /// Optionally requires the system's public key.
+    fn extract_usk<R: Rng + CryptoRng>(
+        pk: Option<&Self::Pk>,
+        sk: &Self::Sk,
+        id: &Self::Id,
+        rng: &mut R,
+    ) -> Self::Usk;
+
+    /// Encapsulate a shared secret for a set of identities using the master public key.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the set of identities is empty or contains more than
+    /// [`IBBKEM::MAX_RECIPIENTS`] identities.
+    fn encaps<R: Rng + CryptoRng>(
+        pk: &Self::Pk,
+        ids: &[Self::Id],
+        rng: &mut R,
+    ) -> Result<(Self::Ct, SharedSecret), Error>;
+
+    /// Decrypt a ciphertext for a set of identities using a user secret key to retrieve the
+    /// shared secret.
+    ///
+    /// The identities must be given in the same order as during encapsulation.
+    /// Optionally requires a public key to perform this operation.
+    fn decaps(
+        mpk: Option<&Self::Pk>,
+        usk: &Self::Usk,
+        ids: &[Self::Id],
+        ct: &Self::Ct,
+    ) -> Result<SharedSecret, Error>;
+}
+
+/// Identity-based signcryption key encapsulation mechanism.
+///
+/// Encapsulates a shared secret for a recipient identity in a single pass, such that the
+/// ciphertext is also authenticated as coming from the holder of the user secret key of the sender
+/// identity. The keys of the underlying [`IBKEM`] are used for both encapsulation and
+/// authentication.
+pub trait Signcryption: IBKEM {
+    /// Signcrypted ciphertext.
+    type SigncryptionCt: Compress;
+
+    /// Size of the signcrypted ciphertext in bytes.
+    const SIGNCRYPTION_CT_BYTES: usize;
+
+    /// Encapsulate a shared secret for a recipient identity, authenticated using the user secret
+    /// key of the sender.
+    fn signcrypt<R: Rng + CryptoRng>(
+        pk: &Self::Pk,
+        usk: &Self::Usk,
+        id: &Self::Id,
+        rng: &mut R,
+    ) -> (Self::SigncryptionCt, SharedSecret);
+
+    /// Decapsulate a shared secret using the user secret key of the recipient, and verify that it
+    /// was signcrypted by the sender identity.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`AuthenticationError`] if the ciphertext was not signcrypted by the sender for
+    /// the identity of the user secret key.
+    fn unsigncrypt(
+        pk: &Self::Pk,
+        usk: &Self::Usk,
+        sender: &Self::Id,
+        ct: &Self::SigncryptionCt,
+    ) -> Result<SharedSecret, AuthenticationError>;
+}
+
+/// Certificateless key encapsulation mechanism (CL-KEM).
+///
+/// The PKG only issues a partial key for an identity, which the user combines with a secret
+/// value of its own into the user secret key. Senders encapsulate for an identity together with
+/// the user public key that belongs to this secret value, such that the PKG can not decapsulate
+/// on behalf of its users (no key escrow). In contrast to traditional PKI, the user public key
+/// does not need a certificate: a replaced user public key does not help anyone without the
+/// partial key.
+pub trait CLKEM: Clone {
+    /// Scheme identifier.
+    const IDENTIFIER: &'static str;
+
+    /// Master public key (Mpk).
+    type Pk: Compress;
+
+    /// Master secret key (Msk).
+    type Sk: Compress;
+
+    /// Partial key, issued by the PKG for an identity.
+    type PartialKey: Compress;
+
+    /// Secret value, generated by the user.
+    type SecretValue: Compress;
+
+    /// User public key (Upk), published by the user.
+    type Upk: Compress;
+
+    /// User secret key (Usk), combined from the partial key and the secret value.
+    type Usk: Compress;
+
+    /// Ciphertext (Ct).
+    type Ct: Compress;
+
+    /// Internal identity type (Id).
+    type Id: Copy + Default + Derive;
+
+    /// Size of the master public key in bytes.
+    const PK_BYTES: usize;
+
+    /// Size of the master secret key in bytes.
+    const SK_BYTES: usize;
+
+    /// Size of the partial key in bytes.
+    const PARTIAL_KEY_BYTES: usize;
+
+    /// Size of the secret value in bytes.
+    const SECRET_VALUE_BYTES: usize;
+
+    /// Size of the user public key in bytes.
+    const UPK_BYTES: usize;
+
+    /// Size of the user secret key in bytes.
+    const USK_BYTES: usize;
+
+    /// Size of the ciphertext in bytes.
+    const CT_BYTES: usize;
+
+    /// Creates an MSK, MPK pair.
+    fn setup<R: Rng + CryptoRng>(rng: &mut R) -> (Self::Pk, Self::Sk);
+
+    /// Extract a partial key for an identity using the MSK.
+    ///
+    /// Optionally requires the system's public key.
+    fn extract_partial_key<R: Rng + CryptoRng>(
+        pk: Option<&Self::Pk>,
+        sk: &Self::Sk,
+        id: &Self::Id,
+        rng: &mut R,
+    ) -> Self::PartialKey;
+
+    /// Generate a secret value and the corresponding user public key.
+    fn generate_user_keys<R: Rng + CryptoRng>(
+        pk: &Self::Pk,
+        rng: &mut R,
+    ) -> (Self::Upk, Self::SecretValue);
+
+    /// Combine a partial key with a secret value into a user secret key.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the partial key was not issued using the MSK of `pk`, or if the
+    /// user public key does not belong to the secret value.
+    fn combine_usk(
+        pk: &Self::Pk,
+        partial: &Self::PartialKey,
+        sv: &Self::SecretValue,
+        upk: &Self::Upk,
+    ) -> Result<Self::Usk, Error>;
+
+    /// Encapsulate a shared secret for an identity and its user public key.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the user public key is malformed.
+    fn encaps<R: Rng + CryptoRng>(
+        pk: &Self::Pk,
+        id: &Self::Id,
+        upk: &Self::Upk,
+        rng: &mut R,
+    ) -> Result<(Self::Ct, SharedSecret), Error>;
+
+    /// Decrypt a ciphertext using a user secret key to retrieve the shared secret.
+    ///
+    /// Optionally requires a public key to perform this operation.
+    fn decaps(
+        mpk: Option<&Self::Pk>,
+        usk: &Self::Usk,
+        ct: &Self::Ct,
+    ) -> Result<SharedSecret, Error>;
+}
+
+/// Puncturable key encapsulation mechanism (PKEM).
+///
+/// Every ciphertext is labelled with [`PKEM::TAGS`] tags. After decapsulating, the holder of the
+/// secret key can puncture its key on a tag, after which the key can no longer decapsulate any
+/// ciphertext that carries this tag. As a result, a leaked secret key does not expose ciphertexts
+/// that were already read (forward secrecy), while the public key stays the same.
+///
+/// The size of the secret key grows linearly in the number of punctures.
+pub trait PKEM: Clone {
+    /// Scheme identifier.
+    const IDENTIFIER: &'static str;
+
+    /// Public key (Pk).
+    type Pk: Compress;
+
+    /// Secret key (Sk), which can be punctured.
+    type Sk: Compress;
+
+    /// Ciphertext (Ct), including its tags.
+    type Ct: Compress;
+
+    /// Internal tag type (Tag).
+    type Tag: Copy + Derive;
+
+    /// Size of the public key in bytes.
+    const PK_BYTES: usize;
+
+    /// Size of the secret key in bytes, before it is punctured.
+    const SK_BYTES: usize;
+
+    /// Size of the ciphertext in bytes.
+    const CT_BYTES: usize;
+
+    /// Number of tags of every ciphertext.
+    const TAGS: usize;
+
+    /// Creates a public key and a secret key that is not punctured.
+    fn keygen<R: Rng + CryptoRng>(rng: &mut R) -> (Self::Pk, Self::Sk);
+
+    /// Encapsulate a shared secret in a ciphertext with the given tags.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if not exactly [`PKEM::TAGS`] distinct tags are given.
+    fn encaps<R: Rng + CryptoRng>(
+        pk: &Self::Pk,
+        tags: &[Self::Tag],
+        rng: &mut R,
+    ) -> Result<(Self::Ct, SharedSecret), Error>;
+
+    /// Decrypt a ciphertext using a secret key to retrieve the shared secret.
+    ///
+    /// Optionally requires the public key to perform this operation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the secret key is punctured on one of the tags of the ciphertext,
+    /// or when an illegitimate ciphertext is used as input.
+    fn decaps(pk: Option<&Self::Pk>, sk: &Self::Sk, ct: &Self::Ct) -> Result<SharedSecret, Error>;
+
+    /// Puncture the secret key on a tag.
+    fn puncture<R: Rng + CryptoRng>(pk: &Self::Pk, sk: &mut Self::Sk, tag: &Self::Tag, rng: &mut R);
+}
+
+/// Domain separation label of [`combine_pkem`].
+const COMBINE_PKEM_LABEL: &[u8] = b"ibe-ibkem-pkem-combiner";
+
+/// Combines the shared secrets of an [`IBKEM`] and a [`PKEM`] ciphertext for the same message
+/// into a single shared secret, which is forward secure for identity-based ciphertexts.
+///
+/// Computes SHAKE256(label ‖ ss_ibkem ‖ ss_pkem ‖ ct_ibkem ‖ ct_pkem), where both ciphertexts
+/// are prefixed with their length. Binding the ciphertexts keeps the combination CCA secure when
+/// either KEM is, unlike combining the shared secrets using XOR, which accepts ciphertexts of
+/// which the components are mixed and matched.
+pub fn combine_pkem<K: IBKEM, P: PKEM>(
+    ss_ibkem: &SharedSecret,
+    ct_ibkem: &K::Ct,
+    ss_pkem: &SharedSecret,
+    ct_pkem: &P::Ct,
+) -> SharedSecret {
+    use tiny_keccak::{Hasher, Xof};
+
+    let mut digest = tiny_keccak::Shake::v256();
+    digest.update(COMBINE_PKEM_LABEL);
+    digest.update(&ss_ibkem.0);
+    digest.update(&ss_pkem.0);
+
+    for ct in [ct_ibkem.to_bytes().as_ref(), ct_pkem.to_bytes().as_ref()] {
+        digest.update(&(ct.len() as u64).to_be_bytes());
+        digest.update(ct);
+    }
+
+    let mut buf = [0u8; SS_BYTES];
+    digest.squeeze(&mut buf);
+
+    SharedSecret(buf)
+}
+
+/// Forward-secure identity-based key encapsulation mechanism (FSKEM).
+///
+/// Shared secrets are encapsulated for an identity and an epoch. A user secret key starts at the
+/// first epoch and can be updated to any later epoch by its holder, without the involvement of
+/// the PKG. After an update, the user secret key can no longer decapsulate ciphertexts for earlier
+/// epochs, which bounds the damage of a compromised key to the current and later epochs.
+pub trait FSKEM: Clone {
+    /// Scheme identifier.
+    const IDENTIFIER: &'static str;
+
+    /// Master public key (Mpk).
+    type Pk: Compress;
+
+    /// Master secret key (Msk).
+    type Sk: Compress;
+
+    /// User secret key (Usk), which evolves through the epochs.
+    type Usk: Compress;
+
+    /// Ciphertext (Ct), including its epoch.
+    type Ct: Compress;
+
+    /// Identity.
+    type Id: Copy + Default + Derive;
+
+    /// Size of the master public key in bytes.
+    const PK_BYTES: usize;
+
+    /// Size of the master secret key in bytes.
+    const SK_BYTES: usize;
+
+    /// Size of the user secret key in bytes, at the first epoch.
+    const USK_BYTES: usize;
+
+    /// Size of the ciphertext in bytes.
+    const CT_BYTES: usize;
+
+    /// Number of epochs, which are numbered starting from zero.
+    const EPOCHS: u64;
+
+    /// Creates a MSK, MPK pair.
+    fn setup<R: Rng + CryptoRng>(rng: &mut R) -> (Self::Pk, Self::Sk);
+
+    /// Extract a user secret key for an identity at the first epoch using the MSK.
+    ///
+    /// Optionally requires the system's public key.
+    fn extract_usk<R: Rng + CryptoRng>(
+        pk: Option<&Self::Pk>,
+        sk: &Self::Sk,
+        id: &Self::Id,
+        rng: &mut R,
+    ) -> Self::Usk;
+
+    /// The current epoch of a user secret key.
+    fn epoch(usk: &Self::Usk) -> u64;
+
+    /// Update a user secret key to a later epoch, erasing the key material of earlier epochs.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the epoch is before the current epoch of the user secret key, or if
+    /// it is not smaller than [`FSKEM::EPOCHS`].
+    fn update<R: Rng + CryptoRng>(
+        pk: &Self::Pk,
+        usk: &mut Self::Usk,
+        epoch: u64,
+        rng: &mut R,
+    ) -> Result<(), Error>;
+
+    /// Encapsulate a shared secret using the master public key, an identity and an epoch.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the epoch is not smaller than [`FSKEM::EPOCHS`].
+    fn encaps<R: Rng + CryptoRng>(
+        pk: &Self::Pk,
+        id: &Self::Id,
+        epoch: u64,
+        rng: &mut R,
+    ) -> Result<(Self::Ct, SharedSecret), Error>;
+
+    /// Decrypt a ciphertext using a user secret key to retrieve the shared secret.
+    ///
+    /// Optionally requires a public key to perform this operation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the ciphertext is not for the current epoch of the user secret key,
+    /// or when an illegitimate ciphertext is used as input. To decapsulate a ciphertext for a
+    /// later epoch, the user secret key has to be updated first.
+    fn decaps(
+        mpk: Option<&Self::Pk>,
+        usk: &Self::Usk,
+        ct: &Self::Ct,
+    ) -> Result<SharedSecret, Error>;
+}
+
+/// Timelock key encapsulation mechanism (TLKEM).
+///
+/// Shared secrets are encapsulated for a future round of a randomness beacon, which periodically
+/// publishes a signature on the round number under its public key. Once the signature of the round
+/// is published, anyone can use it to decapsulate. No trusted party is involved at decapsulation
+/// time, other than the beacon not publishing signatures ahead of time.
+pub trait TLKEM: Clone {
+    /// Scheme identifier.
+    const IDENTIFIER: &'static str;
+
+    /// Public key of the beacon (Pk).
+    type Pk: Compress;
+
+    /// Signature of the beacon on a round (Sig).
+    type Sig: Compress;
+
+    /// Ciphertext (Ct), including its round.
+    type Ct: Compress;
+
+    /// Size of the public key of the beacon in bytes.
+    const PK_BYTES: usize;
+
+    /// Size of the signature of the beacon in bytes.
+    const SIG_BYTES: usize;
+
+    /// Size of the ciphertext in bytes.
+    const CT_BYTES: usize;
+
+    /// Verify the signature of the beacon on a round.
+    fn verify(pk: &Self::Pk, round: u64, sig: &Self::Sig) -> bool;
+
+    /// Encapsulate a shared secret for a round using the public key of the beacon.
+    fn encaps<R: Rng + CryptoRng>(
+        pk: &Self::Pk,
+        round: u64,
+        rng: &mut R,
+    ) -> (Self::Ct, SharedSecret);
+
+    /// The round of a ciphertext, of which the signature is required to decapsulate.
+    fn round(ct: &Self::Ct) -> u64;
+
+    /// Decrypt a ciphertext using the signature of the beacon on its round.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the signature is not a valid signature of the beacon on the round
+    /// of the ciphertext, or when an illegitimate ciphertext is used as input.
+    fn decaps(pk: &Self::Pk, sig: &Self::Sig, ct: &Self::Ct) -> Result<SharedSecret, Error>;
+}
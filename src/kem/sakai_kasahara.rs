@@ -0,0 +1,326 @@
+//! IND-ID-CCA2 secure IBKEM by Sakai and Kasahara (SK-KEM).
+//! * From: "[ID based Cryptosystems with Pairing on Elliptic Curve](https://eprint.iacr.org/2003/054.pdf)"
+//! * Security analysis: "[Security Proof of Sakai-Kasahara's Identity-Based Encryption Scheme](https://eprint.iacr.org/2005/226.pdf)"
+//!
+//! The KEM follows the structure of SAKKE ([RFC 6508](https://www.rfc-editor.org/rfc/rfc6508)):
+//! a random shared secret value is masked with a hash of the target group element, and the
+//! encapsulation randomness is derived from the shared secret value and the identity. This is a
+//! Fujisaki-Okamoto style transform, therefore the public key is needed to decapsulate.
+//!
+//! User secret keys are obtained by exponent inversion. The pairing e(P1, P2) is the generator of
+//! the target group, such that encapsulation does not require a pairing computation.
+
+use crate::kem::{Error, SharedSecret, IBKEM, SS_BYTES};
+use crate::util::*;
+use crate::Compress;
+use arrayref::{array_refs, mut_array_refs};
+use group::Group;
+use pg_curve::{pairing, G1Affine, G2Affine, Gt, Scalar};
+use rand::{CryptoRng, Rng};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
+
+/// Size of the compressed master public key in bytes.
+pub const PK_BYTES: usize = G1_BYTES;
+
+/// Size of the compressed master secret key in bytes.
+pub const SK_BYTES: usize = SCALAR_BYTES;
+
+/// Size of the compressed user secret key in bytes.
+///
+/// The USK includes the identity (needed for re-encapsulation).
+pub const USK_BYTES: usize = G2_BYTES + ID_BYTES;
+
+/// Size of the compressed ciphertext key in bytes.
+pub const CT_BYTES: usize = G1_BYTES + SS_BYTES;
+
+/// Public key parameters generated by the PKG used to encaps messages.
+/// Also known as MPK.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct PublicKey {
+    pub(super) z: G1Affine,
+}
+
+/// Secret key parameter generated by the PKG used to extract user secret keys.
+/// Also known as MSK.
+///
+/// # Zeroization
+///
+/// With the `zeroize` feature enabled this type derives `Zeroize` but **not**
+/// `ZeroizeOnDrop` (it is `Copy`). Secret material is **not** cleared on drop —
+/// you **MUST** call `.zeroize()` explicitly once done. See the
+/// [crate-level docs](crate#zeroizing-secret-material).
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize))]
+pub struct SecretKey {
+    s: Scalar,
+}
+
+/// User secret key. Can be used to decaps the corresponding ciphertext.
+/// Also known as USK_{id}.
+///
+/// # Zeroization
+///
+/// With the `zeroize` feature enabled this type derives `Zeroize` but **not**
+/// `ZeroizeOnDrop` (it is `Copy`). Secret material is **not** cleared on drop —
+/// you **MUST** call `.zeroize()` explicitly once done. See the
+/// [crate-level docs](crate#zeroizing-secret-material).
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize))]
+pub struct UserSecretKey {
+    pub(super) d: G2Affine,
+    pub(super) id: Identity,
+}
+
+/// Encrypted message. Can only be decapsed with a corresponding user secret key.
+/// Also known as CT_{id}
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub struct CipherText {
+    u: G1Affine,
+    h: [u8; SS_BYTES],
+}
+
+/// Derives the encapsulation randomness from the shared secret value and the identity.
+fn coins(ssv: &[u8; SS_BYTES], id: &Identity) -> Scalar {
+    let mut pre_coins = [0u8; SS_BYTES + ID_BYTES];
+    pre_coins[..SS_BYTES].copy_from_slice(ssv);
+    pre_coins[SS_BYTES..].copy_from_slice(&id.0);
+
+    Scalar::from_bytes_wide(&sha3_512(&pre_coins))
+}
+
+/// Computes the ciphertext point U = r * (Z + id * P1).
+fn encode(pk: &PublicKey, id: &Identity, r: &Scalar) -> G1Affine {
+    ((G1Affine::generator() * (id.to_scalar() * r)) + (pk.z * r)).into()
+}
+
+/// The Sakai-Kasahara identity-based key encapsulation scheme.
+#[derive(Debug, Clone, Copy)]
+pub struct SKKEM;
+
+impl IBKEM for SKKEM {
+    const IDENTIFIER: &'static str = "skkem";
+
+    type Pk = PublicKey;
+    type Sk = SecretKey;
+    type Usk = UserSecretKey;
+    type Ct = CipherText;
+    type Id = Identity;
+
+    const PK_BYTES: usize = PK_BYTES;
+    const SK_BYTES: usize = SK_BYTES;
+    const USK_BYTES: usize = USK_BYTES;
+    const CT_BYTES: usize = CT_BYTES;
+
+    /// Generate a keypair used by the Private Key Generator (PKG).
+    fn setup<R: Rng + CryptoRng>(rng: &mut R) -> (PublicKey, SecretKey) {
+        let s = rand_scalar(rng);
+
+        let z = (G1Affine::generator() * s).into();
+
+        (PublicKey { z }, SecretKey { s })
+    }
+
+    /// Extract a user secret key for a given identity.
+    fn extract_usk<R: Rng + CryptoRng>(
+        _pk: Option<&PublicKey>,
+        sk: &SecretKey,
+        v: &Identity,
+        _rng: &mut R,
+    ) -> UserSecretKey {
+        // The inverse does not exist with negligible probability, only when
+        // the hashed identity equals -s.
+        let exp = (sk.s + v.to_scalar()).invert().unwrap();
+
+        UserSecretKey {
+            d: (G2Affine::generator() * exp).into(),
+            id: *v,
+        }
+    }
+
+    /// Encapsulate a shared secret. Does not compute a pairing.
+    fn encaps<R: Rng + CryptoRng>(
+        pk: &PublicKey,
+        id: &Identity,
+        rng: &mut R,
+    ) -> (CipherText, SharedSecret) {
+        let mut ssv = [0u8; SS_BYTES];
+        rng.fill_bytes(&mut ssv);
+
+        let r = coins(&ssv, id);
+        let u = encode(pk, id, &r);
+
+        let mut h = shake256::<SS_BYTES>(&(Gt::generator() * r).to_compressed());
+        for (hi, si) in h.iter_mut().zip(ssv.iter()) {
+            *hi ^= si;
+        }
+
+        (CipherText { u, h }, SharedSecret(ssv))
+    }
+
+    /// Decapsulate a shared secret from the ciphertext.
+    ///
+    /// # Panics
+    ///
+    /// This scheme **does** require the master public key to re-encapsulate.
+    /// This function panics if no master public key is provided.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an [`Error`] when an illegitimate ciphertext is encountered (explicit rejection).
+    fn decaps(
+        opk: Option<&PublicKey>,
+        usk: &UserSecretKey,
+        ct: &CipherText,
+    ) -> Result<SharedSecret, Error> {
+        let pk = opk.unwrap();
+
+        let mut ssv = shake256::<SS_BYTES>(&pairing(&ct.u, &usk.d).to_compressed());
+        for (si, hi) in ssv.iter_mut().zip(ct.h.iter()) {
+            *si ^= hi;
+        }
+
+        let r = coins(&ssv, &usk.id);
+        let u = encode(pk, &usk.id, &r);
+
+        // Can save some time by not doing a constant-time comparison
+        // since we can leak whether the decapsulation succeeds/fails.
+        if u.ct_eq(&ct.u).into() {
+            Ok(SharedSecret(ssv))
+        } else {
+            Err(Error)
+        }
+    }
+}
+
+impl Compress for PublicKey {
+    const OUTPUT_SIZE: usize = PK_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; PK_BYTES] {
+        self.z.to_compressed()
+    }
+
+    fn from_bytes(bytes: &[u8; PK_BYTES]) -> CtOption<Self> {
+        // Use the checked `from_compressed` variant, which verifies that the
+        // point is in the correct prime-order subgroup (GHSA-25fp-2fjj-g84w).
+        G1Affine::from_compressed(bytes).map(|z| PublicKey { z })
+    }
+}
+
+impl Compress for SecretKey {
+    const OUTPUT_SIZE: usize = SK_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; SK_BYTES] {
+        self.s.to_bytes()
+    }
+
+    fn from_bytes(bytes: &[u8; SK_BYTES]) -> CtOption<Self> {
+        Scalar::from_bytes(bytes).map(|s| SecretKey { s })
+    }
+}
+
+impl Compress for UserSecretKey {
+    const OUTPUT_SIZE: usize = USK_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; USK_BYTES] {
+        let mut res = [0u8; USK_BYTES];
+        let (d, id) = mut_array_refs![&mut res, G2_BYTES, ID_BYTES];
+
+        *d = self.d.to_compressed();
+        *id = self.id.0;
+
+        res
+    }
+
+    fn from_bytes(bytes: &[u8; USK_BYTES]) -> CtOption<Self> {
+        let (d, id) = array_refs![bytes, G2_BYTES, ID_BYTES];
+
+        let id = Identity(*id);
+
+        G2Affine::from_compressed(d).map(|d| UserSecretKey { d, id })
+    }
+}
+
+impl Compress for CipherText {
+    const OUTPUT_SIZE: usize = CT_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; CT_BYTES] {
+        let mut res = [0u8; CT_BYTES];
+        let (u, h) = mut_array_refs![&mut res, G1_BYTES, SS_BYTES];
+
+        *u = self.u.to_compressed();
+        *h = self.h;
+
+        res
+    }
+
+    fn from_bytes(bytes: &[u8; CT_BYTES]) -> CtOption<Self> {
+        let (u, h) = array_refs![bytes, G1_BYTES, SS_BYTES];
+
+        G1Affine::from_compressed(u).map(|u| CipherText { u, h: *h })
+    }
+}
+
+impl ConditionallySelectable for CipherText {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        let mut h = [0u8; SS_BYTES];
+        for (i, h) in h.iter_mut().enumerate() {
+            *h = u8::conditional_select(&a.h[i], &b.h[i], choice);
+        }
+
+        CipherText {
+            u: G1Affine::conditional_select(&a.u, &b.u, choice),
+            h,
+        }
+    }
+}
+
+#[cfg(feature = "mkem")]
+impl crate::kem::mkem::MultiRecipient for SKKEM {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Derive;
+
+    test_kem!(SKKEM);
+
+    #[cfg(feature = "mkem")]
+    test_multi_kem!(SKKEM);
+
+    // Regression test for GHSA-25fp-2fjj-g84w. A public key whose G1 component
+    // encodes a valid on-curve point that lies outside the prime-order subgroup
+    // must be rejected by the checked `from_bytes` deserialization.
+    #[test]
+    fn from_bytes_rejects_non_subgroup_point() {
+        let mut rng = rand::thread_rng();
+        let (pk, _sk) = SKKEM::setup(&mut rng);
+        let mut bytes = pk.to_bytes();
+        bytes[..G1_BYTES].copy_from_slice(&NON_SUBGROUP_G1_COMPRESSED);
+        assert!(bool::from(PublicKey::from_bytes(&bytes).is_none()));
+    }
+
+    #[test]
+    fn gt_generator_is_pairing_of_generators() {
+        assert_eq!(
+            Gt::generator(),
+            pairing(&G1Affine::generator(), &G2Affine::generator())
+        );
+    }
+
+    #[test]
+    fn decaps_rejects_modified_tag() {
+        let mut rng = rand::thread_rng();
+        let id = Identity::derive_str(ID1);
+        let (pk, sk) = SKKEM::setup(&mut rng);
+        let usk = SKKEM::extract_usk(None, &sk, &id, &mut rng);
+        let (mut ct, _k) = SKKEM::encaps(&pk, &id, &mut rng);
+
+        ct.h[0] ^= 1;
+
+        assert!(SKKEM::decaps(Some(&pk), &usk, &ct).is_err());
+    }
+}
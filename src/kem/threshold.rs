@@ -0,0 +1,298 @@
+//! This module contains a generic API to split the user secret key of a single identity over
+//! multiple custodians, such that a ciphertext can only be decapsulated with the cooperation of a
+//! threshold number of them.
+//!
+//! The user secret key is shared using Shamir secret sharing in the exponent: each component of
+//! the key is the constant term of a random polynomial of degree `t - 1` over the group, and
+//! custodian `i` receives the evaluation of these polynomials at `i`. For the supported schemes
+//! the target group element computed during decapsulation is linear in the user secret key. A
+//! custodian therefore computes a [`DecapsShare`] with its key share, and any `t` of these
+//! combine into the decapsulated target group element using Lagrange interpolation at zero.
+//!
+//! Note that:
+//! - the key is split by whoever holds the complete user secret key, e.g., the PKG right after
+//!   extraction, which must erase it afterwards,
+//! - decapsulation shares are not verifiable: a wrong or malicious share results in a wrong
+//!   [`SharedSecret`], or an [`Error`] for schemes that validate the ciphertext.
+//!
+//! # Example usage:
+//!
+//! In this example the user secret key is split into five shares, any three of which can
+//! decapsulate.
+//!
+//! ```
+//! use ibe::kem::IBKEM;
+//! use ibe::kem::threshold::ThresholdDecaps;
+//! use ibe::kem::cgw_kv::CGWKV;
+//! use ibe::Derive;
+//!
+//! let mut rng = rand::thread_rng();
+//!
+//! let id = <CGWKV as IBKEM>::Id::derive_str("email:legal@example.com");
+//!
+//! // Create a master key pair and extract the user secret key.
+//! let (pk, sk) = CGWKV::setup(&mut rng);
+//! let usk = CGWKV::extract_usk(None, &sk, &id, &mut rng);
+//!
+//! // Split the user secret key into five shares with threshold three.
+//! let shares = CGWKV::split_usk(&usk, 3, 5, &mut rng).unwrap();
+//!
+//! let (ct, k) = CGWKV::encaps(&pk, &id, &mut rng);
+//!
+//! // Three custodians each compute a decapsulation share.
+//! let decaps_shares: Vec<_> = [&shares[0], &shares[2], &shares[4]]
+//!     .iter()
+//!     .map(|share| CGWKV::decaps_share(share, &ct))
+//!     .collect();
+//!
+//! let k2 = CGWKV::combine(Some(&pk), &id, &ct, &decaps_shares).unwrap();
+//!
+//! assert_eq!(k, k2);
+//! ```
+
+extern crate alloc;
+use alloc::vec::Vec;
+
+use crate::kem::{Error, SharedSecret, IBKEM};
+use crate::util::{lagrange_at, GT_BYTES};
+use crate::Compress;
+use arrayref::{array_refs, mut_array_refs};
+use core::iter;
+use pg_curve::{Gt, Scalar};
+use rand::{CryptoRng, Rng};
+use subtle::CtOption;
+
+#[cfg(feature = "cgwfo")]
+use crate::kem::cgw_fo::CGWFO;
+
+#[cfg(feature = "cgwkv")]
+use crate::kem::cgw_kv::CGWKV;
+
+#[cfg(feature = "kv1")]
+use crate::kem::kiltz_vahlis_one::KV1;
+
+/// Size of the compressed decapsulation share in bytes.
+pub const DECAPS_SHARE_BYTES: usize = 2 + GT_BYTES;
+
+/// Share of a user secret key, held by a single custodian.
+///
+/// # Zeroization
+///
+/// With the `zeroize` feature enabled this type implements `Zeroize` but **not**
+/// `ZeroizeOnDrop` (it is `Copy`). Secret material is **not** cleared on drop —
+/// you **MUST** call `.zeroize()` explicitly once done. See the
+/// [crate-level docs](crate#zeroizing-secret-material).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UskShare<U> {
+    index: u8,
+    threshold: u8,
+    usk: U,
+}
+
+#[cfg(feature = "zeroize")]
+impl<U: zeroize::Zeroize> zeroize::Zeroize for UskShare<U> {
+    fn zeroize(&mut self) {
+        self.index.zeroize();
+        self.threshold.zeroize();
+        self.usk.zeroize();
+    }
+}
+
+/// Decapsulation share of a ciphertext, computed using a [`UskShare`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DecapsShare {
+    index: u8,
+    threshold: u8,
+    d: Gt,
+}
+
+/// Threshold decapsulation of a single user secret key.
+///
+/// Can only be implemented for schemes of which the target group element computed during
+/// decapsulation is linear in the user secret key.
+pub trait ThresholdDecaps: IBKEM<Usk: Copy> {
+    /// Sample a uniformly random user secret key, used as coefficient of the sharing polynomial.
+    fn random_usk<R: Rng + CryptoRng>(rng: &mut R) -> Self::Usk;
+
+    /// Compute `usk * x + other`, componentwise.
+    fn usk_mul_add(usk: &Self::Usk, x: &Scalar, other: &Self::Usk) -> Self::Usk;
+
+    /// Compute the target group element of a decapsulation, which is linear in the user secret
+    /// key.
+    fn decaps_linear(usk: &Self::Usk, ct: &Self::Ct) -> Gt;
+
+    /// Derive the shared secret from the target group element of a decapsulation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the scheme rejects the ciphertext.
+    fn decaps_finish(
+        pk: Option<&Self::Pk>,
+        id: &Self::Id,
+        ct: &Self::Ct,
+        k: &Gt,
+    ) -> Result<SharedSecret, Error>;
+
+    /// Split a user secret key into `n` shares, such that any `threshold` of them can
+    /// decapsulate.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if `threshold` is zero or larger than `n`.
+    fn split_usk<R: Rng + CryptoRng>(
+        usk: &Self::Usk,
+        threshold: u8,
+        n: u8,
+        rng: &mut R,
+    ) -> Result<Vec<UskShare<Self::Usk>>, Error> {
+        if threshold == 0 || threshold > n {
+            return Err(Error);
+        }
+
+        let coeffs: Vec<Self::Usk> = (1..threshold).map(|_| Self::random_usk(rng)).collect();
+
+        Ok((1..=n)
+            .map(|index| {
+                let x = Scalar::from(index as u64);
+
+                // Horner's rule, with the user secret key as constant term.
+                let usk = coeffs
+                    .iter()
+                    .rev()
+                    .chain(iter::once(usk))
+                    .copied()
+                    .reduce(|acc, coeff| Self::usk_mul_add(&acc, &x, &coeff))
+                    .unwrap();
+
+                UskShare {
+                    index,
+                    threshold,
+                    usk,
+                }
+            })
+            .collect())
+    }
+
+    /// Compute a decapsulation share of the ciphertext using a user secret key share.
+    fn decaps_share(share: &UskShare<Self::Usk>, ct: &Self::Ct) -> DecapsShare {
+        DecapsShare {
+            index: share.index,
+            threshold: share.threshold,
+            d: Self::decaps_linear(&share.usk, ct),
+        }
+    }
+
+    /// Combine decapsulation shares of the ciphertext into the shared secret.
+    ///
+    /// Only the first `threshold` shares are used.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the scheme requires the master public key to decapsulate and none is provided.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if there are too few shares, if the shares are inconsistent or
+    /// duplicate, or if the scheme rejects the ciphertext.
+    fn combine(
+        pk: Option<&Self::Pk>,
+        id: &Self::Id,
+        ct: &Self::Ct,
+        shares: &[DecapsShare],
+    ) -> Result<SharedSecret, Error> {
+        let threshold = shares.first().ok_or(Error)?.threshold;
+        let shares = shares.get(..threshold as usize).ok_or(Error)?;
+
+        if shares
+            .iter()
+            .any(|share| share.threshold != threshold || share.index == 0)
+        {
+            return Err(Error);
+        }
+
+        let xs: Vec<Scalar> = shares
+            .iter()
+            .map(|share| Scalar::from(share.index as u64))
+            .collect();
+        let ls = lagrange_at(&xs, &Scalar::zero()).ok_or(Error)?;
+
+        let k = shares
+            .iter()
+            .zip(ls.iter())
+            .fold(Gt::default(), |acc, (share, l)| acc + share.d * l);
+
+        Self::decaps_finish(pk, id, ct, &k)
+    }
+}
+
+impl Compress for DecapsShare {
+    const OUTPUT_SIZE: usize = DECAPS_SHARE_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; DECAPS_SHARE_BYTES] {
+        let mut res = [0u8; DECAPS_SHARE_BYTES];
+        let (index, threshold, d) = mut_array_refs![&mut res, 1, 1, GT_BYTES];
+
+        *index = [self.index];
+        *threshold = [self.threshold];
+        *d = self.d.to_compressed();
+
+        res
+    }
+
+    fn from_bytes(bytes: &[u8; DECAPS_SHARE_BYTES]) -> CtOption<Self> {
+        let (index, threshold, d) = array_refs![bytes, 1, 1, GT_BYTES];
+
+        Gt::from_compressed(d).map(|d| DecapsShare {
+            index: index[0],
+            threshold: threshold[0],
+            d,
+        })
+    }
+}
+
+#[allow(unused_macros)]
+macro_rules! impl_usk_share_compress {
+    ($scheme: ident) => {
+        impl Compress for UskShare<<$scheme as IBKEM>::Usk> {
+            const OUTPUT_SIZE: usize = 2 + $scheme::USK_BYTES;
+            type Output = [u8; Self::OUTPUT_SIZE];
+
+            fn to_bytes(&self) -> Self::Output {
+                let mut res = [0u8; Self::OUTPUT_SIZE];
+                let (index, threshold, usk) = mut_array_refs![&mut res, 1, 1, $scheme::USK_BYTES];
+
+                *index = [self.index];
+                *threshold = [self.threshold];
+                *usk = self.usk.to_bytes();
+
+                res
+            }
+
+            fn from_bytes(output: &Self::Output) -> CtOption<Self> {
+                let (index, threshold, usk) = array_refs![output, 1, 1, $scheme::USK_BYTES];
+
+                let usk = <$scheme as IBKEM>::Usk::from_bytes(usk);
+                let is_some = usk.is_some();
+
+                CtOption::new(
+                    UskShare {
+                        index: index[0],
+                        threshold: threshold[0],
+                        usk: Option::from(usk).unwrap_or_default(),
+                    },
+                    is_some,
+                )
+            }
+        }
+    };
+}
+
+#[cfg(feature = "cgwfo")]
+impl_usk_share_compress!(CGWFO);
+
+#[cfg(feature = "cgwkv")]
+impl_usk_share_compress!(CGWKV);
+
+#[cfg(feature = "kv1")]
+impl_usk_share_compress!(KV1);
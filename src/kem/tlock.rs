@@ -0,0 +1,443 @@
+//! IND-ID-CCA2 secure timelock KEM against a BLS randomness beacon, in the style of tlock.
+//! * From: "[tlock: Practical Timelock Encryption from Threshold BLS](https://eprint.iacr.org/2023/189.pdf)"
+//!
+//! The scheme is the Boneh-Franklin IBKEM in which the identity is a round number of the beacon,
+//! and the user secret key of a round is the BLS signature of the beacon on that round. The
+//! signatures follow the `bls-unchained-g1-rfc9380` scheme of [drand](https://drand.love) (as
+//! used by its `quicknet` network): the public key of the beacon lives in G2, and the signature of
+//! a round is a point on G1, the hash of SHA-256 over the big-endian round number to G1 using the
+//! hash-to-curve construction of RFC 9380. Therefore, signatures published by such a beacon can be
+//! used to decapsulate, which is tested against a signature published by the `quicknet` network.
+//! Note that the ciphertext format is specific to this library, and is not compatible with the
+//! reference implementation of tlock.
+//!
+//! Before decapsulating, the signature is verified against the public key of the beacon. The
+//! [`Beacon`] simulates a beacon locally, which is useful for testing.
+//!
+//! CCA security due to a general approach by Fujisaki and Okamoto.
+//! * From: "[A Modular Analysis of the Fujisaki-Okamoto Transformation](https://eprint.iacr.org/2017/604.pdf)"
+//!
+//! Symmetric primitive G is instantiated using sha3_512.
+
+use crate::kem::{Error, SharedSecret, TLKEM};
+use crate::util::*;
+use crate::Compress;
+use arrayref::{array_refs, mut_array_refs};
+use core::num::NonZeroU64;
+use group::Group;
+use pg_curve::{multi_miller_loop, pairing, G1Affine, G2Affine, G2Prepared, Gt, Scalar};
+use rand::{CryptoRng, Rng};
+use sha2::{Digest, Sha256};
+use subtle::{Choice, ConstantTimeEq, CtOption};
+
+/// Domain separation tag used to hash rounds to G1, as used by drand.
+const DST: &[u8] = b"BLS_SIG_BLS12381G1_XMD:SHA-256_SSWU_RO_NUL_";
+
+/// Size of the compressed public key of the beacon in bytes.
+pub const PK_BYTES: usize = G2_BYTES;
+
+/// Size of the compressed signature of the beacon in bytes.
+pub const SIG_BYTES: usize = G1_BYTES;
+
+/// Size of the compressed ciphertext in bytes.
+///
+/// The ciphertext includes its round (needed for decapsulation).
+pub const CT_BYTES: usize = 8 + G2_BYTES + GT_BYTES;
+
+/// Public key of the beacon.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct PublicKey {
+    p: G2Affine,
+}
+
+/// Signature of the beacon on a round. Acts as the user secret key of the round, and can be
+/// published once the round has passed.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub struct Signature {
+    sig: G1Affine,
+}
+
+/// Encapsulated shared secret for a round.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub struct CipherText {
+    round: u64,
+    u: G2Affine,
+    v: Gt,
+}
+
+impl ConstantTimeEq for CipherText {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.round.ct_eq(&other.round) & self.u.ct_eq(&other.u) & self.v.ct_eq(&other.v)
+    }
+}
+
+/// Local simulator of a randomness beacon, which signs rounds on request.
+///
+/// A real beacon is run by a threshold network, which only publishes the signature of a round once
+/// its time has come.
+///
+/// With the `zeroize` feature enabled this type derives `Zeroize` and `ZeroizeOnDrop`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize, zeroize::ZeroizeOnDrop))]
+pub struct Beacon {
+    s: Scalar,
+}
+
+/// Timing parameters of the chain of a beacon, which relate rounds to UNIX timestamps (in
+/// seconds).
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct ChainInfo {
+    /// Time of the first round.
+    pub genesis_time: u64,
+
+    /// Number of seconds between two rounds.
+    pub period: NonZeroU64,
+}
+
+/// The compressed public key of the drand `quicknet` network.
+pub const QUICKNET_PUBLIC_KEY: [u8; PK_BYTES] = [
+    0x83, 0xcf, 0x0f, 0x28, 0x96, 0xad, 0xee, 0x7e, 0xb8, 0xb5, 0xf0, 0x1f, 0xca, 0xd3, 0x91, 0x22,
+    0x12, 0xc4, 0x37, 0xe0, 0x07, 0x3e, 0x91, 0x1f, 0xb9, 0x00, 0x22, 0xd3, 0xe7, 0x60, 0x18, 0x3c,
+    0x8c, 0x4b, 0x45, 0x0b, 0x6a, 0x0a, 0x6c, 0x3a, 0xc6, 0xa5, 0x77, 0x6a, 0x2d, 0x10, 0x64, 0x51,
+    0x0d, 0x1f, 0xec, 0x75, 0x8c, 0x92, 0x1c, 0xc2, 0x2b, 0x0e, 0x17, 0xe6, 0x3a, 0xaf, 0x4b, 0xcb,
+    0x5e, 0xd6, 0x63, 0x04, 0xde, 0x9c, 0xf8, 0x09, 0xbd, 0x27, 0x4c, 0xa7, 0x3b, 0xab, 0x4a, 0xf5,
+    0xa6, 0xe9, 0xc7, 0x6a, 0x4b, 0xc0, 0x9e, 0x76, 0xea, 0xe8, 0x99, 0x1e, 0xf5, 0xec, 0xe4, 0x5a,
+];
+
+/// The chain parameters of the drand `quicknet` network.
+pub const QUICKNET: ChainInfo = ChainInfo {
+    genesis_time: 1692803367,
+    period: NonZeroU64::new(3).unwrap(),
+};
+
+/// The timelock KEM that results by applying the Fujisaki-Okamoto transform to the Boneh-Franklin
+/// IBE, using the rounds of a BLS randomness beacon as identities.
+#[derive(Debug, Clone, Copy)]
+pub struct Tlock;
+
+/// Hashes a round to a point on G1, i.e. the message that the beacon signs.
+fn hash_to_point(round: u64) -> G1Affine {
+    hash_to_g1(&Sha256::digest(round.to_be_bytes()), DST).into()
+}
+
+/// Computes the coins G(m, round) of the Fujisaki-Okamoto transform.
+fn coins(m: &Gt, round: u64) -> [u8; 64] {
+    let mut pre_coins = [0u8; GT_BYTES + 8];
+    let (pm, pr) = mut_array_refs![&mut pre_coins, GT_BYTES, 8];
+
+    *pm = m.to_compressed();
+    *pr = round.to_be_bytes();
+
+    sha3_512(&pre_coins)
+}
+
+fn encrypt(pk: &PublicKey, round: u64, m: &Gt, coins: &[u8; 64]) -> CipherText {
+    let r = Scalar::from_bytes_wide(coins);
+
+    CipherText {
+        round,
+        u: (G2Affine::generator() * r).into(),
+        v: pairing(&hash_to_point(round), &pk.p) * r + m,
+    }
+}
+
+impl ChainInfo {
+    /// The time at which the signature of the round is published.
+    ///
+    /// Returns `None` if this time does not fit in a `u64`.
+    pub fn time_of(&self, round: u64) -> Option<u64> {
+        round
+            .saturating_sub(1)
+            .checked_mul(self.period.get())
+            .and_then(|offset| self.genesis_time.checked_add(offset))
+    }
+
+    /// The first round of which the signature is published at or after the given time. Encapsulate
+    /// for this round to make the shared secret available from that time onwards.
+    ///
+    /// Returns `None` if this round does not fit in a `u64`.
+    pub fn unlock_round(&self, time: u64) -> Option<u64> {
+        time.saturating_sub(self.genesis_time)
+            .div_ceil(self.period.get())
+            .checked_add(1)
+    }
+}
+
+impl Beacon {
+    /// Create a beacon with a random secret key.
+    pub fn new<R: Rng + CryptoRng>(rng: &mut R) -> Self {
+        Beacon {
+            s: rand_scalar(rng),
+        }
+    }
+
+    /// The public key of the beacon.
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey {
+            p: (G2Affine::generator() * self.s).into(),
+        }
+    }
+
+    /// Sign a round, as the beacon would publish it.
+    pub fn sign(&self, round: u64) -> Signature {
+        Signature {
+            sig: (hash_to_point(round) * self.s).into(),
+        }
+    }
+}
+
+impl TLKEM for Tlock {
+    const IDENTIFIER: &'static str = "tlock";
+
+    type Pk = PublicKey;
+    type Sig = Signature;
+    type Ct = CipherText;
+
+    const PK_BYTES: usize = PK_BYTES;
+    const SIG_BYTES: usize = SIG_BYTES;
+    const CT_BYTES: usize = CT_BYTES;
+
+    /// Verify the signature by checking that e(sig, g2) = e(H(round), P).
+    fn verify(pk: &PublicKey, round: u64, sig: &Signature) -> bool {
+        multi_miller_loop(&[
+            (&sig.sig, &G2Prepared::from(-G2Affine::generator())),
+            (&hash_to_point(round), &G2Prepared::from(pk.p)),
+        ])
+        .final_exponentiation()
+            == Gt::identity()
+    }
+
+    fn encaps<R: Rng + CryptoRng>(
+        pk: &PublicKey,
+        round: u64,
+        rng: &mut R,
+    ) -> (CipherText, SharedSecret) {
+        let m = Gt::random(rng);
+        let ct = encrypt(pk, round, &m, &coins(&m, round));
+
+        (ct, SharedSecret::from(&m))
+    }
+
+    fn round(ct: &CipherText) -> u64 {
+        ct.round
+    }
+
+    /// Decapsulate a shared secret from the ciphertext.
+    ///
+    /// # Errors
+    ///
+    /// This function returns an [`Error`] if the signature of the beacon on the round of the
+    /// ciphertext is invalid, or when an illegitimate ciphertext is encountered (explicit
+    /// rejection).
+    fn decaps(pk: &PublicKey, sig: &Signature, ct: &CipherText) -> Result<SharedSecret, Error> {
+        if !Self::verify(pk, ct.round, sig) {
+            return Err(Error);
+        }
+
+        let m = ct.v - pairing(&sig.sig, &ct.u);
+        let ct2 = encrypt(pk, ct.round, &m, &coins(&m, ct.round));
+
+        // Can save some time by not doing a constant-time comparison
+        // since we can leak whether the decapsulation succeeds/fails.
+        if ct.ct_eq(&ct2).into() {
+            Ok(SharedSecret::from(&m))
+        } else {
+            Err(Error)
+        }
+    }
+}
+
+impl Compress for PublicKey {
+    const OUTPUT_SIZE: usize = PK_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; PK_BYTES] {
+        self.p.to_compressed()
+    }
+
+    fn from_bytes(bytes: &[u8; PK_BYTES]) -> CtOption<Self> {
+        G2Affine::from_compressed(bytes).map(|p| PublicKey { p })
+    }
+}
+
+impl Compress for Signature {
+    const OUTPUT_SIZE: usize = SIG_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; SIG_BYTES] {
+        self.sig.to_compressed()
+    }
+
+    fn from_bytes(bytes: &[u8; SIG_BYTES]) -> CtOption<Self> {
+        G1Affine::from_compressed(bytes).map(|sig| Signature { sig })
+    }
+}
+
+impl Compress for CipherText {
+    const OUTPUT_SIZE: usize = CT_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; CT_BYTES] {
+        let mut res = [0u8; CT_BYTES];
+        let (round, u, v) = mut_array_refs![&mut res, 8, G2_BYTES, GT_BYTES];
+
+        *round = self.round.to_be_bytes();
+        *u = self.u.to_compressed();
+        *v = self.v.to_compressed();
+
+        res
+    }
+
+    fn from_bytes(bytes: &[u8; CT_BYTES]) -> CtOption<Self> {
+        let (round, u, v) = array_refs![bytes, 8, G2_BYTES, GT_BYTES];
+
+        // Use the checked `from_compressed` variant, which verifies that the
+        // point is in the correct prime-order subgroup (GHSA-25fp-2fjj-g84w).
+        let round = u64::from_be_bytes(*round);
+        let u = G2Affine::from_compressed(u);
+        let v = Gt::from_compressed(v);
+
+        u.and_then(|u| v.map(|v| CipherText { round, u, v }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn eq_encaps_decaps() {
+        let mut rng = rand::thread_rng();
+        let beacon = Beacon::new(&mut rng);
+        let pk = beacon.public_key();
+
+        let (ct, k) = Tlock::encaps(&pk, 1000, &mut rng);
+        let sig = beacon.sign(Tlock::round(&ct));
+
+        assert!(Tlock::verify(&pk, 1000, &sig));
+        assert_eq!(Tlock::decaps(&pk, &sig, &ct).unwrap(), k);
+    }
+
+    #[test]
+    fn decaps_rejects_invalid_signatures() {
+        let mut rng = rand::thread_rng();
+        let beacon = Beacon::new(&mut rng);
+        let other = Beacon::new(&mut rng);
+        let pk = beacon.public_key();
+
+        let (ct, _) = Tlock::encaps(&pk, 1000, &mut rng);
+
+        for sig in [beacon.sign(999), other.sign(1000), Signature::default()] {
+            assert!(!Tlock::verify(&pk, 1000, &sig));
+            assert!(Tlock::decaps(&pk, &sig, &ct).is_err());
+        }
+    }
+
+    #[test]
+    fn unlock_round_is_published_after_time() {
+        assert_eq!(QUICKNET.unlock_round(0), Some(1));
+        assert_eq!(QUICKNET.time_of(1), Some(QUICKNET.genesis_time));
+
+        // 1 January 2030, 00:00:00 UTC.
+        let time = 1893456000;
+        let round = QUICKNET.unlock_round(time).unwrap();
+        let unlock = QUICKNET.time_of(round).unwrap();
+
+        assert!(unlock >= time);
+        assert!(QUICKNET.time_of(round - 1).unwrap() < time);
+        assert_eq!(QUICKNET.unlock_round(unlock), Some(round));
+    }
+
+    #[test]
+    fn chain_info_does_not_overflow() {
+        assert_eq!(QUICKNET.time_of(u64::MAX), None);
+
+        let chain = ChainInfo {
+            genesis_time: 0,
+            period: NonZeroU64::MIN,
+        };
+        assert_eq!(chain.time_of(u64::MAX), Some(u64::MAX - 1));
+        assert_eq!(chain.unlock_round(u64::MAX), None);
+    }
+
+    // Test vectors of RFC 9380, appendix J.9.1 (BLS12381G1_XMD:SHA-256_SSWU_RO_), which is the
+    // hash-to-curve suite that drand uses to hash rounds to G1 (with another DST).
+    #[test]
+    fn hash_to_g1_matches_rfc9380() {
+        const RFC_DST: &[u8] = b"QUUX-V01-CS02-with-BLS12381G1_XMD:SHA-256_SSWU_RO_";
+
+        let vectors: [(&[u8], [u8; 2 * G1_BYTES]); 2] = [
+            (
+                b"",
+                [
+                    0x05, 0x29, 0x26, 0xad, 0xd2, 0x20, 0x7b, 0x76, 0xca, 0x4f, 0xa5, 0x7a, 0x87,
+                    0x34, 0x41, 0x6c, 0x8d, 0xc9, 0x5e, 0x24, 0x50, 0x17, 0x72, 0xc8, 0x14, 0x27,
+                    0x87, 0x00, 0xee, 0xd6, 0xd1, 0xe4, 0xe8, 0xcf, 0x62, 0xd9, 0xc0, 0x9d, 0xb0,
+                    0xfa, 0xc3, 0x49, 0x61, 0x2b, 0x75, 0x9e, 0x79, 0xa1, 0x08, 0xba, 0x73, 0x84,
+                    0x53, 0xbf, 0xed, 0x09, 0xcb, 0x54, 0x6d, 0xbb, 0x07, 0x83, 0xdb, 0xb3, 0xa5,
+                    0xf1, 0xf5, 0x66, 0xed, 0x67, 0xbb, 0x6b, 0xe0, 0xe8, 0xc6, 0x7e, 0x2e, 0x81,
+                    0xa4, 0xcc, 0x68, 0xee, 0x29, 0x81, 0x3b, 0xb7, 0x99, 0x49, 0x98, 0xf3, 0xea,
+                    0xe0, 0xc9, 0xc6, 0xa2, 0x65,
+                ],
+            ),
+            (
+                b"abc",
+                [
+                    0x03, 0x56, 0x7b, 0xc5, 0xef, 0x9c, 0x69, 0x0c, 0x2a, 0xb2, 0xec, 0xdf, 0x6a,
+                    0x96, 0xef, 0x1c, 0x13, 0x9c, 0xc0, 0xb2, 0xf2, 0x84, 0xdc, 0xa0, 0xa9, 0xa7,
+                    0x94, 0x33, 0x88, 0xa4, 0x9a, 0x3a, 0xee, 0x66, 0x4b, 0xa5, 0x37, 0x9a, 0x76,
+                    0x55, 0xd3, 0xc6, 0x89, 0x00, 0xbe, 0x2f, 0x69, 0x03, 0x0b, 0x9c, 0x15, 0xf3,
+                    0xfe, 0x6e, 0x5c, 0xf4, 0x21, 0x1f, 0x34, 0x62, 0x71, 0xd7, 0xb0, 0x1c, 0x8f,
+                    0x3b, 0x28, 0xbe, 0x68, 0x9c, 0x84, 0x29, 0xc8, 0x5b, 0x67, 0xaf, 0x21, 0x55,
+                    0x33, 0x31, 0x1f, 0x0b, 0x8d, 0xfa, 0xaa, 0x15, 0x4f, 0xa6, 0xb8, 0x81, 0x76,
+                    0xc2, 0x29, 0xf2, 0x88, 0x5d,
+                ],
+            ),
+        ];
+
+        for (msg, expected) in vectors {
+            let p = G1Affine::from(hash_to_g1(msg, RFC_DST));
+            assert_eq!(p.to_uncompressed().as_ref(), &expected[..]);
+        }
+    }
+
+    #[test]
+    fn quicknet_known_answer() {
+        // The signature of round 1000, as published by the drand `quicknet` network.
+        let sig = Signature::from_bytes(&[
+            0xb4, 0x46, 0x79, 0xb9, 0xa5, 0x9a, 0xf2, 0xec, 0x87, 0x6b, 0x1a, 0x6b, 0x1a, 0xd5,
+            0x2e, 0xa9, 0xb1, 0x61, 0x5f, 0xc3, 0x98, 0x2b, 0x19, 0x57, 0x63, 0x50, 0xf9, 0x34,
+            0x47, 0xcb, 0x11, 0x25, 0xe3, 0x42, 0xb7, 0x3a, 0x8d, 0xd2, 0xba, 0xcb, 0xe4, 0x7e,
+            0x4b, 0x6b, 0x63, 0xed, 0x5e, 0x39,
+        ])
+        .unwrap();
+
+        let pk = PublicKey::from_bytes(&QUICKNET_PUBLIC_KEY).unwrap();
+        assert_eq!(pk.to_bytes(), QUICKNET_PUBLIC_KEY);
+
+        assert!(Tlock::verify(&pk, 1000, &sig));
+        assert!(!Tlock::verify(&pk, 1001, &sig));
+
+        let mut rng = rand::thread_rng();
+        let (ct, k) = Tlock::encaps(&pk, 1000, &mut rng);
+        assert_eq!(Tlock::decaps(&pk, &sig, &ct).unwrap(), k);
+    }
+
+    #[test]
+    fn eq_serialize_deserialize() {
+        let mut rng = rand::thread_rng();
+        let beacon = Beacon::new(&mut rng);
+        let pk = beacon.public_key();
+
+        let (ct, _) = Tlock::encaps(&pk, 1000, &mut rng);
+        let sig = beacon.sign(1000);
+
+        assert_eq!(pk, PublicKey::from_bytes(&pk.to_bytes()).unwrap());
+        assert_eq!(sig, Signature::from_bytes(&sig.to_bytes()).unwrap());
+        assert_eq!(ct, CipherText::from_bytes(&ct.to_bytes()).unwrap());
+
+        let mut bytes = sig.to_bytes();
+        bytes.copy_from_slice(&NON_SUBGROUP_G1_COMPRESSED);
+        assert!(bool::from(Signature::from_bytes(&bytes).is_none()));
+    }
+}
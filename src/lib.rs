@@ -1,13 +1,38 @@
 //! Collection of Identity Based Encryption (IBE) schemes on the [BLS12-381 pairing-friendly elliptic curve](https://github.com/zkcrypto/bls12_381) in Rust.
 //! This crate contains both identity-based encryption schemes (IBEs, see [the ibe module](`crate::ibe`)) and identity-based key encapsulation mechanisms (IBKEMs, see [the kem module](`crate::kem`)).
+//! Attribute-based schemes can be found in the `abe` module, identity-based signature schemes
+//! in the `ibs` module, identity-based non-interactive key exchange schemes in the `nike`
+//! module, and identity-based authenticated key exchange protocols in the `ake` module.
 //! References to papers appear in the respective source files.
 //!
 //! This crate contains the following schemes (in chronological order of publication):
+//! * Sakai-Ohgishi-Kasahara (ID-NIKE),
+//! * Boneh-Franklin (IND-ID-CPA IBE, IND-ID-CCA2 IBKEM),
+//! * Sakai-Kasahara (IND-ID-CCA2 IBKEM),
+//! * Cha-Cheon (EUF-ID-CMA IBS),
+//! * Smart-Chen-Kudla (ID-AKE with forward secrecy),
+//! * Al-Riyami-Paterson (IND-CCA2 certificateless KEM),
+//! * Boneh-Boyen BB1 (IND-sID-CPA IBE, IND-sID-CCA2 IBKEM),
+//! * Boneh-Boyen-Goh (IND-sID-CPA HIBE, constant size ciphertext, IND-sID-CCA2 forward-secure
+//!   IBKEM),
 //! * Waters (IND-ID-CPA IBE),
+//! * Sahai-Waters (IND-sID-CPA fuzzy IBE),
+//! * Barreto-Libert-McCullagh-Quisquater (IND-CCA2 and EUF-CMA signcryption KEM),
+//! * Abdalla-Catalano-Dent-Malone-Lee-Neven-Smart BBG-WIBE (IND-sWID-CPA WIBE),
+//! * Goyal-Pandey-Sahai-Waters (IND-sAtt-CPA KP-ABE, IND-sAtt-CCA2 ABKEM),
 //! * Boyen-Waters (IND-sID-CPA IBE),
+//! * Boyen-Waters anonymous HIBE (IND-sID-CPA HIBE),
 //! * Waters-Naccache (IND-ID-CPA IBE),
+//! * Green-Ateniese (IND-ID-CPA IB-PRE),
+//! * Goyal (IND-ID-CPA accountable-authority IBE),
+//! * Oblivious key oracle for Boneh-Franklin (grants a key for any identity),
+//! * Delerablée (IND-sID-CCA2 IBBKEM, constant size ciphertext),
+//! * Boldyreva-Goyal-Kumar (IND-sRID-CPA revocable IBE),
 //! * Kiltz-Vahlis IBE1 (IND-CCA2 IBKEM),
-//! * Chen-Gay-Wee (IND-ID-CPA IBE, IND-ID-CCA2 IBKEM).
+//! * Chen-Gay-Wee (IND-ID-CPA IBE, IND-ID-CCA2 IBKEM, IND-CPA IPE, IND-CCA2 IP-KEM),
+//! * Green-Miers (IND-PUN-CCA puncturable KEM),
+//! * Agrawal-Chase FAME (IND-CPA CP-ABE, IND-CCA2 ABKEM),
+//! * Gailly-Melissaris-Romailler tlock (IND-ID-CCA2 timelock KEM).
 //!
 //! # Examples
 //!
@@ -99,6 +124,26 @@ mod util;
 pub mod ibe;
 pub mod kem;
 
+#[cfg(feature = "abe")]
+#[cfg_attr(docsrs, doc(cfg(feature = "abe")))]
+pub mod abe;
+
+#[cfg(feature = "ake")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ake")))]
+pub mod ake;
+
+#[cfg(feature = "ibs")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ibs")))]
+pub mod ibs;
+
+#[cfg(feature = "nike")]
+#[cfg_attr(docsrs, doc(cfg(feature = "nike")))]
+pub mod nike;
+
+#[cfg(feature = "pe")]
+#[cfg_attr(docsrs, doc(cfg(feature = "pe")))]
+pub mod pe;
+
 /// Artifacts of the system that can be compressed should implement this trait.
 ///
 /// Secret artifacts such as the master secret key, user secret key should implement this in
@@ -0,0 +1,63 @@
+//! This module contains identity-based non-interactive key exchange schemes (ID-NIKEs).
+//!
+//! Two holders of a user secret key derive the same [`SharedSecret`] from their own user secret
+//! key and the identity of the other party, without exchanging any messages. Identities are
+//! derived using [`Derive`], like the identities of the identity-based encryption schemes.
+//!
+//! The shared secret is static: the same pair of identities always results in the same shared
+//! secret, as long as the master key pair of the PKG does not change.
+//!
+//! Among the schemes are:
+//!
+//! - Sakai-Ohgishi-Kasahara.
+
+#[cfg(feature = "sok")]
+#[cfg_attr(docsrs, doc(cfg(feature = "sok")))]
+pub mod sakai_ohgishi_kasahara;
+
+use crate::kem::SharedSecret;
+use crate::{Compress, Derive};
+use rand::{CryptoRng, Rng};
+
+/// Identity-based non-interactive key exchange (ID-NIKE).
+pub trait NIKE {
+    /// Master public key (Mpk).
+    type Pk: Compress;
+
+    /// Master secret key (Msk).
+    type Sk: Compress;
+
+    /// User secret key (Usk), used to derive shared secrets.
+    type Usk: Compress;
+
+    /// Internal identity type (Id).
+    type Id: Copy + Derive;
+
+    /// Size of the master public key in bytes.
+    const PK_BYTES: usize;
+
+    /// Size of the master secret key in bytes.
+    const SK_BYTES: usize;
+
+    /// Size of the user secret key in bytes.
+    const USK_BYTES: usize;
+
+    /// Creates an MSK, MPK pair.
+    fn setup<R: Rng + CryptoRng>(rng: &mut R) -> (Self::Pk, Self::Sk);
+
+    /// Extract a user secret key for an identity using the MSK.
+    ///
+    /// Optionally requires the system's public key.
+    fn extract_usk<R: Rng + CryptoRng>(
+        pk: Option<&Self::Pk>,
+        sk: &Self::Sk,
+        id: &Self::Id,
+        rng: &mut R,
+    ) -> Self::Usk;
+
+    /// Derive the shared secret between the owner of a user secret key and another identity.
+    ///
+    /// The owner of the user secret key for `other` derives the same shared secret using the
+    /// identity of the owner of `usk`.
+    fn shared_secret(usk: &Self::Usk, other: &Self::Id) -> SharedSecret;
+}
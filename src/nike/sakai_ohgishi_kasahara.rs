@@ -0,0 +1,242 @@
+//! Identity-based non-interactive key exchange by Sakai, Ohgishi and Kasahara (SOK).
+//! * From: "Cryptosystems based on pairing"
+//! * Published in: SCIS, 2000
+//!
+//! The original scheme uses a symmetric pairing, such that both parties compute
+//! e(s·H(A), H(B)) = e(H(A), s·H(B)). BLS12-381 has an asymmetric pairing, so identities are
+//! hashed to both G1 and G2 using the hash-to-curve construction of RFC 9380, and a user secret
+//! key contains s·H1(id) and s·H2(id). The identity that is smallest in byte order takes the
+//! place of the G1 argument, such that both parties compute e(H1(A), H2(B))^s for A ≤ B.
+//!
+//! The target group element is turned into a [`SharedSecret`] using SHAKE256.
+
+use crate::kem::SharedSecret;
+use crate::nike::NIKE;
+use crate::util::*;
+use crate::Compress;
+use arrayref::{array_refs, mut_array_refs};
+use pg_curve::{pairing, G1Affine, G2Affine, Scalar};
+use rand::{CryptoRng, Rng};
+use subtle::CtOption;
+
+/// Domain separation tag used to hash identities to G1.
+const DST_G1: &[u8] = b"NIKE_SOK_BLS12381G1_XMD:SHA-256_SSWU_RO_";
+
+/// Domain separation tag used to hash identities to G2.
+const DST_G2: &[u8] = b"NIKE_SOK_BLS12381G2_XMD:SHA-256_SSWU_RO_";
+
+/// Size of the compressed master public key in bytes.
+pub const PK_BYTES: usize = G2_BYTES;
+
+/// Size of the compressed master secret key in bytes.
+pub const SK_BYTES: usize = SCALAR_BYTES;
+
+/// Size of the compressed user secret key in bytes.
+///
+/// The USK includes the identity (needed to determine the order of the pairing arguments).
+pub const USK_BYTES: usize = ID_BYTES + G1_BYTES + G2_BYTES;
+
+/// Public key parameters generated by the PKG. Not required to derive shared secrets.
+/// Also known as P_pub.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct PublicKey {
+    ppub: G2Affine,
+}
+
+/// Secret key parameter generated by the PKG used to extract user secret keys.
+///
+/// # Zeroization
+///
+/// With the `zeroize` feature enabled this type derives `Zeroize` but **not**
+/// `ZeroizeOnDrop` (it is `Copy`). Secret material is **not** cleared on drop —
+/// you **MUST** call `.zeroize()` explicitly once done. See the
+/// [crate-level docs](crate#zeroizing-secret-material).
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize))]
+pub struct SecretKey {
+    s: Scalar,
+}
+
+/// User secret key. Can be used to derive shared secrets with other identities.
+///
+/// # Zeroization
+///
+/// With the `zeroize` feature enabled this type derives `Zeroize` but **not**
+/// `ZeroizeOnDrop` (it is `Copy`). Secret material is **not** cleared on drop —
+/// you **MUST** call `.zeroize()` explicitly once done. See the
+/// [crate-level docs](crate#zeroizing-secret-material).
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize))]
+pub struct UserSecretKey {
+    id: Identity,
+    d1: G1Affine,
+    d2: G2Affine,
+}
+
+/// The Sakai-Ohgishi-Kasahara identity-based non-interactive key exchange.
+#[derive(Debug)]
+pub struct SOK;
+
+impl NIKE for SOK {
+    type Pk = PublicKey;
+    type Sk = SecretKey;
+    type Usk = UserSecretKey;
+    type Id = Identity;
+
+    const PK_BYTES: usize = PK_BYTES;
+    const SK_BYTES: usize = SK_BYTES;
+    const USK_BYTES: usize = USK_BYTES;
+
+    /// Generate a keypair used by the Private Key Generator (PKG).
+    fn setup<R: Rng + CryptoRng>(rng: &mut R) -> (PublicKey, SecretKey) {
+        let s = rand_scalar(rng);
+        let ppub = (G2Affine::generator() * s).into();
+
+        (PublicKey { ppub }, SecretKey { s })
+    }
+
+    /// Extract a user secret key for a given identity.
+    fn extract_usk<R: Rng + CryptoRng>(
+        _opk: Option<&PublicKey>,
+        sk: &SecretKey,
+        id: &Identity,
+        _rng: &mut R,
+    ) -> UserSecretKey {
+        UserSecretKey {
+            id: *id,
+            d1: (hash_to_g1(&id.0, DST_G1) * sk.s).into(),
+            d2: (hash_to_g2(&id.0, DST_G2) * sk.s).into(),
+        }
+    }
+
+    /// Derive the shared secret e(H1(A), H2(B))^s, where A ≤ B.
+    fn shared_secret(usk: &UserSecretKey, other: &Identity) -> SharedSecret {
+        let k = if usk.id.0 <= other.0 {
+            pairing(&usk.d1, &hash_to_g2(&other.0, DST_G2).into())
+        } else {
+            pairing(&hash_to_g1(&other.0, DST_G1).into(), &usk.d2)
+        };
+
+        SharedSecret::from(&k)
+    }
+}
+
+impl Compress for PublicKey {
+    const OUTPUT_SIZE: usize = PK_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; PK_BYTES] {
+        self.ppub.to_compressed()
+    }
+
+    fn from_bytes(bytes: &[u8; PK_BYTES]) -> CtOption<Self> {
+        G2Affine::from_compressed(bytes).map(|ppub| PublicKey { ppub })
+    }
+}
+
+impl Compress for SecretKey {
+    const OUTPUT_SIZE: usize = SK_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; SK_BYTES] {
+        self.s.to_bytes()
+    }
+
+    fn from_bytes(bytes: &[u8; SK_BYTES]) -> CtOption<Self> {
+        Scalar::from_bytes(bytes).map(|s| SecretKey { s })
+    }
+}
+
+impl Compress for UserSecretKey {
+    const OUTPUT_SIZE: usize = USK_BYTES;
+    type Output = [u8; Self::OUTPUT_SIZE];
+
+    fn to_bytes(&self) -> [u8; USK_BYTES] {
+        let mut buf = [0u8; USK_BYTES];
+        let (id, d1, d2) = mut_array_refs![&mut buf, ID_BYTES, G1_BYTES, G2_BYTES];
+
+        *id = self.id.0;
+        *d1 = self.d1.to_compressed();
+        *d2 = self.d2.to_compressed();
+
+        buf
+    }
+
+    fn from_bytes(bytes: &[u8; USK_BYTES]) -> CtOption<Self> {
+        let (id, d1, d2) = array_refs![bytes, ID_BYTES, G1_BYTES, G2_BYTES];
+
+        // Use the checked `from_compressed` variant, which verifies that each point is in the
+        // correct prime-order subgroup (GHSA-25fp-2fjj-g84w).
+        let d1 = G1Affine::from_compressed(d1);
+        let d2 = G2Affine::from_compressed(d2);
+
+        d1.and_then(|d1| {
+            d2.map(|d2| UserSecretKey {
+                id: Identity(*id),
+                d1,
+                d2,
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Derive;
+
+    const ALICE: &str = "email:alice@example.com";
+    const BOB: &str = "email:bob@example.com";
+    const CHARLIE: &str = "email:charlie@example.com";
+
+    #[test]
+    fn eq_shared_secret() {
+        let mut rng = rand::thread_rng();
+        let (_, sk) = SOK::setup(&mut rng);
+
+        let (alice, bob) = (Identity::derive_str(ALICE), Identity::derive_str(BOB));
+        let usk_alice = SOK::extract_usk(None, &sk, &alice, &mut rng);
+        let usk_bob = SOK::extract_usk(None, &sk, &bob, &mut rng);
+
+        assert_eq!(
+            SOK::shared_secret(&usk_alice, &bob),
+            SOK::shared_secret(&usk_bob, &alice)
+        );
+    }
+
+    #[test]
+    fn shared_secret_depends_on_pair_and_master_key() {
+        let mut rng = rand::thread_rng();
+        let (_, sk) = SOK::setup(&mut rng);
+        let (_, other_sk) = SOK::setup(&mut rng);
+
+        let (alice, bob) = (Identity::derive_str(ALICE), Identity::derive_str(BOB));
+        let charlie = Identity::derive_str(CHARLIE);
+        let usk_alice = SOK::extract_usk(None, &sk, &alice, &mut rng);
+        let usk_charlie = SOK::extract_usk(None, &sk, &charlie, &mut rng);
+        let other_usk_alice = SOK::extract_usk(None, &other_sk, &alice, &mut rng);
+
+        let k = SOK::shared_secret(&usk_alice, &bob);
+
+        assert_ne!(k, SOK::shared_secret(&usk_alice, &charlie));
+        assert_ne!(k, SOK::shared_secret(&usk_charlie, &bob));
+        assert_ne!(k, SOK::shared_secret(&other_usk_alice, &bob));
+    }
+
+    #[test]
+    fn eq_serialize_deserialize() {
+        let mut rng = rand::thread_rng();
+        let (pk, sk) = SOK::setup(&mut rng);
+
+        let alice = Identity::derive_str(ALICE);
+        let usk = SOK::extract_usk(None, &sk, &alice, &mut rng);
+
+        assert_eq!(pk, PublicKey::from_bytes(&pk.to_bytes()).unwrap());
+        assert_eq!(sk, SecretKey::from_bytes(&sk.to_bytes()).unwrap());
+        assert_eq!(usk, UserSecretKey::from_bytes(&usk.to_bytes()).unwrap());
+
+        let mut bytes = usk.to_bytes();
+        bytes[ID_BYTES..ID_BYTES + G1_BYTES].copy_from_slice(&NON_SUBGROUP_G1_COMPRESSED);
+        assert!(bool::from(UserSecretKey::from_bytes(&bytes).is_none()));
+    }
+}
@@ -0,0 +1,676 @@
+//! Generic dual system predicate encryption by Chen, Gay and Wee.
+//! * From: "[Improved Dual System ABE in Prime-Order Groups via Predicate Encodings](https://link.springer.com/chapter/10.1007/978-3-540-79263-5_14)"
+//!
+//! This file contains the engine that compiles a [`PredicateEncoding`] into a passively secure
+//! predicate encryption scheme under the SXDH assumption (`k = 1`). The master key components
+//! `W_1, ..., W_N` are 2x2 matrices, all other vectors are of dimension two.
+//!
+//! The engine produces the blinding factor `e(g1, g2)^{s · k^T · A}` instead of encrypting a
+//! message, such that it can serve both as an encryption scheme and as a key encapsulation
+//! mechanism. See [`CGW`](crate::ibe::cgw::CGW) and [`CGWKV`](crate::kem::cgw_kv::CGWKV).
+//!
+//! The byte serialisation of all structures depends on the dimensions of the encoding and is
+//! returned as a vector.
+
+extern crate alloc;
+use alloc::vec::Vec;
+
+use crate::pe::PredicateEncoding;
+use crate::util::*;
+use crate::Compress;
+use core::convert::TryInto;
+use core::marker::PhantomData;
+use core::ops::Neg;
+use group::{WnafBase, WnafScalar};
+use pg_curve::{
+    multi_miller_loop, pairing, G1Affine, G1Projective, G2Affine, G2Prepared, G2Projective, Gt,
+    Scalar,
+};
+use rand::{CryptoRng, Rng};
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, CtOption};
+
+const WINDOW_SIZE: usize = 4;
+
+/// Master public key: `[A]_1`, `[W_i^T · A]_1` for every master key component and `[k^T · A]_T`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct PublicKey<const N: usize> {
+    pub(crate) a_1: [G1Affine; 2],
+    pub(crate) wta_1: [[G1Affine; 2]; N],
+    pub(crate) kta_t: Gt,
+}
+
+/// Master secret key: `B`, `k` and the master key components `W_i`.
+///
+/// # Zeroization
+///
+/// With the `zeroize` feature enabled this type derives `Zeroize` but **not**
+/// `ZeroizeOnDrop` (it is `Copy`). Secret material is **not** cleared on drop —
+/// you **MUST** call `.zeroize()` explicitly once done. See the
+/// [crate-level docs](crate#zeroizing-secret-material).
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize))]
+pub struct SecretKey<const N: usize> {
+    pub(crate) b: [Scalar; 2],
+    pub(crate) k: [Scalar; 2],
+    pub(crate) w: [[[Scalar; 2]; 2]; N],
+}
+
+/// User secret key: `[B · r]_2` and the `K` key components `[kE(y, k) - rE(y, W · B · r)]_2`.
+///
+/// # Zeroization
+///
+/// With the `zeroize` feature enabled this type derives `Zeroize` but **not**
+/// `ZeroizeOnDrop` (it is `Copy`). Secret material is **not** cleared on drop —
+/// you **MUST** call `.zeroize()` explicitly once done. See the
+/// [crate-level docs](crate#zeroizing-secret-material).
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "zeroize", derive(zeroize::Zeroize))]
+pub struct UserSecretKey<const K: usize> {
+    pub(crate) k0: [G2Affine; 2],
+    pub(crate) k: [[G2Affine; 2]; K],
+}
+
+/// Ciphertext: `[A · s]_1` and the `C` ciphertext components `[sE(x, W^T · A · s)]_1`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct CipherText<const C: usize> {
+    pub(crate) c0: [G1Affine; 2],
+    pub(crate) c: [[G1Affine; 2]; C],
+}
+
+/// The Chen-Gay-Wee engine for the predicate encoding `E`, with `N` master key components, `C`
+/// ciphertext components and `K` key components.
+#[derive(Debug)]
+pub struct CGWEngine<E, const N: usize, const C: usize, const K: usize>(PhantomData<E>);
+
+/// Computes `W^T · v`.
+fn mul_t(w: &[[Scalar; 2]; 2], v: &[Scalar; 2]) -> [Scalar; 2] {
+    [
+        w[0][0] * v[0] + w[1][0] * v[1],
+        w[0][1] * v[0] + w[1][1] * v[1],
+    ]
+}
+
+/// Computes `W · v`.
+fn mul(w: &[[Scalar; 2]; 2], v: &[Scalar; 2]) -> [Scalar; 2] {
+    [
+        w[0][0] * v[0] + w[0][1] * v[1],
+        w[1][0] * v[0] + w[1][1] * v[1],
+    ]
+}
+
+/// Computes the linear combination of vectors of points in G2.
+///
+/// The coefficients are public, which allows to skip the multiplications by one.
+fn combine_g2<const K: usize>(coeffs: &[Scalar; K], points: &[[G2Affine; 2]; K]) -> [G2Affine; 2] {
+    if let ([c], [p]) = (&coeffs[..], &points[..]) {
+        if *c == Scalar::one() {
+            return *p;
+        }
+    }
+
+    let mut acc = [G2Projective::identity(); 2];
+    for (c, p) in coeffs.iter().zip(points.iter()) {
+        for (acc, p) in acc.iter_mut().zip(p.iter()) {
+            *acc += if *c == Scalar::one() {
+                (*p).into()
+            } else {
+                p * c
+            };
+        }
+    }
+
+    let mut out = [G2Affine::default(); 2];
+    G2Projective::batch_normalize(&acc, &mut out);
+
+    out
+}
+
+/// Computes the linear combination of vectors of points in G1.
+///
+/// The coefficients are public, which allows to skip the multiplications by one.
+fn combine_g1<const C: usize>(coeffs: &[Scalar; C], points: &[[G1Affine; 2]; C]) -> [G1Affine; 2] {
+    if let ([c], [p]) = (&coeffs[..], &points[..]) {
+        if *c == Scalar::one() {
+            return *p;
+        }
+    }
+
+    let mut acc = [G1Projective::identity(); 2];
+    for (c, p) in coeffs.iter().zip(points.iter()) {
+        for (acc, p) in acc.iter_mut().zip(p.iter()) {
+            *acc += if *c == Scalar::one() {
+                (*p).into()
+            } else {
+                p * c
+            };
+        }
+    }
+
+    let mut out = [G1Affine::default(); 2];
+    G1Projective::batch_normalize(&acc, &mut out);
+
+    out
+}
+
+impl<E, const N: usize, const C: usize, const K: usize> CGWEngine<E, N, C, K>
+where
+    E: PredicateEncoding<N, C, K>,
+{
+    /// Generate a master keypair.
+    pub fn setup<R: Rng + CryptoRng>(rng: &mut R) -> (PublicKey<N>, SecretKey<N>) {
+        let g1 = G1Affine::generator();
+        let g2 = G2Affine::generator();
+
+        let a = [rand_scalar(rng), rand_scalar(rng)];
+        let b = [rand_scalar(rng), rand_scalar(rng)];
+
+        let mut w = [[[Scalar::zero(); 2]; 2]; N];
+        for wi in w.iter_mut() {
+            *wi = [
+                [rand_scalar(rng), rand_scalar(rng)],
+                [rand_scalar(rng), rand_scalar(rng)],
+            ];
+        }
+
+        let k = [rand_scalar(rng), rand_scalar(rng)];
+
+        let scalars = a.into_iter().chain(w.iter().flat_map(|wi| mul_t(wi, &a)));
+
+        let base = WnafBase::<_, WINDOW_SIZE>::new(G1Projective::generator());
+        let batch: Vec<G1Projective> = scalars
+            .map(|scalar| &base * &WnafScalar::<_, WINDOW_SIZE>::new(&scalar))
+            .collect();
+
+        let mut out = alloc::vec![G1Affine::default(); batch.len()];
+        G1Projective::batch_normalize(&batch, &mut out);
+
+        let mut wta_1 = [[G1Affine::default(); 2]; N];
+        for (wta, chunk) in wta_1.iter_mut().zip(out[2..].chunks_exact(2)) {
+            *wta = [chunk[0], chunk[1]];
+        }
+
+        let kta_t = pairing(&g1, &g2) * (k[0] * a[0] + k[1] * a[1]);
+
+        (
+            PublicKey {
+                a_1: [out[0], out[1]],
+                wta_1,
+                kta_t,
+            },
+            SecretKey { b, k, w },
+        )
+    }
+
+    /// Extract a user secret key for the key attribute `y`.
+    pub fn extract_usk<R: Rng + CryptoRng>(
+        sk: &SecretKey<N>,
+        y: &E::Y,
+        rng: &mut R,
+    ) -> UserSecretKey<K> {
+        let r = rand_scalar(rng);
+        let br = [sk.b[0] * r, sk.b[1] * r];
+
+        let mut wbr = [[Scalar::zero(); 2]; N];
+        for (wbr, wi) in wbr.iter_mut().zip(sk.w.iter()) {
+            *wbr = mul(wi, &br);
+        }
+
+        let (re, ke) = (E::r_e(y), E::k_e(y));
+        let components = re.iter().zip(ke.iter()).flat_map(|(re, ke)| {
+            (0..2).map(move |t| {
+                re.iter()
+                    .zip(wbr.iter())
+                    .fold(ke * sk.k[t], |acc, (coeff, wbr)| acc - coeff * wbr[t])
+            })
+        });
+
+        let base = WnafBase::<_, WINDOW_SIZE>::new(G2Projective::generator());
+        let batch: Vec<G2Projective> = br
+            .into_iter()
+            .chain(components)
+            .map(|scalar| &base * &WnafScalar::<_, WINDOW_SIZE>::new(&scalar))
+            .collect();
+
+        let mut out = alloc::vec![G2Affine::default(); batch.len()];
+        G2Projective::batch_normalize(&batch, &mut out);
+
+        let mut k = [[G2Affine::default(); 2]; K];
+        for (kj, chunk) in k.iter_mut().zip(out[2..].chunks_exact(2)) {
+            *kj = [chunk[0], chunk[1]];
+        }
+
+        UserSecretKey {
+            k0: [out[0], out[1]],
+            k,
+        }
+    }
+
+    /// Compute the first ciphertext component `[A · s]_1`, which is independent of the ciphertext
+    /// attribute.
+    pub fn encrypt_c0(pk: &PublicKey<N>, s: &Scalar) -> [G1Affine; 2] {
+        let mut out = [G1Affine::default(); 2];
+        G1Projective::batch_normalize(&[pk.a_1[0] * s, pk.a_1[1] * s], &mut out);
+
+        out
+    }
+
+    /// Compute the ciphertext components `[sE(x, W^T · A · s)]_1` for the ciphertext attribute `x`.
+    pub fn encrypt_c(pk: &PublicKey<N>, x: &E::X, s: &Scalar) -> [[G1Affine; 2]; C] {
+        let se = E::s_e(x);
+
+        let batch: Vec<G1Projective> = se
+            .iter()
+            .flat_map(|se| {
+                (0..2).map(move |t| {
+                    se.iter()
+                        .zip(pk.wta_1.iter())
+                        .filter(|(coeff, _)| **coeff != Scalar::zero())
+                        .fold(G1Projective::identity(), |acc, (coeff, wta)| {
+                            acc + wta[t] * (s * coeff)
+                        })
+                })
+            })
+            .collect();
+
+        let mut out = alloc::vec![G1Affine::default(); batch.len()];
+        G1Projective::batch_normalize(&batch, &mut out);
+
+        let mut c = [[G1Affine::default(); 2]; C];
+        for (cj, chunk) in c.iter_mut().zip(out.chunks_exact(2)) {
+            *cj = [chunk[0], chunk[1]];
+        }
+
+        c
+    }
+
+    /// The blinding factor `[k^T · A · s]_T` that belongs to the randomness `s`.
+    pub fn blinding(pk: &PublicKey<N>, s: &Scalar) -> Gt {
+        pk.kta_t * s
+    }
+
+    /// Encrypt under the ciphertext attribute `x` with randomness `s`.
+    ///
+    /// Returns the ciphertext and its blinding factor.
+    pub fn encrypt(pk: &PublicKey<N>, x: &E::X, s: &Scalar) -> (CipherText<C>, Gt) {
+        let ct = CipherText {
+            c0: Self::encrypt_c0(pk, s),
+            c: Self::encrypt_c(pk, x, s),
+        };
+
+        (ct, Self::blinding(pk, s))
+    }
+
+    /// Recover the blinding factor of a ciphertext using a user secret key.
+    ///
+    /// The decoding coefficients are those of `sD(x, y)` and `rD(x, y)`, respectively. The result
+    /// is only meaningful if the predicate holds for the attributes of the ciphertext and the key.
+    pub fn decrypt(
+        usk: &UserSecretKey<K>,
+        ct: &CipherText<C>,
+        s_d: &[Scalar; C],
+        r_d: &[Scalar; K],
+    ) -> Gt {
+        let k = combine_g2(r_d, &usk.k);
+        let c = combine_g1(s_d, &ct.c);
+
+        multi_miller_loop(&[
+            (&ct.c0[0], &G2Prepared::from(k[0])),
+            (&ct.c0[1], &G2Prepared::from(k[1])),
+            (&c[0], &G2Prepared::from(usk.k0[0])),
+            (&c[1], &G2Prepared::from(usk.k0[1])),
+        ])
+        .final_exponentiation()
+    }
+}
+
+impl<const N: usize> PublicKey<N> {
+    /// Size of the compressed master public key in bytes.
+    pub const BYTES: usize = (2 + 2 * N) * G1_BYTES + GT_BYTES;
+
+    /// Writes the compressed representation to the first `BYTES` bytes of `out`.
+    pub(crate) fn write_bytes(&self, out: &mut [u8]) {
+        let g1s = self.a_1.iter().chain(self.wta_1.iter().flatten());
+        for (el, chunk) in g1s.zip(out.chunks_exact_mut(G1_BYTES)) {
+            chunk.copy_from_slice(&el.to_compressed());
+        }
+        out[(2 + 2 * N) * G1_BYTES..].copy_from_slice(&self.kta_t.to_compressed());
+    }
+
+    /// Reads the compressed representation, returning whether it is valid.
+    pub(crate) fn read_bytes(bytes: &[u8]) -> (Self, Choice) {
+        let mut res = PublicKey::default();
+
+        // Use the checked `from_compressed` variant, which verifies that each
+        // point is in the correct prime-order subgroup. Public keys may be
+        // deserialized from an unauthenticated source before their integrity is
+        // verified out-of-band; skipping the subgroup check would let an
+        // adversary embed a point outside the prime-order subgroup (BLS12-381
+        // has a large, non-trivial cofactor) and mount a small-subgroup attack
+        // (GHSA-25fp-2fjj-g84w).
+        let (g1s, gt) = bytes.split_at((2 + 2 * N) * G1_BYTES);
+
+        let mut is_some = Gt::from_compressed(gt.try_into().unwrap())
+            .map(|el| res.kta_t = el)
+            .is_some();
+
+        let slots = res.a_1.iter_mut().chain(res.wta_1.iter_mut().flatten());
+        for (slot, chunk) in slots.zip(g1s.chunks_exact(G1_BYTES)) {
+            is_some &= G1Affine::from_compressed(chunk.try_into().unwrap())
+                .map(|el| *slot = el)
+                .is_some();
+        }
+
+        (res, is_some)
+    }
+}
+
+impl<const N: usize> SecretKey<N> {
+    /// Size of the compressed master secret key in bytes.
+    pub const BYTES: usize = (4 + 4 * N) * SCALAR_BYTES;
+
+    /// Writes the compressed representation to the first `BYTES` bytes of `out`.
+    pub(crate) fn write_bytes(&self, out: &mut [u8]) {
+        let scalars = self
+            .b
+            .iter()
+            .chain(self.k.iter())
+            .chain(self.w.iter().flatten().flatten());
+        for (s, chunk) in scalars.zip(out.chunks_exact_mut(SCALAR_BYTES)) {
+            chunk.copy_from_slice(&s.to_bytes());
+        }
+    }
+
+    /// Reads the compressed representation, returning whether it is valid.
+    pub(crate) fn read_bytes(bytes: &[u8]) -> (Self, Choice) {
+        let mut res = SecretKey::default();
+
+        let mut is_some = Choice::from(1u8);
+        let slots = res
+            .b
+            .iter_mut()
+            .chain(res.k.iter_mut())
+            .chain(res.w.iter_mut().flatten().flatten());
+        for (slot, chunk) in slots.zip(bytes.chunks_exact(SCALAR_BYTES)) {
+            is_some &= Scalar::from_bytes(chunk.try_into().unwrap())
+                .map(|s| *slot = s)
+                .is_some();
+        }
+
+        (res, is_some)
+    }
+}
+
+impl<const K: usize> UserSecretKey<K> {
+    /// Size of the compressed user secret key in bytes.
+    pub const BYTES: usize = (2 + 2 * K) * G2_BYTES;
+
+    /// Writes the compressed representation to the first `BYTES` bytes of `out`.
+    pub(crate) fn write_bytes(&self, out: &mut [u8]) {
+        let g2s = self.k0.iter().chain(self.k.iter().flatten());
+        for (el, chunk) in g2s.zip(out.chunks_exact_mut(G2_BYTES)) {
+            chunk.copy_from_slice(&el.to_compressed());
+        }
+    }
+
+    /// Reads the compressed representation, returning whether it is valid.
+    pub(crate) fn read_bytes(bytes: &[u8]) -> (Self, Choice) {
+        let mut res = UserSecretKey::default();
+
+        let mut is_some = Choice::from(1u8);
+        let slots = res.k0.iter_mut().chain(res.k.iter_mut().flatten());
+        for (slot, chunk) in slots.zip(bytes.chunks_exact(G2_BYTES)) {
+            is_some &= G2Affine::from_compressed(chunk.try_into().unwrap())
+                .map(|el| *slot = el)
+                .is_some();
+        }
+
+        (res, is_some)
+    }
+
+    /// Samples a uniformly random user secret key, used for threshold decapsulation.
+    #[cfg(all(feature = "threshold", any(feature = "cgwkv", feature = "cgwfo")))]
+    pub(crate) fn random<R: Rng + CryptoRng>(rng: &mut R) -> Self {
+        let mut res = UserSecretKey::default();
+        for slot in res.k0.iter_mut().chain(res.k.iter_mut().flatten()) {
+            *slot = rand_g2(rng).into();
+        }
+
+        res
+    }
+
+    /// Computes `self * x + other` componentwise, used for threshold decapsulation.
+    #[cfg(all(feature = "threshold", any(feature = "cgwkv", feature = "cgwfo")))]
+    pub(crate) fn mul_add(&self, x: &Scalar, other: &Self) -> Self {
+        let mut res = *other;
+        let slots = res.k0.iter_mut().chain(res.k.iter_mut().flatten());
+        let els = self.k0.iter().chain(self.k.iter().flatten());
+        for (slot, el) in slots.zip(els) {
+            *slot = (el * x + *slot).into();
+        }
+
+        res
+    }
+}
+
+impl<const C: usize> CipherText<C> {
+    /// Size of the compressed ciphertext in bytes.
+    pub const BYTES: usize = (2 + 2 * C) * G1_BYTES;
+
+    /// Writes the compressed representation to the first `BYTES` bytes of `out`.
+    pub(crate) fn write_bytes(&self, out: &mut [u8]) {
+        let g1s = self.c0.iter().chain(self.c.iter().flatten());
+        for (el, chunk) in g1s.zip(out.chunks_exact_mut(G1_BYTES)) {
+            chunk.copy_from_slice(&el.to_compressed());
+        }
+    }
+
+    /// Reads the compressed representation, returning whether it is valid.
+    pub(crate) fn read_bytes(bytes: &[u8]) -> (Self, Choice) {
+        let mut res = CipherText::default();
+
+        let mut is_some = Choice::from(1u8);
+        let slots = res.c0.iter_mut().chain(res.c.iter_mut().flatten());
+        for (slot, chunk) in slots.zip(bytes.chunks_exact(G1_BYTES)) {
+            is_some &= G1Affine::from_compressed(chunk.try_into().unwrap())
+                .map(|el| *slot = el)
+                .is_some();
+        }
+
+        (res, is_some)
+    }
+}
+
+impl<const K: usize> Neg for UserSecretKey<K> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        let mut res = self;
+        for el in res.k0.iter_mut().chain(res.k.iter_mut().flatten()) {
+            *el = -*el;
+        }
+
+        res
+    }
+}
+
+impl<const N: usize> Default for PublicKey<N> {
+    fn default() -> Self {
+        PublicKey {
+            a_1: [G1Affine::default(); 2],
+            wta_1: [[G1Affine::default(); 2]; N],
+            kta_t: Gt::default(),
+        }
+    }
+}
+
+impl<const N: usize> Default for SecretKey<N> {
+    fn default() -> Self {
+        SecretKey {
+            b: [Scalar::default(); 2],
+            k: [Scalar::default(); 2],
+            w: [[[Scalar::default(); 2]; 2]; N],
+        }
+    }
+}
+
+impl<const K: usize> Default for UserSecretKey<K> {
+    fn default() -> Self {
+        UserSecretKey {
+            k0: [G2Affine::default(); 2],
+            k: [[G2Affine::default(); 2]; K],
+        }
+    }
+}
+
+impl<const C: usize> Default for CipherText<C> {
+    fn default() -> Self {
+        CipherText {
+            c0: [G1Affine::default(); 2],
+            c: [[G1Affine::default(); 2]; C],
+        }
+    }
+}
+
+impl<const C: usize> ConstantTimeEq for CipherText<C> {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.c
+            .iter()
+            .zip(other.c.iter())
+            .fold(self.c0[..].ct_eq(&other.c0[..]), |acc, (a, b)| {
+                acc & a[..].ct_eq(&b[..])
+            })
+    }
+}
+
+impl<const C: usize> ConditionallySelectable for CipherText<C> {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        let mut res = *a;
+        let slots = res.c0.iter_mut().chain(res.c.iter_mut().flatten());
+        let others = b.c0.iter().chain(b.c.iter().flatten());
+        for (slot, other) in slots.zip(others) {
+            slot.conditional_assign(other, choice);
+        }
+
+        res
+    }
+}
+
+impl<const K: usize> ConditionallySelectable for UserSecretKey<K> {
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        let mut res = *a;
+        let slots = res.k0.iter_mut().chain(res.k.iter_mut().flatten());
+        let others = b.k0.iter().chain(b.k.iter().flatten());
+        for (slot, other) in slots.zip(others) {
+            slot.conditional_assign(other, choice);
+        }
+
+        res
+    }
+}
+
+/// Implements `Compress` with a vector as output for a structure of the engine.
+macro_rules! impl_engine_compress {
+    ($struct: ident, $dim: ident) => {
+        impl<const $dim: usize> Compress for $struct<$dim> {
+            const OUTPUT_SIZE: usize = Self::BYTES;
+            type Output = Vec<u8>;
+
+            fn to_bytes(&self) -> Vec<u8> {
+                let mut res = alloc::vec![0u8; Self::BYTES];
+                self.write_bytes(&mut res);
+
+                res
+            }
+
+            fn from_bytes(bytes: &Vec<u8>) -> CtOption<Self> {
+                if bytes.len() != Self::BYTES {
+                    return CtOption::new(Self::default(), Choice::from(0));
+                }
+
+                let (res, is_some) = Self::read_bytes(bytes);
+                CtOption::new(res, is_some)
+            }
+        }
+    };
+}
+
+impl_engine_compress!(PublicKey, N);
+impl_engine_compress!(SecretKey, N);
+impl_engine_compress!(UserSecretKey, K);
+impl_engine_compress!(CipherText, C);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pe::encodings::{IbeEncoding, TaggedIbeEncoding};
+    use crate::Derive;
+
+    type IbeEngine = CGWEngine<IbeEncoding, 2, 1, 1>;
+    type TaggedEngine = CGWEngine<TaggedIbeEncoding, 3, 1, 2>;
+
+    #[test]
+    fn eq_blinding_decrypt() {
+        let mut rng = rand::thread_rng();
+        let alice = Identity::derive_str("alice@example.com");
+        let bob = Identity::derive_str("bob@example.com");
+
+        let (pk, sk) = IbeEngine::setup(&mut rng);
+        let usk_alice = IbeEngine::extract_usk(&sk, &alice, &mut rng);
+        let usk_bob = IbeEngine::extract_usk(&sk, &bob, &mut rng);
+
+        let s = rand_scalar(&mut rng);
+        let (ct, blinding) = IbeEngine::encrypt(&pk, &alice, &s);
+
+        let (sd, rd) = (
+            IbeEncoding::s_d(&alice, &alice),
+            IbeEncoding::r_d(&alice, &alice),
+        );
+        assert_eq!(blinding, IbeEngine::decrypt(&usk_alice, &ct, &sd, &rd));
+        assert_ne!(blinding, IbeEngine::decrypt(&usk_bob, &ct, &sd, &rd));
+    }
+
+    #[test]
+    fn eq_blinding_decrypt_tagged() {
+        let mut rng = rand::thread_rng();
+        let alice = Identity::derive_str("alice@example.com");
+        let x = (alice, rand_scalar(&mut rng));
+
+        let (pk, sk) = TaggedEngine::setup(&mut rng);
+        let usk = TaggedEngine::extract_usk(&sk, &alice, &mut rng);
+
+        let s = rand_scalar(&mut rng);
+        let (ct, blinding) = TaggedEngine::encrypt(&pk, &x, &s);
+
+        let (sd, rd) = (
+            TaggedIbeEncoding::s_d(&x, &alice),
+            TaggedIbeEncoding::r_d(&x, &alice),
+        );
+        assert_eq!(blinding, TaggedEngine::decrypt(&usk, &ct, &sd, &rd));
+
+        // Decoding with another tag does not recover the blinding factor.
+        let (sd, rd) = (
+            TaggedIbeEncoding::s_d(&(alice, Scalar::one()), &alice),
+            TaggedIbeEncoding::r_d(&(alice, Scalar::one()), &alice),
+        );
+        assert_ne!(blinding, TaggedEngine::decrypt(&usk, &ct, &sd, &rd));
+    }
+
+    #[test]
+    fn eq_serialize_deserialize() {
+        let mut rng = rand::thread_rng();
+        let alice = Identity::derive_str("alice@example.com");
+
+        let (pk, sk) = TaggedEngine::setup(&mut rng);
+        let usk = TaggedEngine::extract_usk(&sk, &alice, &mut rng);
+        let (ct, _) = TaggedEngine::encrypt(&pk, &(alice, Scalar::one()), &rand_scalar(&mut rng));
+
+        assert_eq!(pk, PublicKey::from_bytes(&pk.to_bytes()).unwrap());
+        assert_eq!(sk, SecretKey::from_bytes(&sk.to_bytes()).unwrap());
+        assert_eq!(usk, UserSecretKey::from_bytes(&usk.to_bytes()).unwrap());
+        assert_eq!(ct, CipherText::from_bytes(&ct.to_bytes()).unwrap());
+
+        let mut bytes = pk.to_bytes();
+        bytes.pop();
+        assert!(bool::from(PublicKey::<3>::from_bytes(&bytes).is_none()));
+    }
+}
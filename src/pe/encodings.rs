@@ -0,0 +1,271 @@
+//! Predicate encodings for use with the [Chen-Gay-Wee engine](crate::pe::cgw).
+
+use crate::pe::PredicateEncoding;
+use crate::util::Identity;
+use pg_curve::Scalar;
+
+/// Encoding of the equality predicate, which yields identity-based encryption.
+///
+/// The master key consists of `(w0, w1)`, encoded as `w0 + id · w1` in both the ciphertext and the
+/// user secret key. The decoding does not depend on the identities.
+#[derive(Debug)]
+pub struct IbeEncoding;
+
+impl PredicateEncoding<2, 1, 1> for IbeEncoding {
+    type X = Identity;
+    type Y = Identity;
+
+    fn s_e(x: &Identity) -> [[Scalar; 2]; 1] {
+        [[Scalar::one(), x.to_scalar()]]
+    }
+
+    fn r_e(y: &Identity) -> [[Scalar; 2]; 1] {
+        [[Scalar::one(), y.to_scalar()]]
+    }
+
+    fn k_e(_y: &Identity) -> [Scalar; 1] {
+        [Scalar::one()]
+    }
+
+    fn s_d(_x: &Identity, _y: &Identity) -> [Scalar; 1] {
+        [Scalar::one()]
+    }
+
+    fn r_d(_x: &Identity, _y: &Identity) -> [Scalar; 1] {
+        [Scalar::one()]
+    }
+}
+
+/// Encoding of the equality predicate, where the ciphertext carries an additional tag.
+///
+/// The master key consists of `(w0, w1, w')`, encoded as `w0 + id · w1 + tag · w'` in the
+/// ciphertext. The user secret key contains `w0 + id · w1` and `w'` separately, such that it can
+/// decode a ciphertext for any tag. This is the structure used for the CCA transformation of
+/// Kiltz and Vahlis.
+#[derive(Debug)]
+pub struct TaggedIbeEncoding;
+
+impl PredicateEncoding<3, 1, 2> for TaggedIbeEncoding {
+    type X = (Identity, Scalar);
+    type Y = Identity;
+
+    fn s_e(x: &(Identity, Scalar)) -> [[Scalar; 3]; 1] {
+        [[Scalar::one(), x.0.to_scalar(), x.1]]
+    }
+
+    fn r_e(y: &Identity) -> [[Scalar; 3]; 2] {
+        [
+            [Scalar::one(), y.to_scalar(), Scalar::zero()],
+            [Scalar::zero(), Scalar::zero(), Scalar::one()],
+        ]
+    }
+
+    fn k_e(_y: &Identity) -> [Scalar; 2] {
+        [Scalar::one(), Scalar::zero()]
+    }
+
+    fn s_d(_x: &(Identity, Scalar), _y: &Identity) -> [Scalar; 1] {
+        [Scalar::one()]
+    }
+
+    fn r_d(x: &(Identity, Scalar), _y: &Identity) -> [Scalar; 2] {
+        [Scalar::one(), x.1]
+    }
+}
+
+/// Encoding of the zero inner product predicate `<x, y> = 0` for vectors of length `L`.
+///
+/// The master key consists of `(u, w_1, ..., w_L)`, encoded as `x_i · u + w_i` in the ciphertext
+/// and as `<y, w>` in the user secret key.
+///
+/// The number of master key components `N` must equal `L + 1`, which is checked at compile time.
+#[derive(Debug)]
+pub struct InnerProductEncoding<const L: usize>;
+
+impl<const L: usize, const N: usize> PredicateEncoding<N, L, 1> for InnerProductEncoding<L> {
+    type X = [Scalar; L];
+    type Y = [Scalar; L];
+
+    fn s_e(x: &[Scalar; L]) -> [[Scalar; N]; L] {
+        const { assert!(N == L + 1, "N must equal L + 1") };
+
+        let mut res = [[Scalar::zero(); N]; L];
+        for (i, (row, xi)) in res.iter_mut().zip(x.iter()).enumerate() {
+            row[0] = *xi;
+            row[1 + i] = Scalar::one();
+        }
+
+        res
+    }
+
+    fn r_e(y: &[Scalar; L]) -> [[Scalar; N]; 1] {
+        const { assert!(N == L + 1, "N must equal L + 1") };
+
+        let mut res = [Scalar::zero(); N];
+        res[1..].copy_from_slice(y);
+
+        [res]
+    }
+
+    fn k_e(_y: &[Scalar; L]) -> [Scalar; 1] {
+        [Scalar::one()]
+    }
+
+    fn s_d(_x: &[Scalar; L], y: &[Scalar; L]) -> [Scalar; L] {
+        *y
+    }
+
+    fn r_d(_x: &[Scalar; L], _y: &[Scalar; L]) -> [Scalar; 1] {
+        [Scalar::one()]
+    }
+}
+
+/// Encoding of the zero inner product predicate for vectors of length `L`, where the ciphertext
+/// carries an additional tag.
+///
+/// The master key consists of `(u, w_1, ..., w_L, w_0, w')`. The ciphertext contains
+/// `x_i · u + w_i` and an additional component `w_0 + tag · w'`. The user secret key contains
+/// `<y, w> + w_0` and `w'` separately, such that it can decode a ciphertext for any tag, like the
+/// [tagged IBE encoding](TaggedIbeEncoding).
+///
+/// The number of master key components `N` must equal `L + 3` and the number of ciphertext
+/// components `C` must equal `L + 1`, which is checked at compile time.
+#[derive(Debug)]
+pub struct TaggedInnerProductEncoding<const L: usize>;
+
+impl<const L: usize, const N: usize, const C: usize> PredicateEncoding<N, C, 2>
+    for TaggedInnerProductEncoding<L>
+{
+    type X = ([Scalar; L], Scalar);
+    type Y = [Scalar; L];
+
+    fn s_e(x: &([Scalar; L], Scalar)) -> [[Scalar; N]; C] {
+        const {
+            assert!(
+                N == L + 3 && C == L + 1,
+                "N must equal L + 3 and C must equal L + 1"
+            )
+        };
+
+        let mut res = [[Scalar::zero(); N]; C];
+        for (i, (row, xi)) in res.iter_mut().zip(x.0.iter()).enumerate() {
+            row[0] = *xi;
+            row[1 + i] = Scalar::one();
+        }
+        res[L][L + 1] = Scalar::one();
+        res[L][L + 2] = x.1;
+
+        res
+    }
+
+    fn r_e(y: &[Scalar; L]) -> [[Scalar; N]; 2] {
+        const {
+            assert!(
+                N == L + 3 && C == L + 1,
+                "N must equal L + 3 and C must equal L + 1"
+            )
+        };
+
+        let mut res = [[Scalar::zero(); N]; 2];
+        res[0][1..=L].copy_from_slice(y);
+        res[0][L + 1] = Scalar::one();
+        res[1][L + 2] = Scalar::one();
+
+        res
+    }
+
+    fn k_e(_y: &[Scalar; L]) -> [Scalar; 2] {
+        [Scalar::one(), Scalar::zero()]
+    }
+
+    fn s_d(_x: &([Scalar; L], Scalar), y: &[Scalar; L]) -> [Scalar; C] {
+        let mut res = [Scalar::one(); C];
+        res[..L].copy_from_slice(y);
+
+        res
+    }
+
+    fn r_d(x: &([Scalar; L], Scalar), _y: &[Scalar; L]) -> [Scalar; 2] {
+        [Scalar::one(), x.1]
+    }
+}
+
+/// Computes the inner product `<x, y>`.
+pub fn inner_product(x: &[Scalar], y: &[Scalar]) -> Scalar {
+    x.iter()
+        .zip(y.iter())
+        .fold(Scalar::zero(), |acc, (xi, yi)| acc + xi * yi)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Derive;
+
+    /// Checks the reconstruction property of an encoding for matching attributes.
+    fn reconstructs<E, const N: usize, const C: usize, const K: usize>(x: &E::X, y: &E::Y) -> bool
+    where
+        E: PredicateEncoding<N, C, K>,
+    {
+        let (se, re, ke) = (E::s_e(x), E::r_e(y), E::k_e(y));
+        let (sd, rd) = (E::s_d(x, y), E::r_d(x, y));
+
+        let lhs = (0..N).map(|i| (0..C).fold(Scalar::zero(), |acc, j| acc + sd[j] * se[j][i]));
+        let rhs = (0..N).map(|i| (0..K).fold(Scalar::zero(), |acc, j| acc + rd[j] * re[j][i]));
+        let alpha = (0..K).fold(Scalar::zero(), |acc, j| acc + rd[j] * ke[j]);
+
+        lhs.eq(rhs) && alpha == Scalar::one()
+    }
+
+    #[test]
+    fn ibe_encoding_reconstructs() {
+        let id = Identity::derive_str("alice@example.com");
+        let other = Identity::derive_str("bob@example.com");
+
+        assert!(reconstructs::<IbeEncoding, 2, 1, 1>(&id, &id));
+        assert!(!reconstructs::<IbeEncoding, 2, 1, 1>(&id, &other));
+    }
+
+    #[test]
+    fn tagged_ibe_encoding_reconstructs() {
+        let id = Identity::derive_str("alice@example.com");
+        let other = Identity::derive_str("bob@example.com");
+        let tag = Scalar::from(42u64);
+
+        assert!(reconstructs::<TaggedIbeEncoding, 3, 1, 2>(&(id, tag), &id));
+        assert!(!reconstructs::<TaggedIbeEncoding, 3, 1, 2>(
+            &(id, tag),
+            &other
+        ));
+    }
+
+    #[test]
+    fn inner_product_encoding_reconstructs() {
+        // The polynomial (X - 2)(X - 3) = X^2 - 5X + 6 evaluated at 2 and 4.
+        let y = [Scalar::from(6u64), -Scalar::from(5u64), Scalar::one()];
+        let root = [Scalar::one(), Scalar::from(2u64), Scalar::from(4u64)];
+        let other = [Scalar::one(), Scalar::from(4u64), Scalar::from(16u64)];
+
+        assert!(reconstructs::<InnerProductEncoding<3>, 4, 3, 1>(&root, &y));
+        assert!(!reconstructs::<InnerProductEncoding<3>, 4, 3, 1>(
+            &other, &y
+        ));
+    }
+
+    #[test]
+    fn tagged_inner_product_encoding_reconstructs() {
+        let y = [Scalar::from(6u64), -Scalar::from(5u64), Scalar::one()];
+        let root = [Scalar::one(), Scalar::from(3u64), Scalar::from(9u64)];
+        let other = [Scalar::one(), Scalar::from(4u64), Scalar::from(16u64)];
+        let tag = Scalar::from(42u64);
+
+        assert!(reconstructs::<TaggedInnerProductEncoding<3>, 6, 4, 2>(
+            &(root, tag),
+            &y
+        ));
+        assert!(!reconstructs::<TaggedInnerProductEncoding<3>, 6, 4, 2>(
+            &(other, tag),
+            &y
+        ));
+    }
+}
@@ -0,0 +1,54 @@
+//! This module contains the building blocks for predicate encryption from predicate encodings.
+//!
+//! A predicate encoding captures, for a predicate `P(x, y)` over ciphertext attributes `x` and
+//! key attributes `y`, how the master key material is distributed over the ciphertext and the user
+//! secret key. The generic [Chen-Gay-Wee engine](crate::pe::cgw) turns any such encoding into an
+//! adaptively secure predicate encryption scheme. Among the encodings are:
+//!
+//! - [`IbeEncoding`](crate::pe::encodings::IbeEncoding), the equality predicate used by
+//!   [`CGW`](crate::ibe::cgw::CGW),
+//! - [`TaggedIbeEncoding`](crate::pe::encodings::TaggedIbeEncoding), the equality predicate with a
+//!   ciphertext tag used by [`CGWKV`](crate::kem::cgw_kv::CGWKV),
+//! - [`InnerProductEncoding`](crate::pe::encodings::InnerProductEncoding) and
+//!   [`TaggedInnerProductEncoding`](crate::pe::encodings::TaggedInnerProductEncoding), the zero
+//!   inner product predicate used by `CGWIPE` and `CGWIPEKV` in the `abe` module.
+
+pub mod cgw;
+pub mod encodings;
+
+use pg_curve::Scalar;
+
+/// Predicate encoding, as defined by Wee and used by Chen, Gay and Wee.
+///
+/// An encoding consists of linear maps over `Z_p` that are given by their coefficients:
+///
+/// - `sE(x)`, from the `N` master key components to the `C` ciphertext components,
+/// - `rE(y)`, from the `N` master key components to the `K` key components,
+/// - `kE(y)`, from the master secret to the `K` key components,
+/// - `sD(x, y)` and `rD(x, y)`, from the `C` ciphertext and `K` key components to `Z_p`.
+///
+/// Whenever `P(x, y)` holds, the encoding must satisfy `sD(x, y) · sE(x) = rD(x, y) · rE(y)` and
+/// `rD(x, y) · kE(y) = 1`. Security additionally requires that `kE(y)` is hidden by `sE(x)` and
+/// `rE(y)` whenever `P(x, y)` does not hold.
+pub trait PredicateEncoding<const N: usize, const C: usize, const K: usize> {
+    /// Ciphertext attribute.
+    type X;
+
+    /// Key attribute.
+    type Y;
+
+    /// Coefficients of the ciphertext encoding `sE(x)`.
+    fn s_e(x: &Self::X) -> [[Scalar; N]; C];
+
+    /// Coefficients of the key encoding `rE(y)`.
+    fn r_e(y: &Self::Y) -> [[Scalar; N]; K];
+
+    /// Coefficients of the master secret encoding `kE(y)`.
+    fn k_e(y: &Self::Y) -> [Scalar; K];
+
+    /// Coefficients of the ciphertext decoding `sD(x, y)`.
+    fn s_d(x: &Self::X, y: &Self::Y) -> [Scalar; C];
+
+    /// Coefficients of the key decoding `rD(x, y)`.
+    fn r_d(x: &Self::X, y: &Self::Y) -> [Scalar; K];
+}
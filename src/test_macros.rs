@@ -96,6 +96,79 @@ macro_rules! test_multi_kem {
     };
 }
 
+macro_rules! test_threshold_kem {
+    ($name: ident) => {
+        #[test]
+        fn eq_threshold_decaps() {
+            use crate::kem::threshold::ThresholdDecaps;
+
+            let mut rng = rand::thread_rng();
+
+            let id = <$name as IBKEM>::Id::derive_str("email:legal@example.com");
+            let (pk, sk) = $name::setup(&mut rng);
+            let usk = $name::extract_usk(Some(&pk), &sk, &id, &mut rng);
+
+            let shares = $name::split_usk(&usk, 3, 5, &mut rng).unwrap();
+            let (ct, k) = $name::encaps(&pk, &id, &mut rng);
+            let ds: [_; 5] = core::array::from_fn(|i| $name::decaps_share(&shares[i], &ct));
+
+            for subset in [[0, 1, 2], [4, 2, 0], [1, 3, 4]] {
+                let subset = subset.map(|i| ds[i]);
+                assert_eq!($name::combine(Some(&pk), &id, &ct, &subset).unwrap(), k);
+            }
+
+            // Additional shares are ignored.
+            assert_eq!($name::combine(Some(&pk), &id, &ct, &ds).unwrap(), k);
+        }
+
+        #[test]
+        fn threshold_rejects_invalid_shares() {
+            use crate::kem::threshold::ThresholdDecaps;
+
+            let mut rng = rand::thread_rng();
+
+            let id = <$name as IBKEM>::Id::derive_str("email:legal@example.com");
+            let (pk, sk) = $name::setup(&mut rng);
+            let usk = $name::extract_usk(Some(&pk), &sk, &id, &mut rng);
+
+            assert!($name::split_usk(&usk, 0, 5, &mut rng).is_err());
+            assert!($name::split_usk(&usk, 6, 5, &mut rng).is_err());
+
+            let shares = $name::split_usk(&usk, 3, 5, &mut rng).unwrap();
+            let (ct, k) = $name::encaps(&pk, &id, &mut rng);
+            let ds: [_; 5] = core::array::from_fn(|i| $name::decaps_share(&shares[i], &ct));
+
+            assert!($name::combine(Some(&pk), &id, &ct, &[]).is_err());
+            assert!($name::combine(Some(&pk), &id, &ct, &ds[..2]).is_err());
+            assert!($name::combine(Some(&pk), &id, &ct, &[ds[0], ds[1], ds[0]]).is_err());
+
+            // Shares of a different sharing of the same key do not combine.
+            let other = $name::split_usk(&usk, 3, 5, &mut rng).unwrap();
+            let mixed = [ds[0], ds[1], $name::decaps_share(&other[2], &ct)];
+            assert!(!matches!($name::combine(Some(&pk), &id, &ct, &mixed), Ok(k2) if k2 == k));
+        }
+
+        #[test]
+        fn eq_threshold_serialize_deserialize() {
+            use crate::kem::threshold::{DecapsShare, ThresholdDecaps, UskShare};
+
+            let mut rng = rand::thread_rng();
+
+            let id = <$name as IBKEM>::Id::derive_str("email:legal@example.com");
+            let (pk, sk) = $name::setup(&mut rng);
+            let usk = $name::extract_usk(Some(&pk), &sk, &id, &mut rng);
+
+            let shares = $name::split_usk(&usk, 2, 3, &mut rng).unwrap();
+            let (ct, _) = $name::encaps(&pk, &id, &mut rng);
+            let ds = $name::decaps_share(&shares[1], &ct);
+
+            let share2 = UskShare::<<$name as IBKEM>::Usk>::from_bytes(&shares[1].to_bytes());
+            assert_eq!(shares[1], share2.unwrap());
+            assert_eq!(ds, DecapsShare::from_bytes(&ds.to_bytes()).unwrap());
+        }
+    };
+}
+
 macro_rules! test_ibe {
     ($name: ident) => {
         #[allow(unused_imports)]
@@ -163,3 +236,96 @@ macro_rules! test_ibe {
         }
     };
 }
+
+macro_rules! test_hibe {
+    ($name: ident) => {
+        fn encrypt_random(pk: &PublicKey, path: &<$name as IBE>::Id) -> (CipherText, Msg) {
+            use group::Group;
+            use rand::RngCore;
+
+            let mut rng = rand::thread_rng();
+            let m = Msg::random(&mut rng);
+
+            type Rng = <$name as IBE>::RngBytes;
+            let mut rand_bytes: Rng = [0u8; core::mem::size_of::<Rng>()];
+            rng.fill_bytes(&mut rand_bytes);
+
+            ($name::encrypt(pk, path, &m, &rand_bytes), m)
+        }
+
+        #[test]
+        fn eq_delegate_decrypt() {
+            use crate::ibe::HIBE;
+
+            let mut rng = rand::thread_rng();
+
+            let domain = <$name as HIBE>::IdComponent::derive_str("example.com");
+            let alice = <$name as HIBE>::IdComponent::derive_str("alice");
+            let bob = <$name as HIBE>::IdComponent::derive_str("bob");
+
+            let (pk, sk) = $name::setup(&mut rng);
+
+            let root = <$name as IBE>::Id::new(&[domain]).unwrap();
+            let path_alice = root.push(&alice).unwrap();
+            let path_bob = root.push(&bob).unwrap();
+
+            let usk_domain = $name::extract_usk(Some(&pk), &sk, &root, &mut rng);
+            let usk_alice = $name::delegate(&pk, &usk_domain, &alice, &mut rng).unwrap();
+            let usk_bob = $name::delegate(&pk, &usk_domain, &bob, &mut rng).unwrap();
+            let usk_extracted = $name::extract_usk(Some(&pk), &sk, &path_alice, &mut rng);
+
+            let (c, m) = encrypt_random(&pk, &path_alice);
+
+            assert_eq!(m, $name::decrypt(&usk_alice, &c));
+            assert_eq!(m, $name::decrypt(&usk_extracted, &c));
+            assert_ne!(m, $name::decrypt(&usk_bob, &c));
+
+            let (c, m) = encrypt_random(&pk, &path_bob);
+            assert_eq!(m, $name::decrypt(&usk_bob, &c));
+            assert_ne!(m, $name::decrypt(&usk_alice, &c));
+        }
+
+        #[test]
+        fn delegated_usk_is_rerandomized() {
+            use crate::ibe::HIBE;
+
+            let mut rng = rand::thread_rng();
+
+            let domain = <$name as HIBE>::IdComponent::derive_str("example.com");
+            let alice = <$name as HIBE>::IdComponent::derive_str("alice");
+
+            let (pk, sk) = $name::setup(&mut rng);
+            let root = <$name as IBE>::Id::new(&[domain]).unwrap();
+            let usk_domain = $name::extract_usk(Some(&pk), &sk, &root, &mut rng);
+
+            let usk1 = $name::delegate(&pk, &usk_domain, &alice, &mut rng).unwrap();
+            let usk2 = $name::delegate(&pk, &usk_domain, &alice, &mut rng).unwrap();
+
+            assert_ne!(usk1, usk2);
+        }
+
+        #[test]
+        fn delegate_respects_max_depth() {
+            use crate::ibe::HIBE;
+
+            let mut rng = rand::thread_rng();
+            let id = <$name as HIBE>::IdComponent::derive_str("level");
+
+            let (pk, sk) = $name::setup(&mut rng);
+            let root = <$name as IBE>::Id::new(&[id]).unwrap();
+            let mut usk = $name::extract_usk(Some(&pk), &sk, &root, &mut rng);
+            let mut path = root;
+
+            for _ in 1..<$name as HIBE>::MAX_DEPTH {
+                usk = $name::delegate(&pk, &usk, &id, &mut rng).unwrap();
+                path = path.push(&id).unwrap();
+            }
+
+            let (c, m) = encrypt_random(&pk, &path);
+            assert_eq!(m, $name::decrypt(&usk, &c));
+
+            assert!($name::delegate(&pk, &usk, &id, &mut rng).is_none());
+            assert!(path.push(&id).is_none());
+        }
+    };
+}
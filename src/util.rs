@@ -1,3 +1,5 @@
+extern crate alloc;
+
 use crate::{Compress, Derive};
 use group::{ff::Field, Group, UncompressedEncoding};
 use pg_curve::{G1Projective, G2Projective, Gt, Scalar};
@@ -136,6 +138,47 @@ pub fn rpc<Gr: UncompressedEncoding>(k: &[u8; 32], gs: &[Gr]) -> Scalar {
     Scalar::from_bytes_wide(&buf)
 }
 
+/// Hashes a byte slice to a point in G1, using the `expand_message_xmd` variant with SHA-256.
+#[cfg(feature = "hash_to_curve")]
+pub fn hash_to_g1(msg: &[u8], dst: &[u8]) -> G1Projective {
+    use pg_curve::hash_to_curve::{ExpandMsgXmd, HashToCurve};
+
+    <G1Projective as HashToCurve<ExpandMsgXmd<sha2_v09::Sha256>>>::hash_to_curve(msg, dst)
+}
+
+/// Hashes a byte slice to a point in G2, using the `expand_message_xmd` variant with SHA-256.
+#[cfg(feature = "hash_to_curve")]
+pub fn hash_to_g2(msg: &[u8], dst: &[u8]) -> G2Projective {
+    use pg_curve::hash_to_curve::{ExpandMsgXmd, HashToCurve};
+
+    <G2Projective as HashToCurve<ExpandMsgXmd<sha2_v09::Sha256>>>::hash_to_curve(msg, dst)
+}
+
+/// Computes the Lagrange coefficients for interpolating at `x` from the points `xs`.
+///
+/// Returns `None` if the points are not distinct.
+#[cfg(any(
+    feature = "green_miers",
+    feature = "sahai_waters",
+    feature = "threshold"
+))]
+pub fn lagrange_at(xs: &[Scalar], x: &Scalar) -> Option<alloc::vec::Vec<Scalar>> {
+    xs.iter()
+        .enumerate()
+        .map(|(i, xi)| {
+            let (num, den) = xs
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .fold((Scalar::one(), Scalar::one()), |(num, den), (_, xj)| {
+                    (num * (x - xj), den * (xi - xj))
+                });
+
+            Option::from(den.invert()).map(|inv: Scalar| num * inv)
+        })
+        .collect()
+}
+
 /// Byte representation of an identity.
 /// Most schemes (not all) use the same representation.
 ///